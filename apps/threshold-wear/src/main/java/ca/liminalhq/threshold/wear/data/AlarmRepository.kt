@@ -83,6 +83,17 @@ class AlarmRepository(context: Context) {
         )
     }
 
+    /**
+     * Replace one cached alarm without a sync, e.g. an interval alarm the watch moved
+     * on to its next instance while the phone was away. The next sync overwrites it.
+     */
+    fun updateLocally(alarm: WatchAlarm) {
+        _alarms.value = _alarms.value
+            .map { if (it.id == alarm.id) alarm else it }
+            .sortedBy { it.hour * 60 + it.minute }
+        saveToPrefs()
+    }
+
     /** Update the sync status indicator. */
     fun setSyncStatus(status: SyncStatus) {
         _syncStatus.value = status
@@ -15,6 +15,7 @@ private const val SEARCH_DAYS = 7
  *
  * If [WatchAlarm.daysOfWeek] is empty, the alarm is treated as daily.
  * Day values use `0..6` where `0 = Sunday`, matching the phone model.
+ * Timers mirrored from the phone aren't alarms, and are passed over.
  */
 fun findNextUpcomingAlarm(
     alarms: List<WatchAlarm>,
@@ -22,7 +23,7 @@ fun findNextUpcomingAlarm(
 ): WatchAlarm? {
     return alarms
         .asSequence()
-        .filter { it.enabled }
+        .filter { it.enabled && !it.timer }
         .mapNotNull { alarm ->
             val nextOccurrence = nextOccurrenceMinutesFromNow(alarm, now) ?: return@mapNotNull null
             alarm to nextOccurrence
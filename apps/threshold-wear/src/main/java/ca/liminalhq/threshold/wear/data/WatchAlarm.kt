@@ -6,6 +6,9 @@
 package ca.liminalhq.threshold.wear.data
 
 import org.json.JSONObject
+import java.time.Instant
+import java.time.LocalTime
+import java.time.ZoneId
 
 /**
  * Watch-side representation of an alarm.
@@ -22,11 +25,50 @@ data class WatchAlarm(
     val daysOfWeek: List<Int> = emptyList(),
     /** Epoch millis of the next scheduled trigger, or null if not scheduled. */
     val nextTrigger: Long? = null,
+    /** Interval alarms only: minutes between instances, from [hour]:[minute] on. */
+    val intervalMinutes: Int? = null,
+    /** Interval alarms only: "HH:MM" the span of instances ends at. */
+    val windowEnd: String? = null,
+    /** A phone countdown timer, rung here but kept out of the alarm list. */
+    val timer: Boolean = false,
+    /** Names of the phone's alarm groups this alarm is in. */
+    val groups: List<String> = emptyList(),
+    /** "YYYY-MM-DD" a phone pause holding this alarm off ends, or null if not paused. */
+    val pausedUntil: String? = null,
 ) {
     /** Formatted time string for display (e.g. "07:30"). */
     val timeDisplay: String
         get() = "%02d:%02d".format(hour, minute)
 
+    /**
+     * For an interval alarm, the instance after [nextTrigger] if it's still inside
+     * that day's span, so the watch can keep going without the phone; null for any
+     * other alarm, or once the span is over.
+     */
+    fun nextIntervalInstance(zone: ZoneId = ZoneId.systemDefault()): Long? {
+        val every = intervalMinutes ?: return null
+        val trigger = nextTrigger ?: return null
+        val endParts = windowEnd?.split(":") ?: return null
+        val end = LocalTime.of(
+            endParts.getOrNull(0)?.toIntOrNull() ?: return null,
+            endParts.getOrNull(1)?.toIntOrNull() ?: return null,
+        )
+        val start = LocalTime.of(hour, minute)
+        val crossesMidnight = !end.isAfter(start)
+
+        // An overnight span's tail after midnight belongs to the day it opened
+        val at = Instant.ofEpochMilli(trigger).atZone(zone)
+        val opened = if (crossesMidnight && at.toLocalTime().isBefore(start)) {
+            at.toLocalDate().minusDays(1)
+        } else {
+            at.toLocalDate()
+        }
+        val closes = (if (crossesMidnight) opened.plusDays(1) else opened).atTime(end).atZone(zone)
+
+        val next = at.plusMinutes(every.toLong())
+        return if (next.isAfter(closes)) null else next.toInstant().toEpochMilli()
+    }
+
     companion object {
         fun fromJson(json: JSONObject): WatchAlarm {
             val days = when {
@@ -41,6 +83,14 @@ data class WatchAlarm(
                 else -> emptyList()
             }
 
+            val nextTrigger = if (json.has("nextTrigger") && !json.isNull("nextTrigger")) {
+                json.getLong("nextTrigger")
+            } else if (json.has("next_trigger") && !json.isNull("next_trigger")) {
+                json.getLong("next_trigger")
+            } else {
+                null
+            }
+
             // Parse hour/minute from either explicit fields or fixedTime "HH:MM" string
             val hour: Int
             val minute: Int
@@ -55,15 +105,23 @@ data class WatchAlarm(
                 val parts = json.getString("windowStart").split(":")
                 hour = parts.getOrNull(0)?.toIntOrNull() ?: 0
                 minute = parts.getOrNull(1)?.toIntOrNull() ?: 0
+            } else if (nextTrigger != null) {
+                // Solar and follow-up alarms have no clock time of their own
+                val trigger = Instant.ofEpochMilli(nextTrigger).atZone(ZoneId.systemDefault())
+                hour = trigger.hour
+                minute = trigger.minute
             } else {
                 hour = 0
                 minute = 0
             }
 
-            val nextTrigger = if (json.has("nextTrigger") && !json.isNull("nextTrigger")) {
-                json.getLong("nextTrigger")
-            } else if (json.has("next_trigger") && !json.isNull("next_trigger")) {
-                json.getLong("next_trigger")
+            val intervalMinutes = if (json.has("intervalMinutes") && !json.isNull("intervalMinutes")) {
+                json.getInt("intervalMinutes")
+            } else {
+                null
+            }
+            val windowEnd = if (intervalMinutes != null && json.has("windowEnd") && !json.isNull("windowEnd")) {
+                json.getString("windowEnd")
             } else {
                 null
             }
@@ -76,8 +134,36 @@ data class WatchAlarm(
                 enabled = json.getBoolean("enabled"),
                 daysOfWeek = days,
                 nextTrigger = nextTrigger,
+                intervalMinutes = intervalMinutes,
+                windowEnd = windowEnd,
+                timer = json.optBoolean("timer", false),
+                groups = json.optJSONArray("groups")?.let { arr ->
+                    (0 until arr.length()).map { arr.getString(it) }
+                } ?: emptyList(),
+                pausedUntil = if (json.isNull("pausedUntil")) null else json.optString("pausedUntil"),
+            )
+        }
+
+        /**
+         * A phone timer as the watch rings it: under the same native id the phone
+         * schedules it with, due when it ends, and disabled while it's paused.
+         */
+        fun fromTimerJson(json: JSONObject): WatchAlarm {
+            val endsAt = if (json.has("endsAt") && !json.isNull("endsAt")) json.getLong("endsAt") else null
+            val at = endsAt?.let { Instant.ofEpochMilli(it).atZone(ZoneId.systemDefault()) }
+            return WatchAlarm(
+                id = TIMER_ID_OFFSET + json.getInt("id"),
+                hour = at?.hour ?: 0,
+                minute = at?.minute ?: 0,
+                label = if (json.isNull("label")) "Timer" else json.optString("label", "Timer"),
+                enabled = json.optString("state") == "RUNNING",
+                nextTrigger = endsAt,
+                timer = true,
             )
         }
+
+        /** Matches the phone's offset for timers' native ids. */
+        const val TIMER_ID_OFFSET = 4_000_000
     }
 
     fun toJson(): JSONObject = JSONObject().apply {
@@ -88,5 +174,10 @@ data class WatchAlarm(
         put("enabled", enabled)
         put("daysOfWeek", org.json.JSONArray(daysOfWeek))
         if (nextTrigger != null) put("nextTrigger", nextTrigger)
+        if (intervalMinutes != null) put("intervalMinutes", intervalMinutes)
+        if (windowEnd != null) put("windowEnd", windowEnd)
+        if (timer) put("timer", true)
+        if (groups.isNotEmpty()) put("groups", org.json.JSONArray(groups))
+        if (pausedUntil != null) put("pausedUntil", pausedUntil)
     }
 }
@@ -61,6 +61,9 @@ import ca.liminalhq.threshold.wear.data.WatchAlarm
 import ca.liminalhq.threshold.wear.presentation.theme.ThresholdAccent
 import ca.liminalhq.threshold.wear.presentation.theme.ThresholdDisabled
 import ca.liminalhq.threshold.wear.presentation.theme.ThresholdSurface
+import java.time.LocalDate
+import java.time.format.DateTimeFormatter
+import java.time.format.DateTimeParseException
 
 /**
  * Main alarm list screen for the Threshold Wear OS companion app.
@@ -77,7 +80,8 @@ fun AlarmListScreen(
     onRefresh: () -> Unit,
     onNavigateToSettings: () -> Unit = {},
 ) {
-    val alarms by repository.alarms.collectAsState()
+    val allAlarms by repository.alarms.collectAsState()
+    val alarms = allAlarms.filterNot { it.timer }
     val syncStatus by repository.syncStatus.collectAsState()
     var alarmToDelete by remember { mutableStateOf<WatchAlarm?>(null) }
 
@@ -220,9 +224,13 @@ private fun AlarmCard(
                     },
                 )
 
-                if (alarm.label.isNotBlank()) {
+                val paused = alarm.pausedUntil?.let { "Paused until ${pauseEndDisplay(it)}" }
+                val subtitle = (listOfNotNull(paused, alarm.label) + alarm.groups)
+                    .filter { it.isNotBlank() }
+                    .joinToString(" · ")
+                if (subtitle.isNotEmpty()) {
                     Text(
-                        text = alarm.label,
+                        text = subtitle,
                         fontSize = 13.sp,
                         color = MaterialTheme.colors.onSurface.copy(alpha = 0.6f),
                         maxLines = 1,
@@ -234,6 +242,15 @@ private fun AlarmCard(
     }
 }
 
+/** A pause's "YYYY-MM-DD" end as a short date, e.g. "25 Oct"; as-is if it won't parse. */
+private fun pauseEndDisplay(until: String): String {
+    return try {
+        LocalDate.parse(until).format(DateTimeFormatter.ofPattern("d MMM"))
+    } catch (e: DateTimeParseException) {
+        until
+    }
+}
+
 @Composable
 private fun SyncStatusIndicator(syncStatus: SyncStatus) {
     Row(
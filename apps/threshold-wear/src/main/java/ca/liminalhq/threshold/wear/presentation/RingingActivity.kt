@@ -59,6 +59,7 @@ class RingingActivity : ComponentActivity() {
         val hour = intent.getIntExtra(WearRingingService.EXTRA_ALARM_HOUR, 0)
         val minute = intent.getIntExtra(WearRingingService.EXTRA_ALARM_MINUTE, 0)
         val snoozeLength = intent.getIntExtra(WearRingingService.EXTRA_SNOOZE_LENGTH, 10)
+        val snoozeAllowed = intent.getBooleanExtra(WearRingingService.EXTRA_SNOOZE_ALLOWED, true)
         val prefs = applicationContext.getSharedPreferences("threshold_wear", Context.MODE_PRIVATE)
         val watchDefaultIs24Hour = DateFormat.is24HourFormat(this)
         val is24HourKnown = prefs.getBoolean("is_24_hour_known", false)
@@ -84,6 +85,7 @@ class RingingActivity : ComponentActivity() {
                     label = label,
                     is24Hour = is24Hour,
                     snoozeLengthMinutes = snoozeLength,
+                    snoozeAllowed = snoozeAllowed,
                     onStop = {
                         Log.d(TAG, "Stop pressed for alarm $alarmId")
                         NativeEventLog.log(applicationContext, TAG, "Stop pressed for alarm id=$alarmId")
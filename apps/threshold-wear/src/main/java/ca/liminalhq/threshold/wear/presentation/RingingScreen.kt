@@ -125,7 +125,7 @@ private fun resolveRingingColors(): RingingColors {
  * - Time display with bloom glow
  * - Alarm label
  * - Threshold indicator (sleep → wake with animated amber dot)
- * - Stop and Snooze pill buttons
+ * - Stop and Snooze pill buttons (Snooze only while [snoozeAllowed])
  *
  * Colours are resolved from the system's Material You palette when
  * available, matching the phone's theme-linked ringing screen.
@@ -140,6 +140,7 @@ fun RingingScreen(
     label: String,
     is24Hour: Boolean = false,
     snoozeLengthMinutes: Int = 10,
+    snoozeAllowed: Boolean = true,
     onStop: () -> Unit,
     onSnooze: () -> Unit,
 ) {
@@ -393,30 +394,32 @@ fun RingingScreen(
                 )
             }
 
-            Spacer(modifier = Modifier.height(6.dp))
+            // Snooze — ghost outline pill, hidden once the alarm's snooze policy says no
+            if (snoozeAllowed) {
+                Spacer(modifier = Modifier.height(6.dp))
 
-            // Snooze — ghost outline pill
-            Button(
-                onClick = onSnooze,
-                modifier = Modifier
-                    .fillMaxWidth(0.88f)
-                    .height(snoozeHeight),
-                colors = ButtonDefaults.buttonColors(
-                    backgroundColor = Color.Transparent,
-                ),
-                border = ButtonDefaults.outlinedButtonBorder(
-                    borderColor = Color.White.copy(alpha = 0.6f),
-                    borderWidth = 2.dp,
-                ),
-                shape = RoundedCornerShape(20.dp),
-            ) {
-                Text(
-                    text = "Snooze (${snoozeLengthMinutes}m)",
-                    fontSize = 14.sp,
-                    fontWeight = FontWeight.Medium,
-                    color = Color.White.copy(alpha = 0.9f),
-                    letterSpacing = 0.5.sp,
-                )
+                Button(
+                    onClick = onSnooze,
+                    modifier = Modifier
+                        .fillMaxWidth(0.88f)
+                        .height(snoozeHeight),
+                    colors = ButtonDefaults.buttonColors(
+                        backgroundColor = Color.Transparent,
+                    ),
+                    border = ButtonDefaults.outlinedButtonBorder(
+                        borderColor = Color.White.copy(alpha = 0.6f),
+                        borderWidth = 2.dp,
+                    ),
+                    shape = RoundedCornerShape(20.dp),
+                ) {
+                    Text(
+                        text = "Snooze (${snoozeLengthMinutes}m)",
+                        fontSize = 14.sp,
+                        fontWeight = FontWeight.Medium,
+                        color = Color.White.copy(alpha = 0.9f),
+                        letterSpacing = 0.5.sp,
+                    )
+                }
             }
 
             Spacer(modifier = Modifier.height(2.dp))
@@ -158,6 +158,18 @@ class DataLayerListenerService : WearableListenerService() {
             val data = String(messageEvent.data, Charsets.UTF_8)
             val json = JSONObject(data)
             val alarmId = json.getInt("alarmId")
+            // The phone has rung unattended for a while and wants the watch to insist
+            val escalated = json.optBoolean("escalated", false)
+
+            if (escalated && WearRingingService.ringingAlarmId == alarmId) {
+                NativeEventLog.log(applicationContext, TAG, "Escalating ring for alarm id=$alarmId")
+                startService(
+                    Intent(this, WearRingingService::class.java).apply {
+                        action = WearRingingService.ACTION_ESCALATE
+                    },
+                )
+                return
+            }
 
             // Deduplication: skip if this alarm is already ringing
             if (WearRingingService.ringingAlarmId == alarmId) {
@@ -174,8 +186,12 @@ class DataLayerListenerService : WearableListenerService() {
             val hour = json.optInt("hour", 0)
             val minute = json.optInt("minute", 0)
             val snoozeLength = json.optInt("snoozeLengthMinutes", 10)
+            // The alarm's snooze policy may have run out of snoozes for this ring
+            val snoozeAllowed = json.optBoolean("snoozeAllowed", true)
             val is24HourKnown = json.optBoolean("is24HourKnown", false)
             val is24Hour = if (json.has("is24Hour")) json.optBoolean("is24Hour", false) else null
+            val preAlarm = json.optBoolean("preAlarm", false)
+            val ringSeconds = json.optInt("ringSeconds", 0)
 
             Log.d(TAG, "Alarm ring: id=$alarmId, $hour:$minute '$label' snooze=${snoozeLength}m is24h=${is24Hour ?: "unknown"} is24hKnown=$is24HourKnown")
 
@@ -196,6 +212,10 @@ class DataLayerListenerService : WearableListenerService() {
                 putExtra(WearRingingService.EXTRA_ALARM_HOUR, hour)
                 putExtra(WearRingingService.EXTRA_ALARM_MINUTE, minute)
                 putExtra(WearRingingService.EXTRA_SNOOZE_LENGTH, snoozeLength)
+                putExtra(WearRingingService.EXTRA_SNOOZE_ALLOWED, snoozeAllowed)
+                putExtra(WearRingingService.EXTRA_PRE_ALARM, preAlarm)
+                putExtra(WearRingingService.EXTRA_RING_SECONDS, ringSeconds)
+                putExtra(WearRingingService.EXTRA_ESCALATED, escalated)
             }
 
             if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
@@ -337,7 +357,15 @@ internal fun parseSyncPayload(alarmsJson: String): SyncAction {
     return try {
         val root = JSONObject(alarmsJson)
         when (root.optString("type")) {
-            "FullSync" -> SyncAction.ReplaceAll(parseAlarmArray(root.getJSONArray("allAlarms")))
+            "FullSync" -> SyncAction.ReplaceAll(
+                withPauses(
+                    withGroups(
+                        parseAlarmArray(root.getJSONArray("allAlarms")),
+                        root.optJSONArray("groups") ?: JSONArray(),
+                    ),
+                    root.optJSONArray("pauses") ?: JSONArray(),
+                ) + parseTimerArray(root.optJSONArray("timers") ?: JSONArray()),
+            )
             "Incremental" -> {
                 val deletedArray = root.getJSONArray("deletedAlarmIds")
                 SyncAction.ApplyIncremental(
@@ -367,6 +395,50 @@ internal fun parseSyncPayload(alarmsJson: String): SyncAction {
     }
 }
 
+/**
+ * Label each alarm with the names of the phone's groups it's in, from a JSON array
+ * of groups (`name`, `alarmIds`).
+ */
+internal fun withGroups(alarms: List<WatchAlarm>, groups: JSONArray): List<WatchAlarm> {
+    val names = mutableMapOf<Int, MutableList<String>>()
+    for (i in 0 until groups.length()) {
+        val group = groups.optJSONObject(i) ?: continue
+        val alarmIds = group.optJSONArray("alarmIds") ?: continue
+        for (j in 0 until alarmIds.length()) {
+            names.getOrPut(alarmIds.getInt(j)) { mutableListOf() }.add(group.optString("name"))
+        }
+    }
+    return alarms.map { alarm -> names[alarm.id]?.let { alarm.copy(groups = it) } ?: alarm }
+}
+
+/**
+ * Mark each alarm a phone pause is holding off with the date it ends, from a JSON
+ * array of pauses (`until`, `alarmIds`).
+ */
+internal fun withPauses(alarms: List<WatchAlarm>, pauses: JSONArray): List<WatchAlarm> {
+    val until = mutableMapOf<Int, String>()
+    for (i in 0 until pauses.length()) {
+        val pause = pauses.optJSONObject(i) ?: continue
+        val alarmIds = pause.optJSONArray("alarmIds") ?: continue
+        for (j in 0 until alarmIds.length()) {
+            until.merge(alarmIds.getInt(j), pause.optString("until"), ::maxOf)
+        }
+    }
+    return alarms.map { alarm -> until[alarm.id]?.let { alarm.copy(pausedUntil = it) } ?: alarm }
+}
+
+/** Parse a JSON array of the phone's timers, skipping entries that fail to parse. */
+internal fun parseTimerArray(array: JSONArray): List<WatchAlarm> {
+    return (0 until array.length()).mapNotNull { i ->
+        try {
+            WatchAlarm.fromTimerJson(array.getJSONObject(i))
+        } catch (e: Exception) {
+            Log.w(TAG, "Failed to parse timer at index $i", e)
+            null
+        }
+    }
+}
+
 /** Parse a JSON array of alarm objects, skipping entries that fail to parse. */
 internal fun parseAlarmArray(array: JSONArray): List<WatchAlarm> {
     return (0 until array.length()).mapNotNull { i ->
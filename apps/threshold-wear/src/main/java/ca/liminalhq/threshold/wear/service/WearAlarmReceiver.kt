@@ -82,5 +82,14 @@ class WearAlarmReceiver : BroadcastReceiver() {
         } else {
             context.startService(serviceIntent)
         }
+
+        // An interval alarm's next instance would otherwise wait for the phone to
+        // hear about this one being dismissed
+        if (app == null || alarm == null) return
+        val nextInstance = alarm.nextIntervalInstance() ?: return
+        val advanced = alarm.copy(nextTrigger = nextInstance)
+        app.alarmRepository.updateLocally(advanced)
+        app.alarmScheduler.schedule(advanced)
+        NativeEventLog.log(context, TAG, "Interval alarm id=$alarmId moved on to $nextInstance locally")
     }
 }
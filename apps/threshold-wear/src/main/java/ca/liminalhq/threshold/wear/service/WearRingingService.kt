@@ -16,7 +16,9 @@ import android.media.AudioAttributes
 import android.media.MediaPlayer
 import android.media.RingtoneManager
 import android.os.Build
+import android.os.Handler
 import android.os.IBinder
+import android.os.Looper
 import android.os.PowerManager
 import android.os.VibrationEffect
 import android.os.Vibrator
@@ -37,6 +39,11 @@ import ca.liminalhq.threshold.wear.presentation.RingingActivity
  * The service is started by [DataLayerListenerService] when a ring
  * message arrives from the phone, or by the local [WearAlarmReceiver]
  * when the watch fires an alarm independently.
+ *
+ * A pre-alarm from the phone only vibrates gently, without sound, and
+ * stops by itself after its ring time. An escalated ring, sent once the
+ * phone has rung unattended for a while or for a backup alarm, vibrates
+ * insistently.
  */
 class WearRingingService : Service() {
 
@@ -44,17 +51,29 @@ class WearRingingService : Service() {
     private var vibrator: Vibrator? = null
     private var wakeLock: PowerManager.WakeLock? = null
     private var currentAlarmId: Int = -1
+    private val handler = Handler(Looper.getMainLooper())
+    private val ringTimeout = Runnable {
+        Log.d(TAG, "Ring time elapsed for alarm $currentAlarmId")
+        NativeEventLog.log(applicationContext, TAG, "Ring time elapsed for alarm id=$currentAlarmId, stopping")
+        stopSelf()
+    }
 
     companion object {
         const val CHANNEL_ID = "wear_alarm_ringing"
         const val NOTIFICATION_ID = 1001
         const val ACTION_DISMISS = "ca.liminalhq.threshold.wear.ACTION_DISMISS"
         const val ACTION_SNOOZE = "ca.liminalhq.threshold.wear.ACTION_SNOOZE"
+        const val ACTION_ESCALATE = "ca.liminalhq.threshold.wear.ACTION_ESCALATE"
         const val EXTRA_ALARM_ID = "alarm_id"
         const val EXTRA_ALARM_LABEL = "alarm_label"
         const val EXTRA_ALARM_HOUR = "alarm_hour"
         const val EXTRA_ALARM_MINUTE = "alarm_minute"
         const val EXTRA_SNOOZE_LENGTH = "snooze_length_minutes"
+        const val EXTRA_SNOOZE_ALLOWED = "snooze_allowed"
+        const val EXTRA_PRE_ALARM = "pre_alarm"
+        const val EXTRA_RING_SECONDS = "ring_seconds"
+        const val EXTRA_ESCALATED = "escalated"
+        private val ESCALATED_VIBRATION = longArrayOf(0, 800, 200, 800, 200, 800, 400)
         private const val TAG = "WearRingingService"
 
         /** Alarm ID currently ringing, or -1 if idle. Used for deduplication. */
@@ -96,6 +115,14 @@ class WearRingingService : Service() {
                 stopSelf()
                 return START_NOT_STICKY
             }
+            ACTION_ESCALATE -> {
+                Log.d(TAG, "Escalate action received")
+                NativeEventLog.log(applicationContext, TAG, "Escalating ring for alarm id=$currentAlarmId")
+                stopVibration()
+                startVibration(ESCALATED_VIBRATION)
+                if (mediaPlayer == null) playAudio()
+                return START_STICKY
+            }
         }
 
         currentAlarmId = intent.getIntExtra(EXTRA_ALARM_ID, -1)
@@ -104,11 +131,20 @@ class WearRingingService : Service() {
         val hour = intent.getIntExtra(EXTRA_ALARM_HOUR, 0)
         val minute = intent.getIntExtra(EXTRA_ALARM_MINUTE, 0)
         val snoozeLength = intent.getIntExtra(EXTRA_SNOOZE_LENGTH, 10)
+        val snoozeAllowed = intent.getBooleanExtra(EXTRA_SNOOZE_ALLOWED, true)
+        val preAlarm = intent.getBooleanExtra(EXTRA_PRE_ALARM, false)
+        val ringSeconds = intent.getIntExtra(EXTRA_RING_SECONDS, 0)
+        val escalated = intent.getBooleanExtra(EXTRA_ESCALATED, false)
+
+        // A pre-alarm may still be ringing when its alarm fires; the alarm takes over.
+        handler.removeCallbacks(ringTimeout)
+        stopAudio()
+        stopVibration()
 
         Log.d(TAG, "Starting ringing for alarm $currentAlarmId ($hour:$minute '$label')")
         NativeEventLog.log(applicationContext, TAG, "Ringing service starting for alarm id=$currentAlarmId")
 
-        val foregroundStarted = startForegroundNotification(hour, minute, label, snoozeLength)
+        val foregroundStarted = startForegroundNotification(hour, minute, label, snoozeLength, snoozeAllowed)
         if (!foregroundStarted) {
             Log.e(TAG, "Failed to enter foreground; stopping ringing service")
             NativeEventLog.log(
@@ -116,13 +152,20 @@ class WearRingingService : Service() {
                 TAG,
                 "Failed to enter foreground for alarm id=$currentAlarmId -- stopping",
             )
-            launchRingingActivity(hour, minute, label, snoozeLength)
+            launchRingingActivity(hour, minute, label, snoozeLength, snoozeAllowed)
             stopSelf(startId)
             return START_NOT_STICKY
         }
-        launchRingingActivity(hour, minute, label, snoozeLength)
-        playAudio()
-        startVibration()
+        launchRingingActivity(hour, minute, label, snoozeLength, snoozeAllowed)
+        if (preAlarm) {
+            startVibration(longArrayOf(0, 300, 2700))
+        } else {
+            playAudio()
+            if (escalated) startVibration(ESCALATED_VIBRATION) else startVibration()
+        }
+        if (ringSeconds > 0) {
+            handler.postDelayed(ringTimeout, ringSeconds * 1000L)
+        }
 
         return START_STICKY
     }
@@ -132,6 +175,7 @@ class WearRingingService : Service() {
         Log.d(TAG, "Service destroying")
         NativeEventLog.log(applicationContext, TAG, "Ringing service destroying for alarm id=$currentAlarmId")
         ringingAlarmId = -1
+        handler.removeCallbacks(ringTimeout)
         stopAudio()
         stopVibration()
         if (wakeLock?.isHeld == true) {
@@ -139,13 +183,20 @@ class WearRingingService : Service() {
         }
     }
 
-    private fun launchRingingActivity(hour: Int, minute: Int, label: String, snoozeLength: Int) {
+    private fun launchRingingActivity(
+        hour: Int,
+        minute: Int,
+        label: String,
+        snoozeLength: Int,
+        snoozeAllowed: Boolean,
+    ) {
         val intent = Intent(this, RingingActivity::class.java).apply {
             putExtra(EXTRA_ALARM_ID, currentAlarmId)
             putExtra(EXTRA_ALARM_LABEL, label)
             putExtra(EXTRA_ALARM_HOUR, hour)
             putExtra(EXTRA_ALARM_MINUTE, minute)
             putExtra(EXTRA_SNOOZE_LENGTH, snoozeLength)
+            putExtra(EXTRA_SNOOZE_ALLOWED, snoozeAllowed)
             flags = Intent.FLAG_ACTIVITY_NEW_TASK or Intent.FLAG_ACTIVITY_CLEAR_TOP
         }
 
@@ -164,6 +215,7 @@ class WearRingingService : Service() {
         minute: Int,
         label: String,
         snoozeLength: Int,
+        snoozeAllowed: Boolean,
     ): Boolean {
         val notificationManager =
             getSystemService(Context.NOTIFICATION_SERVICE) as NotificationManager
@@ -188,6 +240,7 @@ class WearRingingService : Service() {
             putExtra(EXTRA_ALARM_HOUR, hour)
             putExtra(EXTRA_ALARM_MINUTE, minute)
             putExtra(EXTRA_SNOOZE_LENGTH, snoozeLength)
+            putExtra(EXTRA_SNOOZE_ALLOWED, snoozeAllowed)
             this.flags = Intent.FLAG_ACTIVITY_NEW_TASK or Intent.FLAG_ACTIVITY_CLEAR_TOP
         }
         val fullScreenPendingIntent = PendingIntent.getActivity(
@@ -273,7 +326,8 @@ class WearRingingService : Service() {
 
     // ── Vibration ───────────────────────────────────────────────────
 
-    private fun startVibration() {
+    // Same pattern as phone by default: wait 0ms, vibrate 1s, sleep 1s, repeat
+    private fun startVibration(pattern: LongArray = longArrayOf(0, 1000, 1000)) {
         vibrator = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
             val mgr = getSystemService(Context.VIBRATOR_MANAGER_SERVICE) as VibratorManager
             mgr.defaultVibrator
@@ -284,8 +338,6 @@ class WearRingingService : Service() {
 
         if (vibrator?.hasVibrator() != true) return
 
-        // Same pattern as phone: wait 0ms, vibrate 1s, sleep 1s, repeat
-        val pattern = longArrayOf(0, 1000, 1000)
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
             vibrator?.vibrate(VibrationEffect.createWaveform(pattern, 1))
         } else {
@@ -72,4 +72,16 @@ class NextAlarmSelectorTest {
 
         assertNull(next)
     }
+
+    @Test
+    fun `skips timers mirrored from the phone`() {
+        val alarms = listOf(
+            WatchAlarm(id = 4_000_001, hour = 12, minute = 5, label = "Nap", enabled = true, timer = true),
+            WatchAlarm(id = 1, hour = 18, minute = 0, label = "Dinner", enabled = true),
+        )
+
+        val next = findNextUpcomingAlarm(alarms, LocalDateTime.of(2026, 2, 18, 12, 0))
+
+        assertEquals(1, next?.id)
+    }
 }
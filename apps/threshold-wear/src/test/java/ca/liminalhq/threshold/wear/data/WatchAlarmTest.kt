@@ -7,8 +7,13 @@ package ca.liminalhq.threshold.wear.data
 
 import org.json.JSONObject
 import org.junit.Assert.assertEquals
+import org.junit.Assert.assertNull
 import org.junit.Assert.assertTrue
 import org.junit.Test
+import java.time.Instant
+import java.time.LocalDate
+import java.time.LocalTime
+import java.time.ZoneId
 
 class WatchAlarmTest {
 
@@ -69,6 +74,90 @@ class WatchAlarmTest {
         assertTrue(alarm.daysOfWeek.isEmpty())
     }
 
+    @Test
+    fun `fromJson takes a follow-up alarm's time from its next trigger`() {
+        val trigger = 1_767_253_200_000L
+        val json = JSONObject("""
+            {
+                "id": 3,
+                "label": "Leave the house",
+                "enabled": true,
+                "mode": "FOLLOW_UP",
+                "fixedTime": null,
+                "windowStart": null,
+                "activeDays": [],
+                "followUp": { "alarmId": 1, "minutesAfter": 40 },
+                "nextTrigger": $trigger
+            }
+        """)
+
+        val alarm = WatchAlarm.fromJson(json)
+
+        val expected = Instant.ofEpochMilli(trigger).atZone(ZoneId.systemDefault())
+        assertEquals(expected.hour, alarm.hour)
+        assertEquals(expected.minute, alarm.minute)
+        assertEquals(trigger, alarm.nextTrigger)
+    }
+
+    @Test
+    fun `fromJson parses an interval alarm's span`() {
+        val json = JSONObject("""
+            {
+                "id": 4,
+                "label": "Medication",
+                "enabled": true,
+                "mode": "INTERVAL",
+                "fixedTime": null,
+                "windowStart": "08:00",
+                "windowEnd": "20:00",
+                "intervalMinutes": 240,
+                "activeDays": [0, 1, 2, 3, 4, 5, 6]
+            }
+        """)
+
+        val alarm = WatchAlarm.fromJson(json)
+
+        assertEquals(8, alarm.hour)
+        assertEquals(240, alarm.intervalMinutes)
+        assertEquals("20:00", alarm.windowEnd)
+        assertEquals(alarm, WatchAlarm.fromJson(alarm.toJson()))
+    }
+
+    @Test
+    fun `nextIntervalInstance steps through the span and stops at its end`() {
+        val utc = ZoneId.of("UTC")
+        val at = { time: String ->
+            LocalDate.of(2030, 1, 1).atTime(LocalTime.parse(time)).atZone(utc)
+                .toInstant().toEpochMilli()
+        }
+        val alarm = WatchAlarm(
+            id = 4, hour = 8, minute = 0, label = "", enabled = true,
+            nextTrigger = at("12:00"), intervalMinutes = 240, windowEnd = "20:00",
+        )
+
+        assertEquals(at("16:00"), alarm.nextIntervalInstance(utc))
+        assertEquals(at("20:00"), alarm.copy(nextTrigger = at("16:00")).nextIntervalInstance(utc))
+        assertNull(alarm.copy(nextTrigger = at("20:00")).nextIntervalInstance(utc))
+        assertNull(alarm.copy(intervalMinutes = null).nextIntervalInstance(utc))
+    }
+
+    @Test
+    fun `nextIntervalInstance carries an overnight span past midnight`() {
+        val utc = ZoneId.of("UTC")
+        val at = { day: Int, time: String ->
+            LocalDate.of(2030, 1, day).atTime(LocalTime.parse(time)).atZone(utc)
+                .toInstant().toEpochMilli()
+        }
+        val alarm = WatchAlarm(
+            id = 5, hour = 22, minute = 0, label = "", enabled = true,
+            nextTrigger = at(1, "22:00"), intervalMinutes = 120, windowEnd = "02:00",
+        )
+
+        assertEquals(at(2, "00:00"), alarm.nextIntervalInstance(utc))
+        assertEquals(at(2, "02:00"), alarm.copy(nextTrigger = at(2, "00:00")).nextIntervalInstance(utc))
+        assertNull(alarm.copy(nextTrigger = at(2, "02:00")).nextIntervalInstance(utc))
+    }
+
     @Test
     fun `timeDisplay formats correctly`() {
         val alarm = WatchAlarm(id = 1, hour = 7, minute = 5, label = "", enabled = true)
@@ -118,4 +207,47 @@ class WatchAlarmTest {
         assertEquals(true, json.getBoolean("enabled"))
         assertEquals(3, json.getJSONArray("daysOfWeek").length())
     }
+
+    @Test
+    fun `a timer round-trips through toJson`() {
+        val timer = WatchAlarm(
+            id = 4_000_002,
+            hour = 14,
+            minute = 30,
+            label = "Tea",
+            enabled = true,
+            nextTrigger = 1_800_000_000_000L,
+            timer = true,
+        )
+
+        assertEquals(timer, WatchAlarm.fromJson(timer.toJson()))
+    }
+
+    @Test
+    fun `group names round-trip through toJson`() {
+        val alarm = WatchAlarm(
+            id = 3,
+            hour = 6,
+            minute = 45,
+            label = "Gym",
+            enabled = true,
+            groups = listOf("Work week", "Travel"),
+        )
+
+        assertEquals(alarm, WatchAlarm.fromJson(alarm.toJson()))
+    }
+
+    @Test
+    fun `pause end round-trips through toJson`() {
+        val alarm = WatchAlarm(
+            id = 8,
+            hour = 7,
+            minute = 0,
+            label = "Work",
+            enabled = false,
+            pausedUntil = "2026-10-25",
+        )
+
+        assertEquals(alarm, WatchAlarm.fromJson(alarm.toJson()))
+    }
 }
@@ -6,6 +6,7 @@
 package ca.liminalhq.threshold.wear.service
 
 import org.junit.Assert.assertEquals
+import org.junit.Assert.assertNull
 import org.junit.Assert.assertTrue
 import org.junit.Test
 
@@ -38,6 +39,73 @@ class SyncPayloadParsingTest {
         assertTrue((action as SyncAction.ReplaceAll).alarms.isEmpty())
     }
 
+    @Test
+    fun `FullSync envelope carries the phone's timers alongside its alarms`() {
+        val json = """
+            {
+                "type": "FullSync",
+                "allAlarms": [
+                    {"id": 1, "hour": 7, "minute": 0, "label": "Morning", "enabled": true}
+                ],
+                "timers": [
+                    {"id": 3, "label": "Nap", "state": "RUNNING", "endsAt": 1800000000000},
+                    {"id": 4, "label": null, "state": "PAUSED", "endsAt": null}
+                ]
+            }
+        """
+        val action = parseSyncPayload(json) as SyncAction.ReplaceAll
+
+        assertEquals(listOf(1, 4_000_003, 4_000_004), action.alarms.map { it.id })
+        val nap = action.alarms[1]
+        assertTrue(nap.timer)
+        assertTrue(nap.enabled)
+        assertEquals(1_800_000_000_000L, nap.nextTrigger)
+        assertEquals("Nap", nap.label)
+        assertEquals("Timer", action.alarms[2].label)
+        assertTrue(!action.alarms[2].enabled)
+    }
+
+    @Test
+    fun `FullSync envelope labels alarms with the groups they're in`() {
+        val json = """
+            {
+                "type": "FullSync",
+                "allAlarms": [
+                    {"id": 1, "hour": 7, "minute": 0, "label": "Morning", "enabled": true},
+                    {"id": 2, "hour": 9, "minute": 0, "label": "Lie-in", "enabled": true}
+                ],
+                "groups": [
+                    {"id": 1, "name": "Travel", "alarmIds": [1], "revision": 4},
+                    {"id": 2, "name": "Work week", "alarmIds": [1], "revision": 5}
+                ]
+            }
+        """
+        val action = parseSyncPayload(json) as SyncAction.ReplaceAll
+
+        assertEquals(listOf("Travel", "Work week"), action.alarms[0].groups)
+        assertTrue(action.alarms[1].groups.isEmpty())
+    }
+
+    @Test
+    fun `FullSync envelope marks alarms a pause is holding off`() {
+        val json = """
+            {
+                "type": "FullSync",
+                "allAlarms": [
+                    {"id": 1, "hour": 7, "minute": 0, "label": "Morning", "enabled": false},
+                    {"id": 2, "hour": 9, "minute": 0, "label": "Lie-in", "enabled": true}
+                ],
+                "pauses": [
+                    {"id": 1, "groupId": null, "until": "2026-10-25", "resumesAt": 1793000000000, "alarmIds": [1], "revision": 6}
+                ]
+            }
+        """
+        val action = parseSyncPayload(json) as SyncAction.ReplaceAll
+
+        assertEquals("2026-10-25", action.alarms[0].pausedUntil)
+        assertNull(action.alarms[1].pausedUntil)
+    }
+
     @Test
     fun `Incremental envelope resolves to ApplyIncremental`() {
         val json = """
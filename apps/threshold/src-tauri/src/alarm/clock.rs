@@ -0,0 +1,130 @@
+// Time source for the alarm coordinator, swappable so tests can control "now"
+//
+// (c) Copyright 2026 Liminal HQ, Scott Morris
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+/// A wait on a [`Clock`], finished once the clock reads its deadline.
+pub type Sleep<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Where the coordinator reads the current time from. Everything that depends on
+/// "now" (scheduling, snooze validation, dismiss fallbacks, heal, tombstone expiry,
+/// ring timeouts) goes through this, so tests can step across midnight or a DST change
+/// on demand.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn now_millis(&self) -> i64 {
+        self.now().timestamp_millis()
+    }
+
+    /// Waits until this clock reads `deadline`; a deadline already passed doesn't wait.
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> Sleep<'_> {
+        let wait = (deadline - self.now()).to_std().unwrap_or_default();
+        Box::pin(tokio::time::sleep(wait))
+    }
+}
+
+/// The device's wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to. Sleeping on it waits out the gap on tokio's
+/// timer, which tests pause, and then moves the clock on to the deadline.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+    sleeping_until: Mutex<Vec<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+            sleeping_until: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// The earliest deadline something is sleeping until that the clock hasn't reached.
+    pub fn next_wake(&self) -> Option<DateTime<Utc>> {
+        let now = self.now();
+        let sleeping_until = self.sleeping_until.lock().unwrap();
+        sleeping_until.iter().copied().filter(|d| *d > now).min()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> Sleep<'_> {
+        let wait = (deadline - self.now()).to_std().unwrap_or_default();
+        self.sleeping_until.lock().unwrap().push(deadline);
+        Box::pin(async move {
+            tokio::time::sleep(wait).await;
+            let mut sleeping_until = self.sleeping_until.lock().unwrap();
+            if let Some(i) = sleeping_until.iter().position(|d| *d == deadline) {
+                sleeping_until.swap_remove(i);
+            }
+            let mut now = self.now.lock().unwrap();
+            *now = (*now).max(deadline);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_only_moves_when_told() {
+        let start = DateTime::from_timestamp_millis(1_900_000_000_000).unwrap();
+        let clock = ManualClock::new(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(chrono::Duration::minutes(90));
+        assert_eq!(clock.now_millis(), 1_900_000_000_000 + 90 * 60_000);
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_manual_clock_sleep_moves_it_to_the_deadline() {
+        let start = DateTime::from_timestamp_millis(1_900_000_000_000).unwrap();
+        let deadline = start + chrono::Duration::minutes(5);
+        let clock = ManualClock::new(start);
+        let slept_from = tokio::time::Instant::now();
+
+        let sleep = clock.sleep_until(deadline);
+        assert_eq!(clock.next_wake(), Some(deadline));
+        sleep.await;
+        assert_eq!(clock.now(), deadline);
+        assert_eq!(clock.next_wake(), None);
+        assert_eq!(slept_from.elapsed(), std::time::Duration::from_secs(300));
+
+        // A deadline already passed neither waits nor winds the clock back.
+        clock.sleep_until(start).await;
+        assert_eq!(clock.now(), deadline);
+    }
+}
@@ -77,6 +77,16 @@ impl AlarmDatabase {
         Ok(rev)
     }
 
+    /// Random per-install secret that seeds window sampling; created with the schema
+    /// and never changed, so sampled times stay put across launches.
+    pub async fn install_secret(&self) -> Result<String> {
+        let (secret,): (String,) = sqlx::query_as("SELECT secret FROM install_secret WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(secret)
+    }
+
     pub async fn get_all(&self) -> Result<Vec<AlarmRecord>> {
         let rows = sqlx::query_as::<_, AlarmRow>("SELECT * FROM alarms ORDER BY id")
             .fetch_all(&self.pool)
@@ -107,16 +117,68 @@ impl AlarmDatabase {
         let mode_str = match input.mode {
             AlarmMode::Fixed => "FIXED",
             AlarmMode::Window => "WINDOW",
+            AlarmMode::FixedOnce => "FIXED_ONCE",
+            AlarmMode::WindowOnce => "WINDOW_ONCE",
+            AlarmMode::Solar => "SOLAR",
+            AlarmMode::SolarWindow => "SOLAR_WINDOW",
+            AlarmMode::Interval => "INTERVAL",
+            AlarmMode::FollowUp => "FOLLOW_UP",
         };
 
+        let skip_dates_json = serde_json::to_string(&input.skip_dates.unwrap_or_default())?;
+        let window_distribution_json =
+            serde_json::to_string(&input.window_distribution.unwrap_or_default())?;
+        let dst_policy_json = serde_json::to_string(&input.dst_policy.unwrap_or_default())?;
+        let solar_json = input
+            .solar
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let pre_alarm_json = input
+            .pre_alarm
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let snooze_policy_json = input
+            .snooze_policy
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let ring_timeout_json = input
+            .ring_timeout
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let backup_alarm_json = input
+            .backup_alarm
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let follow_up_json = input
+            .follow_up
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+
         let enabled_int = if input.enabled { 1 } else { 0 };
+        let skip_holidays_int = if input.skip_holidays.unwrap_or(false) {
+            1
+        } else {
+            0
+        };
 
         if let Some(id) = input.id {
-            // Update existing
+            // Update existing; a re-saved alarm starts a fresh occurrence, so any
+            // snooze in progress ends
             sqlx::query(
                 "UPDATE alarms SET
                     label=?, enabled=?, mode=?, fixed_time=?, window_start=?,
-                    window_end=?, active_days=?, next_trigger=?, sound_uri=?, sound_title=?,
+                    window_end=?, window_distribution=?, interval_minutes=?, bedtime=?, solar=?,
+                    timezone=?,
+                    dst_policy=?, active_days=?, one_shot_date=?, recurrence_rule=?,
+                    recurrence_anchor=?, skip_dates=?, skip_holidays=?, pre_alarm=?,
+                    snooze_policy=?, snooze_count=0, snoozed_from=NULL, ring_timeout=?,
+                    backup_alarm=?, follow_up=?, next_trigger=?, sound_uri=?, sound_title=?,
                     revision=?
                 WHERE id=?",
             )
@@ -126,7 +188,23 @@ impl AlarmDatabase {
             .bind(input.fixed_time)
             .bind(input.window_start)
             .bind(input.window_end)
+            .bind(&window_distribution_json)
+            .bind(input.interval_minutes)
+            .bind(&input.bedtime)
+            .bind(&solar_json)
+            .bind(&input.timezone)
+            .bind(&dst_policy_json)
             .bind(active_days_json)
+            .bind(input.one_shot_date)
+            .bind(input.recurrence_rule)
+            .bind(input.recurrence_anchor)
+            .bind(&skip_dates_json)
+            .bind(skip_holidays_int)
+            .bind(&pre_alarm_json)
+            .bind(&snooze_policy_json)
+            .bind(&ring_timeout_json)
+            .bind(&backup_alarm_json)
+            .bind(&follow_up_json)
             .bind(next_trigger)
             .bind(input.sound_uri)
             .bind(input.sound_title)
@@ -141,8 +219,13 @@ impl AlarmDatabase {
             let result = sqlx::query(
                 "INSERT INTO alarms
                     (label, enabled, mode, fixed_time, window_start, window_end,
-                     active_days, next_trigger, sound_uri, sound_title, revision)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                     window_distribution, interval_minutes, bedtime, solar, timezone,
+                     dst_policy, active_days,
+                     one_shot_date, recurrence_rule, recurrence_anchor,
+                     skip_dates, skip_holidays, pre_alarm, snooze_policy, ring_timeout,
+                     backup_alarm, follow_up, next_trigger, sound_uri, sound_title, revision)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                        ?, ?)",
             )
             .bind(input.label)
             .bind(enabled_int)
@@ -150,7 +233,23 @@ impl AlarmDatabase {
             .bind(input.fixed_time)
             .bind(input.window_start)
             .bind(input.window_end)
+            .bind(&window_distribution_json)
+            .bind(input.interval_minutes)
+            .bind(&input.bedtime)
+            .bind(&solar_json)
+            .bind(&input.timezone)
+            .bind(&dst_policy_json)
             .bind(active_days_json)
+            .bind(input.one_shot_date)
+            .bind(input.recurrence_rule)
+            .bind(input.recurrence_anchor)
+            .bind(&skip_dates_json)
+            .bind(skip_holidays_int)
+            .bind(&pre_alarm_json)
+            .bind(&snooze_policy_json)
+            .bind(&ring_timeout_json)
+            .bind(&backup_alarm_json)
+            .bind(&follow_up_json)
             .bind(next_trigger)
             .bind(input.sound_uri)
             .bind(input.sound_title)
@@ -167,27 +266,67 @@ impl AlarmDatabase {
             .bind(id)
             .execute(&self.pool)
             .await?;
+        sqlx::query("DELETE FROM alarm_group_members WHERE alarm_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM paused_alarms WHERE alarm_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
+    /// Move the alarm on to its next occurrence, ending any snooze of the current one.
     pub async fn update_next_trigger(
         &self,
         id: i32,
         next_trigger: Option<i64>,
         revision: i64,
     ) -> Result<AlarmRecord> {
-        sqlx::query("UPDATE alarms SET next_trigger = ?, revision = ? WHERE id = ?")
-            .bind(next_trigger)
-            .bind(revision)
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            "UPDATE alarms SET next_trigger = ?, snooze_count = 0, snoozed_from = NULL, revision = ?
+            WHERE id = ?",
+        )
+        .bind(next_trigger)
+        .bind(revision)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_by_id(id).await
+    }
+
+    /// Snooze the current occurrence, first due at `occurrence`, until `snoozed_until`.
+    pub async fn record_snooze(
+        &self,
+        id: i32,
+        snoozed_until: i64,
+        occurrence: i64,
+        revision: i64,
+    ) -> Result<AlarmRecord> {
+        sqlx::query(
+            "UPDATE alarms SET next_trigger = ?, snooze_count = snooze_count + 1,
+                snoozed_from = COALESCE(snoozed_from, ?), revision = ?
+            WHERE id = ?",
+        )
+        .bind(snoozed_until)
+        .bind(occurrence)
+        .bind(revision)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
 
         self.get_by_id(id).await
     }
 
     /// Delete alarm and create tombstone
-    pub async fn delete_with_revision(&self, id: i32, revision: i64) -> Result<()> {
+    pub async fn delete_with_revision(
+        &self,
+        id: i32,
+        revision: i64,
+        deleted_at: i64,
+    ) -> Result<()> {
         let mut tx = self.pool.begin().await?;
 
         // Get label before deleting
@@ -197,11 +336,19 @@ impl AlarmDatabase {
                 .fetch_optional(&mut *tx)
                 .await?;
 
-        // Delete alarm
+        // Delete alarm, and take it out of its groups
         sqlx::query("DELETE FROM alarms WHERE id = ?")
             .bind(id)
             .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM alarm_group_members WHERE alarm_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM paused_alarms WHERE alarm_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
 
         // Create tombstone. Upserts on a repeat delete of the same id (e.g. a retried
         // watch-originated delete message) rather than erroring on the alarm_id primary key --
@@ -216,7 +363,7 @@ impl AlarmDatabase {
         )
         .bind(id)
         .bind(revision)
-        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(deleted_at)
         .bind(label.and_then(|l| l.0))
         .execute(&mut *tx)
         .await?;
@@ -225,6 +372,431 @@ impl AlarmDatabase {
         Ok(())
     }
 
+    /// Replace every holiday from `source` with `holidays` (a re-import of the same
+    /// calendar or region shouldn't accumulate stale dates).
+    pub async fn replace_holidays(&self, source: &str, holidays: &[Holiday]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM holidays WHERE source = ?")
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+
+        for holiday in holidays {
+            sqlx::query("INSERT OR IGNORE INTO holidays (date, name, source) VALUES (?, ?, ?)")
+                .bind(&holiday.date)
+                .bind(&holiday.name)
+                .bind(source)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_holidays(&self) -> Result<Vec<Holiday>> {
+        let rows: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT date, name, source FROM holidays ORDER BY date, source")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(date, name, source)| Holiday { date, name, source })
+            .collect())
+    }
+
+    /// Distinct holiday dates across all sources, for the scheduler.
+    pub async fn holiday_dates(&self) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT DISTINCT date FROM holidays ORDER BY date")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows.into_iter().map(|r| r.0).collect())
+    }
+
+    pub async fn delete_holidays(&self, source: &str) -> Result<()> {
+        sqlx::query("DELETE FROM holidays WHERE source = ?")
+            .bind(source)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Append `entry` to the history, returning the id it was given (`entry.id` is
+    /// ignored).
+    pub async fn record_history(&self, entry: &AlarmHistoryEntry) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO alarm_events
+                (alarm_id, kind, source, scheduled_trigger, actual_fired_at, occurred_at,
+                 next_trigger, sampled)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(entry.alarm_id)
+        .bind(event_kind_str(entry.kind))
+        .bind(event_source_str(entry.source))
+        .bind(entry.scheduled_trigger)
+        .bind(entry.actual_fired_at)
+        .bind(entry.occurred_at)
+        .bind(entry.next_trigger)
+        .bind(entry.sampled)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// History rows matching `query`, newest first.
+    pub async fn get_history(&self, query: &HistoryQuery) -> Result<Vec<AlarmHistoryEntry>> {
+        let rows = sqlx::query_as::<_, HistoryRow>(
+            "SELECT * FROM alarm_events
+            WHERE (?1 IS NULL OR alarm_id = ?1)
+                AND (?2 IS NULL OR occurred_at >= ?2)
+                AND (?3 IS NULL OR occurred_at <= ?3)
+            ORDER BY occurred_at DESC, id DESC
+            LIMIT ?4",
+        )
+        .bind(query.alarm_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit.map_or(-1, i64::from))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// When the occurrence first due at `scheduled_trigger` last rang, if it has.
+    pub async fn last_fired_at(
+        &self,
+        alarm_id: i32,
+        scheduled_trigger: i64,
+    ) -> Result<Option<i64>> {
+        let (fired_at,): (Option<i64>,) = sqlx::query_as(
+            "SELECT MAX(actual_fired_at) FROM alarm_events
+            WHERE alarm_id = ? AND kind = 'FIRED' AND scheduled_trigger = ?",
+        )
+        .bind(alarm_id)
+        .bind(scheduled_trigger)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(fired_at)
+    }
+
+    /// Clean up history more than `days` old as of `now` (epoch millis)
+    pub async fn cleanup_history_older_than_days(&self, days: i64, now: i64) -> Result<()> {
+        let cutoff_timestamp = now - chrono::Duration::days(days).num_milliseconds();
+
+        sqlx::query("DELETE FROM alarm_events WHERE occurred_at < ?")
+            .bind(cutoff_timestamp)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// A coordinator setting, if one has been stored under `key`.
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.0))
+    }
+
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES (?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_setting(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_timers(&self) -> Result<Vec<Timer>> {
+        let rows = sqlx::query_as::<_, TimerRow>("SELECT * FROM timers ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub async fn get_timer(&self, id: i32) -> Result<Timer> {
+        let row = sqlx::query_as::<_, TimerRow>("SELECT * FROM timers WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let row = row.ok_or_else(|| Error::Database(format!("Timer with id {} not found", id)))?;
+
+        Ok(row.into())
+    }
+
+    /// Start a new timer running until `ends_at`.
+    pub async fn insert_timer(
+        &self,
+        label: Option<&str>,
+        duration_seconds: u32,
+        ends_at: i64,
+        created_at: i64,
+        revision: i64,
+    ) -> Result<Timer> {
+        let result = sqlx::query(
+            "INSERT INTO timers (label, duration_seconds, state, ends_at, created_at, revision)
+            VALUES (?, ?, 'RUNNING', ?, ?, ?)",
+        )
+        .bind(label)
+        .bind(duration_seconds)
+        .bind(ends_at)
+        .bind(created_at)
+        .bind(revision)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_timer(result.last_insert_rowid() as i32).await
+    }
+
+    /// Set a timer running until `ends_at`, or paused with `remaining_ms` left.
+    pub async fn update_timer(
+        &self,
+        id: i32,
+        state: TimerState,
+        ends_at: Option<i64>,
+        remaining_ms: Option<i64>,
+        revision: i64,
+    ) -> Result<Timer> {
+        sqlx::query(
+            "UPDATE timers SET state = ?, ends_at = ?, remaining_ms = ?, revision = ? WHERE id = ?",
+        )
+        .bind(timer_state_str(state))
+        .bind(ends_at)
+        .bind(remaining_ms)
+        .bind(revision)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_timer(id).await
+    }
+
+    pub async fn delete_timer(&self, id: i32) -> Result<()> {
+        sqlx::query("DELETE FROM timers WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_groups(&self) -> Result<Vec<AlarmGroup>> {
+        let groups: Vec<(i32, String, i64)> =
+            sqlx::query_as("SELECT id, name, revision FROM alarm_groups ORDER BY name, id")
+                .fetch_all(&self.pool)
+                .await?;
+        let members: Vec<(i32, i32)> =
+            sqlx::query_as("SELECT group_id, alarm_id FROM alarm_group_members ORDER BY alarm_id")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(groups
+            .into_iter()
+            .map(|(id, name, revision)| AlarmGroup {
+                id,
+                name,
+                alarm_ids: members
+                    .iter()
+                    .filter(|(group_id, _)| *group_id == id)
+                    .map(|(_, alarm_id)| *alarm_id)
+                    .collect(),
+                revision,
+            })
+            .collect())
+    }
+
+    pub async fn get_group(&self, id: i32) -> Result<AlarmGroup> {
+        self.get_groups()
+            .await?
+            .into_iter()
+            .find(|g| g.id == id)
+            .ok_or_else(|| Error::Database(format!("Alarm group with id {} not found", id)))
+    }
+
+    /// Insert a group, or rename one and replace its alarms.
+    pub async fn save_group(
+        &self,
+        id: Option<i32>,
+        name: &str,
+        alarm_ids: &[i32],
+        revision: i64,
+    ) -> Result<AlarmGroup> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = match id {
+            Some(id) => {
+                sqlx::query("UPDATE alarm_groups SET name = ?, revision = ? WHERE id = ?")
+                    .bind(name)
+                    .bind(revision)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM alarm_group_members WHERE group_id = ?")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                id
+            }
+            None => sqlx::query("INSERT INTO alarm_groups (name, revision) VALUES (?, ?)")
+                .bind(name)
+                .bind(revision)
+                .execute(&mut *tx)
+                .await?
+                .last_insert_rowid() as i32,
+        };
+        for alarm_id in alarm_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO alarm_group_members (group_id, alarm_id) VALUES (?, ?)",
+            )
+            .bind(id)
+            .bind(alarm_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        self.get_group(id).await
+    }
+
+    pub async fn delete_group(&self, id: i32) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM alarm_group_members WHERE group_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM alarm_groups WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_pauses(&self) -> Result<Vec<AlarmPause>> {
+        let pauses: Vec<(i32, Option<i32>, String, i64, i64)> = sqlx::query_as(
+            "SELECT id, group_id, until, resumes_at, revision FROM alarm_pauses ORDER BY resumes_at, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let paused: Vec<(i32, i32)> =
+            sqlx::query_as("SELECT pause_id, alarm_id FROM paused_alarms ORDER BY alarm_id")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(pauses
+            .into_iter()
+            .map(|(id, group_id, until, resumes_at, revision)| AlarmPause {
+                id,
+                group_id,
+                until,
+                resumes_at,
+                alarm_ids: paused
+                    .iter()
+                    .filter(|(pause_id, _)| *pause_id == id)
+                    .map(|(_, alarm_id)| *alarm_id)
+                    .collect(),
+                revision,
+            })
+            .collect())
+    }
+
+    pub async fn get_pause(&self, id: i32) -> Result<AlarmPause> {
+        self.get_pauses()
+            .await?
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| Error::Database(format!("Pause with id {} not found", id)))
+    }
+
+    /// Start a pause, or move one's end, adding `alarm_ids` to the alarms it switched
+    /// off.
+    pub async fn save_pause(
+        &self,
+        id: Option<i32>,
+        group_id: Option<i32>,
+        until: &str,
+        resumes_at: i64,
+        alarm_ids: &[i32],
+        revision: i64,
+    ) -> Result<AlarmPause> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = match id {
+            Some(id) => {
+                sqlx::query(
+                    "UPDATE alarm_pauses SET until = ?, resumes_at = ?, revision = ? WHERE id = ?",
+                )
+                .bind(until)
+                .bind(resumes_at)
+                .bind(revision)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+                id
+            }
+            None => sqlx::query(
+                "INSERT INTO alarm_pauses (group_id, until, resumes_at, revision) VALUES (?, ?, ?, ?)",
+            )
+            .bind(group_id)
+            .bind(until)
+            .bind(resumes_at)
+            .bind(revision)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid() as i32,
+        };
+        for alarm_id in alarm_ids {
+            sqlx::query("INSERT OR IGNORE INTO paused_alarms (pause_id, alarm_id) VALUES (?, ?)")
+                .bind(id)
+                .bind(alarm_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        self.get_pause(id).await
+    }
+
+    pub async fn delete_pause(&self, id: i32) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM paused_alarms WHERE pause_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM alarm_pauses WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Get alarms changed since revision (for incremental sync)
     pub async fn get_alarms_since_revision(&self, since: i64) -> Result<Vec<AlarmRecord>> {
         let rows =
@@ -247,12 +819,9 @@ impl AlarmDatabase {
         Ok(rows.into_iter().map(|r| r.0).collect())
     }
 
-    /// Clean up old tombstones (time-based retention: 30 days)
-    pub async fn cleanup_tombstones_older_than_days(&self, days: i64) -> Result<()> {
-        let cutoff_timestamp = chrono::Utc::now()
-            .checked_sub_signed(chrono::Duration::days(days))
-            .unwrap()
-            .timestamp_millis();
+    /// Clean up tombstones more than `days` old as of `now` (epoch millis)
+    pub async fn cleanup_tombstones_older_than_days(&self, days: i64, now: i64) -> Result<()> {
+        let cutoff_timestamp = now - chrono::Duration::days(days).num_milliseconds();
 
         sqlx::query("DELETE FROM alarm_tombstones WHERE deleted_at_timestamp < ?")
             .bind(cutoff_timestamp)
@@ -298,22 +867,151 @@ impl AlarmDatabase {
                 .await?;
         }
 
-        if !Self::table_exists(pool, "state_revision").await? {
+        if !Self::column_exists(pool, "alarms", "one_shot_date").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN one_shot_date TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        for column in ["recurrence_rule", "recurrence_anchor"] {
+            if !Self::column_exists(pool, "alarms", column).await? {
+                sqlx::query(&format!("ALTER TABLE alarms ADD COLUMN {column} TEXT"))
+                    .execute(pool)
+                    .await?;
+            }
+        }
+
+        if !Self::column_exists(pool, "alarms", "skip_dates").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN skip_dates TEXT NOT NULL DEFAULT '[]'")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "skip_holidays").await? {
             sqlx::query(
-                r#"
-                    CREATE TABLE IF NOT EXISTS state_revision (
-                        id INTEGER PRIMARY KEY CHECK (id = 1),
-                        current_revision INTEGER NOT NULL DEFAULT 0
-                    )
-                "#,
+                "ALTER TABLE alarms ADD COLUMN skip_holidays INTEGER NOT NULL DEFAULT 0 CHECK(skip_holidays IN (0, 1))",
             )
             .execute(pool)
             .await?;
         }
 
-        sqlx::query("INSERT OR IGNORE INTO state_revision (id, current_revision) VALUES (1, 1)")
-            .execute(pool)
+        if !Self::column_exists(pool, "alarms", "window_distribution").await? {
+            sqlx::query(
+                r#"ALTER TABLE alarms ADD COLUMN window_distribution TEXT NOT NULL DEFAULT '{"kind":"UNIFORM"}'"#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "solar").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN solar TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "timezone").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN timezone TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "bedtime").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN bedtime TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "dst_policy").await? {
+            sqlx::query(
+                r#"ALTER TABLE alarms ADD COLUMN dst_policy TEXT NOT NULL DEFAULT '{"gap":"SKIP","overlap":"FIRE_AT_FIRST"}'"#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "snooze_policy").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN snooze_policy TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "snooze_count").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN snooze_count INTEGER NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "snoozed_from").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN snoozed_from INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "ring_timeout").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN ring_timeout TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "backup_alarm").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN backup_alarm TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "interval_minutes").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN interval_minutes INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "follow_up").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN follow_up TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::column_exists(pool, "alarms", "pre_alarm").await? {
+            sqlx::query("ALTER TABLE alarms ADD COLUMN pre_alarm TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::table_exists(pool, "state_revision").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS state_revision (
+                        id INTEGER PRIMARY KEY CHECK (id = 1),
+                        current_revision INTEGER NOT NULL DEFAULT 0
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        sqlx::query("INSERT OR IGNORE INTO state_revision (id, current_revision) VALUES (1, 1)")
+            .execute(pool)
+            .await?;
+
+        if !Self::table_exists(pool, "install_secret").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS install_secret (
+                        id INTEGER PRIMARY KEY CHECK (id = 1),
+                        secret TEXT NOT NULL
+                    )
+                "#,
+            )
+            .execute(pool)
             .await?;
+        }
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO install_secret (id, secret) VALUES (1, lower(hex(randomblob(16))))",
+        )
+        .execute(pool)
+        .await?;
 
         if !Self::table_exists(pool, "alarm_tombstones").await? {
             sqlx::query(
@@ -330,12 +1028,146 @@ impl AlarmDatabase {
             .await?;
         }
 
+        if !Self::table_exists(pool, "holidays").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS holidays (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        date TEXT NOT NULL,
+                        name TEXT NOT NULL,
+                        source TEXT NOT NULL,
+                        UNIQUE(date, source)
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "alarm_events").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS alarm_events (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        alarm_id INTEGER NOT NULL,
+                        kind TEXT NOT NULL,
+                        source TEXT NOT NULL,
+                        scheduled_trigger INTEGER,
+                        actual_fired_at INTEGER,
+                        occurred_at INTEGER NOT NULL,
+                        next_trigger INTEGER
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::column_exists(pool, "alarm_events", "sampled").await? {
+            sqlx::query("ALTER TABLE alarm_events ADD COLUMN sampled INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        if !Self::table_exists(pool, "settings").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS settings (
+                        key TEXT PRIMARY KEY,
+                        value TEXT NOT NULL
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "timers").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS timers (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        label TEXT,
+                        duration_seconds INTEGER NOT NULL,
+                        state TEXT NOT NULL,
+                        ends_at INTEGER,
+                        remaining_ms INTEGER,
+                        created_at INTEGER NOT NULL,
+                        revision INTEGER NOT NULL
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "alarm_groups").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS alarm_groups (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        name TEXT NOT NULL UNIQUE,
+                        revision INTEGER NOT NULL
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "alarm_group_members").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS alarm_group_members (
+                        group_id INTEGER NOT NULL,
+                        alarm_id INTEGER NOT NULL,
+                        PRIMARY KEY (group_id, alarm_id)
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "alarm_pauses").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS alarm_pauses (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        group_id INTEGER,
+                        until TEXT NOT NULL,
+                        resumes_at INTEGER NOT NULL,
+                        revision INTEGER NOT NULL
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        if !Self::table_exists(pool, "paused_alarms").await? {
+            sqlx::query(
+                r#"
+                    CREATE TABLE IF NOT EXISTS paused_alarms (
+                        pause_id INTEGER NOT NULL,
+                        alarm_id INTEGER NOT NULL,
+                        PRIMARY KEY (pause_id, alarm_id)
+                    )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_alarms_revision ON alarms(revision)")
             .execute(pool)
             .await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_tombstones_revision ON alarm_tombstones(deleted_at_revision)")
             .execute(pool)
             .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_alarm_events_alarm ON alarm_events(alarm_id, occurred_at)")
+            .execute(pool)
+            .await?;
 
         Ok(())
     }
@@ -373,8 +1205,26 @@ struct AlarmRow {
     fixed_time: Option<String>,
     window_start: Option<String>,
     window_end: Option<String>,
+    window_distribution: String,
+    interval_minutes: Option<i64>,
+    bedtime: Option<String>,
+    solar: Option<String>,
+    timezone: Option<String>,
+    dst_policy: String,
     active_days: String,
+    one_shot_date: Option<String>,
+    recurrence_rule: Option<String>,
+    recurrence_anchor: Option<String>,
+    skip_dates: String,
+    skip_holidays: i32,
+    pre_alarm: Option<String>,
+    snooze_policy: Option<String>,
+    snooze_count: i64,
+    ring_timeout: Option<String>,
+    backup_alarm: Option<String>,
+    follow_up: Option<String>,
     next_trigger: Option<i64>,
+    snoozed_from: Option<i64>,
     sound_uri: Option<String>,
     sound_title: Option<String>,
     revision: i64,
@@ -385,6 +1235,12 @@ impl From<AlarmRow> for AlarmRecord {
         let mode = match row.mode.as_str() {
             "FIXED" => AlarmMode::Fixed,
             "WINDOW" => AlarmMode::Window,
+            "FIXED_ONCE" => AlarmMode::FixedOnce,
+            "WINDOW_ONCE" => AlarmMode::WindowOnce,
+            "SOLAR" => AlarmMode::Solar,
+            "SOLAR_WINDOW" => AlarmMode::SolarWindow,
+            "INTERVAL" => AlarmMode::Interval,
+            "FOLLOW_UP" => AlarmMode::FollowUp,
             _ => {
                 log::warn!(
                     "Invalid alarm mode '{}' for alarm {}, defaulting to FIXED",
@@ -404,6 +1260,106 @@ impl From<AlarmRow> for AlarmRecord {
             vec![]
         });
 
+        let window_distribution: WindowDistribution =
+            serde_json::from_str(&row.window_distribution).unwrap_or_else(|e| {
+                log::warn!(
+                    "Failed to parse window_distribution for alarm {}: {}, using uniform",
+                    row.id,
+                    e
+                );
+                WindowDistribution::Uniform
+            });
+
+        let dst_policy: DstPolicy = serde_json::from_str(&row.dst_policy).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse dst_policy for alarm {}: {}, using default",
+                row.id,
+                e
+            );
+            DstPolicy::default()
+        });
+
+        let solar = row.solar.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse solar for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let pre_alarm = row.pre_alarm.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse pre_alarm for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let snooze_policy = row.snooze_policy.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse snooze_policy for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let ring_timeout = row.ring_timeout.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse ring_timeout for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let backup_alarm = row.backup_alarm.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse backup_alarm for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let follow_up = row.follow_up.as_deref().and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| {
+                    log::warn!(
+                        "Failed to parse follow_up for alarm {}: {}, ignoring",
+                        row.id,
+                        e
+                    );
+                })
+                .ok()
+        });
+
+        let skip_dates: Vec<String> = serde_json::from_str(&row.skip_dates).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse skip_dates for alarm {}: {}, using empty array",
+                row.id,
+                e
+            );
+            vec![]
+        });
+
         Self {
             id: row.id,
             label: row.label,
@@ -412,8 +1368,26 @@ impl From<AlarmRow> for AlarmRecord {
             fixed_time: row.fixed_time,
             window_start: row.window_start,
             window_end: row.window_end,
+            window_distribution,
+            interval_minutes: row.interval_minutes.map(|m| m.max(0) as u32),
+            bedtime: row.bedtime,
+            solar,
+            timezone: row.timezone,
+            dst_policy,
             active_days,
+            one_shot_date: row.one_shot_date,
+            recurrence_rule: row.recurrence_rule,
+            recurrence_anchor: row.recurrence_anchor,
+            skip_dates,
+            skip_holidays: row.skip_holidays != 0,
+            pre_alarm,
+            snooze_policy,
+            snooze_count: row.snooze_count.max(0) as u32,
+            ring_timeout,
+            backup_alarm,
+            follow_up,
             next_trigger: row.next_trigger,
+            snoozed_from: row.snoozed_from,
             sound_uri: row.sound_uri,
             sound_title: row.sound_title,
             revision: row.revision,
@@ -421,6 +1395,134 @@ impl From<AlarmRow> for AlarmRecord {
     }
 }
 
+fn event_kind_str(kind: AlarmEventKind) -> &'static str {
+    match kind {
+        AlarmEventKind::Fired => "FIRED",
+        AlarmEventKind::Snoozed => "SNOOZED",
+        AlarmEventKind::Dismissed => "DISMISSED",
+        AlarmEventKind::Missed => "MISSED",
+    }
+}
+
+fn event_source_str(source: AlarmEventSource) -> &'static str {
+    match source {
+        AlarmEventSource::PhoneUi => "PHONE_UI",
+        AlarmEventSource::Notification => "NOTIFICATION",
+        AlarmEventSource::Watch => "WATCH",
+        AlarmEventSource::System => "SYSTEM",
+        AlarmEventSource::Unattended => "UNATTENDED",
+    }
+}
+
+// Helper struct for deserializing history rows
+#[derive(sqlx::FromRow)]
+struct HistoryRow {
+    id: i64,
+    alarm_id: i32,
+    kind: String,
+    source: String,
+    scheduled_trigger: Option<i64>,
+    actual_fired_at: Option<i64>,
+    occurred_at: i64,
+    next_trigger: Option<i64>,
+    sampled: Option<i32>,
+}
+
+impl From<HistoryRow> for AlarmHistoryEntry {
+    fn from(row: HistoryRow) -> Self {
+        let kind = match row.kind.as_str() {
+            "FIRED" => AlarmEventKind::Fired,
+            "SNOOZED" => AlarmEventKind::Snoozed,
+            "DISMISSED" => AlarmEventKind::Dismissed,
+            "MISSED" => AlarmEventKind::Missed,
+            _ => {
+                log::warn!(
+                    "Invalid event kind '{}' for history row {}, defaulting to FIRED",
+                    row.kind,
+                    row.id
+                );
+                AlarmEventKind::Fired
+            }
+        };
+
+        let source = match row.source.as_str() {
+            "PHONE_UI" => AlarmEventSource::PhoneUi,
+            "NOTIFICATION" => AlarmEventSource::Notification,
+            "WATCH" => AlarmEventSource::Watch,
+            "SYSTEM" => AlarmEventSource::System,
+            "UNATTENDED" => AlarmEventSource::Unattended,
+            _ => {
+                log::warn!(
+                    "Invalid event source '{}' for history row {}, defaulting to SYSTEM",
+                    row.source,
+                    row.id
+                );
+                AlarmEventSource::System
+            }
+        };
+
+        AlarmHistoryEntry {
+            id: row.id,
+            alarm_id: row.alarm_id,
+            kind,
+            source,
+            scheduled_trigger: row.scheduled_trigger,
+            actual_fired_at: row.actual_fired_at,
+            occurred_at: row.occurred_at,
+            next_trigger: row.next_trigger,
+            sampled: row.sampled.map(|s| s != 0),
+        }
+    }
+}
+
+fn timer_state_str(state: TimerState) -> &'static str {
+    match state {
+        TimerState::Running => "RUNNING",
+        TimerState::Paused => "PAUSED",
+    }
+}
+
+// Helper struct for deserializing timer rows
+#[derive(sqlx::FromRow)]
+struct TimerRow {
+    id: i32,
+    label: Option<String>,
+    duration_seconds: i64,
+    state: String,
+    ends_at: Option<i64>,
+    remaining_ms: Option<i64>,
+    created_at: i64,
+    revision: i64,
+}
+
+impl From<TimerRow> for Timer {
+    fn from(row: TimerRow) -> Self {
+        let state = match row.state.as_str() {
+            "RUNNING" => TimerState::Running,
+            "PAUSED" => TimerState::Paused,
+            _ => {
+                log::warn!(
+                    "Invalid timer state '{}' for timer {}, defaulting to PAUSED",
+                    row.state,
+                    row.id
+                );
+                TimerState::Paused
+            }
+        };
+
+        Timer {
+            id: row.id,
+            label: row.label,
+            duration_seconds: row.duration_seconds.max(0) as u32,
+            state,
+            ends_at: row.ends_at,
+            remaining_ms: row.remaining_ms,
+            created_at: row.created_at,
+            revision: row.revision,
+        }
+    }
+}
+
 /// Returns database migrations for use with tauri-plugin-sql.
 /// These should be registered during app setup using:
 /// ```rust,ignore
@@ -479,17 +1581,224 @@ pub fn migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
-    ]
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use sqlx::sqlite::SqlitePoolOptions;
+        Migration {
+            version: 3,
+            description: "add_one_shot_date",
+            sql: "ALTER TABLE alarms ADD COLUMN one_shot_date TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "add_recurrence_rule",
+            sql: r#"
+                ALTER TABLE alarms ADD COLUMN recurrence_rule TEXT;
+                ALTER TABLE alarms ADD COLUMN recurrence_anchor TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "add_skip_dates",
+            sql: "ALTER TABLE alarms ADD COLUMN skip_dates TEXT NOT NULL DEFAULT '[]';",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_holidays",
+            sql: r#"
+                ALTER TABLE alarms ADD COLUMN skip_holidays INTEGER NOT NULL DEFAULT 0 CHECK(skip_holidays IN (0, 1));
 
-    /// Helper to create an in-memory test database with migrations applied
-    async fn setup_test_db() -> AlarmDatabase {
-        let pool = SqlitePoolOptions::new()
+                CREATE TABLE IF NOT EXISTS holidays (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    date TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    source TEXT NOT NULL,
+                    UNIQUE(date, source)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_install_secret",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS install_secret (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    secret TEXT NOT NULL
+                );
+
+                INSERT OR IGNORE INTO install_secret (id, secret) VALUES (1, lower(hex(randomblob(16))));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_window_distribution",
+            sql: r#"ALTER TABLE alarms ADD COLUMN window_distribution TEXT NOT NULL DEFAULT '{"kind":"UNIFORM"}';"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_solar",
+            sql: "ALTER TABLE alarms ADD COLUMN solar TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_timezone",
+            sql: "ALTER TABLE alarms ADD COLUMN timezone TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add_bedtime",
+            sql: "ALTER TABLE alarms ADD COLUMN bedtime TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add_dst_policy",
+            sql: r#"ALTER TABLE alarms ADD COLUMN dst_policy TEXT NOT NULL DEFAULT '{"gap":"SKIP","overlap":"FIRE_AT_FIRST"}';"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "add_pre_alarm",
+            sql: "ALTER TABLE alarms ADD COLUMN pre_alarm TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_snooze_policy",
+            sql: r#"
+                ALTER TABLE alarms ADD COLUMN snooze_policy TEXT;
+                ALTER TABLE alarms ADD COLUMN snooze_count INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE alarms ADD COLUMN snoozed_from INTEGER;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "add_alarm_events",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS alarm_events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    alarm_id INTEGER NOT NULL,
+                    kind TEXT NOT NULL,
+                    source TEXT NOT NULL,
+                    scheduled_trigger INTEGER,
+                    actual_fired_at INTEGER,
+                    occurred_at INTEGER NOT NULL,
+                    next_trigger INTEGER
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_alarm_events_alarm ON alarm_events(alarm_id, occurred_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "add_settings",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS settings (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_ring_timeout",
+            sql: "ALTER TABLE alarms ADD COLUMN ring_timeout TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_backup_alarm",
+            sql: "ALTER TABLE alarms ADD COLUMN backup_alarm TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add_follow_up",
+            sql: "ALTER TABLE alarms ADD COLUMN follow_up TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add_interval_minutes",
+            sql: "ALTER TABLE alarms ADD COLUMN interval_minutes INTEGER;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "add_timers",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS timers (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    label TEXT,
+                    duration_seconds INTEGER NOT NULL,
+                    state TEXT NOT NULL,
+                    ends_at INTEGER,
+                    remaining_ms INTEGER,
+                    created_at INTEGER NOT NULL,
+                    revision INTEGER NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "add_alarm_groups",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS alarm_groups (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    revision INTEGER NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS alarm_group_members (
+                    group_id INTEGER NOT NULL,
+                    alarm_id INTEGER NOT NULL,
+                    PRIMARY KEY (group_id, alarm_id)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "add_alarm_pauses",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS alarm_pauses (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    group_id INTEGER,
+                    until TEXT NOT NULL,
+                    resumes_at INTEGER NOT NULL,
+                    revision INTEGER NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS paused_alarms (
+                    pause_id INTEGER NOT NULL,
+                    alarm_id INTEGER NOT NULL,
+                    PRIMARY KEY (pause_id, alarm_id)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 24,
+            description: "add_alarm_event_sampled",
+            sql: "ALTER TABLE alarm_events ADD COLUMN sampled INTEGER;",
+            kind: MigrationKind::Up,
+        },
+    ]
+}
+
+#[cfg(test)]
+impl AlarmDatabase {
+    /// An in-memory database with every migration applied, for tests here and
+    /// in the coordinator.
+    pub(crate) async fn in_memory() -> Self {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
             .max_connections(1)
             .connect("sqlite::memory:")
             .await
@@ -505,6 +1814,16 @@ mod tests {
 
         AlarmDatabase { pool }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper to create an in-memory test database with migrations applied
+    async fn setup_test_db() -> AlarmDatabase {
+        AlarmDatabase::in_memory().await
+    }
 
     #[tokio::test]
     async fn test_save_new_alarm() {
@@ -521,6 +1840,7 @@ mod tests {
             active_days: vec![1, 2, 3, 4, 5],
             sound_uri: None,
             sound_title: None,
+            ..Default::default()
         };
 
         let result = db.save(input.clone(), Some(1234567890), 1).await.unwrap();
@@ -551,6 +1871,7 @@ mod tests {
             active_days: vec![1, 2, 3],
             sound_uri: None,
             sound_title: None,
+            ..Default::default()
         };
         let created = db.save(input, None, 1).await.unwrap();
 
@@ -566,6 +1887,7 @@ mod tests {
             active_days: vec![0, 6],
             sound_uri: Some("custom.mp3".to_string()),
             sound_title: Some("Custom Sound".to_string()),
+            ..Default::default()
         };
         let updated = db.save(update_input, Some(9876543210), 2).await.unwrap();
 
@@ -605,6 +1927,7 @@ mod tests {
                 active_days: vec![i],
                 sound_uri: None,
                 sound_title: None,
+                ..Default::default()
             };
             db.save(input, None, i as i64).await.unwrap();
         }
@@ -617,221 +1940,838 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_get_by_id_success() {
+    async fn test_get_by_id_success() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            id: None,
+            label: Some("Test Alarm".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1, 2, 3],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let created = db.save(input, None, 1).await.unwrap();
+
+        let fetched = db.get_by_id(created.id).await.unwrap();
+        assert_eq!(fetched.id, created.id);
+        assert_eq!(fetched.label, Some("Test Alarm".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_not_found() {
+        let db = setup_test_db().await;
+
+        let result = db.get_by_id(999).await;
+        assert!(result.is_err());
+
+        if let Err(Error::Database(msg)) = result {
+            assert!(msg.contains("not found"));
+        } else {
+            panic!("Expected Database error with 'not found' message");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_alarm() {
+        let db = setup_test_db().await;
+
+        // Create an alarm
+        let input = AlarmInput {
+            id: None,
+            label: Some("To Delete".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let created = db.save(input, None, 1).await.unwrap();
+
+        // Delete it
+        db.delete(created.id).await.unwrap();
+
+        // Verify it's gone
+        let result = db.get_by_id(created.id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_boolean_conversion() {
+        let db = setup_test_db().await;
+
+        // Test enabled = true
+        let input_enabled = AlarmInput {
+            id: None,
+            label: Some("Enabled".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let enabled_alarm = db.save(input_enabled, None, 1).await.unwrap();
+        assert!(enabled_alarm.enabled);
+
+        // Test enabled = false
+        let input_disabled = AlarmInput {
+            id: None,
+            label: Some("Disabled".to_string()),
+            enabled: false,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let disabled_alarm = db.save(input_disabled, None, 2).await.unwrap();
+        assert!(!disabled_alarm.enabled);
+
+        // Verify by fetching
+        let fetched_disabled = db.get_by_id(disabled_alarm.id).await.unwrap();
+        assert!(!fetched_disabled.enabled);
+    }
+
+    #[tokio::test]
+    async fn test_alarm_mode_serialization() {
+        let db = setup_test_db().await;
+
+        // Test FIXED mode
+        let fixed_input = AlarmInput {
+            id: None,
+            label: Some("Fixed".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let fixed_alarm = db.save(fixed_input, None, 1).await.unwrap();
+        assert_eq!(fixed_alarm.mode, AlarmMode::Fixed);
+
+        // Test WINDOW mode
+        let window_input = AlarmInput {
+            id: None,
+            label: Some("Window".to_string()),
+            enabled: true,
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("07:00".to_string()),
+            window_end: Some("08:00".to_string()),
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let window_alarm = db.save(window_input, None, 2).await.unwrap();
+        assert_eq!(window_alarm.mode, AlarmMode::Window);
+    }
+
+    #[tokio::test]
+    async fn test_active_days_json_serialization() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            id: None,
+            label: Some("Test".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![0, 2, 4, 6],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+
+        assert_eq!(alarm.active_days, vec![0, 2, 4, 6]);
+
+        // Verify by fetching
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(fetched.active_days, vec![0, 2, 4, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_empty_active_days() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            id: None,
+            label: Some("No Days".to_string()),
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+
+        assert_eq!(alarm.active_days, Vec::<i32>::new());
+
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(fetched.active_days, Vec::<i32>::new());
+    }
+
+    #[tokio::test]
+    async fn test_null_optional_fields() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            id: None,
+            label: None,
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".to_string()),
+            window_start: None,
+            window_end: None,
+            active_days: vec![1],
+            sound_uri: None,
+            sound_title: None,
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+
+        assert_eq!(alarm.label, None);
+        assert_eq!(alarm.sound_uri, None);
+        assert_eq!(alarm.sound_title, None);
+        assert_eq!(alarm.next_trigger, None);
+
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(fetched.label, None);
+    }
+
+    #[tokio::test]
+    async fn test_one_shot_mode_and_date_round_trip() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            label: Some("Dentist".to_string()),
+            mode: AlarmMode::WindowOnce,
+            fixed_time: None,
+            window_start: Some("07:00".to_string()),
+            window_end: Some("07:30".to_string()),
+            active_days: vec![],
+            one_shot_date: Some("2026-11-03".to_string()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(fetched.mode, AlarmMode::WindowOnce);
+        assert_eq!(fetched.one_shot_date, Some("2026-11-03".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_recurrence_rule_round_trip() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            label: Some("Custody week".to_string()),
+            active_days: vec![],
+            recurrence_rule: Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TU,WE".to_string()),
+            recurrence_anchor: Some("2026-01-05".to_string()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(
+            fetched.recurrence_rule,
+            Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TU,WE".to_string())
+        );
+        assert_eq!(fetched.recurrence_anchor, Some("2026-01-05".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_skip_dates_round_trip() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            skip_dates: Some(vec!["2026-12-25".to_string(), "2026-12-26".to_string()]),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(alarm.skip_dates, vec!["2026-12-25", "2026-12-26"]);
+
+        // An alarm saved without skip dates reads back an empty set.
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert!(plain.skip_dates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_skip_holidays_round_trip() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            skip_holidays: Some(true),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert!(alarm.skip_holidays);
+
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert!(!plain.skip_holidays);
+    }
+
+    #[tokio::test]
+    async fn test_replace_holidays_by_source() {
+        let db = setup_test_db().await;
+        let holiday = |date: &str, source: &str| Holiday {
+            date: date.to_string(),
+            name: "Day off".to_string(),
+            source: source.to_string(),
+        };
+
+        db.replace_holidays(
+            "region:CA",
+            &[
+                holiday("2026-07-01", "region:CA"),
+                holiday("2026-12-25", "region:CA"),
+            ],
+        )
+        .await
+        .unwrap();
+        db.replace_holidays("ics:work", &[holiday("2026-12-25", "ics:work")])
+            .await
+            .unwrap();
+
+        // Re-importing a source drops its old dates but leaves other sources alone.
+        db.replace_holidays("region:CA", &[holiday("2027-07-01", "region:CA")])
+            .await
+            .unwrap();
+
+        let all = db.get_holidays().await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(
+            db.holiday_dates().await.unwrap(),
+            vec!["2026-12-25", "2027-07-01"]
+        );
+
+        db.delete_holidays("ics:work").await.unwrap();
+        assert_eq!(db.holiday_dates().await.unwrap(), vec!["2027-07-01"]);
+    }
+
+    #[tokio::test]
+    async fn test_install_secret_is_stable() {
+        let db = setup_test_db().await;
+
+        let secret = db.install_secret().await.unwrap();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(db.install_secret().await.unwrap(), secret);
+    }
+
+    #[tokio::test]
+    async fn test_window_distribution_round_trip() {
+        let db = setup_test_db().await;
+
+        let distribution = WindowDistribution::TruncatedNormal {
+            target: "06:45".to_string(),
+            std_dev_minutes: 7.5,
+        };
+        let input = AlarmInput {
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("06:30".to_string()),
+            window_end: Some("07:00".to_string()),
+            window_distribution: Some(distribution.clone()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().window_distribution,
+            distribution
+        );
+
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert_eq!(plain.window_distribution, WindowDistribution::Uniform);
+    }
+
+    #[tokio::test]
+    async fn test_solar_alarm_round_trip() {
+        let db = setup_test_db().await;
+
+        let solar = SolarSchedule {
+            event: SolarEvent::CivilDawn,
+            latitude: 45.4215,
+            longitude: -75.6972,
+            offset_minutes: -20,
+            window_minutes: Some(20),
+        };
+        let input = AlarmInput {
+            mode: AlarmMode::SolarWindow,
+            fixed_time: None,
+            solar: Some(solar.clone()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        let fetched = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(fetched.mode, AlarmMode::SolarWindow);
+        assert_eq!(fetched.solar, Some(solar));
+
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert_eq!(plain.solar, None);
+    }
+
+    #[tokio::test]
+    async fn test_timezone_round_trip() {
+        let db = setup_test_db().await;
+
+        let input = AlarmInput {
+            timezone: Some("Europe/London".to_string()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().timezone,
+            Some("Europe/London".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dst_policy_round_trip() {
+        let db = setup_test_db().await;
+
+        let policy = DstPolicy {
+            gap: DstGapPolicy::ShiftForward,
+            overlap: DstOverlapPolicy::FireAtSecond,
+        };
+        let input = AlarmInput {
+            dst_policy: Some(policy),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(db.get_by_id(alarm.id).await.unwrap().dst_policy, policy);
+
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert_eq!(plain.dst_policy, DstPolicy::default());
+    }
+
+    #[tokio::test]
+    async fn test_bedtime_round_trip() {
         let db = setup_test_db().await;
 
         let input = AlarmInput {
-            id: None,
-            label: Some("Test Alarm".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1, 2, 3],
-            sound_uri: None,
-            sound_title: None,
+            mode: AlarmMode::Window,
+            window_start: Some("06:00".to_string()),
+            window_end: Some("07:00".to_string()),
+            window_distribution: Some(WindowDistribution::SleepCycles),
+            bedtime: Some("22:45".to_string()),
+            ..Default::default()
         };
-        let created = db.save(input, None, 1).await.unwrap();
-
-        let fetched = db.get_by_id(created.id).await.unwrap();
-        assert_eq!(fetched.id, created.id);
-        assert_eq!(fetched.label, Some("Test Alarm".to_string()));
+        let alarm = db.save(input, None, 1).await.unwrap();
+        let stored = db.get_by_id(alarm.id).await.unwrap();
+        assert_eq!(stored.window_distribution, WindowDistribution::SleepCycles);
+        assert_eq!(stored.bedtime, Some("22:45".to_string()));
     }
 
     #[tokio::test]
-    async fn test_get_by_id_not_found() {
+    async fn test_pre_alarm_round_trip() {
         let db = setup_test_db().await;
 
-        let result = db.get_by_id(999).await;
-        assert!(result.is_err());
+        let pre_alarm = PreAlarm {
+            minutes_before: 15,
+            sound_uri: Some("content://media/internal/audio/media/12".to_string()),
+            sound_title: Some("Birdsong".to_string()),
+            volume_percent: 30,
+            ring_seconds: 60,
+            skip_main_on_dismiss: true,
+        };
+        let input = AlarmInput {
+            pre_alarm: Some(pre_alarm.clone()),
+            ..Default::default()
+        };
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().pre_alarm,
+            Some(pre_alarm)
+        );
 
-        if let Err(Error::Database(msg)) = result {
-            assert!(msg.contains("not found"));
-        } else {
-            panic!("Expected Database error with 'not found' message");
-        }
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert_eq!(plain.pre_alarm, None);
     }
 
     #[tokio::test]
-    async fn test_delete_alarm() {
+    async fn test_snooze_policy_round_trip() {
         let db = setup_test_db().await;
 
-        // Create an alarm
+        let policy = SnoozePolicy {
+            max_snoozes: Some(3),
+            intervals_minutes: vec![10, 5, 3],
+            no_snooze_after: Some("08:00".to_string()),
+        };
         let input = AlarmInput {
-            id: None,
-            label: Some("To Delete".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+            snooze_policy: Some(policy.clone()),
+            ..Default::default()
         };
-        let created = db.save(input, None, 1).await.unwrap();
-
-        // Delete it
-        db.delete(created.id).await.unwrap();
-
-        // Verify it's gone
-        let result = db.get_by_id(created.id).await;
-        assert!(result.is_err());
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().snooze_policy,
+            Some(policy)
+        );
     }
 
     #[tokio::test]
-    async fn test_boolean_conversion() {
+    async fn test_ring_timeout_round_trip() {
         let db = setup_test_db().await;
 
-        // Test enabled = true
-        let input_enabled = AlarmInput {
-            id: None,
-            label: Some("Enabled".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+        let timeout = RingTimeout {
+            minutes: 10,
+            action: RingTimeoutAction::Snooze,
+            escalate_to_watch: true,
         };
-        let enabled_alarm = db.save(input_enabled, None, 1).await.unwrap();
-        assert!(enabled_alarm.enabled);
-
-        // Test enabled = false
-        let input_disabled = AlarmInput {
-            id: None,
-            label: Some("Disabled".to_string()),
-            enabled: false,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+        let input = AlarmInput {
+            ring_timeout: Some(timeout.clone()),
+            ..Default::default()
         };
-        let disabled_alarm = db.save(input_disabled, None, 2).await.unwrap();
-        assert!(!disabled_alarm.enabled);
-
-        // Verify by fetching
-        let fetched_disabled = db.get_by_id(disabled_alarm.id).await.unwrap();
-        assert!(!fetched_disabled.enabled);
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().ring_timeout,
+            Some(timeout)
+        );
     }
 
     #[tokio::test]
-    async fn test_alarm_mode_serialization() {
+    async fn test_backup_alarm_round_trip() {
         let db = setup_test_db().await;
 
-        // Test FIXED mode
-        let fixed_input = AlarmInput {
-            id: None,
-            label: Some("Fixed".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+        let backup = BackupAlarm {
+            minutes_after: 5,
+            sound_uri: Some("content://media/internal/audio/media/12".into()),
+            sound_title: Some("Klaxon".into()),
+            watch_only: true,
         };
-        let fixed_alarm = db.save(fixed_input, None, 1).await.unwrap();
-        assert_eq!(fixed_alarm.mode, AlarmMode::Fixed);
-
-        // Test WINDOW mode
-        let window_input = AlarmInput {
-            id: None,
-            label: Some("Window".to_string()),
-            enabled: true,
-            mode: AlarmMode::Window,
-            fixed_time: None,
-            window_start: Some("07:00".to_string()),
-            window_end: Some("08:00".to_string()),
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+        let input = AlarmInput {
+            backup_alarm: Some(backup.clone()),
+            ..Default::default()
         };
-        let window_alarm = db.save(window_input, None, 2).await.unwrap();
-        assert_eq!(window_alarm.mode, AlarmMode::Window);
+        let alarm = db.save(input, None, 1).await.unwrap();
+        assert_eq!(
+            db.get_by_id(alarm.id).await.unwrap().backup_alarm,
+            Some(backup)
+        );
     }
 
     #[tokio::test]
-    async fn test_active_days_json_serialization() {
+    async fn test_interval_alarm_round_trip() {
         let db = setup_test_db().await;
 
         let input = AlarmInput {
-            id: None,
-            label: Some("Test".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![0, 2, 4, 6],
-            sound_uri: None,
-            sound_title: None,
+            mode: AlarmMode::Interval,
+            fixed_time: None,
+            window_start: Some("08:00".into()),
+            window_end: Some("20:00".into()),
+            interval_minutes: Some(240),
+            ..Default::default()
         };
         let alarm = db.save(input, None, 1).await.unwrap();
 
-        assert_eq!(alarm.active_days, vec![0, 2, 4, 6]);
-
-        // Verify by fetching
         let fetched = db.get_by_id(alarm.id).await.unwrap();
-        assert_eq!(fetched.active_days, vec![0, 2, 4, 6]);
+        assert_eq!(fetched.mode, AlarmMode::Interval);
+        assert_eq!(fetched.interval_minutes, Some(240));
+        assert_eq!(fetched.window_end.as_deref(), Some("20:00"));
+
+        let plain = db.save(AlarmInput::default(), None, 2).await.unwrap();
+        assert_eq!(plain.interval_minutes, None);
     }
 
     #[tokio::test]
-    async fn test_empty_active_days() {
+    async fn test_follow_up_round_trip() {
         let db = setup_test_db().await;
+        let parent = db.save(AlarmInput::default(), None, 1).await.unwrap();
 
+        let follow_up = FollowUp {
+            alarm_id: parent.id,
+            minutes_after: 40,
+        };
         let input = AlarmInput {
-            id: None,
-            label: Some("No Days".to_string()),
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
+            mode: AlarmMode::FollowUp,
+            fixed_time: None,
             active_days: vec![],
-            sound_uri: None,
-            sound_title: None,
+            follow_up: Some(follow_up.clone()),
+            ..Default::default()
         };
-        let alarm = db.save(input, None, 1).await.unwrap();
-
-        assert_eq!(alarm.active_days, Vec::<i32>::new());
+        let alarm = db.save(input, None, 2).await.unwrap();
 
         let fetched = db.get_by_id(alarm.id).await.unwrap();
-        assert_eq!(fetched.active_days, Vec::<i32>::new());
+        assert_eq!(fetched.mode, AlarmMode::FollowUp);
+        assert_eq!(fetched.follow_up, Some(follow_up));
+        assert_eq!(db.get_by_id(parent.id).await.unwrap().follow_up, None);
     }
 
     #[tokio::test]
-    async fn test_null_optional_fields() {
+    async fn test_snooze_count_covers_one_occurrence() {
         let db = setup_test_db().await;
+        let alarm = db
+            .save(AlarmInput::default(), Some(1_000), 1)
+            .await
+            .unwrap();
+        assert_eq!((alarm.snooze_count, alarm.snoozed_from), (0, None));
+
+        // Re-snoozing keeps the occurrence's original trigger
+        db.record_snooze(alarm.id, 2_000, 1_000, 2).await.unwrap();
+        let snoozed = db.record_snooze(alarm.id, 3_000, 2_000, 3).await.unwrap();
+        assert_eq!(snoozed.next_trigger, Some(3_000));
+        assert_eq!(
+            (snoozed.snooze_count, snoozed.snoozed_from),
+            (2, Some(1_000))
+        );
+
+        // Moving on to the next occurrence starts the count again
+        let next = db
+            .update_next_trigger(alarm.id, Some(90_000), 4)
+            .await
+            .unwrap();
+        assert_eq!((next.snooze_count, next.snoozed_from), (0, None));
 
-        let input = AlarmInput {
-            id: None,
-            label: None,
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".to_string()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![1],
-            sound_uri: None,
-            sound_title: None,
+        db.record_snooze(alarm.id, 95_000, 90_000, 5).await.unwrap();
+        let saved = db.save(snoozed.to_input(), Some(180_000), 6).await.unwrap();
+        assert_eq!((saved.snooze_count, saved.snoozed_from), (0, None));
+    }
+
+    fn history(
+        alarm_id: i32,
+        kind: AlarmEventKind,
+        scheduled_trigger: i64,
+        occurred_at: i64,
+    ) -> AlarmHistoryEntry {
+        AlarmHistoryEntry {
+            id: 0,
+            alarm_id,
+            kind,
+            source: AlarmEventSource::System,
+            scheduled_trigger: Some(scheduled_trigger),
+            actual_fired_at: (kind == AlarmEventKind::Fired).then_some(occurred_at),
+            occurred_at,
+            next_trigger: None,
+            sampled: Some(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_query_by_alarm_and_range() {
+        let db = setup_test_db().await;
+        db.record_history(&history(1, AlarmEventKind::Fired, 1_000, 1_000))
+            .await
+            .unwrap();
+        let snoozed = AlarmHistoryEntry {
+            source: AlarmEventSource::Watch,
+            next_trigger: Some(2_000),
+            sampled: Some(true),
+            ..history(1, AlarmEventKind::Snoozed, 1_000, 1_500)
         };
-        let alarm = db.save(input, None, 1).await.unwrap();
+        db.record_history(&snoozed).await.unwrap();
+        db.record_history(&history(2, AlarmEventKind::Missed, 1_200, 3_000))
+            .await
+            .unwrap();
+
+        // Newest first, round-tripping every column
+        let all = db.get_history(&HistoryQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].kind, AlarmEventKind::Missed);
+        assert_eq!(
+            AlarmHistoryEntry {
+                id: 0,
+                ..all[1].clone()
+            },
+            snoozed
+        );
+
+        let alarm_1 = db
+            .get_history(&HistoryQuery {
+                alarm_id: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(alarm_1.len(), 2);
+
+        // Both bounds are inclusive
+        let range = db
+            .get_history(&HistoryQuery {
+                from: Some(1_500),
+                to: Some(3_000),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(range.len(), 2);
+
+        let limited = db
+            .get_history(&HistoryQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(limited[0].occurred_at, 3_000);
+    }
 
-        assert_eq!(alarm.label, None);
-        assert_eq!(alarm.sound_uri, None);
-        assert_eq!(alarm.sound_title, None);
-        assert_eq!(alarm.next_trigger, None);
+    #[tokio::test]
+    async fn test_last_fired_at_follows_the_occurrence() {
+        let db = setup_test_db().await;
+        assert_eq!(db.last_fired_at(1, 1_000).await.unwrap(), None);
 
-        let fetched = db.get_by_id(alarm.id).await.unwrap();
-        assert_eq!(fetched.label, None);
+        // A snoozed occurrence rings again under its first trigger
+        db.record_history(&history(1, AlarmEventKind::Fired, 1_000, 1_010))
+            .await
+            .unwrap();
+        db.record_history(&history(1, AlarmEventKind::Fired, 1_000, 9_010))
+            .await
+            .unwrap();
+        db.record_history(&history(1, AlarmEventKind::Fired, 90_000, 90_010))
+            .await
+            .unwrap();
+        assert_eq!(db.last_fired_at(1, 1_000).await.unwrap(), Some(9_010));
+        assert_eq!(db.last_fired_at(2, 1_000).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_history_expires_after_retention() {
+        let db = setup_test_db().await;
+        let day = chrono::Duration::days(1).num_milliseconds();
+        db.record_history(&history(1, AlarmEventKind::Fired, 0, 0))
+            .await
+            .unwrap();
+        db.record_history(&history(1, AlarmEventKind::Fired, day, day))
+            .await
+            .unwrap();
+
+        db.cleanup_history_older_than_days(365, 365 * day + 1)
+            .await
+            .unwrap();
+        let kept = db.get_history(&HistoryQuery::default()).await.unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].occurred_at, day);
+    }
+
+    #[tokio::test]
+    async fn test_settings_round_trip() {
+        let db = setup_test_db().await;
+        assert_eq!(db.get_setting("missed_grace_minutes").await.unwrap(), None);
+
+        db.set_setting("missed_grace_minutes", "30").await.unwrap();
+        db.set_setting("missed_grace_minutes", "45").await.unwrap();
+        assert_eq!(
+            db.get_setting("missed_grace_minutes").await.unwrap(),
+            Some("45".into())
+        );
+
+        db.delete_setting("missed_grace_minutes").await.unwrap();
+        assert_eq!(db.get_setting("missed_grace_minutes").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_timer_pause_resume_and_delete() {
+        let db = setup_test_db().await;
+
+        let timer = db
+            .insert_timer(Some("Nap"), 1_200, 1_201_000, 1_000, 1)
+            .await
+            .unwrap();
+        assert_eq!(timer.label.as_deref(), Some("Nap"));
+        assert_eq!(timer.duration_seconds, 1_200);
+        assert_eq!(timer.state, TimerState::Running);
+        assert_eq!(timer.ends_at, Some(1_201_000));
+        assert_eq!(timer.remaining_ms, None);
+
+        let paused = db
+            .update_timer(timer.id, TimerState::Paused, None, Some(600_000), 2)
+            .await
+            .unwrap();
+        assert_eq!(paused.state, TimerState::Paused);
+        assert_eq!(paused.ends_at, None);
+        assert_eq!(paused.remaining_ms, Some(600_000));
+        assert_eq!(paused.revision, 2);
+        assert_eq!(paused.created_at, 1_000);
+
+        db.delete_timer(timer.id).await.unwrap();
+        assert!(db.get_timers().await.unwrap().is_empty());
+        assert!(db.get_timer(timer.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_groups_follow_their_alarms() {
+        let db = setup_test_db().await;
+        let first = db.save(AlarmInput::default(), None, 1).await.unwrap();
+        let second = db.save(AlarmInput::default(), None, 1).await.unwrap();
+
+        let group = db
+            .save_group(None, "Work week", &[second.id, first.id], 2)
+            .await
+            .unwrap();
+        assert_eq!(group.name, "Work week");
+        assert_eq!(group.alarm_ids, vec![first.id, second.id]);
+
+        let renamed = db
+            .save_group(Some(group.id), "Weekdays", &[first.id], 3)
+            .await
+            .unwrap();
+        assert_eq!(renamed.alarm_ids, vec![first.id]);
+        assert_eq!(renamed.revision, 3);
+        assert!(db.save_group(None, "Weekdays", &[], 4).await.is_err());
+
+        db.delete_with_revision(first.id, 5, 0).await.unwrap();
+        assert!(db.get_group(group.id).await.unwrap().alarm_ids.is_empty());
+
+        db.delete_group(group.id).await.unwrap();
+        assert!(db.get_groups().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pause_remembers_the_alarms_it_switched_off() {
+        let db = setup_test_db().await;
+        let first = db.save(AlarmInput::default(), None, 1).await.unwrap();
+        let second = db.save(AlarmInput::default(), None, 1).await.unwrap();
+
+        let pause = db
+            .save_pause(None, None, "2026-10-25", 1_000, &[first.id], 2)
+            .await
+            .unwrap();
+        assert_eq!(pause.group_id, None);
+        assert_eq!(pause.alarm_ids, vec![first.id]);
+
+        let extended = db
+            .save_pause(Some(pause.id), None, "2026-10-27", 2_000, &[second.id], 3)
+            .await
+            .unwrap();
+        assert_eq!(extended.until, "2026-10-27");
+        assert_eq!(extended.resumes_at, 2_000);
+        assert_eq!(extended.alarm_ids, vec![first.id, second.id]);
+
+        db.delete_with_revision(first.id, 4, 0).await.unwrap();
+        assert_eq!(
+            db.get_pause(pause.id).await.unwrap().alarm_ids,
+            vec![second.id]
+        );
+
+        db.delete_pause(pause.id).await.unwrap();
+        assert!(db.get_pauses().await.unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -959,13 +2899,50 @@ mod tests {
         let alarm = db.save(input, None, rev1).await.unwrap();
 
         let rev2 = db.next_revision().await.unwrap();
-        db.delete_with_revision(alarm.id, rev2).await.unwrap();
+        db.delete_with_revision(alarm.id, rev2, 1_000)
+            .await
+            .unwrap();
 
         let deleted_ids = db.get_deleted_since_revision(rev1).await.unwrap();
         assert_eq!(deleted_ids.len(), 1);
         assert_eq!(deleted_ids[0], alarm.id);
     }
 
+    #[tokio::test]
+    async fn test_tombstones_expire_after_retention() {
+        use crate::alarm::clock::{Clock, ManualClock};
+
+        let db = setup_test_db().await;
+        let clock =
+            ManualClock::new(chrono::DateTime::from_timestamp_millis(1_900_000_000_000).unwrap());
+
+        let rev1 = db.next_revision().await.unwrap();
+        let alarm = db.save(AlarmInput::default(), None, rev1).await.unwrap();
+        let rev2 = db.next_revision().await.unwrap();
+        db.delete_with_revision(alarm.id, rev2, clock.now_millis())
+            .await
+            .unwrap();
+
+        clock.advance(chrono::Duration::days(30) - chrono::Duration::minutes(1));
+        db.cleanup_tombstones_older_than_days(30, clock.now_millis())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_deleted_since_revision(rev1).await.unwrap(),
+            vec![alarm.id]
+        );
+
+        clock.advance(chrono::Duration::minutes(2));
+        db.cleanup_tombstones_older_than_days(30, clock.now_millis())
+            .await
+            .unwrap();
+        assert!(db
+            .get_deleted_since_revision(rev1)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_delete_with_revision_is_idempotent_on_repeat_delete() {
         // A retried watch-originated delete for an already-deleted alarm must not error --
@@ -981,10 +2958,14 @@ mod tests {
         let alarm = db.save(input, None, rev1).await.unwrap();
 
         let rev2 = db.next_revision().await.unwrap();
-        db.delete_with_revision(alarm.id, rev2).await.unwrap();
+        db.delete_with_revision(alarm.id, rev2, 1_000)
+            .await
+            .unwrap();
 
         let rev3 = db.next_revision().await.unwrap();
-        db.delete_with_revision(alarm.id, rev3).await.unwrap();
+        db.delete_with_revision(alarm.id, rev3, 2_000)
+            .await
+            .unwrap();
 
         let deleted_ids = db.get_deleted_since_revision(rev1).await.unwrap();
         assert_eq!(deleted_ids.len(), 1);
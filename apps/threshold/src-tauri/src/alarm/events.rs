@@ -3,7 +3,10 @@
 // (c) Copyright 2026 Liminal HQ, Scott Morris
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::alarm::models::{AlarmMode, AlarmRecord};
+use crate::alarm::models::{
+    AlarmGroup, AlarmMode, AlarmPause, AlarmRecord, DstPolicy, DstTransition, RingTimeoutAction,
+    Timer,
+};
 use serde::{Deserialize, Serialize};
 
 // =========================================================================
@@ -65,7 +68,9 @@ pub struct AlarmDeleted {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-/// Emitted when an alarm is scheduled with the native alarm manager.
+/// Emitted when an alarm is scheduled with the native alarm manager. A pre-alarm is
+/// scheduled by its own event, under its own native id, and so is a backup alarm, once
+/// its alarm has fired, a running timer, the bedtime reminder and a pause's end.
 pub struct AlarmScheduled {
     pub id: i32,
     pub trigger_at: i64,
@@ -73,6 +78,35 @@ pub struct AlarmScheduled {
     pub label: Option<String>,
     pub mode: AlarmMode,
     pub revision: i64,
+    /// Pre-alarms only: the alarm this one rings ahead of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_alarm_for: Option<i32>,
+    /// Pre-alarms only: percent of the alarm stream's volume to ring at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_percent: Option<u8>,
+    /// Pre-alarms only: how long to ring before stopping by itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ring_seconds: Option<u32>,
+    /// `Some(false)` when the alarm's snooze policy won't allow snoozing this ring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snooze_allowed: Option<bool>,
+    /// Backup alarms only: the alarm whose undismissed ring this one backs up. Rings
+    /// at the alarm stream's full volume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_for: Option<i32>,
+    /// Backup alarms only: `Some(true)` to ring the watch without ringing the phone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_only: Option<bool>,
+    /// Timers only: the timer this rings for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer_for: Option<i32>,
+    /// Bedtime reminders only: `Some(true)` to post a notification instead of ringing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_only: Option<bool>,
+    /// Pause ends only: `Some(true)` to report the fire, for the pause to end, without
+    /// ringing or notifying.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wake_only: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +118,21 @@ pub struct AlarmCancelled {
     pub revision: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted alongside scheduling when an upcoming occurrence's clock time is skipped or
+/// repeated by a DST change, saying what the alarm's policy does with it.
+pub struct AlarmDstAdjusted {
+    pub id: i32,
+    pub label: Option<String>,
+    pub date: String,       // "YYYY-MM-DD" occurrence affected
+    pub local_time: String, // "HH:MM" that the clocks skip or repeat
+    pub transition: DstTransition,
+    pub policy: DstPolicy,
+    pub next_trigger: Option<i64>,
+    pub revision: i64,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// Enumerates why a scheduled alarm was cancelled.
@@ -92,6 +141,7 @@ pub enum CancelReason {
     Deleted,  // User deleted alarm
     Updated,  // Rescheduling with new trigger
     Expired,  // One-time alarm fired
+    Attended, // Backup alarm's occurrence was dismissed or snoozed
 }
 
 // =========================================================================
@@ -107,21 +157,40 @@ pub struct AlarmFired {
     pub actual_fired_at: i64,
     pub label: Option<String>,
     pub revision: i64,
-    /// Snooze duration in minutes (synced from phone settings).
+    /// Snooze duration in minutes (the alarm's snooze policy, else phone settings).
     #[serde(default = "default_snooze_length")]
     pub snooze_length_minutes: i32,
+    /// Whether the alarm's snooze policy allows snoozing this ring.
+    #[serde(default = "default_snooze_allowed")]
+    pub snooze_allowed: bool,
     /// Time format preference (synced from phone settings).
     #[serde(default = "default_is_24_hour")]
     pub is_24_hour: bool,
     /// Whether the phone time format value is explicitly known.
     #[serde(default = "default_is_24_hour_known")]
     pub is_24_hour_known: bool,
+    /// Pre-alarms only: the alarm this one rings ahead of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_alarm_for: Option<i32>,
+    /// Pre-alarms only: how long it rings before stopping by itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ring_seconds: Option<u32>,
+    /// Backup alarms only: the alarm whose undismissed ring this one backs up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_for: Option<i32>,
+    /// Timers only: the timer that ran out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer_for: Option<i32>,
 }
 
 fn default_snooze_length() -> i32 {
     10
 }
 
+fn default_snooze_allowed() -> bool {
+    true
+}
+
 fn default_is_24_hour() -> bool {
     false
 }
@@ -148,6 +217,110 @@ pub struct AlarmSnoozed {
     pub id: i32,
     pub original_trigger: i64,
     pub snoozed_until: i64,
+    /// Snoozes of this occurrence so far, this one included.
+    pub snooze_count: u32,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when an alarm has rung unattended for half its ring timeout, for the watch
+/// to ring insistently.
+pub struct AlarmEscalated {
+    pub id: i32,
+    pub label: Option<String>,
+    pub times_out_at: i64, // when the ring timeout snoozes or dismisses it
+    pub action: RingTimeoutAction,
+    pub snooze_length_minutes: i32,
+    pub snooze_allowed: bool,
+    pub is_24_hour: bool,
+    pub is_24_hour_known: bool,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when an occurrence is found long past its trigger without being dismissed,
+/// once the alarm has been moved on to its next occurrence.
+pub struct AlarmMissed {
+    pub id: i32,
+    pub label: Option<String>,
+    pub trigger_at: i64,
+    pub reason: MissedReason,
+    pub next_trigger: Option<i64>,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// Enumerates why an occurrence was missed.
+pub enum MissedReason {
+    NeverDismissed, // Rang, but nobody dismissed or snoozed it
+    NeverRang,      // Came due while the app was running, but no ring was reported
+    NotRunning,     // Device was off or the app was killed from before the trigger
+}
+
+// =========================================================================
+// Timer Events
+// =========================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a timer is started, paused or resumed.
+pub struct TimerUpdated {
+    pub timer: Timer,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a timer is cancelled, dismissed after ringing, or found long run out.
+pub struct TimerRemoved {
+    pub id: i32,
+    pub label: Option<String>,
+    pub revision: i64,
+}
+
+// =========================================================================
+// Group Events
+// =========================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a group is created, renamed or has its alarms changed.
+pub struct AlarmGroupUpdated {
+    pub group: AlarmGroup,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a group is deleted. Its alarms are left as they were.
+pub struct AlarmGroupDeleted {
+    pub id: i32,
+    pub name: String,
+    pub revision: i64,
+}
+
+// =========================================================================
+// Pause Events
+// =========================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a pause starts or has its end moved.
+pub struct AlarmPauseUpdated {
+    pub pause: AlarmPause,
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Emitted when a pause ends, on its date or early.
+pub struct AlarmPauseEnded {
+    pub id: i32,
+    pub group_id: Option<i32>,
+    pub restored_ids: Vec<i32>, // alarms switched back on
     pub revision: i64,
 }
 
@@ -165,11 +338,12 @@ pub struct AlarmsBatchUpdated {
 }
 
 impl AlarmsBatchUpdated {
-    pub fn single(id: i32, revision: i64) -> Self {
+    /// A batch of one alarm, stamped with `timestamp` from the coordinator's clock.
+    pub fn single(id: i32, revision: i64, timestamp: i64) -> Self {
         Self {
             updated_ids: vec![id],
             revision,
-            timestamp: chrono::Utc::now().timestamp_millis(),
+            timestamp,
         }
     }
 }
@@ -183,6 +357,15 @@ pub struct AlarmsSyncNeeded {
     /// Pre-serialised JSON array of all alarms for wear sync.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub all_alarms_json: Option<String>,
+    /// Pre-serialised JSON array of all timers for wear sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_timers_json: Option<String>,
+    /// Pre-serialised JSON array of all alarm groups for wear sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_groups_json: Option<String>,
+    /// Pre-serialised JSON array of all pauses for wear sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_pauses_json: Option<String>,
     /// Snooze duration in minutes (from phone settings) to sync to the watch.
     #[serde(default = "default_snooze_length")]
     pub snooze_length_minutes: i32,
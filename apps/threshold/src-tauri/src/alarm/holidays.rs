@@ -0,0 +1,531 @@
+// Parses iCalendar holiday feeds and generates the bundled regional holiday sets
+//
+// (c) Copyright 2026 Liminal HQ, Scott Morris
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::alarm::{models::Holiday, recurrence::RecurrenceRule};
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// How many years ahead bundled sets and recurring .ics events are expanded on import.
+pub const IMPORT_YEARS: i32 = 5;
+
+/// How a region moves a holiday that lands on a weekend to a working day.
+#[derive(Clone, Copy)]
+enum Observance {
+    /// Saturday moves back to Friday, Sunday forward to Monday (US federal).
+    NearestWeekday,
+    /// Moves forward to the next weekday not already a holiday (CA, GB).
+    NextFreeWeekday,
+}
+
+enum HolidayRule {
+    /// A yearly RRULE, expanded from 1 January.
+    Rule(&'static str),
+    /// Days relative to Western Easter Sunday.
+    Easter(i64),
+}
+
+const CA: &[(&str, HolidayRule)] = &[
+    (
+        "New Year's Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1"),
+    ),
+    ("Good Friday", HolidayRule::Easter(-2)),
+    (
+        "Victoria Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=5;BYMONTHDAY=18,19,20,21,22,23,24;BYDAY=MO"),
+    ),
+    (
+        "Canada Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=7;BYMONTHDAY=1"),
+    ),
+    (
+        "Labour Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=9;BYDAY=1MO"),
+    ),
+    (
+        "National Day for Truth and Reconciliation",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=9;BYMONTHDAY=30"),
+    ),
+    (
+        "Thanksgiving",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=10;BYDAY=2MO"),
+    ),
+    (
+        "Remembrance Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=11;BYMONTHDAY=11"),
+    ),
+    (
+        "Christmas Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=25"),
+    ),
+    (
+        "Boxing Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=26"),
+    ),
+];
+
+/// England and Wales bank holidays.
+const GB: &[(&str, HolidayRule)] = &[
+    (
+        "New Year's Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1"),
+    ),
+    ("Good Friday", HolidayRule::Easter(-2)),
+    ("Easter Monday", HolidayRule::Easter(1)),
+    (
+        "Early May bank holiday",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=5;BYDAY=1MO"),
+    ),
+    (
+        "Spring bank holiday",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=5;BYDAY=-1MO"),
+    ),
+    (
+        "Summer bank holiday",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=8;BYDAY=-1MO"),
+    ),
+    (
+        "Christmas Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=25"),
+    ),
+    (
+        "Boxing Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=26"),
+    ),
+];
+
+/// US federal holidays.
+const US: &[(&str, HolidayRule)] = &[
+    (
+        "New Year's Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1"),
+    ),
+    (
+        "Birthday of Martin Luther King, Jr.",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=1;BYDAY=3MO"),
+    ),
+    (
+        "Washington's Birthday",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=2;BYDAY=3MO"),
+    ),
+    (
+        "Memorial Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=5;BYDAY=-1MO"),
+    ),
+    (
+        "Juneteenth",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=6;BYMONTHDAY=19"),
+    ),
+    (
+        "Independence Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=7;BYMONTHDAY=4"),
+    ),
+    (
+        "Labor Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=9;BYDAY=1MO"),
+    ),
+    (
+        "Columbus Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=10;BYDAY=2MO"),
+    ),
+    (
+        "Veterans Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=11;BYMONTHDAY=11"),
+    ),
+    (
+        "Thanksgiving Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH"),
+    ),
+    (
+        "Christmas Day",
+        HolidayRule::Rule("FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=25"),
+    ),
+];
+
+/// The observed holidays for `region` in `from_year..=to_year`, tagged with source
+/// `region:<code>`. Weekend holidays are moved to the day they're observed on, since
+/// that's the day people actually have off.
+pub fn bundled_holidays(
+    region: &str,
+    from_year: i32,
+    to_year: i32,
+) -> Result<Vec<Holiday>, String> {
+    let (rules, observance) = match region.to_ascii_uppercase().as_str() {
+        "CA" => (CA, Observance::NextFreeWeekday),
+        "GB" => (GB, Observance::NextFreeWeekday),
+        "US" => (US, Observance::NearestWeekday),
+        other => return Err(format!("No bundled holidays for region '{other}'")),
+    };
+    let source = region_source(region);
+
+    let mut holidays = Vec::new();
+    for year in from_year..=to_year {
+        let mut dates: Vec<(NaiveDate, &str)> = rules
+            .iter()
+            .filter_map(|(name, rule)| Some((rule_date(rule, year)?, *name)))
+            .collect();
+        dates.sort();
+
+        let mut taken: Vec<NaiveDate> = Vec::new();
+        for (date, name) in dates {
+            let observed = observe(date, observance, &taken);
+            taken.push(observed);
+            holidays.push(Holiday {
+                date: observed.format("%Y-%m-%d").to_string(),
+                name: name.to_string(),
+                source: source.clone(),
+            });
+        }
+    }
+    Ok(holidays)
+}
+
+/// The `holidays.source` a bundled region's dates are stored under.
+pub fn region_source(region: &str) -> String {
+    format!("region:{}", region.to_ascii_uppercase())
+}
+
+fn rule_date(rule: &HolidayRule, year: i32) -> Option<NaiveDate> {
+    match rule {
+        HolidayRule::Rule(rrule) => {
+            let anchor = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            let rule: RecurrenceRule = rrule.parse().ok()?;
+            rule.next_on_or_after(anchor, anchor)
+                .filter(|d| d.year() == year)
+        }
+        HolidayRule::Easter(offset) => Some(easter_sunday(year)? + chrono::Duration::days(*offset)),
+    }
+}
+
+fn observe(date: NaiveDate, observance: Observance, taken: &[NaiveDate]) -> NaiveDate {
+    match (observance, date.weekday()) {
+        (Observance::NearestWeekday, Weekday::Sat) => date - chrono::Duration::days(1),
+        (Observance::NearestWeekday, Weekday::Sun) => date + chrono::Duration::days(1),
+        (Observance::NextFreeWeekday, Weekday::Sat | Weekday::Sun) => date
+            .iter_days()
+            .skip(1)
+            .find(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun) && !taken.contains(d))
+            .unwrap_or(date),
+        _ => date,
+    }
+}
+
+/// Western (Gregorian) Easter Sunday, via the anonymous Gregorian algorithm.
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Parses the all-day events of an iCalendar (.ics) file into holidays tagged with
+/// `source`. Multi-day events cover every day up to their exclusive `DTEND`, and
+/// recurring events are expanded through `expand_until`. Timed events, events without
+/// a usable `DTSTART` and cancelled events are skipped.
+pub fn parse_ics(
+    contents: &str,
+    source: &str,
+    expand_until: NaiveDate,
+) -> Result<Vec<Holiday>, String> {
+    let lines = unfold_lines(contents);
+    if !lines
+        .iter()
+        .any(|l| l.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err("Not an iCalendar file (missing BEGIN:VCALENDAR)".into());
+    }
+
+    let mut holidays = Vec::new();
+    let mut event: Option<Vec<(String, String, String)>> = None;
+
+    for line in &lines {
+        if line.eq_ignore_ascii_case("BEGIN:VEVENT") {
+            event = Some(Vec::new());
+            continue;
+        }
+        if line.eq_ignore_ascii_case("END:VEVENT") {
+            if let Some(props) = event.take() {
+                holidays.extend(event_holidays(&props, source, expand_until));
+            }
+            continue;
+        }
+        if let Some(props) = event.as_mut() {
+            if let Some(prop) = split_property(line) {
+                props.push(prop);
+            }
+        }
+    }
+
+    holidays.sort_by(|a, b| a.date.cmp(&b.date));
+    holidays.dedup_by(|a, b| a.date == b.date);
+    Ok(holidays)
+}
+
+fn event_holidays(
+    props: &[(String, String, String)],
+    source: &str,
+    expand_until: NaiveDate,
+) -> Vec<Holiday> {
+    let get = |name: &str| props.iter().find(|(n, _, _)| n == name);
+
+    if get("STATUS").is_some_and(|(_, _, v)| v.eq_ignore_ascii_case("CANCELLED")) {
+        return Vec::new();
+    }
+    let name = get("SUMMARY")
+        .map(|(_, _, v)| unescape_text(v))
+        .unwrap_or_else(|| "Holiday".to_string());
+    let start = match get("DTSTART") {
+        Some((_, params, v)) if is_date_value(params, v) => parse_ics_date(v),
+        // A meeting or appointment in the calendar isn't a day off.
+        Some(_) => {
+            log::debug!("Skipping timed event '{}' in holiday calendar", name);
+            return Vec::new();
+        }
+        None => None,
+    };
+    let Some(start) = start else {
+        log::warn!("Skipping holiday event without a usable DTSTART");
+        return Vec::new();
+    };
+
+    // DTEND is exclusive, and an event without one covers only its start day.
+    let span_days = match get("DTEND") {
+        Some((_, params, v)) if is_date_value(params, v) => parse_ics_date(v)
+            .map(|end| (end - start).num_days().max(1))
+            .unwrap_or(1),
+        _ => 1,
+    };
+
+    let starts: Vec<NaiveDate> = match get("RRULE").map(|(_, _, v)| v.parse::<RecurrenceRule>()) {
+        None => vec![start],
+        Some(Ok(rule)) => std::iter::successors(rule.next_on_or_after(start, start), |d| {
+            rule.next_on_or_after(start, d.succ_opt()?)
+        })
+        .take_while(|d| *d <= expand_until)
+        .collect(),
+        Some(Err(e)) => {
+            log::warn!("Skipping recurring holiday '{}': {}", name, e);
+            Vec::new()
+        }
+    };
+
+    starts
+        .into_iter()
+        .flat_map(|d| d.iter_days().take(span_days as usize))
+        .map(|date| Holiday {
+            date: date.format("%Y-%m-%d").to_string(),
+            name: name.clone(),
+            source: source.to_string(),
+        })
+        .collect()
+}
+
+/// Undoes RFC 5545 line folding (a CRLF followed by a space or tab continues the line).
+fn unfold_lines(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in contents.lines() {
+        match raw.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(raw.trim_end_matches('\r').to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=X:value` into upper-cased name, raw params and value.
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((
+        name.to_ascii_uppercase(),
+        params.to_ascii_uppercase(),
+        value.to_string(),
+    ))
+}
+
+/// Whether a property holds a DATE (`20261225`) rather than a DATE-TIME value.
+fn is_date_value(params: &str, value: &str) -> bool {
+    let value = value.trim();
+    !params.to_ascii_uppercase().contains("VALUE=DATE-TIME")
+        && value.len() == 8
+        && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y%m%d").ok()
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(holidays: &[Holiday]) -> Vec<&str> {
+        holidays.iter().map(|h| h.date.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_ics_all_day_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20261225\r\n\
+            DTEND;VALUE=DATE:20261226\r\n\
+            SUMMARY:Christmas Day\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20260101\r\n\
+            SUMMARY:New Year\\, again\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let holidays = parse_ics(ics, "ics:test", date("2030-01-01")).unwrap();
+        assert_eq!(dates(&holidays), ["2026-01-01", "2026-12-25"]);
+        assert_eq!(holidays[0].name, "New Year, again");
+        assert_eq!(holidays[1].source, "ics:test");
+    }
+
+    #[test]
+    fn test_parse_ics_unfolds_lines_and_spans_multi_day_events() {
+        let ics = "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            DTSTART;VALUE=DATE:20260803\n\
+            DTEND;VALUE=DATE:20260806\n\
+            SUMMARY:Summer\n  shutdown\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n";
+
+        let holidays = parse_ics(ics, "ics:test", date("2030-01-01")).unwrap();
+        assert_eq!(dates(&holidays), ["2026-08-03", "2026-08-04", "2026-08-05"]);
+        assert_eq!(holidays[0].name, "Summer shutdown");
+    }
+
+    #[test]
+    fn test_parse_ics_expands_recurring_events_and_skips_cancelled() {
+        let ics = "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            DTSTART;VALUE=DATE:20260704\n\
+            RRULE:FREQ=YEARLY\n\
+            SUMMARY:Independence Day\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20260310T090000Z\n\
+            STATUS:CANCELLED\n\
+            SUMMARY:Cancelled\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            SUMMARY:No start\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n";
+
+        let holidays = parse_ics(ics, "ics:test", date("2028-12-31")).unwrap();
+        assert_eq!(dates(&holidays), ["2026-07-04", "2027-07-04", "2028-07-04"]);
+    }
+
+    #[test]
+    fn test_parse_ics_skips_timed_events() {
+        let ics = "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            DTSTART;VALUE=DATE:20261111\n\
+            SUMMARY:Remembrance Day\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            DTSTART;TZID=America/Toronto:20261112T140000\n\
+            DTEND;TZID=America/Toronto:20261112T150000\n\
+            SUMMARY:Dentist\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20261113T000000Z\n\
+            RRULE:FREQ=WEEKLY\n\
+            SUMMARY:Standup\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n";
+
+        let holidays = parse_ics(ics, "ics:test", date("2027-12-31")).unwrap();
+        assert_eq!(dates(&holidays), ["2026-11-11"]);
+    }
+
+    #[test]
+    fn test_parse_ics_rejects_non_calendar() {
+        assert!(parse_ics("hello", "ics:test", date("2030-01-01")).is_err());
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(easter_sunday(2024), Some(date("2024-03-31")));
+        assert_eq!(easter_sunday(2026), Some(date("2026-04-05")));
+        assert_eq!(easter_sunday(2027), Some(date("2027-03-28")));
+    }
+
+    #[test]
+    fn test_bundled_us_moves_weekend_holidays_to_nearest_weekday() {
+        let holidays = bundled_holidays("us", 2026, 2026).unwrap();
+        let days = dates(&holidays);
+
+        // Independence Day 2026 is a Saturday, observed Friday 3 July.
+        assert!(days.contains(&"2026-07-03"));
+        assert!(days.contains(&"2026-11-26")); // Thanksgiving
+        assert!(days.contains(&"2026-05-25")); // Memorial Day
+        assert_eq!(holidays.len(), US.len());
+        assert!(holidays.iter().all(|h| h.source == "region:US"));
+    }
+
+    #[test]
+    fn test_bundled_gb_substitutes_christmas_and_boxing_day() {
+        // Christmas 2027 is a Saturday and Boxing Day a Sunday: observed Mon 27 and Tue 28.
+        let holidays = bundled_holidays("GB", 2027, 2027).unwrap();
+        let days = dates(&holidays);
+
+        assert!(days.contains(&"2027-12-27"));
+        assert!(days.contains(&"2027-12-28"));
+        assert!(days.contains(&"2027-03-26")); // Good Friday
+        assert!(days.contains(&"2027-03-29")); // Easter Monday
+    }
+
+    #[test]
+    fn test_bundled_ca_victoria_day() {
+        let holidays = bundled_holidays("CA", 2026, 2027).unwrap();
+        let days = dates(&holidays);
+
+        assert!(days.contains(&"2026-05-18"));
+        assert!(days.contains(&"2027-05-24"));
+        assert_eq!(holidays.len(), CA.len() * 2);
+    }
+
+    #[test]
+    fn test_bundled_unknown_region_errors() {
+        assert!(bundled_holidays("ZZ", 2026, 2026).is_err());
+    }
+}
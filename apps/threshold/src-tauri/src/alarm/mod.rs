@@ -3,30 +3,78 @@
 // (c) Copyright 2026 Liminal HQ, Scott Morris
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub mod clock;
 pub mod database;
 pub mod error;
 pub mod events;
+pub mod holidays;
 pub mod models;
+pub mod recurrence;
+pub mod report;
 pub mod scheduler;
 
 pub use error::{Error, Result};
 use events::*;
 pub use models::*;
 
+use clock::{Clock, SystemClock};
 use database::AlarmDatabase;
+use scheduler::ScheduleContext;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
+/// Settings key for the missed-alarm grace window.
+const MISSED_GRACE_SETTING: &str = "missed_grace_minutes";
+
+/// Settings key for when the app was last seen running, in epoch millis.
+const LAST_ALIVE_SETTING: &str = "last_alive_at";
+
+/// Minutes an undismissed occurrence is given, by default, before it counts as missed.
+pub const DEFAULT_MISSED_GRACE_MINUTES: u32 = 30;
+/// The grace window has to outlast the ring that cold-starts the app, so the alarm
+/// isn't marked missed while it's still ringing.
+pub const MIN_MISSED_GRACE_MINUTES: u32 = 5;
+/// Twelve hours; anything longer would hold a daily alarm past its next occurrence.
+pub const MAX_MISSED_GRACE_MINUTES: u32 = 720;
+
+/// Settings key for the bedtime reminder's settings, stored as JSON.
+const BEDTIME_REMINDER_SETTING: &str = "bedtime_reminder";
+/// Settings key for when the bedtime reminder is scheduled natively, while it is.
+const BEDTIME_REMINDER_AT_SETTING: &str = "bedtime_reminder_at";
+
+/// Less sleep than this isn't a target worth reminding anyone of.
+pub const MIN_BEDTIME_SLEEP_MINUTES: u32 = 180;
+/// Sixteen hours; any more and the reminder would land before the previous alarm.
+pub const MAX_BEDTIME_SLEEP_MINUTES: u32 = 960;
+/// Three hours of winding down is already an evening.
+pub const MAX_BEDTIME_WIND_DOWN_MINUTES: u32 = 180;
+
+/// A day; anything longer wants an alarm instead.
+pub const MAX_TIMER_SECONDS: u32 = 86_400;
+
+/// Why a timer's ring can't be snoozed.
+const TIMER_SNOOZE_REFUSAL: &str = "A timer can't be snoozed";
+
 /// Central coordinator for all alarm operations
 pub struct AlarmCoordinator {
     db: AlarmDatabase,
+    clock: Arc<dyn Clock>,
 }
 
 impl AlarmCoordinator {
-    /// Create a new coordinator.
+    /// Create a new coordinator on the system clock.
     ///
     /// - `db`: backing alarm database for persistence and revisions.
     pub fn new(db: AlarmDatabase) -> Self {
-        Self { db }
+        Self::with_clock(db, Arc::new(SystemClock))
+    }
+
+    /// Create a new coordinator that reads the time from `clock`.
+    ///
+    /// - `db`: backing alarm database for persistence and revisions.
+    /// - `clock`: source of "now" for scheduling, snoozes, healing and maintenance.
+    pub fn with_clock(db: AlarmDatabase, clock: Arc<dyn Clock>) -> Self {
+        Self { db, clock }
     }
 
     /// Get the phone's current revision number.
@@ -44,12 +92,18 @@ impl AlarmCoordinator {
         self.db.get_all().await
     }
 
-    /// Get a single alarm by id.
+    /// Get a single alarm by id. A pre-alarm's or backup alarm's native id resolves to
+    /// the alarm it rings for, and a timer's to a stand-in carrying the timer's label,
+    /// so a ringing screen opened for any of them can load it.
     ///
     /// - `_app`: app handle for event context (unused here).
-    /// - `id`: alarm identifier.
+    /// - `id`: alarm identifier, or a pre-alarm's, backup alarm's or timer's native id.
     pub async fn get_alarm<R: Runtime>(&self, _app: &AppHandle<R>, id: i32) -> Result<AlarmRecord> {
-        self.db.get_by_id(id).await
+        if let Some(timer_id) = timer_parent(id) {
+            return Ok(self.db.get_timer(timer_id).await?.ringing_record());
+        }
+        let alarm_id = pre_alarm_parent(id).or(backup_alarm_parent(id));
+        self.db.get_by_id(alarm_id.unwrap_or(id)).await
     }
 
     /// Create or update an alarm and emit granular events.
@@ -59,28 +113,134 @@ impl AlarmCoordinator {
     pub async fn save_alarm<R: Runtime>(
         &self,
         app: &AppHandle<R>,
-        input: AlarmInput,
+        mut input: AlarmInput,
     ) -> Result<AlarmRecord> {
         // Fetch previous state if updating (for event diffing)
-        let is_new = input.id.is_none();
         let previous = if let Some(id) = input.id {
             self.db.get_by_id(id).await.ok()
         } else {
             None
         };
 
+        // Omitted fields keep the stored values.
+        if input.skip_dates.is_none() {
+            input.skip_dates = previous.as_ref().map(|p| p.skip_dates.clone());
+        }
+        input.skip_holidays = Some(
+            input
+                .skip_holidays
+                .or(previous.as_ref().map(|p| p.skip_holidays))
+                .unwrap_or(false),
+        );
+        if input.window_distribution.is_none() {
+            input.window_distribution = Some(
+                previous
+                    .as_ref()
+                    .map(|p| p.window_distribution.clone())
+                    .unwrap_or_default(),
+            );
+        }
+        if input.dst_policy.is_none() {
+            input.dst_policy = Some(previous.as_ref().map(|p| p.dst_policy).unwrap_or_default());
+        }
+        if input.bedtime.is_none() {
+            input.bedtime = previous.as_ref().and_then(|p| p.bedtime.clone());
+        }
+        if input.pre_alarm.is_none() {
+            input.pre_alarm = previous.as_ref().and_then(|p| p.pre_alarm.clone());
+        }
+        if input.snooze_policy.is_none() {
+            input.snooze_policy = previous.as_ref().and_then(|p| p.snooze_policy.clone());
+        }
+        if input.ring_timeout.is_none() {
+            input.ring_timeout = previous.as_ref().and_then(|p| p.ring_timeout.clone());
+        }
+        if input.backup_alarm.is_none() {
+            input.backup_alarm = previous.as_ref().and_then(|p| p.backup_alarm.clone());
+        }
+
+        self.store_alarm(app, input, previous).await
+    }
+
+    /// Schedule and persist a fully resolved alarm payload, and emit its events.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `input`: alarm payload to save, with no field left to fall back on `previous`.
+    /// - `previous`: stored state being replaced, if any.
+    async fn store_alarm<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        mut input: AlarmInput,
+        previous: Option<AlarmRecord>,
+    ) -> Result<AlarmRecord> {
+        let is_new = input.id.is_none();
+
+        // Drop skip dates that have already passed so the set doesn't grow forever.
+        input.skip_dates = Some(scheduler::normalize_skip_dates(
+            input.skip_dates.as_deref().unwrap_or_default(),
+            self.clock.now().with_timezone(&chrono::Local).date_naive(),
+        )?);
+        if let Some(pre_alarm) = &input.pre_alarm {
+            scheduler::validate_pre_alarm(pre_alarm)?;
+        }
+        if let Some(policy) = &input.snooze_policy {
+            scheduler::validate_snooze_policy(policy)?;
+        }
+        input.snooze_policy = input.snooze_policy.filter(|p| !p.is_unrestricted());
+        if let Some(timeout) = &input.ring_timeout {
+            scheduler::validate_ring_timeout(timeout)?;
+        }
+        input.ring_timeout = input.ring_timeout.filter(|t| t.minutes > 0);
+        if let Some(backup) = &input.backup_alarm {
+            scheduler::validate_backup_alarm(backup)?;
+        }
+        input.backup_alarm = input.backup_alarm.filter(|b| b.minutes_after > 0);
+        if input.mode != AlarmMode::Interval {
+            input.interval_minutes = None;
+        }
+        if input.mode != AlarmMode::FollowUp {
+            input.follow_up = None;
+        }
+        scheduler::validate_follows_an_alarm(&input)?;
+        if let Some(follow_up) = &input.follow_up {
+            self.validate_follow_up(input.id, follow_up, previous.as_ref())
+                .await?;
+        }
+
         // Calculate next trigger using scheduler
-        let next_trigger = if input.enabled {
-            scheduler::calculate_next_trigger(&input)?
-        } else {
+        let ctx = self.schedule_context(&input).await?;
+        let next_trigger = if !input.enabled {
             None
+        } else if input.mode == AlarmMode::FollowUp {
+            // Only a dismissal sets a follow-up's trigger, so an edit that leaves
+            // what it follows alone keeps one that's pending.
+            previous
+                .as_ref()
+                .filter(|p| p.enabled && p.follow_up == input.follow_up)
+                .and_then(|p| p.next_trigger)
+        } else {
+            scheduler::calculate_next_trigger(&input, self.clock.now_millis(), &ctx)?
         };
 
         // Get next revision
         let revision = self.db.next_revision().await?;
 
         // Save to database
-        let alarm = self.db.save(input, next_trigger, revision).await?;
+        let mut alarm = self.db.save(input, next_trigger, revision).await?;
+
+        // Window samples are seeded by alarm id, which a new alarm only has now;
+        // resample so later recalculations of this occurrence agree with it.
+        if is_new && alarm.enabled && alarm.mode.is_sampled() {
+            let next_trigger = scheduler::calculate_next_trigger(
+                &alarm.to_input(),
+                self.clock.now_millis(),
+                &ctx,
+            )?;
+            alarm = self
+                .db
+                .update_next_trigger(alarm.id, next_trigger, revision)
+                .await?;
+        }
 
         // Emit events IN ORDER:
 
@@ -96,6 +256,7 @@ impl AlarmCoordinator {
         // 2. Scheduling events
         self.emit_scheduling_events(app, &alarm, previous.as_ref(), revision)
             .await?;
+        self.refresh_bedtime_reminder(app, revision, false).await?;
 
         // 3. Batch event
         self.emit_batch_update(app, vec![alarm.id], revision)
@@ -118,21 +279,140 @@ impl AlarmCoordinator {
         let alarm = self.db.get_by_id(id).await?;
 
         let input = AlarmInput {
-            id: Some(alarm.id),
-            label: alarm.label,
             enabled,
-            mode: alarm.mode.clone(),
-            fixed_time: alarm.fixed_time,
-            window_start: alarm.window_start,
-            window_end: alarm.window_end,
-            active_days: alarm.active_days,
-            sound_uri: alarm.sound_uri,
-            sound_title: alarm.sound_title,
+            ..alarm.to_input()
+        };
+
+        self.save_alarm(app, input).await
+    }
+
+    /// Replace the set of occurrence dates an alarm should not fire on, leaving it
+    /// enabled, and reschedule around them.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm identifier.
+    /// - `dates`: occurrence dates ("YYYY-MM-DD") to skip; past dates are dropped.
+    pub async fn set_skip_dates<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        dates: Vec<String>,
+    ) -> Result<AlarmRecord> {
+        let alarm = self.db.get_by_id(id).await?;
+
+        let input = AlarmInput {
+            skip_dates: Some(dates),
+            ..alarm.to_input()
+        };
+
+        self.save_alarm(app, input).await
+    }
+
+    /// Add, change or remove an alarm's pre-alarm, and reschedule it.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm identifier.
+    /// - `pre_alarm`: the new pre-alarm, or `None` to remove it.
+    pub async fn set_pre_alarm<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        pre_alarm: Option<PreAlarm>,
+    ) -> Result<AlarmRecord> {
+        let alarm = self.db.get_by_id(id).await?;
+
+        // Stored directly, since save_alarm reads an omitted pre-alarm as "keep it".
+        let input = AlarmInput {
+            pre_alarm,
+            ..alarm.to_input()
+        };
+
+        self.store_alarm(app, input, Some(alarm)).await
+    }
+
+    /// Set the bedtime a sleep-cycle window counts cycles from to the clock time of
+    /// `at` (e.g. when a bedtime reminder was dismissed), and reschedule.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm identifier.
+    /// - `at`: epoch millis the user went to bed; now if omitted.
+    pub async fn record_bedtime<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        at: Option<i64>,
+    ) -> Result<AlarmRecord> {
+        let alarm = self.db.get_by_id(id).await?;
+        let input = alarm.to_input();
+        let time = scheduler::clock_time(&input, at.unwrap_or_else(|| self.clock.now_millis()))?;
+
+        let input = AlarmInput {
+            bedtime: Some(time.format("%H:%M").to_string()),
+            ..input
         };
 
         self.save_alarm(app, input).await
     }
 
+    /// Skip an alarm's next scheduled occurrence without disabling it.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm identifier.
+    pub async fn skip_next_occurrence<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+    ) -> Result<AlarmRecord> {
+        let alarm = self.db.get_by_id(id).await?;
+        if alarm.mode == AlarmMode::FollowUp {
+            return Err(Error::Validation(
+                "A follow-up alarm has no dated occurrences to skip; dismiss it instead".into(),
+            ));
+        }
+        let Some(next_trigger) = alarm.next_trigger.filter(|_| alarm.enabled) else {
+            return Err(Error::Validation(
+                "Alarm has no upcoming occurrence to skip".into(),
+            ));
+        };
+
+        let input = alarm.to_input();
+        let date = scheduler::occurrence_date(&input, next_trigger)?;
+        let mut dates = alarm.skip_dates.clone();
+        dates.push(date.format("%Y-%m-%d").to_string());
+
+        self.set_skip_dates(app, id, dates).await
+    }
+
+    /// Preview upcoming occurrences for one alarm, or merged across all alarms in
+    /// trigger order. Window alarms report their bounds, not a sampled minute.
+    ///
+    /// - `alarm_id`: alarm to preview, or `None` for every alarm.
+    /// - `query`: the next N occurrences, or those dated within a range.
+    pub async fn get_upcoming_occurrences(
+        &self,
+        alarm_id: Option<i32>,
+        query: &OccurrenceQuery,
+    ) -> Result<Vec<Occurrence>> {
+        let alarms = match alarm_id {
+            Some(id) => vec![self.db.get_by_id(id).await?],
+            None => self.db.get_all().await?,
+        };
+
+        let now = self.clock.now_millis();
+        let mut occurrences = Vec::new();
+        for alarm in alarms {
+            let input = alarm.to_input();
+            let ctx = self.schedule_context(&input).await?;
+            occurrences.extend(scheduler::upcoming_occurrences(&input, now, query, &ctx)?);
+        }
+
+        occurrences.sort_by_key(|o| (o.starts_at, o.alarm_id));
+        if let OccurrenceQuery::Next { count } = query {
+            occurrences.truncate(*count as usize);
+        }
+        Ok(occurrences)
+    }
+
     /// Delete an alarm, create a tombstone, and emit deletion events.
     ///
     /// - `app`: app handle for event emission.
@@ -143,7 +423,9 @@ impl AlarmCoordinator {
         // Get alarm info before delete (for label)
         let alarm = self.db.get_by_id(id).await.ok();
 
-        self.db.delete_with_revision(id, revision).await?;
+        self.db
+            .delete_with_revision(id, revision, self.clock.now_millis())
+            .await?;
 
         // Emit events
         self.emit_alarm_deleted(
@@ -155,17 +437,188 @@ impl AlarmCoordinator {
         .await?;
         self.emit_alarm_cancelled(app, id, CancelReason::Deleted, revision)
             .await?;
+        if alarm.as_ref().is_some_and(|a| a.pre_alarm.is_some()) {
+            self.emit_alarm_cancelled(
+                app,
+                pre_alarm_native_id(id),
+                CancelReason::Deleted,
+                revision,
+            )
+            .await?;
+        }
+        if alarm.as_ref().is_some_and(|a| a.backup_alarm.is_some()) {
+            self.emit_alarm_cancelled(
+                app,
+                backup_alarm_native_id(id),
+                CancelReason::Deleted,
+                revision,
+            )
+            .await?;
+        }
         self.emit_batch_update(app, vec![id], revision).await?;
+        self.refresh_bedtime_reminder(app, revision, false).await?;
+
+        // Its follow-ups could never ring again; switch them off, keeping what they
+        // followed on record.
+        for follow_up in self.db.get_all().await? {
+            if follow_up.enabled && follows(&follow_up, id) {
+                let input = AlarmInput {
+                    enabled: false,
+                    ..follow_up.to_input()
+                };
+                self.store_alarm(app, input, Some(follow_up)).await?;
+            }
+        }
 
         Ok(())
     }
 
-    /// Dismiss a ringing alarm and calculate the next occurrence.
+    /// Dismiss a ringing alarm and calculate the next occurrence. Dismissing a pre-alarm
+    /// leaves its alarm alone, unless it's set to skip it too. A backup alarm rings for
+    /// its alarm's occurrence, so dismissing either dismisses that occurrence and stands
+    /// the backup down. Someone dismissing an occurrence that came due, rather than its
+    /// ring timeout, sets off the alarms that follow it; dismissing one ahead of time
+    /// only skips it. A timer's native id dismisses the timer, which is
+    /// then gone.
     ///
     /// - `app`: app handle for event emission.
-    /// - `id`: alarm identifier.
-    pub async fn dismiss_alarm<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<()> {
+    /// - `id`: alarm identifier, or a pre-alarm's, backup alarm's or timer's native id.
+    /// - `source`: where the dismissal came from, for the history.
+    pub async fn dismiss_alarm<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        source: AlarmEventSource,
+    ) -> Result<()> {
+        if let Some(timer_id) = timer_parent(id) {
+            return self.dismiss_timer(app, timer_id).await;
+        }
+        if let Some(alarm_id) = pre_alarm_parent(id) {
+            return self.end_pre_alarm(app, alarm_id, Some(source)).await;
+        }
+        let id = backup_alarm_parent(id).unwrap_or(id);
         let alarm = self.db.get_by_id(id).await?;
+        let last_rang = match alarm.snoozed_from.or(alarm.next_trigger) {
+            Some(trigger) => self.db.last_fired_at(id, trigger).await?,
+            None => None,
+        };
+        let new_alarm = self.dismiss_occurrence(app, alarm.clone(), None).await?;
+        self.record_history(
+            &alarm,
+            AlarmEventKind::Dismissed,
+            source,
+            new_alarm.next_trigger,
+        )
+        .await;
+
+        // Emit dismissed event
+        let dismissed_at = self.clock.now_millis();
+        let event = AlarmDismissed {
+            id,
+            fired_at: last_rang.unwrap_or(dismissed_at),
+            dismissed_at,
+            next_trigger: new_alarm.next_trigger,
+            revision: new_alarm.revision,
+        };
+        app.emit("alarm:dismissed", &event)?;
+
+        if backup_due(&alarm, dismissed_at) {
+            self.stand_down_backup(app, id, new_alarm.next_trigger, new_alarm.revision)
+                .await?;
+        }
+        if source != AlarmEventSource::Unattended && came_due(&alarm, dismissed_at) {
+            self.schedule_follow_ups(app, id, dismissed_at).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Schedule the enabled follow-ups of an alarm just dismissed, each its
+    /// `minutes_after` from the dismissal, under a single revision and batch event.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm_id`: alarm that was dismissed.
+    /// - `dismissed_at`: epoch millis of the dismissal.
+    async fn schedule_follow_ups<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm_id: i32,
+        dismissed_at: i64,
+    ) -> Result<()> {
+        let follow_ups: Vec<_> = self
+            .db
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|a| a.enabled && follows(a, alarm_id))
+            .collect();
+        if follow_ups.is_empty() {
+            return Ok(());
+        }
+
+        let revision = self.db.next_revision().await?;
+        let mut updated_ids = Vec::with_capacity(follow_ups.len());
+        for alarm in follow_ups {
+            let next_trigger = follow_up_trigger(&alarm, dismissed_at);
+            let updated = self
+                .db
+                .update_next_trigger(alarm.id, next_trigger, revision)
+                .await?;
+            self.emit_alarm_updated(
+                app,
+                &updated,
+                Some(AlarmSnapshot::from_alarm(&alarm)),
+                revision,
+            )
+            .await?;
+            self.emit_scheduling_events(app, &updated, Some(&alarm), revision)
+                .await?;
+            updated_ids.push(updated.id);
+        }
+        self.refresh_bedtime_reminder(app, revision, false).await?;
+        self.emit_batch_update(app, updated_ids, revision).await
+    }
+
+    /// Check what a follow-up alarm follows exists and doesn't lead back to it.
+    ///
+    /// - `alarm_id`: the follow-up being saved, or `None` while it's new.
+    /// - `follow_up`: what it's to follow.
+    /// - `previous`: its stored state, whose link to an alarm since deleted may stand.
+    async fn validate_follow_up(
+        &self,
+        alarm_id: Option<i32>,
+        follow_up: &FollowUp,
+        previous: Option<&AlarmRecord>,
+    ) -> Result<()> {
+        let alarms = self.db.get_all().await?;
+        let relinked = previous.and_then(|p| p.follow_up.as_ref()) != Some(follow_up);
+        if relinked && !alarms.iter().any(|a| a.id == follow_up.alarm_id) {
+            return Err(Error::Validation(format!(
+                "Alarm {} to follow doesn't exist",
+                follow_up.alarm_id
+            )));
+        }
+        let followed = alarms
+            .iter()
+            .filter_map(|a| Some((a.id, a.follow_up.as_ref()?.alarm_id)))
+            .collect();
+        scheduler::validate_follow_up(alarm_id, follow_up, &followed)
+    }
+
+    /// Move an alarm past its current occurrence, or switch off a spent one-shot, and
+    /// emit the state change events.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm whose occurrence is being dismissed.
+    /// - `catch_up_to`: instant the next occurrence must also come after, so one
+    ///   found long missed skips any others that have passed since.
+    async fn dismiss_occurrence<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: AlarmRecord,
+        catch_up_to: Option<i64>,
+    ) -> Result<AlarmRecord> {
+        let id = alarm.id;
 
         // Emit dismissed event
         // Note: We need a revision for this event.
@@ -179,30 +632,31 @@ impl AlarmCoordinator {
         // Let's grab the current revision for the dismissed event, as it relates to the *act* of dismissing.
         // Or better, save_alarm will produce a new revision.
 
-        let dismissed_at = chrono::Utc::now().timestamp_millis();
-        let fired_at = dismissed_at; // Approximation if not tracking exact fire time
+        let dismissed_at = self.clock.now_millis();
+
+        // Instances an interval alarm rang on through are spent along with this one.
+        let catch_up_to = if alarm.mode == AlarmMode::Interval {
+            catch_up_to.max(Some(dismissed_at))
+        } else {
+            catch_up_to
+        };
+
+        // A one-shot alarm is spent once dismissed -- whether it just rang or is being
+        // dismissed ahead of time -- so it switches itself off instead of re-arming.
+        let expired = alarm.enabled && alarm.mode.is_one_shot();
 
         // Recalculate next occurrence after the current scheduled trigger so
         // dismissing an upcoming alarm skips this occurrence.
         let input = AlarmInput {
-            id: Some(alarm.id),
-            label: alarm.label.clone(),
-            enabled: alarm.enabled,
-            mode: alarm.mode.clone(),
-            fixed_time: alarm.fixed_time.clone(),
-            window_start: alarm.window_start.clone(),
-            window_end: alarm.window_end.clone(),
-            active_days: alarm.active_days.clone(),
-            sound_uri: alarm.sound_uri.clone(),
-            sound_title: alarm.sound_title.clone(),
+            enabled: alarm.enabled && !expired,
+            ..alarm.to_input()
         };
 
         let next_trigger = if input.enabled {
-            let reference_ms = alarm
-                .next_trigger
-                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis())
-                + 1_000;
-            scheduler::calculate_next_trigger_after(&input, reference_ms)?
+            let reference_ms = (alarm.next_trigger.unwrap_or(dismissed_at) + 1_000)
+                .max(catch_up_to.unwrap_or(i64::MIN));
+            let ctx = self.schedule_context(&input).await?;
+            scheduler::calculate_next_trigger_after(&input, reference_ms, &ctx)?
         } else {
             None
         };
@@ -216,23 +670,103 @@ impl AlarmCoordinator {
             revision,
         )
         .await?;
-        self.emit_scheduling_events(app, &new_alarm, Some(&alarm), revision)
-            .await?;
+        if expired {
+            self.emit_alarm_cancelled(app, id, CancelReason::Expired, revision)
+                .await?;
+            self.emit_pre_alarm_events(app, &new_alarm, Some(&alarm), revision)
+                .await?;
+        } else {
+            self.emit_scheduling_events(app, &new_alarm, Some(&alarm), revision)
+                .await?;
+        }
+        self.refresh_bedtime_reminder(app, revision, false).await?;
         self.emit_batch_update(app, vec![id], revision).await?;
 
-        // Emit dismissed event
+        Ok(new_alarm)
+    }
+
+    /// Stop a ringing pre-alarm, first skipping the alarm it leads if it's set to and
+    /// that alarm is still to come.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm_id`: alarm the pre-alarm rings ahead of.
+    /// - `dismissed_by`: where a dismissal, which may skip the alarm, came from; unset
+    ///   for a snooze.
+    async fn end_pre_alarm<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm_id: i32,
+        dismissed_by: Option<AlarmEventSource>,
+    ) -> Result<()> {
+        let alarm = self.db.get_by_id(alarm_id).await?;
+        let dismissed_at = self.clock.now_millis();
+
+        let skip_main = dismissed_by.is_some()
+            && alarm.enabled
+            && alarm
+                .pre_alarm
+                .as_ref()
+                .is_some_and(|p| p.skip_main_on_dismiss)
+            && alarm.next_trigger.is_some_and(|t| t > dismissed_at);
+        let alarm = match dismissed_by.filter(|_| skip_main) {
+            Some(source) => {
+                let skipped = self.dismiss_occurrence(app, alarm.clone(), None).await?;
+                self.record_history(
+                    &alarm,
+                    AlarmEventKind::Dismissed,
+                    source,
+                    skipped.next_trigger,
+                )
+                .await;
+                skipped
+            }
+            None => alarm,
+        };
+
         let event = AlarmDismissed {
-            id,
-            fired_at,
+            id: pre_alarm_native_id(alarm_id),
+            fired_at: dismissed_at,
             dismissed_at,
-            next_trigger: new_alarm.next_trigger,
-            revision: new_alarm.revision,
+            next_trigger: alarm.next_trigger,
+            revision: self.db.current_revision().await?,
         };
         app.emit("alarm:dismissed", &event)?;
 
         Ok(())
     }
 
+    /// Whether the alarm's ringing occurrence may be snoozed now under its snooze
+    /// policy, and for how long.
+    ///
+    /// - `id`: alarm identifier, a backup alarm's native id (its alarm's allowance), or
+    ///   a pre-alarm's native id (always allowed, since snoozing one just stops it). A
+    ///   timer's native id is never allowed.
+    pub async fn get_snooze_allowance(&self, id: i32) -> Result<SnoozeAllowance> {
+        if timer_parent(id).is_some() {
+            return Ok(SnoozeAllowance {
+                allowed: false,
+                minutes: None,
+                reason: Some(TIMER_SNOOZE_REFUSAL.into()),
+            });
+        }
+        if pre_alarm_parent(id).is_some() {
+            return Ok(SnoozeAllowance {
+                allowed: true,
+                minutes: None,
+                reason: None,
+            });
+        }
+        let alarm = self
+            .db
+            .get_by_id(backup_alarm_parent(id).unwrap_or(id))
+            .await?;
+        scheduler::snooze_allowance(
+            &alarm.to_input(),
+            alarm.snooze_count,
+            self.clock.now_millis(),
+        )
+    }
+
     /// Snooze a ringing alarm by setting the next trigger to an explicit timestamp.
     ///
     /// - `app`: app handle for event emission.
@@ -240,72 +774,156 @@ impl AlarmCoordinator {
     /// - `snoozed_until`: absolute epoch-millisecond timestamp for the new trigger.
     ///   The TS layer is responsible for computing the anchor (now + N for ringing,
     ///   original_trigger + N for upcoming) and enforcing a minimum-in-future floor.
+    ///
+    /// The alarm's snooze policy is enforced here, so the phone, its notification and
+    /// the watch all get the same answer: a refused snooze is a validation error, and
+    /// the policy's interval replaces the length the device asked for.
+    ///
+    /// A pre-alarm has no snooze of its own -- the alarm it leads is its snooze -- so
+    /// snoozing one just stops it. Snoozing a backup alarm snoozes its alarm's
+    /// occurrence, and either snooze stands the backup down.
+    ///
+    /// - `source`: where the snooze came from, for the history.
     pub async fn snooze_alarm<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         id: i32,
         snoozed_until: i64,
+        source: AlarmEventSource,
     ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
+        if timer_parent(id).is_some() {
+            return Err(Error::Validation(TIMER_SNOOZE_REFUSAL.into()));
+        }
+        if let Some(alarm_id) = pre_alarm_parent(id) {
+            return self.end_pre_alarm(app, alarm_id, None).await;
+        }
+        let id = backup_alarm_parent(id).unwrap_or(id);
+        let now = self.clock.now_millis();
         if snoozed_until <= now {
             return Err(Error::Validation(
                 "snoozed_until must be in the future".into(),
             ));
         }
         let alarm = self.db.get_by_id(id).await?;
+
+        // A ringing alarm snoozes from now, an upcoming one from its trigger.
+        let anchor = alarm.next_trigger.filter(|t| *t > now).unwrap_or(now);
+        let allowance = scheduler::snooze_allowance(&alarm.to_input(), alarm.snooze_count, anchor)?;
+        if !allowance.allowed {
+            return Err(Error::Validation(
+                allowance
+                    .reason
+                    .unwrap_or_else(|| "Snoozing is not allowed".into()),
+            ));
+        }
+        let snoozed_until = allowance
+            .minutes
+            .map_or(snoozed_until, |m| anchor + m as i64 * 60_000);
         let original_trigger = alarm.next_trigger.unwrap_or(now);
 
         let revision = self.db.next_revision().await?;
         let updated = self
             .db
-            .update_next_trigger(id, Some(snoozed_until), revision)
+            .record_snooze(id, snoozed_until, original_trigger, revision)
             .await?;
+        self.record_history(&alarm, AlarmEventKind::Snoozed, source, Some(snoozed_until))
+            .await;
 
         let event = AlarmSnoozed {
             id,
             original_trigger,
             snoozed_until,
+            snooze_count: updated.snooze_count,
             revision,
         };
         app.emit("alarm:snoozed", &event)?;
 
-        self.emit_scheduling_events(app, &updated, Some(&alarm), revision)
+        if backup_due(&alarm, now) {
+            self.stand_down_backup(app, id, Some(snoozed_until), revision)
+                .await?;
+        }
+
+        // The pre-alarm belongs to the occurrence's own time, not to a snooze.
+        let snoozed = AlarmRecord {
+            pre_alarm: None,
+            ..updated
+        };
+        self.emit_scheduling_events(app, &snoozed, Some(&alarm), revision)
             .await?;
+        self.refresh_bedtime_reminder(app, revision, false).await?;
         self.emit_batch_update(app, vec![id], revision).await?;
 
         Ok(())
     }
 
-    /// Report that an alarm fired (lifecycle event only).
+    /// Report that an alarm fired (lifecycle event only). An alarm with a backup has it
+    /// scheduled from this ring.
+    ///
+    /// A backup alarm can fire just as its occurrence is dismissed or snoozed, before
+    /// the cancellation reaches the native scheduler. It's stood down instead of rung,
+    /// and `false` is returned for the caller to stop any native ringing it started.
+    ///
+    /// A timer rings the same way, and is left ringing until it's dismissed or
+    /// cancelled. A pause's end rings nothing; it ends the pauses that are due.
     ///
     /// - `app`: app handle for event emission.
-    /// - `id`: alarm identifier.
+    /// - `id`: alarm identifier, or a pre-alarm's, backup alarm's, timer's or pause
+    ///   end's native id.
     /// - `actual_fired_at`: wall-clock firing time in epoch milliseconds.
     pub async fn report_alarm_fired<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         id: i32,
         actual_fired_at: i64,
-    ) -> Result<()> {
-        use std::sync::atomic::Ordering;
-
-        let alarm = self.db.get_by_id(id).await?;
+    ) -> Result<bool> {
+        if pause_end_parent(id).is_some() {
+            self.end_due_pauses(app).await?;
+            return Ok(true);
+        }
+        if let Some(timer_id) = timer_parent(id) {
+            return self
+                .report_timer_fired(app, timer_id, actual_fired_at)
+                .await;
+        }
+        let parent = pre_alarm_parent(id);
+        let backup_for = backup_alarm_parent(id);
+        let alarm = self
+            .db
+            .get_by_id(parent.or(backup_for).unwrap_or(id))
+            .await?;
         let revision = self.db.current_revision().await?;
-        let trigger_at = alarm.next_trigger.unwrap_or(actual_fired_at);
+        if backup_for.is_some() && !backup_due(&alarm, actual_fired_at) {
+            log::info!(
+                "Backup alarm for {} fired after its occurrence was attended to; standing it down",
+                alarm.id
+            );
+            self.stand_down_backup(app, alarm.id, alarm.next_trigger, revision)
+                .await?;
+            return Ok(false);
+        }
+        let pre_alarm = alarm.pre_alarm.as_ref().filter(|_| parent.is_some());
+        let trigger_at = match (alarm.next_trigger, pre_alarm) {
+            (Some(trigger), Some(pre)) => trigger - pre.minutes_before as i64 * 60_000,
+            (trigger, _) => trigger.unwrap_or(actual_fired_at),
+        };
 
-        // Read snooze length from managed state (synced from frontend settings)
-        let snooze = app
-            .try_state::<crate::SnoozeLengthState>()
-            .map(|s: tauri::State<'_, crate::SnoozeLengthState>| s.load(Ordering::Relaxed))
-            .unwrap_or(10);
-        let is_24_hour = app
-            .try_state::<crate::TimeFormatState>()
-            .map(|s: tauri::State<'_, crate::TimeFormatState>| s.load(Ordering::Relaxed))
-            .unwrap_or(false);
-        let is_24_hour_known = app
-            .try_state::<crate::TimeFormatKnownState>()
-            .map(|s: tauri::State<'_, crate::TimeFormatKnownState>| s.load(Ordering::Relaxed))
-            .unwrap_or(false);
+        // A pre-alarm's snooze just stops it, so only the alarm's own rings are limited.
+        let allowance = match parent {
+            Some(_) => None,
+            None => Some(scheduler::snooze_allowance(
+                &alarm.to_input(),
+                alarm.snooze_count,
+                actual_fired_at,
+            )?),
+        };
+
+        // Read snooze length from the policy, else managed state (synced from frontend
+        // settings)
+        let snooze = allowance
+            .as_ref()
+            .and_then(|a| a.minutes)
+            .map_or_else(|| snooze_length_setting(app), |m| m as i32);
+        let (is_24_hour, is_24_hour_known) = time_format_setting(app);
 
         let event = AlarmFired {
             id,
@@ -314,14 +932,213 @@ impl AlarmCoordinator {
             label: alarm.label.clone(),
             revision,
             snooze_length_minutes: snooze,
+            snooze_allowed: allowance.is_none_or(|a| a.allowed),
             is_24_hour,
             is_24_hour_known,
+            pre_alarm_for: parent,
+            ring_seconds: pre_alarm.map(|p| p.ring_seconds),
+            backup_for,
+            timer_for: None,
         };
         app.emit("alarm:fired", &event)?;
 
+        // A pre-alarm is a lead-in and a backup rings for an occurrence that already
+        // has, so only the alarm's own rings go in the history. What comes next is
+        // decided when it's dismissed.
+        if parent.is_none() && backup_for.is_none() {
+            let entry = AlarmHistoryEntry {
+                id: 0,
+                alarm_id: id,
+                kind: AlarmEventKind::Fired,
+                source: AlarmEventSource::System,
+                scheduled_trigger: Some(alarm.snoozed_from.unwrap_or(trigger_at)),
+                actual_fired_at: Some(actual_fired_at),
+                occurred_at: actual_fired_at,
+                next_trigger: None,
+                sampled: Some(alarm.mode.is_sampled()),
+            };
+            if let Err(e) = self.db.record_history(&entry).await {
+                log::warn!("Failed to record alarm {id} firing in history: {e}");
+            }
+
+            if let Some(backup) = &alarm.backup_alarm {
+                let scheduled = AlarmScheduled {
+                    id: backup_alarm_native_id(id),
+                    trigger_at: actual_fired_at + backup.minutes_after as i64 * 60_000,
+                    sound_uri: backup.sound_uri.clone().or(alarm.sound_uri.clone()),
+                    label: alarm.label.clone(),
+                    mode: alarm.mode.clone(),
+                    revision,
+                    pre_alarm_for: None,
+                    volume_percent: None,
+                    ring_seconds: None,
+                    snooze_allowed: (!event.snooze_allowed).then_some(false),
+                    backup_for: Some(id),
+                    watch_only: backup.watch_only.then_some(true),
+                    timer_for: None,
+                    notification_only: None,
+                    wake_only: None,
+                };
+                app.emit("alarm:scheduled", &scheduled)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Cancel the backup of an occurrence that has been dismissed or snoozed, and stop
+    /// it wherever it has already started ringing.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm_id`: alarm the backup belongs to.
+    /// - `next_trigger`: the alarm's trigger now that the occurrence has moved on.
+    /// - `revision`: revision stamped on the mutation.
+    async fn stand_down_backup<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm_id: i32,
+        next_trigger: Option<i64>,
+        revision: i64,
+    ) -> Result<()> {
+        let id = backup_alarm_native_id(alarm_id);
+        self.emit_alarm_cancelled(app, id, CancelReason::Attended, revision)
+            .await?;
+
+        let stopped_at = self.clock.now_millis();
+        let event = AlarmDismissed {
+            id,
+            fired_at: stopped_at,
+            dismissed_at: stopped_at,
+            next_trigger,
+            revision,
+        };
+        app.emit("alarm:dismissed", &event)?;
         Ok(())
     }
 
+    /// Watch over a ring of an alarm that just fired: if its ring timeout runs out with
+    /// nobody having dismissed or snoozed the occurrence, snooze or dismiss it as the
+    /// alarm's policy says, recorded as `UNATTENDED`, after escalating to the watch
+    /// halfway through if asked to. Returns what was done, so the caller can stop the
+    /// native ringing.
+    ///
+    /// Nothing needs cancelling on a dismiss or snooze: each stage checks the occurrence
+    /// is still the one that rang, and stands down if it has moved on.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm that fired (not a pre-alarm, which stops by itself). An id with no
+    ///   alarm behind it, like the test ring's, is left alone.
+    pub async fn supervise_ring<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+    ) -> Result<Option<RingTimeoutAction>> {
+        let Ok(rang) = self.db.get_by_id(id).await else {
+            log::debug!("Alarm {id} has no record; not watching its ring");
+            return Ok(None);
+        };
+        let Some(timeout) = rang.ring_timeout.clone() else {
+            return Ok(None);
+        };
+        let rang_at = self.clock.now();
+        let timeout_span = chrono::Duration::minutes(timeout.minutes as i64);
+        let deadline = rang_at + timeout_span;
+
+        if timeout.escalate_to_watch {
+            self.clock.sleep_until(rang_at + timeout_span / 2).await;
+            let Some(alarm) = self.still_ringing(&rang).await else {
+                return Ok(None);
+            };
+            let now = self.clock.now_millis();
+            let allowance =
+                scheduler::snooze_allowance(&alarm.to_input(), alarm.snooze_count, now)?;
+            let (is_24_hour, is_24_hour_known) = time_format_setting(app);
+            log::info!("Alarm {id} is ringing unattended; escalating to the watch");
+            let event = AlarmEscalated {
+                id,
+                label: alarm.label.clone(),
+                times_out_at: deadline.timestamp_millis(),
+                action: unattended_action(&timeout, allowance.allowed),
+                snooze_length_minutes: allowance
+                    .minutes
+                    .map_or_else(|| snooze_length_setting(app), |m| m as i32),
+                snooze_allowed: allowance.allowed,
+                is_24_hour,
+                is_24_hour_known,
+                revision: alarm.revision,
+            };
+            app.emit("alarm:escalated", &event)?;
+        }
+
+        self.clock.sleep_until(deadline).await;
+        let Some(alarm) = self.still_ringing(&rang).await else {
+            return Ok(None);
+        };
+        let now = self.clock.now_millis();
+        let allowance = scheduler::snooze_allowance(&alarm.to_input(), alarm.snooze_count, now)?;
+        let action = unattended_action(&timeout, allowance.allowed);
+        log::info!(
+            "Alarm {id} rang unattended for {} min; {action:?}",
+            timeout.minutes
+        );
+        match action {
+            RingTimeoutAction::Snooze => {
+                // The policy's interval, if it has one, replaces this length.
+                let snoozed_until = now + snooze_length_setting(app) as i64 * 60_000;
+                self.snooze_alarm(app, id, snoozed_until, AlarmEventSource::Unattended)
+                    .await?;
+            }
+            RingTimeoutAction::Dismiss => {
+                self.dismiss_alarm(app, id, AlarmEventSource::Unattended)
+                    .await?;
+            }
+        }
+
+        Ok(Some(action))
+    }
+
+    /// The alarm as it is now, if it's still on the occurrence that `rang`; `None` once
+    /// it's been dismissed, snoozed, edited or deleted.
+    async fn still_ringing(&self, rang: &AlarmRecord) -> Option<AlarmRecord> {
+        let alarm = self.db.get_by_id(rang.id).await.ok()?;
+        same_occurrence(rang, &alarm).then_some(alarm)
+    }
+
+    /// Get the occurrence history matching `query`, newest first.
+    ///
+    /// - `query`: alarm and `occurred_at` range to fetch.
+    pub async fn get_alarm_history(&self, query: &HistoryQuery) -> Result<Vec<AlarmHistoryEntry>> {
+        self.db.get_history(query).await
+    }
+
+    /// Summarise the history into wake-up statistics.
+    ///
+    /// - `alarm_id`: alarm to report on, or omitted for every alarm.
+    /// - `from`: earliest epoch-millisecond time to include, or omitted for no limit.
+    /// - `to`: latest epoch-millisecond time to include, or omitted for no limit.
+    pub async fn get_wake_report(
+        &self,
+        alarm_id: Option<i32>,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<WakeReport> {
+        let query = HistoryQuery {
+            alarm_id,
+            from,
+            to,
+            limit: None,
+        };
+        let history = self.db.get_history(&query).await?;
+        let alarms: Vec<_> = self
+            .db
+            .get_all()
+            .await?
+            .iter()
+            .map(AlarmRecord::to_input)
+            .collect();
+        Ok(report::wake_report(&history, &alarms))
+    }
+
     /// Emit an explicit sync request (wear-sync).
     ///
     /// - `app`: app handle for event emission.
@@ -354,6 +1171,9 @@ impl AlarmCoordinator {
             reason,
             revision,
             all_alarms_json,
+            all_timers_json: serde_json::to_string(&self.db.get_timers().await?).ok(),
+            all_groups_json: serde_json::to_string(&self.db.get_groups().await?).ok(),
+            all_pauses_json: serde_json::to_string(&self.db.get_pauses().await?).ok(),
             snooze_length_minutes: snooze,
             is_24_hour,
             is_24_hour_known,
@@ -363,380 +1183,3576 @@ impl AlarmCoordinator {
     }
 
     // =========================================================================
-    // Maintenance & Recovery
+    // Holidays
     // =========================================================================
 
-    /// Initialise the coordinator and heal any inconsistencies.
+    /// Get every imported holiday, across all sources.
+    pub async fn get_holidays(&self) -> Result<Vec<Holiday>> {
+        self.db.get_holidays().await
+    }
+
+    /// Import the all-day events of an .ics calendar as holidays, replacing any earlier
+    /// import under the same name, and reschedule alarms that skip holidays.
     ///
     /// - `app`: app handle for event emission.
-    pub async fn heal_on_launch<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
-        log::info!("🔧 Starting heal-on-launch: syncing alarm-manager cache with DB");
+    /// - `name`: calendar name; stored as source `ics:<name>`.
+    /// - `contents`: raw .ics text.
+    pub async fn import_holidays_ics<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        name: &str,
+        contents: &str,
+    ) -> Result<Vec<Holiday>> {
+        let today = self.clock.now().with_timezone(&chrono::Local).date_naive();
+        let expand_until = today + chrono::Duration::days(365 * holidays::IMPORT_YEARS as i64);
+        let source = format!("ics:{}", name.trim());
 
-        let now = chrono::Utc::now().timestamp_millis();
-        let alarms = self.get_all_alarms(app).await?;
-        // An elapsed next_trigger means the alarm already fired and hasn't been advanced to
-        // its next occurrence yet (that only happens once the user dismisses/snoozes it) --
-        // re-emitting scheduling for it here would hand Kotlin a trigger time in the past,
-        // and AlarmManager.setAlarmClock() fires immediately on a past trigger, causing the
-        // alarm to ring a second time moments after the app cold-starts off its own firing.
-        let due: Vec<_> = alarms
-            .into_iter()
-            .filter(|a| a.enabled && a.next_trigger.is_some_and(|t| t > now))
-            .collect();
+        let imported = holidays::parse_ics(contents, &source, expand_until)?;
+        self.db.replace_holidays(&source, &imported).await?;
+        self.reschedule_holiday_alarms(app).await?;
 
-        log::info!(
-            "Found {} enabled alarms, re-emitting scheduling events",
-            due.len()
-        );
+        self.db.get_holidays().await
+    }
 
-        for alarm in due {
-            // Re-emit scheduling event to heal SharedPreferences cache
-            // We use the alarm's *current* revision because we aren't changing it, just re-syncing
-            self.emit_alarm_scheduled(app, &alarm, alarm.revision)
-                .await?;
-        }
+    /// Import a bundled regional holiday set for this year and the next few, replacing
+    /// any earlier import of that region, and reschedule alarms that skip holidays.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `region`: region code (`CA`, `GB` or `US`).
+    pub async fn import_bundled_holidays<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        region: &str,
+    ) -> Result<Vec<Holiday>> {
+        use chrono::Datelike;
 
-        log::info!("✅ Heal-on-launch complete");
-        Ok(())
+        let year = self.clock.now().with_timezone(&chrono::Local).year();
+        let imported = holidays::bundled_holidays(region, year, year + holidays::IMPORT_YEARS - 1)?;
+        self.db
+            .replace_holidays(&holidays::region_source(region), &imported)
+            .await?;
+        self.reschedule_holiday_alarms(app).await?;
+
+        self.db.get_holidays().await
     }
 
-    /// Run periodic maintenance (tombstone cleanup).
-    pub async fn run_maintenance(&self) -> Result<()> {
-        // Keep tombstones for 30 days
-        self.db.cleanup_tombstones_older_than_days(30).await?;
-        Ok(())
+    /// Remove every holiday from one source and reschedule alarms that skip holidays.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `source`: holiday source to remove (e.g. `region:CA`, `ics:Work`).
+    pub async fn clear_holidays<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        source: &str,
+    ) -> Result<Vec<Holiday>> {
+        self.db.delete_holidays(source).await?;
+        self.reschedule_holiday_alarms(app).await?;
+
+        self.db.get_holidays().await
+    }
+
+    /// Move every enabled alarm that skips holidays onto the changed holiday calendar,
+    /// under a single revision and batch event.
+    ///
+    /// - `app`: app handle for event emission.
+    async fn reschedule_holiday_alarms<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let ctx = ScheduleContext::new(self.db.install_secret().await?)
+            .with_holidays(&self.db.holiday_dates().await?)?;
+
+        let mut moved = Vec::new();
+        for alarm in self.db.get_all().await? {
+            if !alarm.enabled || !alarm.skip_holidays || alarm.mode == AlarmMode::FollowUp {
+                continue;
+            }
+            let input = alarm.to_input();
+            let next_trigger =
+                scheduler::calculate_next_trigger(&input, self.clock.now_millis(), &ctx)?;
+            if occurrence_moved(&input, alarm.next_trigger, next_trigger)? {
+                moved.push((alarm, input, next_trigger));
+            }
+        }
+        if moved.is_empty() {
+            return Ok(());
+        }
+
+        let revision = self.db.next_revision().await?;
+        let mut updated_ids = Vec::with_capacity(moved.len());
+        for (alarm, input, next_trigger) in moved {
+            let updated = self.db.save(input, next_trigger, revision).await?;
+            self.emit_alarm_updated(
+                app,
+                &updated,
+                Some(AlarmSnapshot::from_alarm(&alarm)),
+                revision,
+            )
+            .await?;
+            self.emit_scheduling_events(app, &updated, Some(&alarm), revision)
+                .await?;
+            updated_ids.push(updated.id);
+        }
+        self.refresh_bedtime_reminder(app, revision, false).await?;
+        self.emit_batch_update(app, updated_ids, revision).await
     }
 
     // =========================================================================
-    // Event Emission Helpers
+    // Timers
     // =========================================================================
 
-    /// Emit an alarm created event.
+    /// Get every timer, running, paused or ringing.
+    pub async fn get_timers(&self) -> Result<Vec<Timer>> {
+        self.db.get_timers().await
+    }
+
+    /// Start a countdown timer, scheduled natively like an alarm under its own id.
     ///
     /// - `app`: app handle for event emission.
-    /// - `alarm`: alarm record to include.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_alarm_created<R: Runtime>(
+    /// - `duration_seconds`: how long it runs, up to `MAX_TIMER_SECONDS`.
+    /// - `label`: what it's for, e.g. "Nap"; blank leaves it unlabelled.
+    pub async fn start_timer<R: Runtime>(
         &self,
         app: &AppHandle<R>,
-        alarm: &AlarmRecord,
-        revision: i64,
-    ) -> Result<()> {
-        let event = AlarmCreated {
-            alarm: alarm.clone(),
-            revision,
+        duration_seconds: u32,
+        label: Option<String>,
+    ) -> Result<Timer> {
+        if !(1..=MAX_TIMER_SECONDS).contains(&duration_seconds) {
+            return Err(Error::Validation(format!(
+                "A timer must run for between 1 and {MAX_TIMER_SECONDS} seconds"
+            )));
+        }
+        let label = label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty());
+        let now = self.clock.now_millis();
+
+        let revision = self.db.next_revision().await?;
+        let timer = self
+            .db
+            .insert_timer(
+                label.as_deref(),
+                duration_seconds,
+                now + duration_seconds as i64 * 1_000,
+                now,
+                revision,
+            )
+            .await?;
+        self.emit_timer_updated(app, &timer, revision).await?;
+        Ok(timer)
+    }
+
+    /// Pause a running timer, keeping the time it has left.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: timer identifier.
+    pub async fn pause_timer<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<Timer> {
+        let timer = self.db.get_timer(id).await?;
+        let Some(remaining_ms) = pause_remaining(&timer, self.clock.now_millis()) else {
+            return Err(Error::Validation(
+                "Only a timer that's still counting down can be paused".into(),
+            ));
         };
-        app.emit("alarm:created", &event)?;
+
+        let revision = self.db.next_revision().await?;
+        let paused = self
+            .db
+            .update_timer(id, TimerState::Paused, None, Some(remaining_ms), revision)
+            .await?;
+        self.emit_timer_updated(app, &paused, revision).await?;
+        Ok(paused)
+    }
+
+    /// Resume a paused timer from the time it had left.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: timer identifier.
+    pub async fn resume_timer<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<Timer> {
+        let timer = self.db.get_timer(id).await?;
+        let (TimerState::Paused, Some(remaining_ms)) = (timer.state, timer.remaining_ms) else {
+            return Err(Error::Validation(
+                "Only a paused timer can be resumed".into(),
+            ));
+        };
+
+        let revision = self.db.next_revision().await?;
+        let resumed = self
+            .db
+            .update_timer(
+                id,
+                TimerState::Running,
+                Some(self.clock.now_millis() + remaining_ms),
+                None,
+                revision,
+            )
+            .await?;
+        self.emit_timer_updated(app, &resumed, revision).await?;
+        Ok(resumed)
+    }
+
+    /// Cancel a timer, stopping it if it's ringing.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: timer identifier.
+    pub async fn cancel_timer<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<()> {
+        let timer = self.db.get_timer(id).await?;
+        if pause_remaining(&timer, self.clock.now_millis()).is_none()
+            && timer.state == TimerState::Running
+        {
+            return self.dismiss_timer(app, id).await;
+        }
+        self.remove_timer(app, &timer, CancelReason::Deleted)
+            .await?;
         Ok(())
     }
 
-    /// Emit an alarm updated event with an optional snapshot.
+    /// Stop a timer that has run out, wherever it's ringing, and remove it.
     ///
     /// - `app`: app handle for event emission.
-    /// - `alarm`: updated alarm record.
-    /// - `previous`: optional snapshot of the prior state.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_alarm_updated<R: Runtime>(
-        &self,
-        app: &AppHandle<R>,
-        alarm: &AlarmRecord,
-        previous: Option<AlarmSnapshot>,
-        revision: i64,
-    ) -> Result<()> {
-        let event = AlarmUpdated {
-            alarm: alarm.clone(),
-            previous,
+    /// - `id`: timer identifier.
+    async fn dismiss_timer<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<()> {
+        let timer = self.db.get_timer(id).await?;
+        let revision = self
+            .remove_timer(app, &timer, CancelReason::Expired)
+            .await?;
+
+        let dismissed_at = self.clock.now_millis();
+        let event = AlarmDismissed {
+            id: timer_native_id(id),
+            fired_at: timer.ends_at.unwrap_or(dismissed_at),
+            dismissed_at,
+            next_trigger: None,
             revision,
         };
-        app.emit("alarm:updated", &event)?;
+        app.emit("alarm:dismissed", &event)?;
         Ok(())
     }
 
-    /// Emit an alarm deleted event.
+    /// Report that a timer ran out, for it to ring on the phone and the watch. Returns
+    /// `false` if it was cancelled as it fired, for the caller to stop the ringing.
     ///
     /// - `app`: app handle for event emission.
-    /// - `id`: deleted alarm identifier.
-    /// - `label`: optional label for UI display.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_alarm_deleted<R: Runtime>(
+    /// - `id`: timer identifier.
+    /// - `actual_fired_at`: wall-clock firing time in epoch milliseconds.
+    async fn report_timer_fired<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         id: i32,
-        label: Option<String>,
-        revision: i64,
-    ) -> Result<()> {
-        let event = AlarmDeleted {
-            id,
-            label,
-            revision,
+        actual_fired_at: i64,
+    ) -> Result<bool> {
+        let Ok(timer) = self.db.get_timer(id).await else {
+            log::info!("Timer {id} fired after it was cancelled; stopping it");
+            return Ok(false);
         };
-        app.emit("alarm:deleted", &event)?;
-        Ok(())
+        let (is_24_hour, is_24_hour_known) = time_format_setting(app);
+
+        let event = AlarmFired {
+            id: timer_native_id(id),
+            trigger_at: timer.ends_at.unwrap_or(actual_fired_at),
+            actual_fired_at,
+            label: Some(timer.label.unwrap_or_else(|| "Timer".into())),
+            revision: self.db.current_revision().await?,
+            snooze_length_minutes: snooze_length_setting(app),
+            snooze_allowed: false,
+            is_24_hour,
+            is_24_hour_known,
+            pre_alarm_for: None,
+            ring_seconds: None,
+            backup_for: None,
+            timer_for: Some(id),
+        };
+        app.emit("alarm:fired", &event)?;
+        Ok(true)
     }
 
-    /// Emit scheduling events based on the previous and next state.
+    /// Re-schedule running timers after a restart, and remove any that ran out more
+    /// than the missed-alarm grace window ago with nobody dismissing them.
     ///
     /// - `app`: app handle for event emission.
-    /// - `alarm`: updated alarm record.
-    /// - `previous`: prior alarm record (if any).
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_scheduling_events<R: Runtime>(
+    /// - `now`: current epoch-millisecond time.
+    /// - `grace_ms`: the missed-alarm grace window.
+    async fn heal_timers<R: Runtime>(
         &self,
         app: &AppHandle<R>,
-        alarm: &AlarmRecord,
-        previous: Option<&AlarmRecord>,
-        revision: i64,
+        now: i64,
+        grace_ms: i64,
     ) -> Result<()> {
-        match classify_scheduling_transition(previous, alarm) {
-            SchedulingTransition::Schedule => {
-                self.emit_alarm_scheduled(app, alarm, revision).await?;
-            }
-            SchedulingTransition::Cancel(reason) => {
-                self.emit_alarm_cancelled(app, alarm.id, reason, revision)
+        for timer in self.db.get_timers().await? {
+            if timer_abandoned(&timer, now, grace_ms) {
+                log::info!("Timer {} ran out unattended; removing it", timer.id);
+                self.remove_timer(app, &timer, CancelReason::Expired)
                     .await?;
-            }
-            SchedulingTransition::Reschedule => {
-                self.emit_alarm_cancelled(app, alarm.id, CancelReason::Updated, revision)
+            } else if pause_remaining(&timer, now).is_some() {
+                self.emit_timer_scheduled(app, &timer, timer.revision)
                     .await?;
-                self.emit_alarm_scheduled(app, alarm, revision).await?;
             }
-            SchedulingTransition::NoOp => {}
         }
-
         Ok(())
     }
 
-    /// Emit an alarm scheduled event.
+    /// Delete a timer and cancel its native schedule, under a new revision that's
+    /// returned.
     ///
     /// - `app`: app handle for event emission.
-    /// - `alarm`: alarm record to schedule.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_alarm_scheduled<R: Runtime>(
+    /// - `timer`: timer to remove.
+    /// - `reason`: why its native schedule is cancelled.
+    async fn remove_timer<R: Runtime>(
         &self,
         app: &AppHandle<R>,
-        alarm: &AlarmRecord,
-        revision: i64,
-    ) -> Result<()> {
-        if let Some(trigger) = alarm.next_trigger {
-            let event = AlarmScheduled {
-                id: alarm.id,
-                trigger_at: trigger,
-                sound_uri: alarm.sound_uri.clone(),
-                label: alarm.label.clone(),
-                mode: alarm.mode.clone(),
-                revision,
-            };
-            app.emit("alarm:scheduled", &event)?;
-        }
-        Ok(())
-    }
+        timer: &Timer,
+        reason: CancelReason,
+    ) -> Result<i64> {
+        let revision = self.db.next_revision().await?;
+        self.db.delete_timer(timer.id).await?;
+        self.emit_alarm_cancelled(app, timer_native_id(timer.id), reason, revision)
+            .await?;
 
-    /// Emit an alarm cancelled event.
+        let event = TimerRemoved {
+            id: timer.id,
+            label: timer.label.clone(),
+            revision,
+        };
+        app.emit("timer:removed", &event)?;
+        self.emit_batch_update(app, vec![timer_native_id(timer.id)], revision)
+            .await?;
+        Ok(revision)
+    }
+
+    // =========================================================================
+    // Groups
+    // =========================================================================
+
+    /// Get every alarm group, by name.
+    pub async fn get_groups(&self) -> Result<Vec<AlarmGroup>> {
+        self.db.get_groups().await
+    }
+
+    /// Create or update an alarm group.
     ///
     /// - `app`: app handle for event emission.
-    /// - `id`: alarm identifier.
-    /// - `reason`: cancellation reason.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_alarm_cancelled<R: Runtime>(
+    /// - `id`: group to update, or `None` to create one.
+    /// - `name`: group name, unique regardless of case.
+    /// - `alarm_ids`: the alarms in the group.
+    pub async fn save_group<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: Option<i32>,
+        name: String,
+        mut alarm_ids: Vec<i32>,
+    ) -> Result<AlarmGroup> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(Error::Validation("A group needs a name".into()));
+        }
+        let groups = self.db.get_groups().await?;
+        if groups
+            .iter()
+            .any(|g| Some(g.id) != id && g.name.eq_ignore_ascii_case(&name))
+        {
+            return Err(Error::Validation(format!(
+                "There's already a group called {name}"
+            )));
+        }
+        let previous = match id {
+            Some(id) => Some(self.db.get_group(id).await?),
+            None => None,
+        };
+        alarm_ids.sort_unstable();
+        alarm_ids.dedup();
+        for alarm_id in &alarm_ids {
+            self.db.get_by_id(*alarm_id).await?;
+        }
+
+        let revision = self.db.next_revision().await?;
+        let group = self.db.save_group(id, &name, &alarm_ids, revision).await?;
+        let event = AlarmGroupUpdated {
+            group: group.clone(),
+            revision,
+        };
+        app.emit("group:updated", &event)?;
+
+        // Alarms joining or leaving the group are what the watch has to hear about.
+        let mut changed = group.alarm_ids.clone();
+        changed.extend(previous.into_iter().flat_map(|p| p.alarm_ids));
+        changed.sort_unstable();
+        changed.dedup();
+        self.emit_batch_update(app, changed, revision).await?;
+        Ok(group)
+    }
+
+    /// Delete an alarm group, leaving its alarms as they are.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: group identifier.
+    pub async fn delete_group<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<()> {
+        let group = self.db.get_group(id).await?;
+
+        let revision = self.db.next_revision().await?;
+        self.db.delete_group(id).await?;
+        let event = AlarmGroupDeleted {
+            id,
+            name: group.name,
+            revision,
+        };
+        app.emit("group:deleted", &event)?;
+        self.emit_batch_update(app, group.alarm_ids, revision).await
+    }
+
+    /// Switch every alarm in a group on or off together, under a single revision and
+    /// batch event. Returns the alarms that changed.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: group identifier.
+    /// - `enabled`: desired enabled state.
+    pub async fn set_group_enabled<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         id: i32,
+        enabled: bool,
+    ) -> Result<Vec<AlarmRecord>> {
+        let group = self.db.get_group(id).await?;
+        let alarms: Vec<_> = self
+            .db
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|a| a.enabled != enabled && group.alarm_ids.contains(&a.id))
+            .collect();
+        if alarms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let revision = self.db.next_revision().await?;
+        let switched = self.switch_alarms(app, alarms, enabled, revision).await?;
+        self.emit_batch_update(app, switched.iter().map(|a| a.id).collect(), revision)
+            .await?;
+        Ok(switched)
+    }
+
+    /// Switch alarms on or off under `revision`, emitting their updates and scheduling
+    /// events, then moving the bedtime reminder once for the lot; the caller seals them
+    /// with a batch event. Returns them as switched.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarms`: alarms to switch, none of them already in that state.
+    /// - `enabled`: desired enabled state.
+    /// - `revision`: revision stamped on the mutation.
+    async fn switch_alarms<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarms: Vec<AlarmRecord>,
+        enabled: bool,
+        revision: i64,
+    ) -> Result<Vec<AlarmRecord>> {
+        let mut switched = Vec::with_capacity(alarms.len());
+        for alarm in alarms {
+            let input = AlarmInput {
+                enabled,
+                ..alarm.to_input()
+            };
+            // Only a dismissal sets a follow-up's trigger, so one switched on waits.
+            let next_trigger = if enabled && input.mode != AlarmMode::FollowUp {
+                let ctx = self.schedule_context(&input).await?;
+                scheduler::calculate_next_trigger(&input, self.clock.now_millis(), &ctx)?
+            } else {
+                None
+            };
+            let updated = self.db.save(input, next_trigger, revision).await?;
+            self.emit_alarm_updated(
+                app,
+                &updated,
+                Some(AlarmSnapshot::from_alarm(&alarm)),
+                revision,
+            )
+            .await?;
+            self.emit_scheduling_events(app, &updated, Some(&alarm), revision)
+                .await?;
+            switched.push(updated);
+        }
+        self.refresh_bedtime_reminder(app, revision, false).await?;
+        Ok(switched)
+    }
+
+    // =========================================================================
+    // Pauses
+    // =========================================================================
+
+    /// Get every pause, soonest to end first.
+    pub async fn get_pauses(&self) -> Result<Vec<AlarmPause>> {
+        self.db.get_pauses().await
+    }
+
+    /// Switch alarms off until a date, remembering which were on to switch back on
+    /// then. Pausing what's already paused moves the pause's end instead.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `group_id`: group to pause, or `None` for every alarm.
+    /// - `until`: "YYYY-MM-DD" the alarms come back on; a day after today at soonest.
+    pub async fn pause_alarms<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        group_id: Option<i32>,
+        until: String,
+    ) -> Result<AlarmPause> {
+        let today = self.clock.now().with_timezone(&chrono::Local).date_naive();
+        let resumes_at = pause_resumes_at(&until, today)?;
+        let scope = match group_id {
+            Some(id) => Some(self.db.get_group(id).await?.alarm_ids),
+            None => None,
+        };
+        let existing = self
+            .db
+            .get_pauses()
+            .await?
+            .into_iter()
+            .find(|p| p.group_id == group_id);
+        let alarms: Vec<_> = self
+            .db
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|a| a.enabled && scope.as_ref().is_none_or(|ids| ids.contains(&a.id)))
+            .collect();
+        let alarm_ids: Vec<_> = alarms.iter().map(|a| a.id).collect();
+
+        let revision = self.db.next_revision().await?;
+        let pause = self
+            .db
+            .save_pause(
+                existing.map(|p| p.id),
+                group_id,
+                &until,
+                resumes_at,
+                &alarm_ids,
+                revision,
+            )
+            .await?;
+        self.switch_alarms(app, alarms, false, revision).await?;
+        self.emit_pause_end_scheduled(app, &pause, revision).await?;
+
+        let event = AlarmPauseUpdated {
+            pause: pause.clone(),
+            revision,
+        };
+        app.emit("pause:updated", &event)?;
+        self.emit_batch_update(app, alarm_ids, revision).await?;
+        Ok(pause)
+    }
+
+    /// End a pause early, switching its alarms back on.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: pause identifier.
+    pub async fn end_pause<R: Runtime>(&self, app: &AppHandle<R>, id: i32) -> Result<()> {
+        let pause = self.db.get_pause(id).await?;
+        self.resume_paused(app, pause, CancelReason::Deleted).await
+    }
+
+    /// End every pause whose date has come. Run on launch, when a pause's native
+    /// wake-up fires, and periodically while the app is up.
+    ///
+    /// - `app`: app handle for event emission.
+    pub async fn end_due_pauses<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let now = self.clock.now_millis();
+        for pause in self.db.get_pauses().await? {
+            if pause.resumes_at <= now {
+                log::info!(
+                    "Pause {} reached {}; resuming its alarms",
+                    pause.id,
+                    pause.until
+                );
+                self.resume_paused(app, pause, CancelReason::Expired)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a pause, cancel its native wake-up and switch its alarms back on, under
+    /// one revision and batch event. An alarm another pause still covers is handed on
+    /// to that pause instead.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `pause`: pause that's ending.
+    /// - `reason`: why its wake-up is cancelled: it came due, or it was ended early.
+    async fn resume_paused<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        pause: AlarmPause,
         reason: CancelReason,
+    ) -> Result<()> {
+        let groups = self.db.get_groups().await?;
+        let others: Vec<_> = self
+            .db
+            .get_pauses()
+            .await?
+            .into_iter()
+            .filter(|p| p.id != pause.id)
+            .collect();
+
+        let revision = self.db.next_revision().await?;
+        self.db.delete_pause(pause.id).await?;
+        self.emit_alarm_cancelled(app, pause_end_native_id(pause.id), reason, revision)
+            .await?;
+        let mut restore = Vec::new();
+        for alarm_id in &pause.alarm_ids {
+            match others.iter().find(|p| pause_covers(p, &groups, *alarm_id)) {
+                Some(other) => {
+                    self.db
+                        .save_pause(
+                            Some(other.id),
+                            other.group_id,
+                            &other.until,
+                            other.resumes_at,
+                            &[*alarm_id],
+                            revision,
+                        )
+                        .await?;
+                }
+                None => restore.push(*alarm_id),
+            }
+        }
+        let alarms: Vec<_> = self
+            .db
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|a| !a.enabled && restore.contains(&a.id))
+            .collect();
+        let restored: Vec<_> = self
+            .switch_alarms(app, alarms, true, revision)
+            .await?
+            .iter()
+            .map(|a| a.id)
+            .collect();
+
+        let event = AlarmPauseEnded {
+            id: pause.id,
+            group_id: pause.group_id,
+            restored_ids: restored,
+            revision,
+        };
+        app.emit("pause:ended", &event)?;
+        self.emit_batch_update(app, pause.alarm_ids, revision).await
+    }
+
+    // =========================================================================
+    // Bedtime Reminder
+    // =========================================================================
+
+    /// The bedtime reminder's settings; off until someone opts in.
+    pub async fn get_bedtime_reminder(&self) -> Result<BedtimeReminder> {
+        let stored = self.db.get_setting(BEDTIME_REMINDER_SETTING).await?;
+        Ok(stored
+            .and_then(|value| {
+                serde_json::from_str(&value)
+                    .inspect_err(|e| log::warn!("Invalid bedtime reminder '{value}': {e}"))
+                    .ok()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Change the bedtime reminder's settings, and move or cancel the reminder to suit.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `reminder`: sleep target between `MIN_BEDTIME_SLEEP_MINUTES` and
+    ///   `MAX_BEDTIME_SLEEP_MINUTES`, and a wind-down of at most
+    ///   `MAX_BEDTIME_WIND_DOWN_MINUTES`.
+    pub async fn set_bedtime_reminder<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        reminder: BedtimeReminder,
+    ) -> Result<BedtimeReminder> {
+        if !(MIN_BEDTIME_SLEEP_MINUTES..=MAX_BEDTIME_SLEEP_MINUTES)
+            .contains(&reminder.sleep_minutes)
+        {
+            return Err(Error::Validation(format!(
+                "Target sleep must be between {MIN_BEDTIME_SLEEP_MINUTES} and \
+                 {MAX_BEDTIME_SLEEP_MINUTES} minutes"
+            )));
+        }
+        if reminder.wind_down_minutes > MAX_BEDTIME_WIND_DOWN_MINUTES {
+            return Err(Error::Validation(format!(
+                "Wind-down can be at most {MAX_BEDTIME_WIND_DOWN_MINUTES} minutes"
+            )));
+        }
+        let value = serde_json::to_string(&reminder)
+            .map_err(|e| Error::Validation(format!("Invalid bedtime reminder: {e}")))?;
+        self.db
+            .set_setting(BEDTIME_REMINDER_SETTING, &value)
+            .await?;
+
+        let revision = self.db.next_revision().await?;
+        self.refresh_bedtime_reminder(app, revision, false).await?;
+        Ok(reminder)
+    }
+
+    /// Move the bedtime reminder to follow the next alarm, scheduling, rescheduling or
+    /// cancelling it natively when its time changes.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `revision`: revision stamped on the mutation that may have moved it.
+    /// - `resync`: re-emit an unchanged reminder too, to heal the native schedule.
+    async fn refresh_bedtime_reminder<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
         revision: i64,
+        resync: bool,
     ) -> Result<()> {
-        let event = AlarmCancelled {
+        let reminder = self.get_bedtime_reminder().await?;
+        let next = bedtime_reminder_at(
+            &self.db.get_all().await?,
+            &reminder,
+            self.clock.now_millis(),
+        );
+        let scheduled = self
+            .db
+            .get_setting(BEDTIME_REMINDER_AT_SETTING)
+            .await?
+            .and_then(|at| at.parse().ok());
+
+        let transition = match classify_bedtime_transition(scheduled, next, reminder.enabled) {
+            SchedulingTransition::NoOp if resync && next.is_some() => {
+                SchedulingTransition::Schedule
+            }
+            transition => transition,
+        };
+        match transition {
+            SchedulingTransition::Schedule => {}
+            SchedulingTransition::Cancel(reason) => {
+                self.emit_alarm_cancelled(app, BEDTIME_REMINDER_ID, reason, revision)
+                    .await?;
+            }
+            SchedulingTransition::Reschedule => {
+                self.emit_alarm_cancelled(
+                    app,
+                    BEDTIME_REMINDER_ID,
+                    CancelReason::Updated,
+                    revision,
+                )
+                .await?;
+            }
+            SchedulingTransition::NoOp => return Ok(()),
+        }
+
+        match next {
+            Some(at) => {
+                self.emit_bedtime_reminder_scheduled(app, at, revision)
+                    .await?;
+                self.db
+                    .set_setting(BEDTIME_REMINDER_AT_SETTING, &at.to_string())
+                    .await
+            }
+            None => self.db.delete_setting(BEDTIME_REMINDER_AT_SETTING).await,
+        }
+    }
+
+    // =========================================================================
+    // Maintenance & Recovery
+    // =========================================================================
+
+    /// Initialise the coordinator and heal any inconsistencies.
+    ///
+    /// - `app`: app handle for event emission.
+    pub async fn heal_on_launch<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        log::info!("🔧 Starting heal-on-launch: syncing alarm-manager cache with DB");
+
+        let now = self.clock.now_millis();
+        let grace_ms = self.get_missed_grace_minutes().await? as i64 * 60_000;
+        let alarms = self.get_all_alarms(app).await?;
+
+        // The app was down from when it was last seen running until this launch.
+        let down_since = self
+            .db
+            .get_setting(LAST_ALIVE_SETTING)
+            .await?
+            .and_then(|value| value.parse().ok());
+        self.advance_all_missed(app, &alarms, now, grace_ms, down_since)
+            .await;
+
+        // Re-emitting scheduling for an elapsed trigger would hand Kotlin a trigger time in
+        // the past, and AlarmManager.setAlarmClock() fires immediately on a past trigger,
+        // causing the alarm to ring a second time moments after the app cold-starts off its
+        // own firing. Missed alarms were rescheduled as they were advanced.
+        let due: Vec<_> = alarms
+            .into_iter()
+            .filter(|a| a.enabled && a.next_trigger.is_some_and(|t| t > now))
+            .collect();
+
+        log::info!(
+            "Found {} enabled alarms, re-emitting scheduling events",
+            due.len()
+        );
+
+        for alarm in due {
+            // Re-emit scheduling event to heal SharedPreferences cache
+            // We use the alarm's *current* revision because we aren't changing it, just re-syncing
+            self.emit_alarm_scheduled(app, &alarm, alarm.revision)
+                .await?;
+            self.emit_pre_alarm_scheduled(app, &alarm, alarm.revision)
+                .await?;
+        }
+        self.heal_timers(app, now, grace_ms).await?;
+        self.end_due_pauses(app).await?;
+        for pause in self.db.get_pauses().await? {
+            self.emit_pause_end_scheduled(app, &pause, pause.revision)
+                .await?;
+        }
+        let revision = self.db.current_revision().await?;
+        self.refresh_bedtime_reminder(app, revision, true).await?;
+        self.db
+            .set_setting(LAST_ALIVE_SETTING, &now.to_string())
+            .await?;
+
+        log::info!("✅ Heal-on-launch complete");
+        Ok(())
+    }
+
+    /// Look for occurrences that went past their grace window while the app was
+    /// running, and record and move them on as on launch. Also notes the app as
+    /// running, for telling the next launch how long it was down.
+    ///
+    /// - `app`: app handle for event emission.
+    pub async fn detect_missed<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let now = self.clock.now_millis();
+        let grace_ms = self.get_missed_grace_minutes().await? as i64 * 60_000;
+        let alarms = self.db.get_all().await?;
+        self.advance_all_missed(app, &alarms, now, grace_ms, None)
+            .await;
+        self.db
+            .set_setting(LAST_ALIVE_SETTING, &now.to_string())
+            .await
+    }
+
+    /// Advance every enabled alarm whose trigger is past the grace window.
+    ///
+    /// An elapsed next_trigger means the alarm already fired and hasn't been advanced to
+    /// its next occurrence yet (that only happens once the user dismisses/snoozes it).
+    /// Within the grace window it may well still be ringing -- often it's what just
+    /// cold-started the app -- so it's left alone. Past it, nobody is coming back for
+    /// that occurrence, and it's recorded as missed and moved on.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarms`: every alarm.
+    /// - `now`: current epoch-millisecond time.
+    /// - `grace_ms`: the missed-alarm grace window.
+    /// - `down_since`: when the app was last seen running, if it has just launched.
+    async fn advance_all_missed<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarms: &[AlarmRecord],
+        now: i64,
+        grace_ms: i64,
+        down_since: Option<i64>,
+    ) {
+        for alarm in alarms
+            .iter()
+            .filter(|a| a.enabled && a.next_trigger.is_some_and(|t| t <= now - grace_ms))
+        {
+            if let Err(e) = self
+                .advance_missed(app, alarm.clone(), now, down_since)
+                .await
+            {
+                log::error!("Failed to advance missed alarm {}: {e}", alarm.id);
+            }
+        }
+    }
+
+    /// Record an occurrence that was never dismissed as missed, and move its alarm on to
+    /// the next occurrence after `now` (or switch off a spent one-shot).
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm whose trigger is past the grace window.
+    /// - `now`: current epoch-millisecond time.
+    /// - `down_since`: when the app was last seen running, if it has just launched.
+    async fn advance_missed<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: AlarmRecord,
+        now: i64,
+        down_since: Option<i64>,
+    ) -> Result<()> {
+        let Some(trigger_at) = alarm.next_trigger else {
+            return Ok(());
+        };
+        let last_rang = self
+            .db
+            .last_fired_at(alarm.id, alarm.snoozed_from.unwrap_or(trigger_at))
+            .await?;
+        let reason = classify_missed(trigger_at, last_rang, down_since);
+
+        let new_alarm = self
+            .dismiss_occurrence(app, alarm.clone(), Some(now))
+            .await?;
+        self.record_history(
+            &alarm,
+            AlarmEventKind::Missed,
+            AlarmEventSource::System,
+            new_alarm.next_trigger,
+        )
+        .await;
+
+        log::info!(
+            "Alarm {} missed its {trigger_at} trigger ({reason:?}); next is {:?}",
+            alarm.id,
+            new_alarm.next_trigger
+        );
+        let event = AlarmMissed {
+            id: alarm.id,
+            label: alarm.label.clone(),
+            trigger_at,
+            reason,
+            next_trigger: new_alarm.next_trigger,
+            revision: new_alarm.revision,
+        };
+        app.emit("alarm:missed", &event)?;
+
+        Ok(())
+    }
+
+    /// How long past its trigger an undismissed occurrence is left before it counts as
+    /// missed.
+    pub async fn get_missed_grace_minutes(&self) -> Result<u32> {
+        let stored = self.db.get_setting(MISSED_GRACE_SETTING).await?;
+        Ok(stored
+            .and_then(|value| {
+                value
+                    .parse()
+                    .inspect_err(|e| log::warn!("Invalid missed-alarm grace '{value}': {e}"))
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_MISSED_GRACE_MINUTES))
+    }
+
+    /// Set the missed-alarm grace window.
+    ///
+    /// - `minutes`: between `MIN_MISSED_GRACE_MINUTES` and `MAX_MISSED_GRACE_MINUTES`.
+    pub async fn set_missed_grace_minutes(&self, minutes: u32) -> Result<()> {
+        if !(MIN_MISSED_GRACE_MINUTES..=MAX_MISSED_GRACE_MINUTES).contains(&minutes) {
+            return Err(Error::Validation(format!(
+                "Missed-alarm grace must be between {MIN_MISSED_GRACE_MINUTES} and \
+                 {MAX_MISSED_GRACE_MINUTES} minutes"
+            )));
+        }
+        self.db
+            .set_setting(MISSED_GRACE_SETTING, &minutes.to_string())
+            .await
+    }
+
+    /// Run periodic maintenance (tombstone and history cleanup).
+    pub async fn run_maintenance(&self) -> Result<()> {
+        // Keep tombstones for 30 days
+        self.db
+            .cleanup_tombstones_older_than_days(30, self.clock.now_millis())
+            .await?;
+        // Keep a year of occurrence history
+        self.db
+            .cleanup_history_older_than_days(365, self.clock.now_millis())
+            .await?;
+        Ok(())
+    }
+
+    /// Add an event for the alarm's current occurrence to the history. The history is a
+    /// record rather than state, so a failed write is logged instead of failing the
+    /// action that's already been taken.
+    ///
+    /// - `alarm`: alarm as it was before the event.
+    /// - `kind`: what happened to the occurrence.
+    /// - `source`: where it came from.
+    /// - `next_trigger`: when the alarm rings next as a result.
+    async fn record_history(
+        &self,
+        alarm: &AlarmRecord,
+        kind: AlarmEventKind,
+        source: AlarmEventSource,
+        next_trigger: Option<i64>,
+    ) {
+        let scheduled_trigger = alarm.snoozed_from.or(alarm.next_trigger);
+        let result = async {
+            let actual_fired_at = match scheduled_trigger {
+                Some(trigger) => self.db.last_fired_at(alarm.id, trigger).await?,
+                None => None,
+            };
+            let entry = AlarmHistoryEntry {
+                id: 0,
+                alarm_id: alarm.id,
+                kind,
+                source,
+                scheduled_trigger,
+                actual_fired_at,
+                occurred_at: self.clock.now_millis(),
+                next_trigger,
+                sampled: Some(alarm.mode.is_sampled()),
+            };
+            self.db.record_history(&entry).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            log::warn!(
+                "Failed to record {kind:?} for alarm {} in history: {e}",
+                alarm.id
+            );
+        }
+    }
+
+    /// Load the scheduler's non-alarm inputs -- only what this alarm actually uses.
+    ///
+    /// - `input`: alarm about to be scheduled.
+    async fn schedule_context(&self, input: &AlarmInput) -> Result<ScheduleContext> {
+        let ctx = ScheduleContext::new(self.db.install_secret().await?);
+        if input.skip_holidays.unwrap_or(false) {
+            ctx.with_holidays(&self.db.holiday_dates().await?)
+        } else {
+            Ok(ctx)
+        }
+    }
+
+    // =========================================================================
+    // Event Emission Helpers
+    // =========================================================================
+
+    /// Emit an alarm created event.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm record to include.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_alarm_created<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmCreated {
+            alarm: alarm.clone(),
+            revision,
+        };
+        app.emit("alarm:created", &event)?;
+        Ok(())
+    }
+
+    /// Emit an alarm updated event with an optional snapshot.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: updated alarm record.
+    /// - `previous`: optional snapshot of the prior state.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_alarm_updated<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        previous: Option<AlarmSnapshot>,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmUpdated {
+            alarm: alarm.clone(),
+            previous,
+            revision,
+        };
+        app.emit("alarm:updated", &event)?;
+        Ok(())
+    }
+
+    /// Emit an alarm deleted event.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: deleted alarm identifier.
+    /// - `label`: optional label for UI display.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_alarm_deleted<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        label: Option<String>,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmDeleted {
+            id,
+            label,
+            revision,
+        };
+        app.emit("alarm:deleted", &event)?;
+        Ok(())
+    }
+
+    /// Emit scheduling events based on the previous and next state.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: updated alarm record.
+    /// - `previous`: prior alarm record (if any).
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_scheduling_events<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        previous: Option<&AlarmRecord>,
+        revision: i64,
+    ) -> Result<()> {
+        let transition = classify_scheduling_transition(previous, alarm);
+        match transition {
+            SchedulingTransition::Schedule => {
+                self.emit_alarm_scheduled(app, alarm, revision).await?;
+            }
+            SchedulingTransition::Cancel(reason) => {
+                self.emit_alarm_cancelled(app, alarm.id, reason, revision)
+                    .await?;
+            }
+            SchedulingTransition::Reschedule => {
+                self.emit_alarm_cancelled(app, alarm.id, CancelReason::Updated, revision)
+                    .await?;
+                self.emit_alarm_scheduled(app, alarm, revision).await?;
+            }
+            SchedulingTransition::NoOp => {}
+        }
+
+        // A new alarm is checked even with nothing to schedule: a one-shot whose only
+        // time the clocks skip would otherwise vanish without a word.
+        if transition != SchedulingTransition::NoOp || previous.is_none() {
+            self.emit_dst_warning(app, alarm, revision).await?;
+        }
+
+        self.emit_pre_alarm_events(app, alarm, previous, revision)
+            .await
+    }
+
+    /// Emit the pre-alarm's own scheduling events, under its native id.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: updated alarm record.
+    /// - `previous`: prior alarm record (if any).
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_pre_alarm_events<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        previous: Option<&AlarmRecord>,
+        revision: i64,
+    ) -> Result<()> {
+        let pre_id = pre_alarm_native_id(alarm.id);
+        match classify_pre_alarm_transition(previous, alarm, self.clock.now_millis()) {
+            SchedulingTransition::Schedule => {
+                self.emit_pre_alarm_scheduled(app, alarm, revision).await?;
+            }
+            SchedulingTransition::Cancel(reason) => {
+                self.emit_alarm_cancelled(app, pre_id, reason, revision)
+                    .await?;
+            }
+            SchedulingTransition::Reschedule => {
+                self.emit_alarm_cancelled(app, pre_id, CancelReason::Updated, revision)
+                    .await?;
+                self.emit_pre_alarm_scheduled(app, alarm, revision).await?;
+            }
+            SchedulingTransition::NoOp => {}
+        }
+        Ok(())
+    }
+
+    /// Warn when an upcoming occurrence falls on a DST change, so a skipped or moved
+    /// alarm is never a surprise.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm record just (re)scheduled or cancelled.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_dst_warning<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        revision: i64,
+    ) -> Result<()> {
+        if !alarm.enabled {
+            return Ok(());
+        }
+        let input = alarm.to_input();
+        let ctx = self.schedule_context(&input).await?;
+        let Some(adjustment) =
+            scheduler::dst_adjustment(&input, self.clock.now_millis(), alarm.next_trigger, &ctx)?
+        else {
+            return Ok(());
+        };
+
+        let event = AlarmDstAdjusted {
+            id: alarm.id,
+            label: alarm.label.clone(),
+            date: adjustment.date.format("%Y-%m-%d").to_string(),
+            local_time: adjustment.time.format("%H:%M").to_string(),
+            transition: adjustment.transition,
+            policy: alarm.dst_policy,
+            next_trigger: alarm.next_trigger,
+            revision,
+        };
+        app.emit("alarm:dst:adjusted", &event)?;
+        Ok(())
+    }
+
+    /// Emit an alarm scheduled event.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm record to schedule.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_alarm_scheduled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        revision: i64,
+    ) -> Result<()> {
+        if let Some(trigger) = alarm.next_trigger {
+            let snooze_allowed =
+                scheduler::snooze_allowance(&alarm.to_input(), alarm.snooze_count, trigger)
+                    .map(|a| a.allowed)
+                    .unwrap_or(true);
+            let event = AlarmScheduled {
+                id: alarm.id,
+                trigger_at: trigger,
+                sound_uri: alarm.sound_uri.clone(),
+                label: alarm.label.clone(),
+                mode: alarm.mode.clone(),
+                revision,
+                pre_alarm_for: None,
+                volume_percent: None,
+                ring_seconds: None,
+                snooze_allowed: (!snooze_allowed).then_some(false),
+                backup_for: None,
+                watch_only: None,
+                timer_for: None,
+                notification_only: None,
+                wake_only: None,
+            };
+            app.emit("alarm:scheduled", &event)?;
+        }
+        Ok(())
+    }
+
+    /// Emit a scheduled event for an alarm's pre-alarm, if it has one still to ring.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `alarm`: alarm record whose pre-alarm to schedule.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_pre_alarm_scheduled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        alarm: &AlarmRecord,
+        revision: i64,
+    ) -> Result<()> {
+        if let Some((trigger_at, pre_alarm)) = pending_pre_alarm(alarm, self.clock.now_millis()) {
+            let event = AlarmScheduled {
+                id: pre_alarm_native_id(alarm.id),
+                trigger_at,
+                sound_uri: pre_alarm.sound_uri.clone(),
+                label: alarm.label.clone(),
+                mode: alarm.mode.clone(),
+                revision,
+                pre_alarm_for: Some(alarm.id),
+                volume_percent: Some(pre_alarm.volume_percent),
+                ring_seconds: Some(pre_alarm.ring_seconds),
+                snooze_allowed: None,
+                backup_for: None,
+                watch_only: None,
+                timer_for: None,
+                notification_only: None,
+                wake_only: None,
+            };
+            app.emit("alarm:scheduled", &event)?;
+        }
+        Ok(())
+    }
+
+    /// Emit a scheduled event for the bedtime reminder, which only posts a notification.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `trigger_at`: epoch millis to remind at.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_bedtime_reminder_scheduled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        trigger_at: i64,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmScheduled {
+            id: BEDTIME_REMINDER_ID,
+            trigger_at,
+            sound_uri: None,
+            label: Some("Bedtime".into()),
+            mode: AlarmMode::FixedOnce,
+            revision,
+            pre_alarm_for: None,
+            volume_percent: None,
+            ring_seconds: None,
+            snooze_allowed: Some(false),
+            backup_for: None,
+            watch_only: None,
+            timer_for: None,
+            notification_only: Some(true),
+            wake_only: None,
+        };
+        app.emit("alarm:scheduled", &event)?;
+        Ok(())
+    }
+
+    /// Schedule a pause's end natively, to wake the app then to switch its alarms back
+    /// on, whether or not it's running. Scheduling it again moves it.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `pause`: pause to end.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_pause_end_scheduled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        pause: &AlarmPause,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmScheduled {
+            id: pause_end_native_id(pause.id),
+            trigger_at: pause.resumes_at,
+            sound_uri: None,
+            label: None,
+            mode: AlarmMode::FixedOnce,
+            revision,
+            pre_alarm_for: None,
+            volume_percent: None,
+            ring_seconds: None,
+            snooze_allowed: Some(false),
+            backup_for: None,
+            watch_only: None,
+            timer_for: None,
+            notification_only: None,
+            wake_only: Some(true),
+        };
+        app.emit("alarm:scheduled", &event)?;
+        Ok(())
+    }
+
+    /// Emit an alarm cancelled event.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `id`: alarm identifier.
+    /// - `reason`: cancellation reason.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_alarm_cancelled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: i32,
+        reason: CancelReason,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmCancelled {
+            id,
+            reason,
+            revision,
+        };
+        app.emit("alarm:cancelled", &event)?;
+        Ok(())
+    }
+
+    /// Emit a timer's update, scheduling it natively while it runs and cancelling its
+    /// native schedule while it's paused.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `timer`: timer as it now stands.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_timer_updated<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        timer: &Timer,
+        revision: i64,
+    ) -> Result<()> {
+        match timer.state {
+            TimerState::Running => self.emit_timer_scheduled(app, timer, revision).await?,
+            TimerState::Paused => {
+                self.emit_alarm_cancelled(
+                    app,
+                    timer_native_id(timer.id),
+                    CancelReason::Disabled,
+                    revision,
+                )
+                .await?
+            }
+        }
+        let event = TimerUpdated {
+            timer: timer.clone(),
+            revision,
+        };
+        app.emit("timer:updated", &event)?;
+        self.emit_batch_update(app, vec![timer_native_id(timer.id)], revision)
+            .await
+    }
+
+    /// Emit a scheduled event for a running timer, under its native id. It rings like
+    /// a one-time alarm that can't be snoozed.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `timer`: running timer to schedule.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_timer_scheduled<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        timer: &Timer,
+        revision: i64,
+    ) -> Result<()> {
+        if let Some(ends_at) = timer.ends_at {
+            let event = AlarmScheduled {
+                id: timer_native_id(timer.id),
+                trigger_at: ends_at,
+                sound_uri: None,
+                label: Some(timer.label.clone().unwrap_or_else(|| "Timer".into())),
+                mode: AlarmMode::FixedOnce,
+                revision,
+                pre_alarm_for: None,
+                volume_percent: None,
+                ring_seconds: None,
+                snooze_allowed: Some(false),
+                backup_for: None,
+                watch_only: None,
+                timer_for: Some(timer.id),
+                notification_only: None,
+                wake_only: None,
+            };
+            app.emit("alarm:scheduled", &event)?;
+        }
+        Ok(())
+    }
+
+    /// Emit a batch updated event for sync collectors.
+    ///
+    /// - `app`: app handle for event emission.
+    /// - `updated_ids`: alarm ids included in this batch.
+    /// - `revision`: revision stamped on the mutation.
+    async fn emit_batch_update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        updated_ids: Vec<i32>,
+        revision: i64,
+    ) -> Result<()> {
+        let event = AlarmsBatchUpdated {
+            updated_ids,
+            revision,
+            timestamp: self.clock.now_millis(),
+        };
+        app.emit("alarms:batch:updated", &event)?;
+        Ok(())
+    }
+}
+
+/// What, if anything, a mutation should do to an alarm's native schedule. Pulled out of
+/// `emit_scheduling_events` as a pure function so it's testable without an `AppHandle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SchedulingTransition {
+    Schedule,
+    Cancel(CancelReason),
+    Reschedule,
+    NoOp,
+}
+
+fn classify_scheduling_transition(
+    previous: Option<&AlarmRecord>,
+    alarm: &AlarmRecord,
+) -> SchedulingTransition {
+    let was_scheduled = previous
+        .map(|p| p.enabled && p.next_trigger.is_some())
+        .unwrap_or(false);
+
+    let should_schedule = alarm.enabled && alarm.next_trigger.is_some();
+
+    match (was_scheduled, should_schedule) {
+        (false, true) => SchedulingTransition::Schedule,
+        (true, false) => {
+            let reason = if alarm.enabled {
+                CancelReason::Updated
+            } else {
+                CancelReason::Disabled
+            };
+            SchedulingTransition::Cancel(reason)
+        }
+        (true, true) => {
+            // Re-schedule on a trigger change, or a sound change alone -- the native
+            // scheduler needs to know about the latter too, or an edited sound won't
+            // take effect until some other change happens to trigger a reschedule.
+            let needs_reschedule = previous
+                .map(|p| p.next_trigger != alarm.next_trigger || p.sound_uri != alarm.sound_uri)
+                .unwrap_or(false);
+
+            if needs_reschedule {
+                SchedulingTransition::Reschedule
+            } else {
+                SchedulingTransition::NoOp
+            }
+        }
+        (false, false) => SchedulingTransition::NoOp,
+    }
+}
+
+/// An alarm's pre-alarm and when it rings, if it's enabled and still to come.
+fn pending_pre_alarm(alarm: &AlarmRecord, now: i64) -> Option<(i64, &PreAlarm)> {
+    let pre_alarm = alarm.pre_alarm.as_ref().filter(|_| alarm.enabled)?;
+    let trigger_at = scheduler::pre_alarm_trigger(pre_alarm, alarm.next_trigger?, now)?;
+    Some((trigger_at, pre_alarm))
+}
+
+/// What a mutation should do to an alarm's pre-alarm, which is scheduled natively
+/// apart from the alarm itself. One that has already rung is left alone.
+fn classify_pre_alarm_transition(
+    previous: Option<&AlarmRecord>,
+    alarm: &AlarmRecord,
+    now: i64,
+) -> SchedulingTransition {
+    let was_scheduled = previous.and_then(|p| pending_pre_alarm(p, now));
+    match (was_scheduled, pending_pre_alarm(alarm, now)) {
+        (None, Some(_)) => SchedulingTransition::Schedule,
+        (Some(_), None) => {
+            let reason = if alarm.enabled {
+                CancelReason::Updated
+            } else {
+                CancelReason::Disabled
+            };
+            SchedulingTransition::Cancel(reason)
+        }
+        (Some(before), Some(after)) if before != after => SchedulingTransition::Reschedule,
+        _ => SchedulingTransition::NoOp,
+    }
+}
+
+/// When to remind about bedtime: `sleep_minutes` and `wind_down_minutes` before the
+/// next alarm to wake someone, counting a window alarm from its window start. `None`
+/// when the reminder is off, there's no alarm coming, or bedtime has already passed.
+fn bedtime_reminder_at(
+    alarms: &[AlarmRecord],
+    reminder: &BedtimeReminder,
+    now: i64,
+) -> Option<i64> {
+    if !reminder.enabled {
+        return None;
+    }
+    let wake = alarms
+        .iter()
+        .filter(|a| a.enabled)
+        .filter_map(|a| {
+            let trigger = a.next_trigger.filter(|t| *t > now)?;
+            scheduler::wake_time(&a.to_input(), trigger)
+                .inspect_err(|e| log::warn!("No wake time for alarm {}: {e}", a.id))
+                .ok()
+        })
+        .filter(|wake| *wake > now)
+        .min()?;
+    let before = (reminder.sleep_minutes + reminder.wind_down_minutes) as i64 * 60_000;
+    Some(wake - before).filter(|at| *at > now)
+}
+
+/// What a change to the next alarm, or to the reminder's settings, should do to the
+/// bedtime reminder scheduled at `scheduled`.
+fn classify_bedtime_transition(
+    scheduled: Option<i64>,
+    next: Option<i64>,
+    enabled: bool,
+) -> SchedulingTransition {
+    match (scheduled, next) {
+        (None, Some(_)) => SchedulingTransition::Schedule,
+        (Some(_), None) => {
+            let reason = if enabled {
+                CancelReason::Updated
+            } else {
+                CancelReason::Disabled
+            };
+            SchedulingTransition::Cancel(reason)
+        }
+        (Some(before), Some(after)) if before != after => SchedulingTransition::Reschedule,
+        _ => SchedulingTransition::NoOp,
+    }
+}
+
+/// Whether a holiday calendar change moved an alarm to a different occurrence.
+/// Comparing occurrence dates rather than timestamps leaves a snoozed or
+/// already-sampled window trigger alone when its day is still a working day.
+fn occurrence_moved(
+    input: &AlarmInput,
+    current: Option<i64>,
+    recomputed: Option<i64>,
+) -> Result<bool> {
+    Ok(match (current, recomputed) {
+        (Some(current), Some(recomputed)) => {
+            scheduler::occurrence_date(input, current)?
+                != scheduler::occurrence_date(input, recomputed)?
+        }
+        (current, recomputed) => current.is_some() != recomputed.is_some(),
+    })
+}
+
+/// The snooze length the phone's settings ask for, in minutes (synced from the
+/// frontend).
+fn snooze_length_setting<R: Runtime>(app: &AppHandle<R>) -> i32 {
+    use std::sync::atomic::Ordering;
+
+    app.try_state::<crate::SnoozeLengthState>()
+        .map(|s: tauri::State<'_, crate::SnoozeLengthState>| s.load(Ordering::Relaxed))
+        .unwrap_or(10)
+}
+
+/// The phone's time format: whether it's 24-hour, and whether that's known yet.
+fn time_format_setting<R: Runtime>(app: &AppHandle<R>) -> (bool, bool) {
+    use std::sync::atomic::Ordering;
+
+    let is_24_hour = app
+        .try_state::<crate::TimeFormatState>()
+        .map(|s: tauri::State<'_, crate::TimeFormatState>| s.load(Ordering::Relaxed))
+        .unwrap_or(false);
+    let is_24_hour_known = app
+        .try_state::<crate::TimeFormatKnownState>()
+        .map(|s: tauri::State<'_, crate::TimeFormatKnownState>| s.load(Ordering::Relaxed))
+        .unwrap_or(false);
+    (is_24_hour, is_24_hour_known)
+}
+
+/// Whether `now` is still on the occurrence `rang` was ringing for: not moved on by a
+/// dismissal or an edit, nor snoozed since.
+fn same_occurrence(rang: &AlarmRecord, now: &AlarmRecord) -> bool {
+    now.enabled
+        && now.next_trigger == rang.next_trigger
+        && now.snooze_count == rang.snooze_count
+        && now.snoozed_from == rang.snoozed_from
+}
+
+/// What a ring timeout does to an unattended occurrence: its own action, except that
+/// a snooze the snooze policy won't allow becomes a dismissal.
+fn unattended_action(timeout: &RingTimeout, snooze_allowed: bool) -> RingTimeoutAction {
+    match timeout.action {
+        RingTimeoutAction::Snooze if snooze_allowed => RingTimeoutAction::Snooze,
+        _ => RingTimeoutAction::Dismiss,
+    }
+}
+
+/// Whether `alarm`'s backup is owed a ring at `at_ms`: the occurrence it backs up has
+/// come due and hasn't since been dismissed, snoozed, switched off or had its backup
+/// removed, any of which moves the alarm on.
+fn backup_due(alarm: &AlarmRecord, at_ms: i64) -> bool {
+    alarm.backup_alarm.is_some() && alarm.enabled && alarm.next_trigger.is_some_and(|t| t <= at_ms)
+}
+
+/// Whether the occurrence `alarm` is on had come due by `at_ms`: it was due to ring, or
+/// it rang and was snoozed, rather than being dismissed ahead of time.
+fn came_due(alarm: &AlarmRecord, at_ms: i64) -> bool {
+    alarm.snoozed_from.is_some() || alarm.next_trigger.is_some_and(|t| t <= at_ms)
+}
+
+/// Epoch millis of the local start of `until`, the day a pause ends, which must come
+/// after `today`. A midnight lost to a DST change ends it at the day's first hour.
+fn pause_resumes_at(until: &str, today: chrono::NaiveDate) -> Result<i64> {
+    let date = chrono::NaiveDate::parse_from_str(until, "%Y-%m-%d")
+        .map_err(|_| Error::Validation(format!("Invalid pause date: {until}")))?;
+    if date <= today {
+        return Err(Error::Validation(
+            "A pause has to last until a day after today".into(),
+        ));
+    }
+    (0..3)
+        .find_map(|hour| {
+            date.and_hms_opt(hour, 0, 0)?
+                .and_local_timezone(chrono::Local)
+                .earliest()
+        })
+        .map(|at| at.timestamp_millis())
+        .ok_or_else(|| Error::Validation(format!("Invalid pause date: {until}")))
+}
+
+/// Whether `pause` holds the alarm `alarm_id` off: it pauses every alarm, or a group
+/// the alarm is in.
+fn pause_covers(pause: &AlarmPause, groups: &[AlarmGroup], alarm_id: i32) -> bool {
+    match pause.group_id {
+        None => true,
+        Some(group_id) => groups
+            .iter()
+            .any(|g| g.id == group_id && g.alarm_ids.contains(&alarm_id)),
+    }
+}
+
+/// What a timer has left to run at `now_ms` if it's counting down, or `None` if it's
+/// paused or has run out.
+fn pause_remaining(timer: &Timer, now_ms: i64) -> Option<i64> {
+    match (timer.state, timer.ends_at) {
+        (TimerState::Running, Some(ends_at)) if ends_at > now_ms => Some(ends_at - now_ms),
+        _ => None,
+    }
+}
+
+/// Whether a timer ran out more than `grace_ms` before `now_ms`, with nobody there to
+/// dismiss it.
+fn timer_abandoned(timer: &Timer, now_ms: i64, grace_ms: i64) -> bool {
+    timer.state == TimerState::Running && timer.ends_at.is_some_and(|t| t + grace_ms <= now_ms)
+}
+
+/// Whether `alarm` is a follow-up of the alarm `alarm_id`.
+fn follows(alarm: &AlarmRecord, alarm_id: i32) -> bool {
+    alarm.mode == AlarmMode::FollowUp
+        && alarm
+            .follow_up
+            .as_ref()
+            .is_some_and(|f| f.alarm_id == alarm_id)
+}
+
+/// When a follow-up rings after the alarm it follows is dismissed at `dismissed_at`.
+fn follow_up_trigger(alarm: &AlarmRecord, dismissed_at: i64) -> Option<i64> {
+    let follow_up = alarm.follow_up.as_ref()?;
+    Some(dismissed_at + follow_up.minutes_after as i64 * 60_000)
+}
+
+/// Why an occurrence last due at `trigger_at` was missed, given when it last rang and,
+/// on launch, since when the app had been down. A snoozed occurrence that rang the
+/// first time but not after its snooze never rang when it was last due.
+fn classify_missed(
+    trigger_at: i64,
+    last_rang: Option<i64>,
+    down_since: Option<i64>,
+) -> MissedReason {
+    match last_rang {
+        Some(at) if at >= trigger_at => MissedReason::NeverDismissed,
+        _ if down_since.is_some_and(|at| at < trigger_at) => MissedReason::NotRunning,
+        _ => MissedReason::NeverRang,
+    }
+}
+
+/// A coordinator driven end to end: a fresh database, a clock only the test moves,
+/// and a mock app whose emitted events can be collected.
+#[cfg(test)]
+mod test_support {
+    use super::*;
+    use clock::ManualClock;
+    use std::sync::Mutex;
+    use tauri::test::{mock_app, MockRuntime};
+    use tauri::{App, Listener};
+
+    pub(super) struct Harness {
+        pub app: App<MockRuntime>,
+        pub clock: Arc<ManualClock>,
+        pub coordinator: AlarmCoordinator,
+        // Dropping this ends a blocking task that, while it runs, stops a paused tokio
+        // clock from auto-advancing: every wait on sqlite's worker thread would jump it
+        // straight past the pool's timeouts. Paused tests move it on with `wake`.
+        _hold_clock: std::sync::mpsc::Sender<()>,
+    }
+
+    impl Harness {
+        pub async fn at(now: chrono::DateTime<chrono::Utc>) -> Self {
+            let (hold_clock, held) = std::sync::mpsc::channel::<()>();
+            tokio::task::spawn_blocking(move || held.recv());
+            let clock = Arc::new(ManualClock::new(now));
+            let db = AlarmDatabase::in_memory().await;
+            Self {
+                app: mock_app(),
+                coordinator: AlarmCoordinator::with_clock(db, clock.clone()),
+                clock,
+                _hold_clock: hold_clock,
+            }
+        }
+
+        /// Waits for the coordinator to go to sleep on the clock, and returns when it
+        /// will wake.
+        pub async fn asleep_until(&self) -> chrono::DateTime<chrono::Utc> {
+            loop {
+                if let Some(wake_at) = self.clock.next_wake() {
+                    return wake_at;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+
+        /// Moves the clock, and tokio's paused clock with it, on to when the
+        /// coordinator's sleep ends, once it's asleep.
+        pub async fn wake(&self) {
+            let wake_at = self.asleep_until().await;
+            let gap = (wake_at - self.clock.now()).to_std().unwrap();
+            self.clock.set(wake_at);
+            tokio::time::advance(gap).await;
+        }
+
+        pub fn handle(&self) -> &AppHandle<MockRuntime> {
+            self.app.handle()
+        }
+
+        /// Collects the payload of every `event` emitted from here on.
+        pub fn capture(&self, event: &str) -> Arc<Mutex<Vec<serde_json::Value>>> {
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            let sink = seen.clone();
+            self.handle().listen_any(event, move |e| {
+                sink.lock()
+                    .unwrap()
+                    .push(serde_json::from_str(e.payload()).unwrap());
+            });
+            seen
+        }
+    }
+
+    /// An enabled every-day 07:00 alarm, id 1, with nothing else set and nothing
+    /// scheduled. Cases override what they're about with struct-update syntax.
+    pub(crate) fn base_alarm() -> AlarmRecord {
+        AlarmRecord {
+            id: 1,
+            label: None,
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".into()),
+            window_start: None,
+            window_end: None,
+            window_distribution: WindowDistribution::Uniform,
+            interval_minutes: None,
+            bedtime: None,
+            solar: None,
+            timezone: None,
+            dst_policy: DstPolicy::default(),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            one_shot_date: None,
+            recurrence_rule: None,
+            recurrence_anchor: None,
+            skip_dates: vec![],
+            skip_holidays: false,
+            pre_alarm: None,
+            snooze_policy: None,
+            snooze_count: 0,
+            ring_timeout: None,
+            backup_alarm: None,
+            follow_up: None,
+            next_trigger: None,
+            snoozed_from: None,
+            sound_uri: None,
+            sound_title: None,
+            revision: 1,
+        }
+    }
+
+    pub(super) const TORONTO: &str = "America/Toronto";
+
+    /// An every-day alarm at `time` in Toronto, whatever zone the suite runs in.
+    pub(super) fn daily_at(time: &str) -> AlarmInput {
+        AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some(time.into()),
+            timezone: Some(TORONTO.into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        }
+    }
+
+    /// Epoch millis of `date` and `time` in Toronto.
+    pub(super) fn at(date: &str, time: &str) -> i64 {
+        zoned(TORONTO, date, time).timestamp_millis()
+    }
+
+    /// `date` and `time` on a zone's wall clock.
+    pub(super) fn zoned(zone: &str, date: &str, time: &str) -> chrono::DateTime<chrono::Utc> {
+        let tz: chrono_tz::Tz = zone.parse().unwrap();
+        chrono::NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_local_timezone(tz)
+            .earliest()
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    }
+}
+
+#[cfg(test)]
+mod clock_driven_tests {
+    use super::test_support::{at, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+    use clock::Clock;
+
+    #[tokio::test]
+    async fn test_save_schedules_from_the_coordinator_clock() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-02", "07:00")));
+    }
+
+    #[tokio::test]
+    async fn test_batches_are_stamped_from_the_coordinator_clock() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let batches = h.capture("alarms:batch:updated");
+        h.coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0]["timestamp"], at("2030-01-01", "12:00"));
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_moves_on_across_midnight_and_spring_forward() {
+        // Toronto skips 02:00-03:00 on 2030-03-10.
+        let h = Harness::at(zoned(TORONTO, "2030-03-09", "23:00")).await;
+        let dismissed = h.capture("alarm:dismissed");
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-03-10", "07:00")));
+
+        h.clock.set(zoned(TORONTO, "2030-03-10", "07:01"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        let alarm = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-03-11", "07:00")));
+        let dismissed = dismissed.lock().unwrap();
+        assert_eq!(dismissed.len(), 1);
+        assert_eq!(dismissed[0]["dismissedAt"], h.clock.now_millis());
+    }
+
+    #[tokio::test]
+    async fn test_snooze_is_validated_against_the_coordinator_clock() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-02", "07:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+
+        // Ringing a day later: "now" is whatever the clock says, not the machine's.
+        h.clock.set(zoned(TORONTO, "2030-01-03", "07:00"));
+        let err = h
+            .coordinator
+            .snooze_alarm(
+                h.handle(),
+                alarm.id,
+                at("2030-01-03", "06:59"),
+                AlarmEventSource::PhoneUi,
+            )
+            .await;
+        assert!(matches!(err, Err(Error::Validation(_))));
+
+        h.coordinator
+            .snooze_alarm(
+                h.handle(),
+                alarm.id,
+                at("2030-01-03", "07:10"),
+                AlarmEventSource::PhoneUi,
+            )
+            .await
+            .unwrap();
+        let alarm = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:10")));
+    }
+
+    #[tokio::test]
+    async fn test_heal_advances_an_occurrence_once_the_clock_passes_its_grace() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let missed = h.capture("alarm:missed");
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+
+        // Still inside the default 30-minute grace: possibly still ringing.
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:29"));
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        assert!(missed.lock().unwrap().is_empty());
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:31"));
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        let alarm = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+        assert_eq!(missed.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::test_support::{at, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+
+    async fn history(h: &Harness, alarm_id: i32) -> Vec<AlarmHistoryEntry> {
+        let query = HistoryQuery {
+            alarm_id: Some(alarm_id),
+            ..Default::default()
+        };
+        let mut entries = h.coordinator.get_alarm_history(&query).await.unwrap();
+        entries.reverse();
+        entries
+    }
+
+    #[tokio::test]
+    async fn test_fire_snooze_fire_dismiss_is_recorded_against_one_occurrence() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let occurrence = at("2030-01-02", "07:00");
+        let fired_at = occurrence + 4_000;
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:00"));
+        assert!(h
+            .coordinator
+            .report_alarm_fired(h.handle(), alarm.id, fired_at)
+            .await
+            .unwrap());
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:01"));
+        h.coordinator
+            .snooze_alarm(
+                h.handle(),
+                alarm.id,
+                at("2030-01-02", "07:10"),
+                AlarmEventSource::Watch,
+            )
+            .await
+            .unwrap();
+
+        // The snoozed ring still belongs to the 07:00 occurrence.
+        let refired_at = at("2030-01-02", "07:10") + 2_000;
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:10"));
+        h.coordinator
+            .report_alarm_fired(h.handle(), alarm.id, refired_at)
+            .await
+            .unwrap();
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:12"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::Notification)
+            .await
+            .unwrap();
+
+        let entries = history(&h, alarm.id).await;
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.kind, e.source, e.actual_fired_at, e.next_trigger))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    AlarmEventKind::Fired,
+                    AlarmEventSource::System,
+                    Some(fired_at),
+                    None
+                ),
+                (
+                    AlarmEventKind::Snoozed,
+                    AlarmEventSource::Watch,
+                    Some(fired_at),
+                    Some(at("2030-01-02", "07:10"))
+                ),
+                (
+                    AlarmEventKind::Fired,
+                    AlarmEventSource::System,
+                    Some(refired_at),
+                    None
+                ),
+                (
+                    AlarmEventKind::Dismissed,
+                    AlarmEventSource::Notification,
+                    Some(refired_at),
+                    Some(at("2030-01-03", "07:00"))
+                ),
+            ]
+        );
+        assert!(entries
+            .iter()
+            .all(|e| e.scheduled_trigger == Some(occurrence)));
+        assert_eq!(
+            entries.last().unwrap().occurred_at,
+            at("2030-01-02", "07:12")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dismissed_event_carries_the_recorded_fire_time() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let dismissed = h.capture("alarm:dismissed");
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let fired_at = at("2030-01-02", "07:00") + 3_000;
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:00"));
+        h.coordinator
+            .report_alarm_fired(h.handle(), alarm.id, fired_at)
+            .await
+            .unwrap();
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:04"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        // Dismissing the next occurrence before it rings has no fire time to report.
+        h.clock.set(zoned(TORONTO, "2030-01-02", "21:00"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        let dismissed = dismissed.lock().unwrap();
+        assert_eq!(dismissed.len(), 2);
+        assert_eq!(dismissed[0]["firedAt"], fired_at);
+        assert_eq!(dismissed[0]["dismissedAt"], at("2030-01-02", "07:04"));
+        assert_eq!(dismissed[1]["firedAt"], at("2030-01-02", "21:00"));
+        assert_eq!(dismissed[1]["dismissedAt"], at("2030-01-02", "21:00"));
+    }
+
+    #[tokio::test]
+    async fn test_dismissing_ahead_records_no_ring() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        let entries = history(&h, alarm.id).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, AlarmEventKind::Dismissed);
+        assert_eq!(
+            entries[0].scheduled_trigger,
+            Some(at("2030-01-02", "07:00"))
+        );
+        assert_eq!(entries[0].actual_fired_at, None);
+        assert_eq!(entries[0].next_trigger, Some(at("2030-01-03", "07:00")));
+    }
+
+    #[tokio::test]
+    async fn test_pre_alarm_rings_stay_out_of_the_history() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+
+        h.coordinator
+            .report_alarm_fired(
+                h.handle(),
+                pre_alarm_native_id(alarm.id),
+                at("2030-01-02", "06:50"),
+            )
+            .await
+            .unwrap();
+        assert!(history(&h, alarm.id).await.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod scheduling_transition_tests {
+    use super::test_support::base_alarm;
+    use super::*;
+
+    fn alarm(enabled: bool, next_trigger: Option<i64>, sound_uri: Option<&str>) -> AlarmRecord {
+        AlarmRecord {
+            enabled,
+            next_trigger,
+            sound_uri: sound_uri.map(|s| s.to_string()),
+            ..base_alarm()
+        }
+    }
+
+    #[test]
+    fn schedules_a_newly_enabled_alarm() {
+        let previous = alarm(false, None, None);
+        let current = alarm(true, Some(1_000), None);
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::Schedule
+        );
+    }
+
+    #[test]
+    fn schedules_a_brand_new_alarm_with_no_previous_state() {
+        let current = alarm(true, Some(1_000), None);
+
+        assert_eq!(
+            classify_scheduling_transition(None, &current),
+            SchedulingTransition::Schedule
+        );
+    }
+
+    #[test]
+    fn cancels_as_disabled_when_the_user_toggles_off() {
+        let previous = alarm(true, Some(1_000), None);
+        let current = alarm(false, Some(1_000), None);
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::Cancel(CancelReason::Disabled)
+        );
+    }
+
+    #[test]
+    fn cancels_as_updated_when_still_enabled_but_no_longer_has_a_trigger() {
+        let previous = alarm(true, Some(1_000), None);
+        let current = alarm(true, None, None);
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::Cancel(CancelReason::Updated)
+        );
+    }
+
+    #[test]
+    fn reschedules_when_the_trigger_time_changes() {
+        let previous = alarm(true, Some(1_000), None);
+        let current = alarm(true, Some(2_000), None);
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::Reschedule
+        );
+    }
+
+    #[test]
+    fn reschedules_when_only_the_sound_changes() {
+        let previous = alarm(true, Some(1_000), Some("a.mp3"));
+        let current = alarm(true, Some(1_000), Some("b.mp3"));
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::Reschedule
+        );
+    }
+
+    #[test]
+    fn does_nothing_when_neither_trigger_nor_sound_changed() {
+        let previous = alarm(true, Some(1_000), Some("a.mp3"));
+        let current = alarm(true, Some(1_000), Some("a.mp3"));
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::NoOp
+        );
+    }
+
+    #[test]
+    fn does_nothing_for_an_alarm_that_was_never_and_still_is_not_scheduled() {
+        let previous = alarm(false, None, None);
+        let current = alarm(false, None, None);
+
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::NoOp
+        );
+    }
+
+    #[test]
+    fn does_nothing_for_a_brand_new_disabled_alarm_with_no_previous_state() {
+        let current = alarm(false, None, None);
+
+        assert_eq!(
+            classify_scheduling_transition(None, &current),
+            SchedulingTransition::NoOp
+        );
+    }
+
+    const HOUR: i64 = 3_600_000;
+
+    fn with_pre_alarm(alarm: AlarmRecord, minutes_before: u32) -> AlarmRecord {
+        AlarmRecord {
+            pre_alarm: Some(PreAlarm {
+                minutes_before,
+                sound_uri: None,
+                sound_title: None,
+                volume_percent: 30,
+                ring_seconds: 60,
+                skip_main_on_dismiss: false,
+            }),
+            ..alarm
+        }
+    }
+
+    #[test]
+    fn schedules_a_pre_alarm_added_to_a_scheduled_alarm() {
+        let previous = alarm(true, Some(10 * HOUR), None);
+        let current = with_pre_alarm(previous.clone(), 15);
+
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &current, 0),
+            SchedulingTransition::Schedule
+        );
+        assert_eq!(
+            classify_scheduling_transition(Some(&previous), &current),
+            SchedulingTransition::NoOp
+        );
+    }
+
+    #[test]
+    fn reschedules_a_pre_alarm_with_its_alarm_or_its_own_settings() {
+        let previous = with_pre_alarm(alarm(true, Some(10 * HOUR), None), 15);
+        let moved = AlarmRecord {
+            next_trigger: Some(11 * HOUR),
+            ..previous.clone()
+        };
+        let earlier = with_pre_alarm(previous.clone(), 30);
+
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &moved, 0),
+            SchedulingTransition::Reschedule
+        );
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &earlier, 0),
+            SchedulingTransition::Reschedule
+        );
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &previous.clone(), 0),
+            SchedulingTransition::NoOp
+        );
+    }
+
+    #[test]
+    fn cancels_a_pre_alarm_when_its_alarm_is_disabled_or_it_is_removed() {
+        let previous = with_pre_alarm(alarm(true, Some(10 * HOUR), None), 15);
+        let disabled = AlarmRecord {
+            enabled: false,
+            next_trigger: None,
+            ..previous.clone()
+        };
+        let removed = AlarmRecord {
+            pre_alarm: None,
+            ..previous.clone()
+        };
+
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &disabled, 0),
+            SchedulingTransition::Cancel(CancelReason::Disabled)
+        );
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &removed, 0),
+            SchedulingTransition::Cancel(CancelReason::Updated)
+        );
+    }
+
+    #[test]
+    fn leaves_a_pre_alarm_that_has_already_rung() {
+        // Ten minutes before the alarm: its 15-minute pre-alarm has rung, and moving
+        // the alarm by a snooze-sized step doesn't bring it back.
+        let now = 10 * HOUR - 10 * 60_000;
+        let previous = with_pre_alarm(alarm(true, Some(10 * HOUR), None), 15);
+        let nudged = AlarmRecord {
+            next_trigger: Some(10 * HOUR + 60_000),
+            ..previous.clone()
+        };
+
+        assert_eq!(
+            classify_pre_alarm_transition(Some(&previous), &nudged, now),
+            SchedulingTransition::NoOp
+        );
+        assert_eq!(pending_pre_alarm(&previous, now), None);
+    }
+
+    #[test]
+    fn pre_alarm_ids_stop_short_of_the_backup_offset() {
+        assert_eq!(pre_alarm_parent(PRE_ALARM_ID_OFFSET), None);
+        assert_eq!(pre_alarm_parent(PRE_ALARM_ID_OFFSET + 1), Some(1));
+        assert_eq!(
+            pre_alarm_parent(BACKUP_ALARM_ID_OFFSET - 1),
+            Some(BACKUP_ALARM_ID_OFFSET - PRE_ALARM_ID_OFFSET - 1)
+        );
+        assert_eq!(pre_alarm_parent(BACKUP_ALARM_ID_OFFSET), None);
+    }
+}
+
+#[cfg(test)]
+mod holiday_reschedule_tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> i64 {
+        chrono::Local
+            .with_ymd_and_hms(2026, 3, day, hour, 0, 0)
+            .earliest()
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn keeps_a_trigger_whose_day_is_unchanged() {
+        // e.g. a snoozed trigger later on the same, still-working day
+        let input = AlarmInput::default();
+        assert!(!occurrence_moved(&input, Some(at(10, 7)), Some(at(10, 8))).unwrap());
+    }
+
+    #[test]
+    fn moves_a_trigger_onto_another_day() {
+        let input = AlarmInput::default();
+        assert!(occurrence_moved(&input, Some(at(10, 7)), Some(at(11, 7))).unwrap());
+    }
+
+    #[test]
+    fn moves_when_a_trigger_appears_or_disappears() {
+        let input = AlarmInput::default();
+        assert!(occurrence_moved(&input, Some(at(10, 7)), None).unwrap());
+        assert!(occurrence_moved(&input, None, Some(at(10, 7))).unwrap());
+        assert!(!occurrence_moved(&input, None, None).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod missed_alarm_tests {
+    use super::test_support::{at, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+
+    #[test]
+    fn a_ring_at_or_after_the_trigger_was_never_dismissed() {
+        assert_eq!(
+            classify_missed(1_000, Some(1_000), None),
+            MissedReason::NeverDismissed
+        );
+        assert_eq!(
+            classify_missed(1_000, Some(1_250), Some(500)),
+            MissedReason::NeverDismissed
+        );
+    }
+
+    #[test]
+    fn no_ring_while_running_never_rang() {
+        assert_eq!(classify_missed(1_000, None, None), MissedReason::NeverRang);
+        // Rang before a snooze, then nothing was reported when the snooze came due
+        assert_eq!(
+            classify_missed(1_000, Some(400), None),
+            MissedReason::NeverRang
+        );
+        // Seen running after the trigger, so it was up when it came due
+        assert_eq!(
+            classify_missed(1_000, None, Some(1_200)),
+            MissedReason::NeverRang
+        );
+    }
+
+    #[test]
+    fn no_ring_while_down_was_not_running() {
+        assert_eq!(
+            classify_missed(1_000, None, Some(900)),
+            MissedReason::NotRunning
+        );
+        assert_eq!(
+            classify_missed(1_000, Some(400), Some(900)),
+            MissedReason::NotRunning
+        );
+    }
+
+    async fn seven_am(h: &Harness) -> AlarmRecord {
+        h.coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn detection_waits_out_the_grace_window_then_moves_on() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let missed = h.capture("alarm:missed");
+        h.coordinator.set_missed_grace_minutes(10).await.unwrap();
+        let alarm = seven_am(&h).await;
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:09"));
+        h.coordinator.detect_missed(h.handle()).await.unwrap();
+        assert!(missed.lock().unwrap().is_empty());
+        let unchanged = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(unchanged.next_trigger, Some(at("2030-01-02", "07:00")));
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:10"));
+        h.coordinator.detect_missed(h.handle()).await.unwrap();
+        let advanced = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(advanced.next_trigger, Some(at("2030-01-03", "07:00")));
+
+        let missed = missed.lock().unwrap().clone();
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0]["triggerAt"], at("2030-01-02", "07:00"));
+        assert_eq!(missed[0]["reason"], "NEVER_RANG");
+        let history = h
+            .coordinator
+            .get_alarm_history(&HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history[0].kind, AlarmEventKind::Missed);
+        assert_eq!(history[0].next_trigger, Some(at("2030-01-03", "07:00")));
+    }
+
+    #[tokio::test]
+    async fn a_launch_after_being_down_through_the_trigger_was_not_running() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let missed = h.capture("alarm:missed");
+        seven_am(&h).await;
+        h.clock.set(zoned(TORONTO, "2030-01-01", "23:00"));
+        h.coordinator.detect_missed(h.handle()).await.unwrap();
+
+        // Nothing ran again until a launch the next afternoon.
+        h.clock.set(zoned(TORONTO, "2030-01-02", "13:00"));
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        assert_eq!(missed.lock().unwrap()[0]["reason"], "NOT_RUNNING");
+    }
+
+    #[tokio::test]
+    async fn a_reported_ring_was_never_dismissed() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let missed = h.capture("alarm:missed");
+        let alarm = seven_am(&h).await;
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:00"));
+        h.coordinator
+            .report_alarm_fired(h.handle(), alarm.id, at("2030-01-02", "07:00"))
+            .await
+            .unwrap();
+        h.clock.set(zoned(TORONTO, "2030-01-02", "13:00"));
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        assert_eq!(missed.lock().unwrap()[0]["reason"], "NEVER_DISMISSED");
+    }
+}
+
+#[cfg(test)]
+mod ring_timeout_tests {
+    use super::test_support::{at, base_alarm, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+    use clock::Clock;
+
+    fn ringing(next_trigger: i64, snooze_count: u32) -> AlarmRecord {
+        AlarmRecord {
+            snooze_count,
+            ring_timeout: Some(RingTimeout {
+                minutes: 10,
+                action: RingTimeoutAction::Dismiss,
+                escalate_to_watch: false,
+            }),
+            next_trigger: Some(next_trigger),
+            ..base_alarm()
+        }
+    }
+
+    #[test]
+    fn stands_down_once_the_occurrence_moves_on() {
+        let rang = ringing(1_000, 0);
+        assert!(same_occurrence(&rang, &rang.clone()));
+
+        let dismissed = ringing(86_401_000, 0);
+        assert!(!same_occurrence(&rang, &dismissed));
+        let snoozed = AlarmRecord {
+            snoozed_from: Some(1_000),
+            ..ringing(601_000, 1)
+        };
+        assert!(!same_occurrence(&rang, &snoozed));
+        let switched_off = AlarmRecord {
+            enabled: false,
+            ..rang.clone()
+        };
+        assert!(!same_occurrence(&rang, &switched_off));
+    }
+
+    #[test]
+    fn a_refused_snooze_dismisses_instead() {
+        let timeout = |action| RingTimeout {
+            minutes: 10,
+            action,
+            escalate_to_watch: false,
+        };
+        let snooze = timeout(RingTimeoutAction::Snooze);
+        assert_eq!(unattended_action(&snooze, true), RingTimeoutAction::Snooze);
+        assert_eq!(
+            unattended_action(&snooze, false),
+            RingTimeoutAction::Dismiss
+        );
+        let dismiss = timeout(RingTimeoutAction::Dismiss);
+        assert_eq!(
+            unattended_action(&dismiss, true),
+            RingTimeoutAction::Dismiss
+        );
+    }
+
+    /// A 07:00 alarm with a ten-minute ring timeout, ringing at 07:00 on 2 January 2030.
+    async fn ringing_unattended(
+        action: RingTimeoutAction,
+        escalate_to_watch: bool,
+    ) -> (Harness, i32) {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let input = AlarmInput {
+            ring_timeout: Some(RingTimeout {
+                minutes: 10,
+                action,
+                escalate_to_watch,
+            }),
+            ..daily_at("07:00")
+        };
+        let alarm = h.coordinator.save_alarm(h.handle(), input).await.unwrap();
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:00"));
+        (h, alarm.id)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_unattended_ring_escalates_then_snoozes() {
+        let (h, id) = ringing_unattended(RingTimeoutAction::Snooze, true).await;
+        let escalated = h.capture("alarm:escalated");
+
+        let (action, _) = tokio::join!(h.coordinator.supervise_ring(h.handle(), id), async {
+            h.wake().await;
+            assert_eq!(h.clock.now(), zoned(TORONTO, "2030-01-02", "07:05"));
+            h.wake().await;
+        });
+
+        assert_eq!(action.unwrap(), Some(RingTimeoutAction::Snooze));
+        assert_eq!(h.clock.now(), zoned(TORONTO, "2030-01-02", "07:10"));
+        let escalated = escalated.lock().unwrap().clone();
+        assert_eq!(escalated.len(), 1);
+        assert_eq!(escalated[0]["timesOutAt"], at("2030-01-02", "07:10"));
+        assert_eq!(escalated[0]["action"], "SNOOZE");
+        let alarm = h.coordinator.get_alarm(h.handle(), id).await.unwrap();
+        assert_eq!(alarm.snooze_count, 1);
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-02", "07:20")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_unattended_ring_times_out_into_a_dismiss() {
+        let (h, id) = ringing_unattended(RingTimeoutAction::Dismiss, false).await;
+        let escalated = h.capture("alarm:escalated");
+        let dismissed = h.capture("alarm:dismissed");
+
+        let (action, _) = tokio::join!(h.coordinator.supervise_ring(h.handle(), id), h.wake());
+
+        assert_eq!(action.unwrap(), Some(RingTimeoutAction::Dismiss));
+        assert_eq!(h.clock.now(), zoned(TORONTO, "2030-01-02", "07:10"));
+        assert!(escalated.lock().unwrap().is_empty());
+        assert_eq!(dismissed.lock().unwrap().len(), 1);
+        let alarm = h.coordinator.get_alarm(h.handle(), id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stands_down_once_the_user_dismisses() {
+        let (h, id) = ringing_unattended(RingTimeoutAction::Snooze, true).await;
+        let escalated = h.capture("alarm:escalated");
+        let snoozed = h.capture("alarm:snoozed");
+
+        let (action, _) = tokio::join!(h.coordinator.supervise_ring(h.handle(), id), async {
+            h.wake().await;
+            // Escalated to the watch, where it's dismissed at 07:05.
+            assert_eq!(
+                h.asleep_until().await,
+                zoned(TORONTO, "2030-01-02", "07:10")
+            );
+            h.coordinator
+                .dismiss_alarm(h.handle(), id, AlarmEventSource::Watch)
+                .await
+                .unwrap();
+            h.wake().await;
+        });
+
+        assert_eq!(action.unwrap(), None);
+        assert_eq!(escalated.lock().unwrap().len(), 1);
+        assert!(snoozed.lock().unwrap().is_empty());
+        let alarm = h.coordinator.get_alarm(h.handle(), id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn leaves_a_ring_with_no_alarm_alone() {
+        let (h, _) = ringing_unattended(RingTimeoutAction::Dismiss, false).await;
+        let action = h.coordinator.supervise_ring(h.handle(), 999).await.unwrap();
+        assert_eq!(action, None);
+        assert_eq!(h.clock.now(), zoned(TORONTO, "2030-01-02", "07:00"));
+    }
+}
+
+#[cfg(test)]
+mod backup_alarm_tests {
+    use super::test_support::{at, base_alarm, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+    use std::sync::Mutex;
+
+    const MINUTE: i64 = 60_000;
+    const DAY: i64 = 24 * 60 * MINUTE;
+    const TRIGGER: i64 = 7 * 60 * MINUTE;
+
+    /// An alarm due at `next_trigger` with a backup five minutes after it rings.
+    fn with_backup(next_trigger: i64) -> AlarmRecord {
+        AlarmRecord {
+            backup_alarm: Some(BackupAlarm {
+                minutes_after: 5,
+                sound_uri: None,
+                sound_title: None,
+                watch_only: false,
+            }),
+            next_trigger: Some(next_trigger),
+            ..base_alarm()
+        }
+    }
+
+    #[test]
+    fn native_ids_tell_backups_from_pre_alarms() {
+        assert_eq!(backup_alarm_parent(backup_alarm_native_id(7)), Some(7));
+        assert_eq!(pre_alarm_parent(backup_alarm_native_id(7)), None);
+        assert_eq!(pre_alarm_parent(pre_alarm_native_id(7)), Some(7));
+        assert_eq!(backup_alarm_parent(pre_alarm_native_id(7)), None);
+        assert_eq!(backup_alarm_parent(7), None);
+    }
+
+    #[test]
+    fn backup_ids_stop_short_of_the_timer_offset() {
+        assert_eq!(backup_alarm_parent(BACKUP_ALARM_ID_OFFSET), None);
+        assert_eq!(
+            backup_alarm_parent(TIMER_ID_OFFSET - 1),
+            Some(TIMER_ID_OFFSET - BACKUP_ALARM_ID_OFFSET - 1)
+        );
+        assert_eq!(backup_alarm_parent(TIMER_ID_OFFSET), None);
+    }
+
+    #[test]
+    fn rings_while_the_occurrence_is_left_ringing() {
+        assert!(backup_due(&with_backup(TRIGGER), TRIGGER + 5 * MINUTE));
+    }
+
+    #[test]
+    fn a_dismiss_that_lands_first_stands_the_backup_down() {
+        // Dismissed at 4:59; the backup's fire is reported after the alarm has moved
+        // on to tomorrow.
+        let dismissed = with_backup(TRIGGER + DAY);
+        assert!(!backup_due(&dismissed, TRIGGER + 5 * MINUTE));
+
+        // A one-shot switches itself off instead of moving on.
+        let spent = AlarmRecord {
+            enabled: false,
+            ..with_backup(TRIGGER)
+        };
+        assert!(!backup_due(&spent, TRIGGER + 5 * MINUTE));
+    }
+
+    #[test]
+    fn a_dismiss_that_lands_second_still_finds_the_backup_due() {
+        // The backup fired first, so the dismissal sees the occurrence still ringing
+        // and stops the backup along with it.
+        let ringing = with_backup(TRIGGER);
+        assert!(backup_due(&ringing, TRIGGER + 5 * MINUTE + 1));
+    }
+
+    #[test]
+    fn a_snooze_stands_the_backup_down_until_the_snooze_rings() {
+        let snoozed = AlarmRecord {
+            snooze_count: 1,
+            snoozed_from: Some(TRIGGER),
+            ..with_backup(TRIGGER + 9 * MINUTE)
+        };
+        assert!(!backup_due(&snoozed, TRIGGER + 5 * MINUTE));
+        // Once the snooze rings, it's rescheduled from that ring.
+        assert!(backup_due(&snoozed, TRIGGER + 14 * MINUTE));
+    }
+
+    #[test]
+    fn removing_the_backup_stands_it_down() {
+        let removed = AlarmRecord {
+            backup_alarm: None,
+            ..with_backup(TRIGGER)
+        };
+        assert!(!backup_due(&removed, TRIGGER + 5 * MINUTE));
+    }
+
+    /// A 07:00 alarm with a five-minute backup, rung at 07:00 on 2 January 2030.
+    async fn rung_with_backup() -> (Harness, AlarmRecord) {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let input = AlarmInput {
+            backup_alarm: with_backup(0).backup_alarm,
+            ..daily_at("07:00")
+        };
+        let alarm = h.coordinator.save_alarm(h.handle(), input).await.unwrap();
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:00"));
+        assert!(h
+            .coordinator
+            .report_alarm_fired(h.handle(), alarm.id, at("2030-01-02", "07:00"))
+            .await
+            .unwrap());
+        (h, alarm)
+    }
+
+    /// Ids of the events captured in `seen`.
+    fn ids(seen: &Mutex<Vec<serde_json::Value>>) -> Vec<i64> {
+        let seen = seen.lock().unwrap();
+        seen.iter().map(|e| e["id"].as_i64().unwrap()).collect()
+    }
+
+    /// Ids cancelled in `cancelled` because their occurrence was attended to; the
+    /// alarm's own reschedule cancels it for an update as well.
+    fn stood_down(cancelled: &Mutex<Vec<serde_json::Value>>) -> Vec<i64> {
+        let cancelled = cancelled.lock().unwrap();
+        cancelled
+            .iter()
+            .filter(|e| e["reason"] == "ATTENDED")
+            .map(|e| e["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn a_backup_firing_after_a_dismiss_inside_its_delay_is_stood_down() {
+        let (h, alarm) = rung_with_backup().await;
+        let backup_id = backup_alarm_native_id(alarm.id) as i64;
+        let cancelled = h.capture("alarm:cancelled");
+        let dismissed = h.capture("alarm:dismissed");
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:03"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+        assert_eq!(stood_down(&cancelled), vec![backup_id]);
+        assert_eq!(ids(&dismissed), vec![alarm.id as i64, backup_id]);
+
+        // The cancellation lost the race with the native scheduler.
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:05"));
+        let rang = h
+            .coordinator
+            .report_alarm_fired(
+                h.handle(),
+                backup_alarm_native_id(alarm.id),
+                at("2030-01-02", "07:05"),
+            )
+            .await
+            .unwrap();
+        assert!(!rang);
+        assert_eq!(stood_down(&cancelled), vec![backup_id, backup_id]);
+        assert_eq!(ids(&dismissed), vec![alarm.id as i64, backup_id, backup_id]);
+    }
+
+    #[tokio::test]
+    async fn a_dismiss_after_the_backup_rang_stops_it_too() {
+        let (h, alarm) = rung_with_backup().await;
+        let backup_id = backup_alarm_native_id(alarm.id) as i64;
+        let cancelled = h.capture("alarm:cancelled");
+        let dismissed = h.capture("alarm:dismissed");
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:05"));
+        assert!(h
+            .coordinator
+            .report_alarm_fired(
+                h.handle(),
+                backup_alarm_native_id(alarm.id),
+                at("2030-01-02", "07:05"),
+            )
+            .await
+            .unwrap());
+        assert!(stood_down(&cancelled).is_empty());
+
+        // Dismissing the backup's ring dismisses the occurrence it backs up.
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:06"));
+        h.coordinator
+            .dismiss_alarm(
+                h.handle(),
+                backup_alarm_native_id(alarm.id),
+                AlarmEventSource::Watch,
+            )
+            .await
+            .unwrap();
+        assert_eq!(stood_down(&cancelled), vec![backup_id]);
+        assert_eq!(ids(&dismissed), vec![alarm.id as i64, backup_id]);
+        let alarm = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+    }
+}
+
+#[cfg(test)]
+mod follow_up_tests {
+    use super::test_support::{at, base_alarm, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+
+    const DISMISSED_AT: i64 = 1_767_250_800_000; // 2026-01-01 07:00 UTC
+    const MINUTE: i64 = 60_000;
+
+    fn follow_up(id: i32, alarm_id: i32, minutes_after: u32) -> AlarmRecord {
+        AlarmRecord {
             id,
-            reason,
-            revision,
+            label: Some("Leave the house".into()),
+            mode: AlarmMode::FollowUp,
+            fixed_time: None,
+            active_days: vec![],
+            follow_up: Some(FollowUp {
+                alarm_id,
+                minutes_after,
+            }),
+            ..base_alarm()
+        }
+    }
+
+    #[test]
+    fn rings_its_minutes_after_the_dismissal() {
+        assert_eq!(
+            follow_up_trigger(&follow_up(2, 1, 40), DISMISSED_AT),
+            Some(DISMISSED_AT + 40 * MINUTE)
+        );
+    }
+
+    #[test]
+    fn only_follows_the_alarm_it_names() {
+        let alarm = follow_up(3, 2, 10);
+        assert!(follows(&alarm, 2));
+        assert!(!follows(&alarm, 1));
+        assert!(!follows(&alarm, 3));
+    }
+
+    #[test]
+    fn a_link_left_over_from_another_mode_is_ignored() {
+        let fixed = AlarmRecord {
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".into()),
+            ..follow_up(2, 1, 40)
         };
-        app.emit("alarm:cancelled", &event)?;
-        Ok(())
+        assert!(!follows(&fixed, 1));
     }
 
-    /// Emit a batch updated event for sync collectors.
-    ///
-    /// - `app`: app handle for event emission.
-    /// - `updated_ids`: alarm ids included in this batch.
-    /// - `revision`: revision stamped on the mutation.
-    async fn emit_batch_update<R: Runtime>(
-        &self,
-        app: &AppHandle<R>,
-        updated_ids: Vec<i32>,
-        revision: i64,
-    ) -> Result<()> {
-        let event = AlarmsBatchUpdated {
-            updated_ids,
-            revision,
-            timestamp: chrono::Utc::now().timestamp_millis(),
+    #[test]
+    fn only_an_occurrence_that_came_due_or_was_snoozed_has_come_due() {
+        let upcoming = AlarmRecord {
+            next_trigger: Some(DISMISSED_AT + MINUTE),
+            ..base_alarm()
         };
-        app.emit("alarms:batch:updated", &event)?;
-        Ok(())
+        assert!(!came_due(&upcoming, DISMISSED_AT));
+        assert!(came_due(&upcoming, DISMISSED_AT + MINUTE));
+        let snoozed = AlarmRecord {
+            snoozed_from: Some(DISMISSED_AT - 9 * MINUTE),
+            ..upcoming
+        };
+        assert!(came_due(&snoozed, DISMISSED_AT));
     }
-}
 
-/// What, if anything, a mutation should do to an alarm's native schedule. Pulled out of
-/// `emit_scheduling_events` as a pure function so it's testable without an `AppHandle`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum SchedulingTransition {
-    Schedule,
-    Cancel(CancelReason),
-    Reschedule,
-    NoOp,
+    /// A 07:00 alarm and a follow-up 40 minutes after it, saved at noon on 1 January
+    /// 2030.
+    async fn with_follow_up() -> (Harness, AlarmRecord, AlarmRecord) {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let input = AlarmInput {
+            mode: AlarmMode::FollowUp,
+            fixed_time: None,
+            follow_up: Some(FollowUp {
+                alarm_id: alarm.id,
+                minutes_after: 40,
+            }),
+            ..daily_at("07:00")
+        };
+        let follow_up = h.coordinator.save_alarm(h.handle(), input).await.unwrap();
+        assert_eq!(follow_up.next_trigger, None);
+        (h, alarm, follow_up)
+    }
+
+    #[tokio::test]
+    async fn a_follow_up_must_name_the_alarm_it_follows() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        for enabled in [true, false] {
+            let input = AlarmInput {
+                enabled,
+                mode: AlarmMode::FollowUp,
+                fixed_time: None,
+                follow_up: None,
+                ..daily_at("07:00")
+            };
+            let err = h.coordinator.save_alarm(h.handle(), input).await;
+            assert!(matches!(err, Err(Error::Validation(_))));
+        }
+        assert!(h
+            .coordinator
+            .get_all_alarms(h.handle())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn dismissing_an_occurrence_ahead_of_time_leaves_its_follow_ups_alone() {
+        let (h, alarm, follow_up) = with_follow_up().await;
+
+        h.clock.set(zoned(TORONTO, "2030-01-01", "22:00"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        let alarm = h.coordinator.get_alarm(h.handle(), alarm.id).await.unwrap();
+        assert_eq!(alarm.next_trigger, Some(at("2030-01-03", "07:00")));
+        let follow_up = h
+            .coordinator
+            .get_alarm(h.handle(), follow_up.id)
+            .await
+            .unwrap();
+        assert_eq!(follow_up.next_trigger, None);
+    }
+
+    #[tokio::test]
+    async fn dismissing_an_occurrence_that_came_due_sets_off_its_follow_ups() {
+        let (h, alarm, follow_up) = with_follow_up().await;
+
+        h.clock.set(zoned(TORONTO, "2030-01-02", "07:02"));
+        h.coordinator
+            .dismiss_alarm(h.handle(), alarm.id, AlarmEventSource::PhoneUi)
+            .await
+            .unwrap();
+
+        let follow_up = h
+            .coordinator
+            .get_alarm(h.handle(), follow_up.id)
+            .await
+            .unwrap();
+        assert_eq!(follow_up.next_trigger, Some(at("2030-01-02", "07:42")));
+    }
 }
 
-fn classify_scheduling_transition(
-    previous: Option<&AlarmRecord>,
-    alarm: &AlarmRecord,
-) -> SchedulingTransition {
-    let was_scheduled = previous
-        .map(|p| p.enabled && p.next_trigger.is_some())
-        .unwrap_or(false);
+#[cfg(test)]
+mod timer_tests {
+    use super::test_support::{zoned, Harness, TORONTO};
+    use super::*;
 
-    let should_schedule = alarm.enabled && alarm.next_trigger.is_some();
+    const MINUTE: i64 = 60_000;
+    const ENDS_AT: i64 = 20 * MINUTE;
 
-    match (was_scheduled, should_schedule) {
-        (false, true) => SchedulingTransition::Schedule,
-        (true, false) => {
-            let reason = if alarm.enabled {
-                CancelReason::Updated
-            } else {
-                CancelReason::Disabled
-            };
-            SchedulingTransition::Cancel(reason)
+    fn timer(state: TimerState, ends_at: Option<i64>) -> Timer {
+        Timer {
+            id: 7,
+            label: Some("Nap".into()),
+            duration_seconds: 1_200,
+            state,
+            ends_at,
+            remaining_ms: ends_at.is_none().then_some(5 * MINUTE),
+            created_at: 0,
+            revision: 1,
         }
-        (true, true) => {
-            // Re-schedule on a trigger change, or a sound change alone -- the native
-            // scheduler needs to know about the latter too, or an edited sound won't
-            // take effect until some other change happens to trigger a reschedule.
-            let needs_reschedule = previous
-                .map(|p| p.next_trigger != alarm.next_trigger || p.sound_uri != alarm.sound_uri)
-                .unwrap_or(false);
+    }
 
-            if needs_reschedule {
-                SchedulingTransition::Reschedule
-            } else {
-                SchedulingTransition::NoOp
-            }
-        }
-        (false, false) => SchedulingTransition::NoOp,
+    #[test]
+    fn a_running_timer_pauses_with_what_it_has_left() {
+        let running = timer(TimerState::Running, Some(ENDS_AT));
+        assert_eq!(
+            pause_remaining(&running, ENDS_AT - 3 * MINUTE),
+            Some(3 * MINUTE)
+        );
+    }
+
+    #[test]
+    fn a_timer_that_ran_out_or_is_paused_can_not_pause() {
+        assert_eq!(
+            pause_remaining(&timer(TimerState::Running, Some(ENDS_AT)), ENDS_AT),
+            None
+        );
+        assert_eq!(pause_remaining(&timer(TimerState::Paused, None), 0), None);
+    }
+
+    #[test]
+    fn a_timer_is_abandoned_only_past_the_grace_window() {
+        let running = timer(TimerState::Running, Some(ENDS_AT));
+        assert!(!timer_abandoned(
+            &running,
+            ENDS_AT + 29 * MINUTE,
+            30 * MINUTE
+        ));
+        assert!(timer_abandoned(
+            &running,
+            ENDS_AT + 30 * MINUTE,
+            30 * MINUTE
+        ));
+        assert!(!timer_abandoned(
+            &timer(TimerState::Paused, None),
+            ENDS_AT * 10,
+            30 * MINUTE
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_ringing_timer_loads_under_its_native_id_with_its_label() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let timer = h
+            .coordinator
+            .start_timer(h.handle(), 1_200, Some("Nap".into()))
+            .await
+            .unwrap();
+
+        let native = timer_native_id(timer.id);
+        let ringing = h.coordinator.get_alarm(h.handle(), native).await.unwrap();
+        assert_eq!(ringing.id, native);
+        assert_eq!(ringing.label.as_deref(), Some("Nap"));
+        assert_eq!(ringing.next_trigger, timer.ends_at);
+        assert_eq!(ringing.backup_alarm, None);
+    }
+
+    #[test]
+    fn timer_native_ids_belong_to_nothing_else() {
+        let native = timer_native_id(7);
+        assert_eq!(timer_parent(native), Some(7));
+        assert_eq!(backup_alarm_parent(native), None);
+        assert_eq!(pre_alarm_parent(native), None);
+        assert_eq!(timer_parent(BACKUP_ALARM_ID_OFFSET + 7), None);
+        assert_eq!(timer_parent(BEDTIME_REMINDER_ID), None);
     }
 }
 
 #[cfg(test)]
-mod scheduling_transition_tests {
+mod pause_tests {
+    use super::test_support::{daily_at, zoned, Harness, TORONTO};
     use super::*;
+    use chrono::{NaiveDate, TimeZone};
 
-    fn alarm(enabled: bool, next_trigger: Option<i64>, sound_uri: Option<&str>) -> AlarmRecord {
-        AlarmRecord {
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn pause(group_id: Option<i32>) -> AlarmPause {
+        AlarmPause {
             id: 1,
-            label: None,
-            enabled,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("07:00".into()),
-            window_start: None,
-            window_end: None,
-            active_days: vec![0, 1, 2, 3, 4, 5, 6],
-            next_trigger,
-            sound_uri: sound_uri.map(|s| s.to_string()),
-            sound_title: None,
+            group_id,
+            until: "2026-10-25".into(),
+            resumes_at: 0,
+            alarm_ids: vec![],
             revision: 1,
         }
     }
 
     #[test]
-    fn schedules_a_newly_enabled_alarm() {
-        let previous = alarm(false, None, None);
-        let current = alarm(true, Some(1_000), None);
+    fn resumes_at_the_local_start_of_its_date() {
+        let start = chrono::Local
+            .with_ymd_and_hms(2026, 10, 25, 0, 0, 0)
+            .earliest()
+            .unwrap()
+            .timestamp_millis();
+        assert_eq!(pause_resumes_at("2026-10-25", today()).unwrap(), start);
+    }
 
-        assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::Schedule
-        );
+    #[test]
+    fn has_to_end_after_today() {
+        assert!(pause_resumes_at("2026-10-18", today()).is_err());
+        assert!(pause_resumes_at("2026-10-01", today()).is_err());
+        assert!(pause_resumes_at("2026-10-19", today()).is_ok());
+        assert!(pause_resumes_at("next week", today()).is_err());
     }
 
     #[test]
-    fn schedules_a_brand_new_alarm_with_no_previous_state() {
-        let current = alarm(true, Some(1_000), None);
+    fn a_global_pause_covers_every_alarm() {
+        assert!(pause_covers(&pause(None), &[], 7));
+    }
 
-        assert_eq!(
-            classify_scheduling_transition(None, &current),
-            SchedulingTransition::Schedule
-        );
+    #[test]
+    fn a_group_pause_covers_only_its_group() {
+        let groups = [AlarmGroup {
+            id: 2,
+            name: "Work week".into(),
+            alarm_ids: vec![1, 3],
+            revision: 1,
+        }];
+        assert!(pause_covers(&pause(Some(2)), &groups, 3));
+        assert!(!pause_covers(&pause(Some(2)), &groups, 4));
+        assert!(!pause_covers(&pause(Some(5)), &groups, 3));
     }
 
     #[test]
-    fn cancels_as_disabled_when_the_user_toggles_off() {
-        let previous = alarm(true, Some(1_000), None);
-        let current = alarm(false, Some(1_000), None);
+    fn pause_end_native_ids_belong_to_nothing_else() {
+        let native = pause_end_native_id(3);
+        assert_eq!(pause_end_parent(native), Some(3));
+        assert_eq!(timer_parent(native), None);
+        assert_eq!(pause_end_parent(BEDTIME_REMINDER_ID), None);
+        assert_eq!(pause_end_parent(timer_native_id(3)), None);
+    }
 
+    #[test]
+    fn pause_end_ids_stay_inside_their_block() {
+        assert_eq!(pause_end_parent(PAUSE_END_ID_OFFSET), None);
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::Cancel(CancelReason::Disabled)
+            pause_end_parent(PAUSE_END_ID_OFFSET + 999_999),
+            Some(999_999)
         );
+        assert_eq!(pause_end_parent(PAUSE_END_ID_OFFSET + 1_000_000), None);
+        assert_eq!(pause_end_parent(i32::MAX), None);
+    }
+
+    /// Moves the harness's clock to when `pause` ends.
+    fn move_to_end_of(h: &Harness, pause: &AlarmPause) {
+        h.clock
+            .set(chrono::DateTime::from_timestamp_millis(pause.resumes_at).unwrap());
+    }
+
+    async fn enabled(h: &Harness, id: i32) -> bool {
+        h.coordinator
+            .get_alarm(h.handle(), id)
+            .await
+            .unwrap()
+            .enabled
+    }
+
+    #[tokio::test]
+    async fn a_pause_wakes_the_app_natively_when_it_ends() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let scheduled = h.capture("alarm:scheduled");
+        let cancelled = h.capture("alarm:cancelled");
+
+        let pause = h
+            .coordinator
+            .pause_alarms(h.handle(), None, "2030-01-05".into())
+            .await
+            .unwrap();
+        let wake_up = pause_end_native_id(pause.id);
+        {
+            let scheduled = scheduled.lock().unwrap();
+            let event = scheduled
+                .iter()
+                .find(|e| e["id"] == wake_up)
+                .expect("the pause's end is scheduled");
+            assert_eq!(event["triggerAt"], pause.resumes_at);
+            assert_eq!(event["wakeOnly"], true);
+        }
+
+        move_to_end_of(&h, &pause);
+        let fired = h
+            .coordinator
+            .report_alarm_fired(h.handle(), wake_up, pause.resumes_at)
+            .await
+            .unwrap();
+        assert!(fired);
+        assert!(enabled(&h, alarm.id).await);
+        assert!(h.coordinator.get_pauses().await.unwrap().is_empty());
+        let cancelled = cancelled.lock().unwrap().clone();
+        assert!(cancelled
+            .iter()
+            .any(|e| e["id"] == wake_up && e["reason"] == "EXPIRED"));
+    }
+
+    #[tokio::test]
+    async fn launch_reschedules_a_running_pause_and_ends_one_that_came_due() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let alarm = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let pause = h
+            .coordinator
+            .pause_alarms(h.handle(), None, "2030-01-05".into())
+            .await
+            .unwrap();
+        let scheduled = h.capture("alarm:scheduled");
+
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        let wake_ups: Vec<_> = scheduled
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e["id"] == pause_end_native_id(pause.id))
+            .map(|e| e["triggerAt"].as_i64().unwrap())
+            .collect();
+        assert_eq!(wake_ups, vec![pause.resumes_at]);
+        assert!(!enabled(&h, alarm.id).await);
+
+        // The app was down, with the wake-up lost to a reboot, when the pause ended.
+        move_to_end_of(&h, &pause);
+        h.clock.advance(chrono::Duration::hours(3));
+        h.coordinator.heal_on_launch(h.handle()).await.unwrap();
+        assert!(enabled(&h, alarm.id).await);
+        assert!(h.coordinator.get_pauses().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn an_alarm_already_off_stays_off_after_the_pause() {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let on = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let off = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("08:00"))
+            .await
+            .unwrap();
+        h.coordinator
+            .toggle_alarm(h.handle(), off.id, false)
+            .await
+            .unwrap();
+
+        let pause = h
+            .coordinator
+            .pause_alarms(h.handle(), None, "2030-01-05".into())
+            .await
+            .unwrap();
+        assert_eq!(pause.alarm_ids, vec![on.id]);
+
+        move_to_end_of(&h, &pause);
+        h.coordinator.end_due_pauses(h.handle()).await.unwrap();
+        assert!(enabled(&h, on.id).await);
+        assert!(!enabled(&h, off.id).await);
+    }
+
+    /// Alarm `grouped` in group "Work week" and alarm `loose` in none, each daily.
+    async fn grouped_and_loose() -> (Harness, AlarmGroup, i32, i32) {
+        let h = Harness::at(zoned(TORONTO, "2030-01-01", "12:00")).await;
+        let grouped = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap()
+            .id;
+        let loose = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("08:00"))
+            .await
+            .unwrap()
+            .id;
+        let group = h
+            .coordinator
+            .save_group(h.handle(), None, "Work week".into(), vec![grouped])
+            .await
+            .unwrap();
+        (h, group, grouped, loose)
+    }
+
+    #[tokio::test]
+    async fn a_group_pause_ending_inside_a_global_one_hands_its_alarms_on() {
+        let (h, group, grouped, loose) = grouped_and_loose().await;
+        let group_pause = h
+            .coordinator
+            .pause_alarms(h.handle(), Some(group.id), "2030-01-05".into())
+            .await
+            .unwrap();
+        let global = h
+            .coordinator
+            .pause_alarms(h.handle(), None, "2030-01-08".into())
+            .await
+            .unwrap();
+        assert_eq!(global.alarm_ids, vec![loose]);
+
+        move_to_end_of(&h, &group_pause);
+        h.coordinator.end_due_pauses(h.handle()).await.unwrap();
+        assert!(!enabled(&h, grouped).await);
+        assert!(!enabled(&h, loose).await);
+        let pauses = h.coordinator.get_pauses().await.unwrap();
+        assert_eq!(pauses.len(), 1);
+        assert_eq!(pauses[0].alarm_ids, vec![grouped, loose]);
+
+        move_to_end_of(&h, &global);
+        h.coordinator.end_due_pauses(h.handle()).await.unwrap();
+        assert!(enabled(&h, grouped).await);
+        assert!(enabled(&h, loose).await);
+    }
+
+    #[tokio::test]
+    async fn a_global_pause_ending_inside_a_group_one_hands_the_group_on() {
+        let (h, group, grouped, loose) = grouped_and_loose().await;
+        let global = h
+            .coordinator
+            .pause_alarms(h.handle(), None, "2030-01-05".into())
+            .await
+            .unwrap();
+        let group_pause = h
+            .coordinator
+            .pause_alarms(h.handle(), Some(group.id), "2030-01-08".into())
+            .await
+            .unwrap();
+
+        move_to_end_of(&h, &global);
+        h.coordinator.end_due_pauses(h.handle()).await.unwrap();
+        assert!(!enabled(&h, grouped).await);
+        assert!(enabled(&h, loose).await);
+        let pauses = h.coordinator.get_pauses().await.unwrap();
+        assert_eq!(pauses.len(), 1);
+        assert_eq!(pauses[0].id, group_pause.id);
+        assert_eq!(pauses[0].alarm_ids, vec![grouped]);
+
+        move_to_end_of(&h, &group_pause);
+        h.coordinator.end_due_pauses(h.handle()).await.unwrap();
+        assert!(enabled(&h, grouped).await);
+    }
+}
+
+#[cfg(test)]
+mod bedtime_reminder_tests {
+    use super::test_support::{at, base_alarm, daily_at, zoned, Harness, TORONTO};
+    use super::*;
+
+    /// An alarm in Toronto at `fixed_time`, next due at `next_trigger`.
+    fn alarm(id: i32, fixed_time: &str, next_trigger: i64) -> AlarmRecord {
+        AlarmRecord {
+            id,
+            fixed_time: Some(fixed_time.into()),
+            timezone: Some(TORONTO.into()),
+            next_trigger: Some(next_trigger),
+            ..base_alarm()
+        }
+    }
+
+    fn reminder() -> BedtimeReminder {
+        BedtimeReminder {
+            enabled: true,
+            sleep_minutes: 480,
+            wind_down_minutes: 30,
+        }
     }
 
     #[test]
-    fn cancels_as_updated_when_still_enabled_but_no_longer_has_a_trigger() {
-        let previous = alarm(true, Some(1_000), None);
-        let current = alarm(true, None, None);
+    fn reminds_sleep_and_wind_down_before_the_next_alarm() {
+        let alarms = [
+            alarm(1, "07:00", at("2026-10-20", "07:00")),
+            alarm(2, "09:00", at("2026-10-20", "09:00")),
+        ];
 
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::Cancel(CancelReason::Updated)
+            bedtime_reminder_at(&alarms, &reminder(), at("2026-10-19", "12:00")),
+            Some(at("2026-10-19", "22:30"))
         );
     }
 
     #[test]
-    fn reschedules_when_the_trigger_time_changes() {
-        let previous = alarm(true, Some(1_000), None);
-        let current = alarm(true, Some(2_000), None);
+    fn moving_the_next_alarm_earlier_moves_the_reminder() {
+        let before = [alarm(1, "07:00", at("2026-10-20", "07:00"))];
+        let after = [alarm(1, "06:30", at("2026-10-20", "06:30"))];
+        let now = at("2026-10-19", "12:00");
 
+        let scheduled = bedtime_reminder_at(&before, &reminder(), now);
+        let next = bedtime_reminder_at(&after, &reminder(), now);
+
+        assert_eq!(next, Some(at("2026-10-19", "22:00")));
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
+            classify_bedtime_transition(scheduled, next, true),
             SchedulingTransition::Reschedule
         );
     }
 
     #[test]
-    fn reschedules_when_only_the_sound_changes() {
-        let previous = alarm(true, Some(1_000), Some("a.mp3"));
-        let current = alarm(true, Some(1_000), Some("b.mp3"));
+    fn switching_off_the_next_alarm_moves_the_reminder_to_the_one_after() {
+        let mut alarms = [
+            alarm(1, "07:00", at("2026-10-20", "07:00")),
+            alarm(2, "09:00", at("2026-10-20", "09:00")),
+        ];
+        alarms[0].enabled = false;
 
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::Reschedule
+            bedtime_reminder_at(&alarms, &reminder(), at("2026-10-19", "12:00")),
+            Some(at("2026-10-20", "00:30"))
         );
     }
 
     #[test]
-    fn does_nothing_when_neither_trigger_nor_sound_changed() {
-        let previous = alarm(true, Some(1_000), Some("a.mp3"));
-        let current = alarm(true, Some(1_000), Some("a.mp3"));
+    fn a_window_alarm_counts_from_its_window_start() {
+        let window = AlarmRecord {
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("06:30".into()),
+            window_end: Some("07:00".into()),
+            ..alarm(1, "06:30", at("2026-10-20", "06:47"))
+        };
 
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::NoOp
+            bedtime_reminder_at(&[window], &reminder(), at("2026-10-19", "12:00")),
+            Some(at("2026-10-19", "22:00"))
         );
     }
 
     #[test]
-    fn does_nothing_for_an_alarm_that_was_never_and_still_is_not_scheduled() {
-        let previous = alarm(false, None, None);
-        let current = alarm(false, None, None);
+    fn no_reminder_when_off_or_once_bedtime_has_passed() {
+        let alarms = [alarm(1, "07:00", at("2026-10-20", "07:00"))];
+        let off = BedtimeReminder {
+            enabled: false,
+            ..reminder()
+        };
 
         assert_eq!(
-            classify_scheduling_transition(Some(&previous), &current),
-            SchedulingTransition::NoOp
+            bedtime_reminder_at(&alarms, &off, at("2026-10-19", "12:00")),
+            None
+        );
+        assert_eq!(
+            bedtime_reminder_at(&alarms, &reminder(), at("2026-10-19", "23:00")),
+            None
+        );
+        assert_eq!(
+            bedtime_reminder_at(&[], &reminder(), at("2026-10-19", "12:00")),
+            None
         );
     }
 
     #[test]
-    fn does_nothing_for_a_brand_new_disabled_alarm_with_no_previous_state() {
-        let current = alarm(false, None, None);
+    fn only_a_changed_reminder_time_touches_the_native_schedule() {
+        let bedtime = Some(at("2026-10-19", "22:30"));
 
         assert_eq!(
-            classify_scheduling_transition(None, &current),
+            classify_bedtime_transition(None, bedtime, true),
+            SchedulingTransition::Schedule
+        );
+        assert_eq!(
+            classify_bedtime_transition(bedtime, bedtime, true),
             SchedulingTransition::NoOp
         );
+        assert_eq!(
+            classify_bedtime_transition(bedtime, None, true),
+            SchedulingTransition::Cancel(CancelReason::Updated)
+        );
+        assert_eq!(
+            classify_bedtime_transition(bedtime, None, false),
+            SchedulingTransition::Cancel(CancelReason::Disabled)
+        );
+    }
+
+    /// Revisions of the bedtime reminder's events in `seen`.
+    fn reminder_events(seen: &std::sync::Mutex<Vec<serde_json::Value>>) -> Vec<i64> {
+        let seen = seen.lock().unwrap();
+        seen.iter()
+            .filter(|e| e["id"] == BEDTIME_REMINDER_ID)
+            .map(|e| e["revision"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn switching_a_group_off_moves_the_reminder_once() {
+        let h = Harness::at(zoned(TORONTO, "2026-10-19", "12:00")).await;
+        let first = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let second = h
+            .coordinator
+            .save_alarm(h.handle(), daily_at("09:00"))
+            .await
+            .unwrap();
+        h.coordinator
+            .set_bedtime_reminder(h.handle(), reminder())
+            .await
+            .unwrap();
+        let group = h
+            .coordinator
+            .save_group(h.handle(), None, "Work".into(), vec![first.id, second.id])
+            .await
+            .unwrap();
+        let scheduled = h.capture("alarm:scheduled");
+        let cancelled = h.capture("alarm:cancelled");
+
+        h.coordinator
+            .set_group_enabled(h.handle(), group.id, false)
+            .await
+            .unwrap();
+
+        // Not moved to the 09:00 alarm on the way to being cancelled outright.
+        assert!(reminder_events(&scheduled).is_empty());
+        assert_eq!(reminder_events(&cancelled).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn changing_the_reminder_stamps_a_new_revision() {
+        let h = Harness::at(zoned(TORONTO, "2026-10-19", "12:00")).await;
+        h.coordinator
+            .save_alarm(h.handle(), daily_at("07:00"))
+            .await
+            .unwrap();
+        let before = h.coordinator.current_revision().await.unwrap();
+        let scheduled = h.capture("alarm:scheduled");
+
+        h.coordinator
+            .set_bedtime_reminder(h.handle(), reminder())
+            .await
+            .unwrap();
+
+        assert_eq!(reminder_events(&scheduled), [before + 1]);
+        assert_eq!(h.coordinator.current_revision().await.unwrap(), before + 1);
     }
 }
@@ -18,13 +18,39 @@ pub struct AlarmRecord {
     pub fixed_time: Option<String>,   // "HH:MM"
     pub window_start: Option<String>, // "HH:MM"
     pub window_end: Option<String>,   // "HH:MM"
-    pub active_days: Vec<i32>,        // [0-6] where 0=Sun
+    pub window_distribution: WindowDistribution,
+    // INTERVAL mode only: minutes between instances from window_start to window_end
+    pub interval_minutes: Option<u32>,
+    // "HH:MM" that sleep-cycle windows count 90-minute cycles from
+    pub bedtime: Option<String>,
+    pub solar: Option<SolarSchedule>, // solar modes only
+    pub timezone: Option<String>,     // IANA zone the times are in; device zone if unset
+    pub dst_policy: DstPolicy,
+    pub active_days: Vec<i32>,             // [0-6] where 0=Sun
+    pub one_shot_date: Option<String>,     // "YYYY-MM-DD", one-shot modes only
+    pub recurrence_rule: Option<String>,   // RFC 5545 RRULE subset; replaces active_days
+    pub recurrence_anchor: Option<String>, // "YYYY-MM-DD", the rule's DTSTART
+    pub skip_dates: Vec<String>,           // "YYYY-MM-DD" occurrence dates not to fire on
+    pub skip_holidays: bool,               // also skip dates in the holidays table
+    // A quieter alarm that rings a set time before this one
+    pub pre_alarm: Option<PreAlarm>,
+    // Limits on snoozing each occurrence; unrestricted if unset
+    pub snooze_policy: Option<SnoozePolicy>,
+    pub snooze_count: u32, // snoozes of the current occurrence, 0 once it ends
+    // What happens to a ring nobody attends to; rings until dismissed if unset
+    pub ring_timeout: Option<RingTimeout>,
+    // A louder second alarm for a ring left undismissed
+    pub backup_alarm: Option<BackupAlarm>,
+    pub follow_up: Option<FollowUp>, // FOLLOW_UP mode only
     // i64 -> bigint by default in ts-rs; these are millisecond timestamps and
     // a revision counter, both safely within JS's Number.MAX_SAFE_INTEGER for
     // the app's realistic lifetime, and all existing call sites already treat
     // them as `number`.
     #[cfg_attr(test, ts(type = "number | null"))]
     pub next_trigger: Option<i64>, // Epoch millis
+    // While snoozed: the trigger the occurrence was first due at
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub snoozed_from: Option<i64>,
     pub sound_uri: Option<String>,
     pub sound_title: Option<String>,
     #[cfg_attr(test, ts(type = "number"))]
@@ -49,8 +75,49 @@ pub struct AlarmInput {
     pub window_start: Option<String>,
     #[cfg_attr(test, ts(optional = nullable))]
     pub window_end: Option<String>,
+    // Omitted keeps the alarm's existing distribution (uniform for new alarms).
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub window_distribution: Option<WindowDistribution>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub interval_minutes: Option<u32>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub bedtime: Option<String>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub solar: Option<SolarSchedule>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub timezone: Option<String>,
+    // Omitted keeps the alarm's existing policy (skip gaps, first of an overlap for
+    // new alarms).
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub dst_policy: Option<DstPolicy>,
     pub active_days: Vec<i32>,
     #[cfg_attr(test, ts(optional = nullable))]
+    pub one_shot_date: Option<String>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub recurrence_rule: Option<String>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub recurrence_anchor: Option<String>,
+    // Omitted (e.g. by the edit form) keeps the alarm's existing skip dates.
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub skip_dates: Option<Vec<String>>,
+    // Omitted keeps the alarm's existing setting (off for new alarms).
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub skip_holidays: Option<bool>,
+    // Omitted keeps the alarm's existing pre-alarm; `set_pre_alarm` removes one.
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub pre_alarm: Option<PreAlarm>,
+    // Omitted keeps the alarm's existing policy; an unrestricted one removes it.
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub snooze_policy: Option<SnoozePolicy>,
+    // Omitted keeps the alarm's existing timeout; one of 0 minutes removes it.
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub ring_timeout: Option<RingTimeout>,
+    // Omitted keeps the alarm's existing backup; one of 0 minutes removes it.
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub backup_alarm: Option<BackupAlarm>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub follow_up: Option<FollowUp>,
+    #[cfg_attr(test, ts(optional = nullable))]
     pub sound_uri: Option<String>,
     #[cfg_attr(test, ts(optional = nullable))]
     pub sound_title: Option<String>,
@@ -61,6 +128,339 @@ pub struct AlarmInput {
 pub enum AlarmMode {
     Fixed,
     Window,
+    /// A single `fixed_time` occurrence on `one_shot_date`; `active_days` is ignored.
+    FixedOnce,
+    /// A single random time inside the window that opens on `one_shot_date`.
+    WindowOnce,
+    /// `solar.offset_minutes` from a sunrise or sunset on each active day.
+    Solar,
+    /// A random time inside the `solar.window_minutes` window that opens
+    /// `solar.offset_minutes` from a sunrise or sunset on each active day.
+    SolarWindow,
+    /// Every `interval_minutes` from `window_start` until `window_end` on each active
+    /// day; dismissing one instance moves on to the next.
+    Interval,
+    /// `follow_up.minutes_after` each time the alarm it follows is dismissed;
+    /// `active_days` is ignored.
+    FollowUp,
+}
+
+/// How a window alarm's minute is picked between `window_start` and `window_end`.
+/// Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WindowDistribution {
+    /// Every minute equally likely.
+    #[default]
+    Uniform,
+    /// Most likely at the window start, tapering linearly to the end.
+    TriangularStart,
+    /// Most likely at the window end, tapering linearly from the start.
+    TriangularEnd,
+    /// Clustered around `target` ("HH:MM"), never outside the window.
+    #[serde(rename_all = "camelCase")]
+    TruncatedNormal {
+        target: String,
+        std_dev_minutes: f64,
+    },
+    /// Uniform, but never in the window's final `minutes`.
+    #[serde(rename_all = "camelCase")]
+    AvoidLast { minutes: u32 },
+    /// On a 90-minute sleep-cycle boundary counted from the alarm's `bedtime`, when
+    /// one falls inside the window; uniform otherwise.
+    SleepCycles,
+}
+
+/// The sun position a solar alarm is anchored to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SolarEvent {
+    /// Upper limb on the horizon, allowing for refraction (-0.833 degrees).
+    Sunrise,
+    Sunset,
+    /// Sun 6 degrees below the horizon: first light bright enough to be outdoors.
+    CivilDawn,
+    CivilDusk,
+}
+
+/// Where, and relative to which solar event, a solar alarm fires. Computed on the
+/// device; no network lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SolarSchedule {
+    pub event: SolarEvent,
+    pub latitude: f64,       // degrees, north positive
+    pub longitude: f64,      // degrees, east positive
+    pub offset_minutes: i32, // negative fires before the event
+    // SOLAR_WINDOW only: the window opens at event + offset and lasts this long.
+    pub window_minutes: Option<u32>,
+}
+
+/// What a clock time does on the nights daylight saving time starts or ends. Stored
+/// as JSON in the alarm row.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DstPolicy {
+    pub gap: DstGapPolicy,
+    pub overlap: DstOverlapPolicy,
+}
+
+/// A time that doesn't exist because the clocks spring forward past it (e.g. 02:30).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DstGapPolicy {
+    /// Don't fire that day.
+    #[default]
+    Skip,
+    /// Fire as if the clocks hadn't changed yet, i.e. later by the size of the jump
+    /// (02:30 becomes 03:30).
+    ShiftForward,
+}
+
+/// A time that happens twice because the clocks fall back over it (e.g. 01:30).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DstOverlapPolicy {
+    /// Fire at the earlier of the two, still on daylight time.
+    #[default]
+    FireAtFirst,
+    /// Fire at the later of the two, after the clocks have gone back.
+    FireAtSecond,
+}
+
+/// A gentle first stage that rings `minutes_before` the alarm's trigger, quietly and
+/// only for a while. Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PreAlarm {
+    pub minutes_before: u32,
+    pub sound_uri: Option<String>, // device default alarm sound if unset
+    pub sound_title: Option<String>,
+    pub volume_percent: u8, // of the alarm stream, 1-100
+    pub ring_seconds: u32,  // stops by itself after this long
+    // Dismissing the pre-alarm also skips the alarm it comes before.
+    pub skip_main_on_dismiss: bool,
+}
+
+/// Pre-alarms are scheduled natively under their alarm's id plus this offset, clear of
+/// alarm ids and of the upcoming-notification ids (alarm id + 1,000,000).
+pub const PRE_ALARM_ID_OFFSET: i32 = 2_000_000;
+
+/// The native id an alarm's pre-alarm is scheduled, fired and dismissed under.
+pub fn pre_alarm_native_id(alarm_id: i32) -> i32 {
+    PRE_ALARM_ID_OFFSET + alarm_id
+}
+
+/// The alarm a native id is the pre-alarm of, or `None` for an alarm's own id.
+pub fn pre_alarm_parent(native_id: i32) -> Option<i32> {
+    (native_id > PRE_ALARM_ID_OFFSET && native_id < BACKUP_ALARM_ID_OFFSET)
+        .then(|| native_id - PRE_ALARM_ID_OFFSET)
+}
+
+/// Limits on snoozing an occurrence, enforced whichever device the snooze comes from.
+/// Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SnoozePolicy {
+    pub max_snoozes: Option<u32>, // per occurrence; 0 turns snoozing off
+    // Minutes for the 1st, 2nd, ... snooze, the last one repeating (e.g. [10, 5, 3]);
+    // empty uses the snooze length the device asks for.
+    pub intervals_minutes: Vec<u32>,
+    // "HH:MM" from which an occurrence can no longer be snoozed
+    pub no_snooze_after: Option<String>,
+}
+
+impl SnoozePolicy {
+    /// Whether the policy leaves snoozing as it is without one.
+    pub fn is_unrestricted(&self) -> bool {
+        self.max_snoozes.is_none()
+            && self.intervals_minutes.is_empty()
+            && self.no_snooze_after.is_none()
+    }
+}
+
+/// Whether a ringing occurrence may be snoozed now, and for how long.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SnoozeAllowance {
+    pub allowed: bool,
+    // The policy's length for this snooze; unset uses the device's snooze length
+    pub minutes: Option<u32>,
+    // Why snoozing is refused, for display
+    pub reason: Option<String>,
+}
+
+/// What happens to an occurrence left ringing with nobody dismissing or snoozing it.
+/// Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RingTimeout {
+    pub minutes: u32, // of ringing before `action` is taken
+    pub action: RingTimeoutAction,
+    // Ring the watch insistently halfway through, in case the phone is out of earshot
+    pub escalate_to_watch: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RingTimeoutAction {
+    Snooze, // dismisses instead once the snooze policy refuses
+    Dismiss,
+}
+
+/// A second, louder alarm that rings `minutes_after` an occurrence first rang if it
+/// still hasn't been dismissed or snoozed by then. Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BackupAlarm {
+    pub minutes_after: u32,
+    pub sound_uri: Option<String>, // the alarm's own sound if unset
+    pub sound_title: Option<String>,
+    // Ring only the watch, for when the phone is out of reach or would wake others
+    pub watch_only: bool,
+}
+
+/// Backup alarms are scheduled natively under their alarm's id plus this offset, clear
+/// of the pre-alarm ids below it.
+pub const BACKUP_ALARM_ID_OFFSET: i32 = 3_000_000;
+
+/// The native id an alarm's backup is scheduled, fired and dismissed under.
+pub fn backup_alarm_native_id(alarm_id: i32) -> i32 {
+    BACKUP_ALARM_ID_OFFSET + alarm_id
+}
+
+/// The alarm a native id is the backup of, or `None` for any other id.
+pub fn backup_alarm_parent(native_id: i32) -> Option<i32> {
+    (native_id > BACKUP_ALARM_ID_OFFSET && native_id < TIMER_ID_OFFSET)
+        .then(|| native_id - BACKUP_ALARM_ID_OFFSET)
+}
+
+/// Timers are scheduled natively under their own id plus this offset, clear of every
+/// alarm's ids below it.
+pub const TIMER_ID_OFFSET: i32 = 4_000_000;
+
+/// The native id a timer is scheduled, fired and dismissed under.
+pub fn timer_native_id(timer_id: i32) -> i32 {
+    TIMER_ID_OFFSET + timer_id
+}
+
+/// The timer a native id belongs to, or `None` for an alarm's ids or the bedtime
+/// reminder's.
+pub fn timer_parent(native_id: i32) -> Option<i32> {
+    (native_id > TIMER_ID_OFFSET && native_id < BEDTIME_REMINDER_ID)
+        .then(|| native_id - TIMER_ID_OFFSET)
+}
+
+/// The bedtime reminder is scheduled natively under this id, clear of every timer's
+/// below it; there's only ever one.
+pub const BEDTIME_REMINDER_ID: i32 = 5_000_000;
+
+/// A pause's end is scheduled natively under its id plus this offset, above the
+/// bedtime reminder's, to wake the app to switch its alarms back on.
+pub const PAUSE_END_ID_OFFSET: i32 = 6_000_000;
+
+/// The top of the pause ends' ids, leaving them the same million-id block as every
+/// other kind of native id.
+const PAUSE_END_ID_LIMIT: i32 = PAUSE_END_ID_OFFSET + 1_000_000;
+
+/// The native id a pause's end is scheduled and fired under.
+pub fn pause_end_native_id(pause_id: i32) -> i32 {
+    PAUSE_END_ID_OFFSET + pause_id
+}
+
+/// The pause a native id ends, or `None` for any other id.
+pub fn pause_end_parent(native_id: i32) -> Option<i32> {
+    (native_id > PAUSE_END_ID_OFFSET && native_id < PAUSE_END_ID_LIMIT)
+        .then(|| native_id - PAUSE_END_ID_OFFSET)
+}
+
+/// The alarm a follow-up alarm rings after. Stored as JSON in the alarm row.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FollowUp {
+    pub alarm_id: i32,      // which may itself be a follow-up, but never this one's
+    pub minutes_after: u32, // of that alarm's occurrence being dismissed
+}
+
+/// How a clock time an alarm uses falls around a DST change.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DstTransition {
+    Gap,
+    Overlap,
+}
+
+impl AlarmMode {
+    /// Whether the trigger is sampled from `window_start`..`window_end` rather than
+    /// taken from `fixed_time`.
+    pub fn is_window(&self) -> bool {
+        matches!(self, AlarmMode::Window | AlarmMode::WindowOnce)
+    }
+
+    /// Whether the trigger is a random draw from some window, clock- or sun-based.
+    pub fn is_sampled(&self) -> bool {
+        self.is_window() || *self == AlarmMode::SolarWindow
+    }
+
+    /// Whether the trigger follows the sun at `solar`'s position rather than a clock
+    /// time.
+    pub fn is_solar(&self) -> bool {
+        matches!(self, AlarmMode::Solar | AlarmMode::SolarWindow)
+    }
+
+    /// Whether the alarm fires once on `one_shot_date` and then switches itself off,
+    /// rather than recurring on `active_days`.
+    pub fn is_one_shot(&self) -> bool {
+        matches!(self, AlarmMode::FixedOnce | AlarmMode::WindowOnce)
+    }
+}
+
+impl AlarmRecord {
+    /// Rebuilds the save payload for this alarm, for coordinator paths that re-save an
+    /// existing alarm with one or two fields changed (toggle, dismiss).
+    pub fn to_input(&self) -> AlarmInput {
+        AlarmInput {
+            id: Some(self.id),
+            label: self.label.clone(),
+            enabled: self.enabled,
+            mode: self.mode.clone(),
+            fixed_time: self.fixed_time.clone(),
+            window_start: self.window_start.clone(),
+            window_end: self.window_end.clone(),
+            window_distribution: Some(self.window_distribution.clone()),
+            interval_minutes: self.interval_minutes,
+            bedtime: self.bedtime.clone(),
+            solar: self.solar.clone(),
+            timezone: self.timezone.clone(),
+            dst_policy: Some(self.dst_policy),
+            active_days: self.active_days.clone(),
+            one_shot_date: self.one_shot_date.clone(),
+            recurrence_rule: self.recurrence_rule.clone(),
+            recurrence_anchor: self.recurrence_anchor.clone(),
+            skip_dates: Some(self.skip_dates.clone()),
+            skip_holidays: Some(self.skip_holidays),
+            pre_alarm: self.pre_alarm.clone(),
+            snooze_policy: self.snooze_policy.clone(),
+            ring_timeout: self.ring_timeout.clone(),
+            backup_alarm: self.backup_alarm.clone(),
+            follow_up: self.follow_up.clone(),
+            sound_uri: self.sound_uri.clone(),
+            sound_title: self.sound_title.clone(),
+        }
+    }
 }
 
 impl Default for AlarmInput {
@@ -73,13 +473,306 @@ impl Default for AlarmInput {
             fixed_time: Some("07:00".into()),
             window_start: None,
             window_end: None,
+            window_distribution: None,
+            interval_minutes: None,
+            bedtime: None,
+            solar: None,
+            timezone: None,
+            dst_policy: None,
             active_days: vec![1, 2, 3, 4, 5], // Weekdays
+            one_shot_date: None,
+            recurrence_rule: None,
+            recurrence_anchor: None,
+            skip_dates: None,
+            skip_holidays: None,
+            pre_alarm: None,
+            snooze_policy: None,
+            ring_timeout: None,
+            backup_alarm: None,
+            follow_up: None,
+            sound_uri: None,
+            sound_title: None,
+        }
+    }
+}
+
+/// One upcoming occurrence of an alarm, for previews such as a week-ahead timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Occurrence {
+    pub alarm_id: i32,
+    pub date: String, // "YYYY-MM-DD" the occurrence belongs to (a window's opening day)
+    // Epoch millis: the trigger for fixed alarms, the opening for window alarms.
+    #[cfg_attr(test, ts(type = "number"))]
+    pub starts_at: i64,
+    // Epoch millis the window closes; null for fixed alarms. Windows are reported as
+    // bounds, not the minute that will be sampled inside them.
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub ends_at: Option<i64>,
+}
+
+/// Which occurrences a preview asks for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OccurrenceQuery {
+    /// The next `count` occurrences still to come.
+    Next { count: u32 },
+    /// Every occurrence whose date falls in `from..=to` ("YYYY-MM-DD").
+    Range { from: String, to: String },
+}
+
+/// A day off imported from an .ics file or a bundled regional set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Holiday {
+    pub date: String, // "YYYY-MM-DD"
+    pub name: String,
+    pub source: String, // "region:<code>" or "ics:<name>"; re-imports replace by source
+}
+
+/// A countdown timer, such as a nap: it rings once, `duration_seconds` after it was
+/// started plus however long it spent paused, and is gone once dismissed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Timer {
+    pub id: i32,
+    pub label: Option<String>,
+    pub duration_seconds: u32, // as started
+    pub state: TimerState,
+    // Epoch millis it rings at while running (it's ringing once that has passed); null
+    // while paused
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub ends_at: Option<i64>,
+    // Milliseconds it had left when paused; null while running
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub remaining_ms: Option<i64>,
+    #[cfg_attr(test, ts(type = "number"))]
+    pub created_at: i64,
+    #[cfg_attr(test, ts(type = "number"))]
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimerState {
+    Running,
+    Paused,
+}
+
+impl Timer {
+    /// What a ringing screen opened under the timer's native id loads in place of an
+    /// alarm: its label, and none of an alarm's settings.
+    pub fn ringing_record(&self) -> AlarmRecord {
+        AlarmRecord {
+            id: timer_native_id(self.id),
+            label: self.label.clone(),
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: None,
+            window_start: None,
+            window_end: None,
+            window_distribution: WindowDistribution::default(),
+            interval_minutes: None,
+            bedtime: None,
+            solar: None,
+            timezone: None,
+            dst_policy: DstPolicy::default(),
+            active_days: vec![],
+            one_shot_date: None,
+            recurrence_rule: None,
+            recurrence_anchor: None,
+            skip_dates: vec![],
+            skip_holidays: false,
+            pre_alarm: None,
+            snooze_policy: None,
+            snooze_count: 0,
+            ring_timeout: None,
+            backup_alarm: None,
+            follow_up: None,
+            next_trigger: self.ends_at,
+            snoozed_from: None,
             sound_uri: None,
             sound_title: None,
+            revision: self.revision,
+        }
+    }
+}
+
+/// A named set of alarms, such as "Work week" or "Travel", switched on or off
+/// together. An alarm can be in any number of groups.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmGroup {
+    pub id: i32,
+    pub name: String,
+    pub alarm_ids: Vec<i32>, // ascending
+    #[cfg_attr(test, ts(type = "number"))]
+    pub revision: i64,
+}
+
+/// Alarms switched off until a date, e.g. while away on holiday: every alarm, or one
+/// group's. The alarms it switched off come back on when it ends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmPause {
+    pub id: i32,
+    pub group_id: Option<i32>, // null pauses every alarm
+    pub until: String,         // "YYYY-MM-DD" the alarms come back on
+    #[cfg_attr(test, ts(type = "number"))]
+    pub resumes_at: i64, // epoch millis of the local start of `until`
+    pub alarm_ids: Vec<i32>,   // the alarms it switched off, ascending
+    #[cfg_attr(test, ts(type = "number"))]
+    pub revision: i64,
+}
+
+/// An opt-in nudge to start winding down, timed back from the next alarm so there's
+/// still `sleep_minutes` of sleep before it once the wind-down is over.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BedtimeReminder {
+    pub enabled: bool,
+    pub sleep_minutes: u32,     // target sleep before the next alarm
+    pub wind_down_minutes: u32, // reminded this long before going to sleep
+}
+
+impl Default for BedtimeReminder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sleep_minutes: 480,
+            wind_down_minutes: 30,
         }
     }
 }
 
+/// What happened to an alarm occurrence, as recorded in its history.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlarmEventKind {
+    Fired,
+    Snoozed,
+    Dismissed,
+    Missed,
+}
+
+/// Where a history event came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlarmEventSource {
+    PhoneUi,      // the app's own screens
+    Notification, // an action on a phone notification
+    Watch,
+    System,     // the app itself: a scheduled ring, or a miss it noticed
+    Unattended, // the app, after the alarm's ring timeout ran out
+}
+
+/// One fired, snoozed, dismissed or missed occurrence in an alarm's history. Rows
+/// outlive the alarm, so a deleted alarm's history still counts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmHistoryEntry {
+    #[cfg_attr(test, ts(type = "number"))]
+    pub id: i64,
+    pub alarm_id: i32,
+    pub kind: AlarmEventKind,
+    pub source: AlarmEventSource,
+    // Epoch millis the occurrence was first due, before any snoozes
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub scheduled_trigger: Option<i64>,
+    // Epoch millis the occurrence last rang; null if it hadn't
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub actual_fired_at: Option<i64>,
+    // Epoch millis the event happened
+    #[cfg_attr(test, ts(type = "number"))]
+    pub occurred_at: i64,
+    // Epoch millis the alarm rings next as a result; null if it won't
+    #[cfg_attr(test, ts(type = "number | null"))]
+    pub next_trigger: Option<i64>,
+    // Whether the alarm's time was sampled from a window when this was recorded; null
+    // on rows from before that was kept
+    #[cfg_attr(test, ts(type = "boolean | null"))]
+    pub sampled: Option<bool>,
+}
+
+/// Which history rows to fetch, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub alarm_id: Option<i32>, // unset covers every alarm, deleted ones included
+    // Epoch millis bounds on `occurredAt`, both inclusive
+    #[cfg_attr(test, ts(optional, type = "number | null"))]
+    pub from: Option<i64>,
+    #[cfg_attr(test, ts(optional, type = "number | null"))]
+    pub to: Option<i64>,
+    #[cfg_attr(test, ts(optional = nullable))]
+    pub limit: Option<u32>,
+}
+
+/// Wake-up statistics drawn from the history, shaped for the report's charts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WakeReport {
+    pub overall: WakeStats,
+    // Window alarms against fixed-time ones; deleted alarms only count overall
+    pub window: WakeStats,
+    pub fixed: WakeStats,
+    // When window alarms were dismissed, Sunday first
+    pub weekdays: Vec<WeekdayWakeTimes>,
+    // How late each occurrence first rang, oldest first
+    pub fire_latency: Vec<FireLatency>,
+    pub average_fire_latency_ms: Option<f64>,
+}
+
+/// Averages over the mornings an alarm rang and was dismissed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WakeStats {
+    pub mornings: u32,
+    pub missed: u32,
+    // From the first ring to the dismissal, snoozes included
+    pub average_seconds_to_dismiss: Option<f64>,
+    pub average_snoozes: Option<f64>,
+}
+
+/// The spread of wake-up times on one weekday.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WeekdayWakeTimes {
+    pub weekday: u32, // 0 = Sunday, as in `active_days`
+    // Minutes after local midnight of each dismissal, ascending
+    pub wake_minutes: Vec<u32>,
+    pub median_minute: Option<u32>,
+}
+
+/// How far an occurrence's first ring drifted from its trigger.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FireLatency {
+    pub alarm_id: i32,
+    #[cfg_attr(test, ts(type = "number"))]
+    pub trigger_at: i64,
+    // `actual_fired_at - trigger_at`; negative if it rang early
+    #[cfg_attr(test, ts(type = "number"))]
+    pub latency_ms: i64,
+}
+
 /// Generates `apps/threshold/src/types/alarm.ts` from the types above and
 /// keeps it honest: this test fails if the committed file has drifted from
 /// a fresh generation, so `AlarmRecord`/`AlarmInput` can't silently diverge
@@ -106,11 +799,14 @@ export { AlarmMode };
 ";
 
     /// ts-rs prefixes every export with its own "generated by ts-rs" banner
-    /// comment; strip it since the combined file has one shared header.
+    /// comment, plus imports of any types it references; strip both since the
+    /// combined file has one shared header and defines those types itself.
     fn strip_ts_rs_banner(generated: &str) -> String {
         generated
             .lines()
-            .skip_while(|line| line.starts_with("//") || line.trim().is_empty())
+            .skip_while(|line| {
+                line.starts_with("//") || line.starts_with("import type") || line.trim().is_empty()
+            })
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -119,7 +815,39 @@ export { AlarmMode };
         let cfg = ts_rs::Config::new();
         let record = strip_ts_rs_banner(&AlarmRecord::export_to_string(&cfg).unwrap());
         let input = strip_ts_rs_banner(&AlarmInput::export_to_string(&cfg).unwrap());
-        format!("{HEADER}\n{record}\n\n{input}\n")
+        let distribution = strip_ts_rs_banner(&WindowDistribution::export_to_string(&cfg).unwrap());
+        let solar = strip_ts_rs_banner(&SolarSchedule::export_to_string(&cfg).unwrap());
+        let solar_event = strip_ts_rs_banner(&SolarEvent::export_to_string(&cfg).unwrap());
+        let dst_policy = strip_ts_rs_banner(&DstPolicy::export_to_string(&cfg).unwrap());
+        let dst_gap = strip_ts_rs_banner(&DstGapPolicy::export_to_string(&cfg).unwrap());
+        let dst_overlap = strip_ts_rs_banner(&DstOverlapPolicy::export_to_string(&cfg).unwrap());
+        let pre_alarm = strip_ts_rs_banner(&PreAlarm::export_to_string(&cfg).unwrap());
+        let snooze_policy = strip_ts_rs_banner(&SnoozePolicy::export_to_string(&cfg).unwrap());
+        let allowance = strip_ts_rs_banner(&SnoozeAllowance::export_to_string(&cfg).unwrap());
+        let ring_timeout = strip_ts_rs_banner(&RingTimeout::export_to_string(&cfg).unwrap());
+        let timeout_action =
+            strip_ts_rs_banner(&RingTimeoutAction::export_to_string(&cfg).unwrap());
+        let backup_alarm = strip_ts_rs_banner(&BackupAlarm::export_to_string(&cfg).unwrap());
+        let follow_up = strip_ts_rs_banner(&FollowUp::export_to_string(&cfg).unwrap());
+        let occurrence = strip_ts_rs_banner(&Occurrence::export_to_string(&cfg).unwrap());
+        let query = strip_ts_rs_banner(&OccurrenceQuery::export_to_string(&cfg).unwrap());
+        let holiday = strip_ts_rs_banner(&Holiday::export_to_string(&cfg).unwrap());
+        let timer = strip_ts_rs_banner(&Timer::export_to_string(&cfg).unwrap());
+        let timer_state = strip_ts_rs_banner(&TimerState::export_to_string(&cfg).unwrap());
+        let group = strip_ts_rs_banner(&AlarmGroup::export_to_string(&cfg).unwrap());
+        let pause = strip_ts_rs_banner(&AlarmPause::export_to_string(&cfg).unwrap());
+        let bedtime = strip_ts_rs_banner(&BedtimeReminder::export_to_string(&cfg).unwrap());
+        let event_kind = strip_ts_rs_banner(&AlarmEventKind::export_to_string(&cfg).unwrap());
+        let event_source = strip_ts_rs_banner(&AlarmEventSource::export_to_string(&cfg).unwrap());
+        let history = strip_ts_rs_banner(&AlarmHistoryEntry::export_to_string(&cfg).unwrap());
+        let history_query = strip_ts_rs_banner(&HistoryQuery::export_to_string(&cfg).unwrap());
+        let report = strip_ts_rs_banner(&WakeReport::export_to_string(&cfg).unwrap());
+        let stats = strip_ts_rs_banner(&WakeStats::export_to_string(&cfg).unwrap());
+        let weekday = strip_ts_rs_banner(&WeekdayWakeTimes::export_to_string(&cfg).unwrap());
+        let latency = strip_ts_rs_banner(&FireLatency::export_to_string(&cfg).unwrap());
+        format!(
+            "{HEADER}\n{record}\n\n{input}\n\n{distribution}\n\n{solar}\n\n{solar_event}\n\n{dst_policy}\n\n{dst_gap}\n\n{dst_overlap}\n\n{pre_alarm}\n\n{snooze_policy}\n\n{allowance}\n\n{ring_timeout}\n\n{timeout_action}\n\n{backup_alarm}\n\n{follow_up}\n\n{occurrence}\n\n{query}\n\n{holiday}\n\n{timer}\n\n{timer_state}\n\n{group}\n\n{pause}\n\n{bedtime}\n\n{event_kind}\n\n{event_source}\n\n{history}\n\n{history_query}\n\n{report}\n\n{stats}\n\n{weekday}\n\n{latency}\n"
+        )
     }
 
     fn output_path() -> PathBuf {
@@ -164,5 +892,35 @@ export { AlarmMode };
             serde_json::json!("WINDOW"),
             "packages/core/src/types.ts: AlarmMode.Window must serialise to 'WINDOW'"
         );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::FixedOnce).unwrap(),
+            serde_json::json!("FIXED_ONCE"),
+            "packages/core/src/types.ts: AlarmMode.FixedOnce must serialise to 'FIXED_ONCE'"
+        );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::WindowOnce).unwrap(),
+            serde_json::json!("WINDOW_ONCE"),
+            "packages/core/src/types.ts: AlarmMode.WindowOnce must serialise to 'WINDOW_ONCE'"
+        );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::Solar).unwrap(),
+            serde_json::json!("SOLAR"),
+            "packages/core/src/types.ts: AlarmMode.Solar must serialise to 'SOLAR'"
+        );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::SolarWindow).unwrap(),
+            serde_json::json!("SOLAR_WINDOW"),
+            "packages/core/src/types.ts: AlarmMode.SolarWindow must serialise to 'SOLAR_WINDOW'"
+        );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::Interval).unwrap(),
+            serde_json::json!("INTERVAL"),
+            "packages/core/src/types.ts: AlarmMode.Interval must serialise to 'INTERVAL'"
+        );
+        assert_eq!(
+            serde_json::to_value(AlarmMode::FollowUp).unwrap(),
+            serde_json::json!("FOLLOW_UP"),
+            "packages/core/src/types.ts: AlarmMode.FollowUp must serialise to 'FOLLOW_UP'"
+        );
     }
 }
@@ -0,0 +1,605 @@
+// Parses and expands the RFC 5545 RRULE subset used for alarm recurrence
+//
+// (c) Copyright 2026 Liminal HQ, Scott Morris
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use chrono::{Datelike, Months, NaiveDate, Weekday};
+use std::str::FromStr;
+
+/// How far ahead (in years) a rule is expanded before giving up. Every rule that can
+/// match at all does so within one 400-year Gregorian cycle of any date.
+const MAX_LOOKAHEAD_YEARS: i32 = 400;
+
+/// Supported RRULE parts: `FREQ` (DAILY, WEEKLY, MONTHLY, YEARLY), `INTERVAL`, `COUNT`,
+/// `UNTIL`, `WKST`, `BYMONTH`, `BYMONTHDAY` (negative counts from month end) and `BYDAY`
+/// (with an optional ordinal like `2MO` or `-1FR` for MONTHLY/YEARLY rules). Anything
+/// else is rejected rather than silently ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+    pub week_start: Weekday,
+    pub by_month: Vec<u32>,
+    pub by_month_day: Vec<i32>,
+    pub by_day: Vec<ByDay>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry: a weekday, optionally restricted to its nth (or nth-from-last,
+/// when negative) occurrence within the month or year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let body = s.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            week_start: Weekday::Mon,
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+        };
+
+        for part in body.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed recurrence rule part '{part}'"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unsupported recurrence FREQ '{other}'")),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = parse_number(key, value)?;
+                    if rule.interval == 0 {
+                        return Err("Recurrence INTERVAL must be at least 1".into());
+                    }
+                }
+                "COUNT" => {
+                    let count = parse_number(key, value)?;
+                    if count == 0 {
+                        return Err("Recurrence COUNT must be at least 1".into());
+                    }
+                    rule.count = Some(count);
+                }
+                "UNTIL" => rule.until = Some(parse_until(value)?),
+                "WKST" => rule.week_start = parse_weekday(value)?,
+                "BYMONTH" => {
+                    for month in value.split(',') {
+                        let month: u32 = parse_number(key, month)?;
+                        if !(1..=12).contains(&month) {
+                            return Err(format!("Recurrence BYMONTH '{month}' out of range"));
+                        }
+                        rule.by_month.push(month);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        let day: i32 = parse_number(key, day)?;
+                        if day == 0 || !(-31..=31).contains(&day) {
+                            return Err(format!("Recurrence BYMONTHDAY '{day}' out of range"));
+                        }
+                        rule.by_month_day.push(day);
+                    }
+                }
+                "BYDAY" => {
+                    for entry in value.split(',') {
+                        rule.by_day.push(parse_by_day(entry)?);
+                    }
+                }
+                other => return Err(format!("Unsupported recurrence rule part '{other}'")),
+            }
+        }
+
+        rule.frequency = frequency.ok_or("Recurrence rule missing FREQ")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("Recurrence rule cannot set both COUNT and UNTIL".into());
+        }
+        let has_ordinal = rule.by_day.iter().any(|d| d.ordinal.is_some());
+        if has_ordinal && !matches!(rule.frequency, Frequency::Monthly | Frequency::Yearly) {
+            return Err("Recurrence BYDAY ordinals need FREQ=MONTHLY or FREQ=YEARLY".into());
+        }
+        if rule.frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err("Recurrence BYMONTHDAY is not allowed with FREQ=WEEKLY".into());
+        }
+
+        Ok(rule)
+    }
+}
+
+impl RecurrenceRule {
+    /// The first date on or after `from` that the rule produces when it starts on
+    /// `anchor` (the DTSTART date), or `None` once the rule is exhausted.
+    pub fn next_on_or_after(&self, anchor: NaiveDate, from: NaiveDate) -> Option<NaiveDate> {
+        let from = from.max(anchor);
+        let horizon = from.with_year(from.year() + MAX_LOOKAHEAD_YEARS)?;
+        // COUNT is defined from DTSTART, so those rules have to be walked from the
+        // beginning; everything else can jump straight to the period containing `from`.
+        let mut period = if self.count.is_some() {
+            0
+        } else {
+            self.first_period_for(anchor, from)
+        };
+        let mut produced = 0;
+
+        loop {
+            let start = self.period_start(anchor, period)?;
+            if start > horizon || self.until.is_some_and(|until| start > until) {
+                return None;
+            }
+            for date in self.expand_period(anchor, start) {
+                if date < anchor || self.until.is_some_and(|until| date > until) {
+                    continue;
+                }
+                produced += 1;
+                if self.count.is_some_and(|count| produced > count) {
+                    return None;
+                }
+                if date >= from {
+                    return Some(date);
+                }
+            }
+            period += 1;
+        }
+    }
+
+    /// Whether the rule, started on `anchor`, produces `date`.
+    pub fn occurs_on(&self, anchor: NaiveDate, date: NaiveDate) -> bool {
+        self.next_on_or_after(anchor, date) == Some(date)
+    }
+
+    /// Index of the earliest interval-aligned period that can still contain `from`.
+    fn first_period_for(&self, anchor: NaiveDate, from: NaiveDate) -> u32 {
+        let interval = self.interval as i64;
+        let elapsed = match self.frequency {
+            Frequency::Daily => (from - anchor).num_days(),
+            Frequency::Weekly => (self.week_of(from) - self.week_of(anchor)).num_days() / 7,
+            Frequency::Monthly => months_between(anchor, from),
+            Frequency::Yearly => (from.year() - anchor.year()) as i64,
+        };
+        (elapsed / interval) as u32
+    }
+
+    /// First day of the `index`th period counted from the anchor's period.
+    fn period_start(&self, anchor: NaiveDate, index: u32) -> Option<NaiveDate> {
+        let steps = index.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => anchor.checked_add_days(chrono::Days::new(steps as u64)),
+            Frequency::Weekly => self
+                .week_of(anchor)
+                .checked_add_days(chrono::Days::new(steps as u64 * 7)),
+            Frequency::Monthly => anchor.with_day(1)?.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => NaiveDate::from_ymd_opt(anchor.year() + steps as i32, 1, 1),
+        }
+    }
+
+    /// Every date the rule produces inside the period beginning on `start`, in order.
+    fn expand_period(&self, anchor: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                let keep = self.month_allowed(start.month())
+                    && (self.by_month_day.is_empty()
+                        || matches_month_day(&self.by_month_day, start))
+                    && (self.by_day.is_empty()
+                        || self.by_day.iter().any(|d| d.weekday == start.weekday()));
+                if keep {
+                    vec![start]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![anchor.weekday()]
+                } else {
+                    self.by_day.iter().map(|d| d.weekday).collect()
+                };
+                start
+                    .iter_days()
+                    .take(7)
+                    .filter(|d| weekdays.contains(&d.weekday()) && self.month_allowed(d.month()))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                if self.month_allowed(start.month()) {
+                    self.expand_month(anchor, start, true)
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Yearly => self.expand_year(anchor, start.year()),
+        };
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+
+    fn expand_year(&self, anchor: NaiveDate, year: i32) -> Vec<NaiveDate> {
+        if !self.by_month.is_empty() {
+            return self
+                .by_month
+                .iter()
+                .filter_map(|&month| NaiveDate::from_ymd_opt(year, month, 1))
+                .flat_map(|first| self.expand_month(anchor, first, true))
+                .collect();
+        }
+        if !self.by_month_day.is_empty() {
+            return (1..=12)
+                .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+                .flat_map(|first| self.expand_month(anchor, first, false))
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            // BYDAY ordinals without BYMONTH count across the whole year.
+            let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+                return Vec::new();
+            };
+            let days: Vec<NaiveDate> = first.iter_days().take_while(|d| d.year() == year).collect();
+            return expand_by_day(&self.by_day, &days);
+        }
+        NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day())
+            .into_iter()
+            .collect()
+    }
+
+    /// Dates within the month starting at `first`. With neither BYMONTHDAY nor BYDAY
+    /// the anchor's day of month is reused, so monthly rules anchored on the 31st skip
+    /// shorter months the way RFC 5545 specifies.
+    fn expand_month(
+        &self,
+        anchor: NaiveDate,
+        first: NaiveDate,
+        default_day: bool,
+    ) -> Vec<NaiveDate> {
+        let days: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|d| d.month() == first.month())
+            .collect();
+
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            return if default_day {
+                first.with_day(anchor.day()).into_iter().collect()
+            } else {
+                Vec::new()
+            };
+        }
+
+        let candidates = if self.by_day.is_empty() {
+            days
+        } else {
+            expand_by_day(&self.by_day, &days)
+        };
+        if self.by_month_day.is_empty() {
+            candidates
+        } else {
+            candidates
+                .into_iter()
+                .filter(|d| matches_month_day(&self.by_month_day, *d))
+                .collect()
+        }
+    }
+
+    fn month_allowed(&self, month: u32) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&month)
+    }
+
+    /// First day of the week (per WKST) that contains `date`.
+    fn week_of(&self, date: NaiveDate) -> NaiveDate {
+        let offset = (7 + date.weekday().num_days_from_monday()
+            - self.week_start.num_days_from_monday())
+            % 7;
+        date - chrono::Duration::days(offset as i64)
+    }
+}
+
+/// Selects the dates in `days` (a whole month or year, in order) matching any `BYDAY`
+/// entry, honouring ordinals relative to that span.
+fn expand_by_day(by_day: &[ByDay], days: &[NaiveDate]) -> Vec<NaiveDate> {
+    let mut selected = Vec::new();
+    for entry in by_day {
+        let matching: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|d| d.weekday() == entry.weekday)
+            .collect();
+        match entry.ordinal {
+            None => selected.extend(matching),
+            Some(n) if n > 0 => selected.extend(matching.get(n as usize - 1)),
+            Some(n) => selected.extend(
+                matching
+                    .len()
+                    .checked_sub(n.unsigned_abs() as usize)
+                    .and_then(|i| matching.get(i)),
+            ),
+        }
+    }
+    selected
+}
+
+fn matches_month_day(by_month_day: &[i32], date: NaiveDate) -> bool {
+    let days_in_month = days_in_month(date);
+    by_month_day.iter().any(|&day| {
+        let resolved = if day > 0 {
+            day
+        } else {
+            days_in_month as i32 + day + 1
+        };
+        resolved == date.day() as i32
+    })
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).expect("day 1 always exists");
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64
+}
+
+fn parse_number<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid recurrence {key} value '{value}'"))
+}
+
+/// `UNTIL` may be a DATE (`20261231`) or DATE-TIME (`20261231T235959Z`); alarms
+/// recur per day, so only the date part is kept.
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    let date = value.split('T').next().unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y%m%d")
+        .map_err(|_| format!("Invalid recurrence UNTIL value '{value}'"))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(format!("Invalid recurrence weekday '{other}'")),
+    }
+}
+
+fn parse_by_day(entry: &str) -> Result<ByDay, String> {
+    let entry = entry.trim();
+    // Split on the last two characters rather than bytes so a non-ASCII
+    // entry is rejected instead of panicking on a char boundary.
+    let split = entry
+        .char_indices()
+        .rev()
+        .nth(1)
+        .map_or(0, |(index, _)| index);
+    let (ordinal, weekday) = entry.split_at(split);
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        let n: i32 = parse_number("BYDAY", ordinal)?;
+        if n == 0 || !(-53..=53).contains(&n) {
+            return Err(format!("Recurrence BYDAY ordinal '{n}' out of range"));
+        }
+        Some(n)
+    };
+    Ok(ByDay {
+        ordinal,
+        weekday: parse_weekday(weekday)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn rule(s: &str) -> RecurrenceRule {
+        s.parse().unwrap()
+    }
+
+    /// The first `n` dates produced on or after `from`.
+    fn upcoming(rule: &RecurrenceRule, anchor: &str, from: &str, n: usize) -> Vec<String> {
+        let anchor = date(anchor);
+        let mut cursor = date(from);
+        let mut out = Vec::new();
+        while out.len() < n {
+            let Some(next) = rule.next_on_or_after(anchor, cursor) else {
+                break;
+            };
+            out.push(next.format("%Y-%m-%d").to_string());
+            cursor = next.succ_opt().unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_every_n_days() {
+        let r = rule("FREQ=DAILY;INTERVAL=3");
+        assert_eq!(
+            upcoming(&r, "2026-03-01", "2026-03-02", 3),
+            ["2026-03-04", "2026-03-07", "2026-03-10"]
+        );
+    }
+
+    #[test]
+    fn test_every_other_week_on_chosen_days() {
+        // 2026-01-05 is a Monday.
+        let r = rule("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR");
+        assert_eq!(
+            upcoming(&r, "2026-01-05", "2026-01-06", 4),
+            ["2026-01-09", "2026-01-19", "2026-01-23", "2026-02-02"]
+        );
+    }
+
+    #[test]
+    fn test_weekly_defaults_to_anchor_weekday() {
+        let r = rule("FREQ=WEEKLY;INTERVAL=2");
+        // 2026-01-07 is a Wednesday.
+        assert_eq!(
+            upcoming(&r, "2026-01-07", "2026-01-01", 2),
+            ["2026-01-07", "2026-01-21"]
+        );
+    }
+
+    #[test]
+    fn test_week_start_decides_which_fortnight_sunday_belongs_to() {
+        // Anchor Monday 2026-01-05; with WKST=SU the Sunday before it opens the week.
+        let monday_weeks = rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=SU,MO");
+        let sunday_weeks = rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=SU,MO;WKST=SU");
+        assert_eq!(
+            upcoming(&monday_weeks, "2026-01-05", "2026-01-05", 2),
+            ["2026-01-05", "2026-01-11"]
+        );
+        assert_eq!(
+            upcoming(&sunday_weeks, "2026-01-05", "2026-01-05", 2),
+            ["2026-01-05", "2026-01-18"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_by_date_skips_short_months() {
+        let r = rule("FREQ=MONTHLY");
+        assert_eq!(
+            upcoming(&r, "2026-01-31", "2026-02-01", 3),
+            ["2026-03-31", "2026-05-31", "2026-07-31"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_by_negative_month_day() {
+        let r = rule("FREQ=MONTHLY;BYMONTHDAY=-1");
+        assert_eq!(
+            upcoming(&r, "2026-01-01", "2026-01-01", 3),
+            ["2026-01-31", "2026-02-28", "2026-03-31"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_by_nth_weekday() {
+        let second_tuesday = rule("FREQ=MONTHLY;BYDAY=2TU");
+        assert_eq!(
+            upcoming(&second_tuesday, "2026-01-01", "2026-01-01", 3),
+            ["2026-01-13", "2026-02-10", "2026-03-10"]
+        );
+
+        let last_friday = rule("FREQ=MONTHLY;BYDAY=-1FR");
+        assert_eq!(
+            upcoming(&last_friday, "2026-01-01", "2026-01-01", 2),
+            ["2026-01-30", "2026-02-27"]
+        );
+    }
+
+    #[test]
+    fn test_yearly_defaults_to_anchor_date() {
+        let r = rule("FREQ=YEARLY");
+        assert_eq!(
+            upcoming(&r, "2024-02-29", "2024-03-01", 2),
+            ["2028-02-29", "2032-02-29"]
+        );
+    }
+
+    #[test]
+    fn test_yearly_nth_weekday_of_month() {
+        // Fourth Thursday of November.
+        let r = rule("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH");
+        assert_eq!(
+            upcoming(&r, "2026-01-01", "2026-01-01", 2),
+            ["2026-11-26", "2027-11-25"]
+        );
+    }
+
+    #[test]
+    fn test_until_and_count_end_the_rule() {
+        let until = rule("FREQ=DAILY;INTERVAL=2;UNTIL=20260105T235959Z");
+        assert_eq!(
+            upcoming(&until, "2026-01-01", "2026-01-01", 5),
+            ["2026-01-01", "2026-01-03", "2026-01-05"]
+        );
+
+        let count = rule("FREQ=WEEKLY;COUNT=3");
+        assert_eq!(
+            upcoming(&count, "2026-01-05", "2026-01-13", 5),
+            ["2026-01-19"]
+        );
+    }
+
+    #[test]
+    fn test_looks_ahead_arbitrarily_far() {
+        let r = rule("FREQ=YEARLY;INTERVAL=10");
+        assert_eq!(
+            r.next_on_or_after(date("2026-06-01"), date("2026-06-02")),
+            Some(date("2036-06-01"))
+        );
+    }
+
+    #[test]
+    fn test_impossible_rule_terminates() {
+        let r = rule("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30");
+        assert_eq!(
+            r.next_on_or_after(date("2026-01-01"), date("2026-01-01")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_occurs_on() {
+        let r = rule("FREQ=DAILY;INTERVAL=2");
+        assert!(r.occurs_on(date("2026-01-01"), date("2026-01-03")));
+        assert!(!r.occurs_on(date("2026-01-01"), date("2026-01-04")));
+        assert!(!r.occurs_on(date("2026-01-01"), date("2025-12-30")));
+    }
+
+    #[test]
+    fn test_rejects_unsupported_or_invalid_rules() {
+        for bad in [
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=WEEKLY;BYDAY=2MO",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=DAILY;COUNT=2;UNTIL=20260101",
+            "FREQ=DAILY;BYDAY=XX",
+            "FREQ=WEEKLY;BYDAY=xéx",
+            "FREQ=MONTHLY;BYDAY=1é",
+            "FREQ=WEEKLY;BYDAY=é",
+        ] {
+            assert!(
+                bad.parse::<RecurrenceRule>().is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+}
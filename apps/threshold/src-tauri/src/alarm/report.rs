@@ -0,0 +1,401 @@
+// Summarises recorded alarm history into wake-up statistics for the report screen
+//
+// (c) Copyright 2026 Liminal HQ, Scott Morris
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::alarm::{models::*, scheduler};
+use chrono::{Datelike, Timelike};
+use std::collections::BTreeMap;
+
+/// One occurrence's history, gathered under the trigger it was first due at (which
+/// its snoozed re-rings and its dismissal are recorded against too).
+#[derive(Default)]
+struct OccurrenceEvents<'a> {
+    rings: Vec<i64>,
+    snoozes: u32,
+    dismissal: Option<&'a AlarmHistoryEntry>,
+    missed: bool,
+    sampled: Option<bool>,
+}
+
+/// Running totals behind a `WakeStats`.
+#[derive(Default)]
+struct Tally {
+    mornings: u32,
+    missed: u32,
+    dismiss_ms: i64,
+    snoozes: u32,
+}
+
+impl Tally {
+    fn stats(&self) -> WakeStats {
+        let per_morning = |total: f64| (self.mornings > 0).then(|| total / self.mornings as f64);
+        WakeStats {
+            mornings: self.mornings,
+            missed: self.missed,
+            average_seconds_to_dismiss: per_morning(self.dismiss_ms as f64 / 1_000.0),
+            average_snoozes: per_morning(self.snoozes as f64),
+        }
+    }
+}
+
+/// Build the wake report from `history`, reading each alarm's zone from `alarms`. A
+/// morning is an occurrence that rang and was then dismissed; one dismissed before it
+/// rang woke nobody, so it isn't counted, and one its ring timeout dismissed counts as
+/// missed. An occurrence is a window or fixed one by the mode its rows were recorded
+/// under, so switching an alarm's mode doesn't move its past mornings. Weekdays and
+/// wake-up minutes are local to the alarm's zone, the weekday being the occurrence's
+/// own date (a window's opening day); an occurrence that can't be placed there is
+/// logged and left out of them rather than failing the report.
+///
+/// - `history`: the history rows to summarise, in any order.
+/// - `alarms`: current alarms. Rows recorded without a mode fall back to their
+///   alarm's current one, and count towards `overall` only once it's deleted.
+pub fn wake_report(history: &[AlarmHistoryEntry], alarms: &[AlarmInput]) -> WakeReport {
+    let mut occurrences: BTreeMap<(i32, i64), OccurrenceEvents> = BTreeMap::new();
+    for entry in history {
+        let Some(trigger) = entry.scheduled_trigger else {
+            continue;
+        };
+        let events = occurrences.entry((entry.alarm_id, trigger)).or_default();
+        events.sampled = events.sampled.or(entry.sampled);
+        match entry.kind {
+            AlarmEventKind::Fired => events.rings.extend(entry.actual_fired_at),
+            AlarmEventKind::Snoozed => events.snoozes += 1,
+            // Dismissed by its ring timeout, it woke nobody either
+            AlarmEventKind::Dismissed if entry.source == AlarmEventSource::Unattended => {
+                events.missed = true
+            }
+            AlarmEventKind::Dismissed => events.dismissal = Some(entry),
+            AlarmEventKind::Missed => events.missed = true,
+        }
+    }
+
+    let (mut overall, mut window, mut fixed) =
+        (Tally::default(), Tally::default(), Tally::default());
+    let mut wake_minutes: Vec<Vec<u32>> = vec![Vec::new(); 7];
+    let mut fire_latency = Vec::new();
+
+    for (&(alarm_id, trigger_at), events) in &occurrences {
+        let alarm = alarms.iter().find(|a| a.id == Some(alarm_id));
+        let sampled = events
+            .sampled
+            .or_else(|| alarm.map(|a| a.mode.is_sampled()));
+        let mut tallies = vec![&mut overall];
+        match sampled {
+            Some(true) => tallies.push(&mut window),
+            Some(false) => tallies.push(&mut fixed),
+            None => {}
+        }
+
+        let first_ring = events.rings.iter().min().copied();
+        if let Some(fired_at) = first_ring {
+            fire_latency.push(FireLatency {
+                alarm_id,
+                trigger_at,
+                latency_ms: fired_at - trigger_at,
+            });
+        }
+
+        if events.missed {
+            for tally in tallies.iter_mut() {
+                tally.missed += 1;
+            }
+        }
+
+        let Some((dismissal, rang_at)) = events
+            .dismissal
+            .and_then(|d| Some((d, first_ring.or(d.actual_fired_at)?)))
+        else {
+            continue;
+        };
+        for tally in tallies.iter_mut() {
+            tally.mornings += 1;
+            tally.dismiss_ms += (dismissal.occurred_at - rang_at).max(0);
+            tally.snoozes += events.snoozes;
+        }
+
+        if let Some(alarm) = alarm.filter(|_| sampled == Some(true)) {
+            let placed = scheduler::occurrence_date(alarm, trigger_at).and_then(|date| {
+                let woke = scheduler::clock_time(alarm, dismissal.occurred_at)?;
+                Ok((date.weekday().num_days_from_sunday(), woke))
+            });
+            match placed {
+                Ok((weekday, woke)) => {
+                    wake_minutes[weekday as usize].push(woke.hour() * 60 + woke.minute())
+                }
+                Err(e) => log::warn!(
+                    "Leaving alarm {alarm_id}'s {trigger_at} occurrence out of wake times: {e}"
+                ),
+            }
+        }
+    }
+
+    fire_latency.sort_by_key(|l| (l.trigger_at, l.alarm_id));
+    let average_fire_latency_ms = (!fire_latency.is_empty()).then(|| {
+        fire_latency
+            .iter()
+            .map(|l| l.latency_ms as f64)
+            .sum::<f64>()
+            / fire_latency.len() as f64
+    });
+
+    let weekdays = wake_minutes
+        .into_iter()
+        .enumerate()
+        .map(|(weekday, mut minutes)| {
+            minutes.sort_unstable();
+            WeekdayWakeTimes {
+                weekday: weekday as u32,
+                median_minute: median(&minutes),
+                wake_minutes: minutes,
+            }
+        })
+        .collect();
+
+    WakeReport {
+        overall: overall.stats(),
+        window: window.stats(),
+        fixed: fixed.stats(),
+        weekdays,
+        fire_latency,
+        average_fire_latency_ms,
+    }
+}
+
+/// Middle of sorted `values`, rounding down between the two middle ones.
+fn median(values: &[u32]) -> Option<u32> {
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(values[mid]),
+        _ => Some((values[mid - 1] + values[mid]) / 2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+    use chrono_tz::America::Toronto;
+
+    const MINUTE: i64 = 60_000;
+
+    /// Epoch millis for a Toronto wall-clock time in October 2026 (the 19th is a Monday).
+    fn at(day: u32, hour: u32, minute: u32) -> i64 {
+        Toronto
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2026, 10, day)
+                    .unwrap()
+                    .and_hms_opt(hour, minute, 0)
+                    .unwrap(),
+            )
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    fn alarm(id: i32, mode: AlarmMode) -> AlarmInput {
+        AlarmInput {
+            id: Some(id),
+            mode,
+            fixed_time: Some("07:00".into()),
+            window_start: Some("06:30".into()),
+            window_end: Some("07:30".into()),
+            timezone: Some("America/Toronto".into()),
+            ..Default::default()
+        }
+    }
+
+    fn event(
+        alarm_id: i32,
+        kind: AlarmEventKind,
+        scheduled_trigger: i64,
+        occurred_at: i64,
+    ) -> AlarmHistoryEntry {
+        AlarmHistoryEntry {
+            id: 0,
+            alarm_id,
+            kind,
+            source: AlarmEventSource::PhoneUi,
+            scheduled_trigger: Some(scheduled_trigger),
+            actual_fired_at: (kind == AlarmEventKind::Fired).then_some(occurred_at),
+            occurred_at,
+            next_trigger: None,
+            sampled: None,
+        }
+    }
+
+    /// An occurrence that rang at `trigger` (plus `late_ms`), was snoozed `snoozes`
+    /// times for five minutes, and was dismissed `dismissed_after` minutes in.
+    fn morning(
+        alarm_id: i32,
+        trigger: i64,
+        late_ms: i64,
+        snoozes: i64,
+        dismissed_after: i64,
+    ) -> Vec<AlarmHistoryEntry> {
+        let mut events = vec![event(
+            alarm_id,
+            AlarmEventKind::Fired,
+            trigger,
+            trigger + late_ms,
+        )];
+        for n in 0..snoozes {
+            let rang = trigger + n * 5 * MINUTE;
+            events.push(event(
+                alarm_id,
+                AlarmEventKind::Snoozed,
+                trigger,
+                rang + 30_000,
+            ));
+            events.push(event(
+                alarm_id,
+                AlarmEventKind::Fired,
+                trigger,
+                rang + 5 * MINUTE,
+            ));
+        }
+        let dismissed = event(
+            alarm_id,
+            AlarmEventKind::Dismissed,
+            trigger,
+            trigger + dismissed_after * MINUTE,
+        );
+        events.push(AlarmHistoryEntry {
+            actual_fired_at: Some(trigger + snoozes * 5 * MINUTE),
+            ..dismissed
+        });
+        events
+    }
+
+    #[test]
+    fn compares_window_and_fixed_mornings() {
+        let alarms = [alarm(1, AlarmMode::Window), alarm(2, AlarmMode::Fixed)];
+        let history = [
+            morning(1, at(19, 6, 40), 0, 0, 2),
+            morning(1, at(20, 6, 50), 0, 0, 4),
+            morning(2, at(19, 7, 0), 0, 2, 12),
+            morning(2, at(20, 7, 0), 0, 1, 8),
+        ]
+        .concat();
+
+        let report = wake_report(&history, &alarms);
+        assert_eq!(report.window.mornings, 2);
+        assert_eq!(report.window.average_seconds_to_dismiss, Some(180.0));
+        assert_eq!(report.window.average_snoozes, Some(0.0));
+        assert_eq!(report.fixed.average_seconds_to_dismiss, Some(600.0));
+        assert_eq!(report.fixed.average_snoozes, Some(1.5));
+        assert_eq!(report.overall.mornings, 4);
+        assert_eq!(report.overall.average_snoozes, Some(0.75));
+    }
+
+    #[test]
+    fn spreads_window_wake_times_by_weekday() {
+        let alarms = [alarm(1, AlarmMode::Window), alarm(2, AlarmMode::Fixed)];
+        let history = [
+            morning(1, at(19, 6, 40), 0, 0, 5),
+            morning(1, at(26, 6, 55), 0, 0, 5),
+            morning(1, at(20, 7, 10), 0, 0, 0),
+            morning(2, at(19, 7, 0), 0, 0, 1),
+        ]
+        .concat();
+
+        let report = wake_report(&history, &alarms);
+        assert_eq!(report.weekdays.len(), 7);
+        let monday = &report.weekdays[1];
+        assert_eq!(monday.wake_minutes, vec![6 * 60 + 45, 7 * 60]);
+        assert_eq!(monday.median_minute, Some(6 * 60 + 52));
+        assert_eq!(report.weekdays[2].wake_minutes, vec![7 * 60 + 10]);
+        assert!(report.weekdays[0].wake_minutes.is_empty());
+        assert_eq!(report.weekdays[0].median_minute, None);
+    }
+
+    #[test]
+    fn measures_drift_from_each_first_ring() {
+        let alarms = [alarm(1, AlarmMode::Fixed)];
+        let history = [
+            morning(1, at(19, 7, 0), 1_500, 2, 12),
+            morning(1, at(20, 7, 0), -500, 0, 1),
+        ]
+        .concat();
+
+        let report = wake_report(&history, &alarms);
+        let drift: Vec<_> = report.fire_latency.iter().map(|l| l.latency_ms).collect();
+        assert_eq!(drift, vec![1_500, -500]);
+        assert_eq!(report.average_fire_latency_ms, Some(500.0));
+    }
+
+    #[test]
+    fn counts_only_mornings_that_rang() {
+        let alarms = [alarm(1, AlarmMode::Fixed)];
+        let history = vec![
+            // Dismissed ahead of time from the notification
+            event(1, AlarmEventKind::Dismissed, at(19, 7, 0), at(19, 6, 30)),
+            event(1, AlarmEventKind::Missed, at(20, 7, 0), at(20, 9, 0)),
+            AlarmHistoryEntry {
+                source: AlarmEventSource::Unattended,
+                ..event(1, AlarmEventKind::Dismissed, at(21, 7, 0), at(21, 7, 10))
+            },
+            // Deleted since, so it only counts overall
+            event(9, AlarmEventKind::Missed, at(20, 8, 0), at(20, 9, 0)),
+        ];
+
+        let report = wake_report(&history, &alarms);
+        assert_eq!(report.overall.mornings, 0);
+        assert_eq!(report.overall.average_seconds_to_dismiss, None);
+        assert_eq!((report.overall.missed, report.fixed.missed), (3, 2));
+        assert!(report.fire_latency.is_empty());
+        assert_eq!(report.average_fire_latency_ms, None);
+    }
+
+    /// `rows` as recorded while their alarm was, or wasn't, sampled from a window.
+    fn recorded(rows: Vec<AlarmHistoryEntry>, sampled: bool) -> Vec<AlarmHistoryEntry> {
+        rows.into_iter()
+            .map(|row| AlarmHistoryEntry {
+                sampled: Some(sampled),
+                ..row
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classifies_by_the_mode_each_morning_was_recorded_under() {
+        // Both alarms have switched mode since these mornings.
+        let alarms = [alarm(1, AlarmMode::Fixed), alarm(2, AlarmMode::Window)];
+        let history = [
+            recorded(morning(1, at(19, 6, 40), 0, 0, 5), true),
+            recorded(morning(2, at(19, 7, 0), 0, 1, 8), false),
+            // Deleted since, but its rows still say what it was
+            recorded(morning(9, at(20, 7, 0), 0, 0, 1), true),
+        ]
+        .concat();
+
+        let report = wake_report(&history, &alarms);
+        assert_eq!(report.window.mornings, 2);
+        assert_eq!(report.fixed.mornings, 1);
+        assert_eq!(report.fixed.average_snoozes, Some(1.0));
+        // A deleted alarm's zone is gone, so only alarm 1 has a wake time.
+        assert_eq!(report.weekdays[1].wake_minutes, vec![6 * 60 + 45]);
+        assert!(report.weekdays[2].wake_minutes.is_empty());
+    }
+
+    #[test]
+    fn leaves_out_wake_times_it_cannot_place() {
+        let alarms = [
+            AlarmInput {
+                timezone: Some("Mars/Olympus_Mons".into()),
+                ..alarm(1, AlarmMode::Window)
+            },
+            alarm(2, AlarmMode::Window),
+        ];
+        let history = [
+            morning(1, at(19, 6, 40), 0, 0, 5),
+            morning(2, at(19, 6, 50), 0, 0, 5),
+        ]
+        .concat();
+
+        let report = wake_report(&history, &alarms);
+        assert_eq!(report.window.mornings, 2);
+        assert_eq!(report.weekdays[1].wake_minutes, vec![6 * 60 + 55]);
+    }
+}
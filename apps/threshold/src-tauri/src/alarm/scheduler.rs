@@ -1,17 +1,51 @@
-// Computes the next alarm trigger timestamp for fixed and window alarm modes
+// Computes the next alarm trigger timestamp for fixed, window, solar and interval alarm modes
 //
 // (c) Copyright 2026 Liminal HQ, Scott Morris
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::alarm::{error::Result, models::*};
-use chrono::{DateTime, TimeZone};
-use chrono::{Datelike, Local, NaiveTime, Timelike};
-use rand::Rng;
+use crate::alarm::{error::Result, models::*, recurrence::RecurrenceRule};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono::{Datelike, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike};
+use chrono_tz::Tz;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{HashMap, HashSet};
 
 /// Minimum lead time when sampling inside an already-open window, so the
 /// alarm never fires "immediately" the moment it's enabled.
 const MIN_LEAD_SECONDS: i64 = 30;
 
+/// How far ahead a solar alarm looks for a day on which its event happens. A year
+/// covers the longest polar night or midnight sun anywhere on Earth.
+const SOLAR_SEARCH_DAYS: i64 = 366;
+
+/// Length of one sleep cycle, which sleep-cycle windows align to.
+const SLEEP_CYCLE_MINUTES: i64 = 90;
+
+/// Upper bounds on a pre-alarm: how far ahead of its alarm, and how long it rings.
+pub const MAX_PRE_ALARM_MINUTES: u32 = 180;
+pub const MAX_PRE_ALARM_RING_SECONDS: u32 = 600;
+
+/// Longest snooze interval a policy may set.
+pub const MAX_SNOOZE_MINUTES: u32 = 120;
+
+/// Longest an alarm can ring unattended before its ring timeout acts.
+pub const MAX_RING_TIMEOUT_MINUTES: u32 = 60;
+
+/// Longest a backup alarm can wait after its alarm first rang.
+pub const MAX_BACKUP_ALARM_MINUTES: u32 = 60;
+
+/// Bounds on how far apart an interval alarm's instances are.
+pub const MIN_INTERVAL_MINUTES: u32 = 5;
+pub const MAX_INTERVAL_MINUTES: u32 = 720;
+
+/// Longest a follow-up alarm can wait after the alarm it follows is dismissed.
+pub const MAX_FOLLOW_UP_MINUTES: u32 = 720;
+
+/// Upper bounds on a preview, so a UI request can't ask for unbounded work.
+pub const MAX_PREVIEW_OCCURRENCES: u32 = 500;
+pub const MAX_PREVIEW_DAYS: i64 = 366;
+
 /// Distinguishes a fresh "what should fire next, from right now" query from
 /// a "what's next after this specific occurrence" query (used after a
 /// dismiss/re-arm). Only the former may sample the remaining time of a
@@ -23,525 +57,3959 @@ enum ReferenceKind {
     AfterOccurrence,
 }
 
-/// Calculate next trigger timestamp for an alarm
-pub fn calculate_next_trigger(alarm: &AlarmInput) -> Result<Option<i64>> {
-    calculate_next_trigger_from(alarm, Local::now(), ReferenceKind::Fresh)
+/// State outside the alarm itself that scheduling depends on, loaded by the
+/// coordinator so this module stays free of database access.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleContext {
+    /// Mixed into window sampling seeds, so two installs don't share wake times
+    /// for the same alarm id and date.
+    pub install_secret: String,
+    /// Dates skipped by alarms that opt in to `skip_holidays`.
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl ScheduleContext {
+    pub fn new(install_secret: String) -> Self {
+        Self {
+            install_secret,
+            holidays: Vec::new(),
+        }
+    }
+
+    pub fn with_holidays(mut self, dates: &[String]) -> Result<Self> {
+        self.holidays = parse_skip_dates(dates)?;
+        Ok(self)
+    }
+}
+
+/// What a window sample is seeded from besides its occurrence date. A fixed seed
+/// per occurrence means recalculating (say, after a label edit) keeps the same
+/// minute instead of silently re-rolling tomorrow's wake time.
+struct SampleSeed<'a> {
+    install_secret: &'a str,
+    alarm_id: i32,
+}
+
+impl SampleSeed<'_> {
+    fn rng_for(&self, date: NaiveDate) -> ChaCha8Rng {
+        // FNV-1a rather than std's DefaultHasher, whose output may change between
+        // Rust releases and would move every sampled time on an app update.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let bytes = self
+            .install_secret
+            .bytes()
+            .chain([0xff])
+            .chain(self.alarm_id.to_le_bytes())
+            .chain(date.format("%Y-%m-%d").to_string().into_bytes());
+        for byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        // ChaCha8 rather than StdRng, whose algorithm rand may swap in any release.
+        ChaCha8Rng::seed_from_u64(hash)
+    }
+}
+
+/// A uniform integer in `lo..=hi`, mapped from the raw stream by hand because
+/// rand's own range sampling isn't value-stable across versions either. The
+/// modulo bias is negligible for spans of a day's minutes.
+fn uniform_in(rng: &mut ChaCha8Rng, lo: i64, hi: i64) -> i64 {
+    lo + (rng.next_u64() % (hi - lo + 1) as u64) as i64
+}
+
+/// A uniform float in `[0, 1)` from the top 53 bits of the stream.
+fn unit_f64(rng: &mut ChaCha8Rng) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Calculate next trigger timestamp for an alarm, as of `now_ms` (the coordinator's clock).
+pub fn calculate_next_trigger(
+    alarm: &AlarmInput,
+    now_ms: i64,
+    ctx: &ScheduleContext,
+) -> Result<Option<i64>> {
+    let now = DateTime::from_timestamp_millis(now_ms).ok_or("Invalid reference timestamp")?;
+    calculate_next_trigger_from(alarm, now, ReferenceKind::Fresh, ctx)
 }
 
 /// Calculate next trigger timestamp for an alarm after a given reference instant.
-pub fn calculate_next_trigger_after(alarm: &AlarmInput, after_ms: i64) -> Result<Option<i64>> {
-    let reference = Local
-        .timestamp_millis_opt(after_ms)
-        .single()
-        .ok_or("Invalid reference timestamp")?;
-    calculate_next_trigger_from(alarm, reference, ReferenceKind::AfterOccurrence)
+pub fn calculate_next_trigger_after(
+    alarm: &AlarmInput,
+    after_ms: i64,
+    ctx: &ScheduleContext,
+) -> Result<Option<i64>> {
+    let reference =
+        DateTime::from_timestamp_millis(after_ms).ok_or("Invalid reference timestamp")?;
+    calculate_next_trigger_from(alarm, reference, ReferenceKind::AfterOccurrence, ctx)
+}
+
+/// Parses an alarm's pinned IANA zone name (e.g. "Europe/London").
+pub fn parse_timezone(name: &str) -> Result<Tz> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown time zone '{name}'").into())
 }
 
-fn calculate_next_trigger_from(
+/// Schedules in the alarm's pinned zone if it has one, otherwise the device's.
+fn calculate_next_trigger_from<Z: TimeZone>(
     alarm: &AlarmInput,
-    now: DateTime<Local>,
+    now: DateTime<Z>,
     kind: ReferenceKind,
+    ctx: &ScheduleContext,
 ) -> Result<Option<i64>> {
+    match alarm.timezone.as_deref() {
+        Some(name) => {
+            let zone = parse_timezone(name)?;
+            calculate_next_trigger_in(alarm, now.with_timezone(&zone), kind, ctx)
+        }
+        None => calculate_next_trigger_in(alarm, now.with_timezone(&Local), kind, ctx),
+    }
+}
+
+fn calculate_next_trigger_in<Z: TimeZone>(
+    alarm: &AlarmInput,
+    now: DateTime<Z>,
+    kind: ReferenceKind,
+    ctx: &ScheduleContext,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
     if !alarm.enabled {
         return Ok(None);
     }
 
+    // New alarms are scheduled again once they have an id (see save_alarm).
+    let seed = SampleSeed {
+        install_secret: &ctx.install_secret,
+        alarm_id: alarm.id.unwrap_or(0),
+    };
+
     match alarm.mode {
         AlarmMode::Fixed => {
             let time = alarm
                 .fixed_time
                 .as_ref()
                 .ok_or("Fixed alarm missing fixedTime")?;
-            calculate_fixed_trigger(time, &alarm.active_days, now)
+            calculate_fixed_trigger(
+                time,
+                &DaySchedule::from_alarm(alarm, ctx)?,
+                now,
+                alarm.dst_policy.unwrap_or_default(),
+            )
         }
-        AlarmMode::Window => {
-            let start = alarm
-                .window_start
+        AlarmMode::Window => calculate_window_trigger(
+            &Window::from_alarm(alarm)?,
+            &DaySchedule::from_alarm(alarm, ctx)?,
+            now,
+            kind,
+            &seed,
+        ),
+        AlarmMode::FixedOnce => {
+            let time = alarm
+                .fixed_time
                 .as_ref()
-                .ok_or("Window alarm missing windowStart")?;
-            let end = alarm
-                .window_end
+                .ok_or("Fixed alarm missing fixedTime")?;
+            let Some(date) = unskipped_one_shot_date(alarm, ctx)? else {
+                return Ok(None);
+            };
+            calculate_one_shot_fixed_trigger(time, date, now, alarm.dst_policy.unwrap_or_default())
+        }
+        AlarmMode::WindowOnce => {
+            // Only the window that opens on the one-shot date is considered, so an
+            // overnight one-shot window still counts as pending while `now` sits in
+            // its after-midnight tail.
+            let window = Window::from_alarm(alarm)?;
+            let Some(date) = unskipped_one_shot_date(alarm, ctx)? else {
+                return Ok(None);
+            };
+            sample_window_for_day(&window, date, now, kind, &seed)
+        }
+        AlarmMode::Solar | AlarmMode::SolarWindow => calculate_solar_trigger(
+            &SolarWindow::from_alarm(alarm)?,
+            &DaySchedule::from_alarm(alarm, ctx)?,
+            now,
+            kind,
+            &seed,
+        ),
+        // Instances left in a span are found the same way whether or not one has just
+        // been dismissed, so `kind` doesn't apply.
+        AlarmMode::Interval => Ok(calculate_interval_trigger(
+            &Interval::from_alarm(alarm)?,
+            &DaySchedule::from_alarm(alarm, ctx)?,
+            now,
+        )),
+        AlarmMode::FollowUp => {
+            // Only the dismissal of the alarm it follows sets a trigger.
+            alarm
+                .follow_up
                 .as_ref()
-                .ok_or("Window alarm missing windowEnd")?;
-            calculate_window_trigger(start, end, &alarm.active_days, now, kind)
+                .ok_or("Follow-up alarm missing followUp")?;
+            Ok(None)
         }
     }
 }
 
-fn calculate_fixed_trigger(
-    time_str: &str,
-    active_days: &[i32],
-    now: DateTime<Local>,
-) -> Result<Option<i64>> {
-    let target_time = NaiveTime::parse_from_str(time_str, "%H:%M")?;
+fn parse_one_shot_date(alarm: &AlarmInput) -> Result<NaiveDate> {
+    let date = alarm
+        .one_shot_date
+        .as_ref()
+        .ok_or("One-shot alarm missing oneShotDate")?;
+    Ok(NaiveDate::parse_from_str(date, "%Y-%m-%d")?)
+}
 
-    // Find next occurrence in active days
-    for days_ahead in 0..8 {
-        let candidate = now + chrono::Duration::days(days_ahead);
-        let weekday = candidate.weekday().num_days_from_sunday() as i32;
+/// A skipped one-shot alarm has nothing left to fire.
+fn unskipped_one_shot_date(alarm: &AlarmInput, ctx: &ScheduleContext) -> Result<Option<NaiveDate>> {
+    let date = parse_one_shot_date(alarm)?;
+    let skipped = skipped_dates(alarm, ctx)?;
+    Ok(Some(date).filter(|d| !skipped.contains(d)))
+}
 
-        if active_days.contains(&weekday) {
-            // Use earliest() to handle DST fallback safely (pick the first occurrence)
-            // If None (invalid time), continue to next day
-            if let Some(candidate_dt) = candidate
-                .date_naive()
-                .and_time(target_time)
-                .and_local_timezone(Local)
-                .earliest()
-            {
-                if candidate_dt > now {
-                    return Ok(Some(candidate_dt.timestamp_millis()));
-                }
-            }
-        }
+/// The alarm's own skip dates, plus the holidays if it opts in to skipping them.
+fn skipped_dates(alarm: &AlarmInput, ctx: &ScheduleContext) -> Result<Vec<NaiveDate>> {
+    let mut skipped = parse_skip_dates(alarm.skip_dates.as_deref().unwrap_or_default())?;
+    if alarm.skip_holidays.unwrap_or(false) {
+        skipped.extend_from_slice(&ctx.holidays);
     }
+    Ok(skipped)
+}
 
-    // No active days found in next week
-    Ok(None)
+fn parse_skip_dates(dates: &[String]) -> Result<Vec<NaiveDate>> {
+    dates
+        .iter()
+        .map(|d| Ok(NaiveDate::parse_from_str(d, "%Y-%m-%d")?))
+        .collect()
 }
 
-fn calculate_window_trigger(
-    start_str: &str,
-    end_str: &str,
-    active_days: &[i32],
-    now: DateTime<Local>,
-    kind: ReferenceKind,
-) -> Result<Option<i64>> {
-    let start_time = NaiveTime::parse_from_str(start_str, "%H:%M")?;
-    let end_time = NaiveTime::parse_from_str(end_str, "%H:%M")?;
+/// Validates a skip-date list and tidies it for storage: sorted, deduplicated, and
+/// without dates that can no longer affect scheduling. Yesterday is kept, since an
+/// overnight window that opened yesterday may still be open.
+pub fn normalize_skip_dates(dates: &[String], today: NaiveDate) -> Result<Vec<String>> {
+    let mut parsed = parse_skip_dates(dates)?;
+    parsed.retain(|d| *d >= today - chrono::Duration::days(1));
+    parsed.sort();
+    parsed.dedup();
+    Ok(parsed
+        .into_iter()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect())
+}
 
-    if start_time == end_time {
-        return Err("Window end must differ from start".into());
+/// The occurrence date a trigger belongs to: the day its window opened (which for an
+/// overnight window can be the day before the trigger), or its one-shot date.
+pub fn occurrence_date(alarm: &AlarmInput, trigger_ms: i64) -> Result<NaiveDate> {
+    if alarm.mode.is_one_shot() {
+        return parse_one_shot_date(alarm);
     }
-    // If end <= start, the window crosses midnight (e.g. 23:00 -> 01:00);
-    // its end falls on the day after whichever day it starts on.
-    let crosses_midnight = end_time <= start_time;
+    let trigger = DateTime::from_timestamp_millis(trigger_ms).ok_or("Invalid trigger timestamp")?;
+    match alarm.timezone.as_deref() {
+        Some(name) => occurrence_date_in(alarm, trigger.with_timezone(&parse_timezone(name)?)),
+        None => occurrence_date_in(alarm, trigger.with_timezone(&Local)),
+    }
+}
 
-    // Overnight windows: "now" might already be inside the tail end of a
-    // window that started yesterday (e.g. it's 00:30 and yesterday's
-    // 23:00-01:00 window is still open). Only a fresh query may resample
-    // that remaining time; prefer it over jumping to the next occurrence.
-    if crosses_midnight && kind == ReferenceKind::Fresh {
-        let yesterday = now - chrono::Duration::days(1);
-        let yesterday_weekday = yesterday.weekday().num_days_from_sunday() as i32;
-        if active_days.contains(&yesterday_weekday) {
-            if let Some(trigger) =
-                sample_window_for_day(yesterday, start_time, end_time, true, now, kind)?
-            {
-                return Ok(Some(trigger));
-            }
-        }
+/// The wall-clock minute `at_ms` falls on in the alarm's zone, e.g. to record a
+/// bedtime from when a reminder was dismissed.
+pub fn clock_time(alarm: &AlarmInput, at_ms: i64) -> Result<NaiveTime> {
+    let at = DateTime::from_timestamp_millis(at_ms).ok_or("Invalid timestamp")?;
+    let time = match alarm.timezone.as_deref() {
+        Some(name) => at.with_timezone(&parse_timezone(name)?).time(),
+        None => at.with_timezone(&Local).time(),
+    };
+    Ok(time
+        .with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time))
+}
+
+/// When the occurrence a trigger belongs to starts waking someone: the opening of its
+/// window for window, interval and sun-window alarms, which could ring any time from
+/// then, or else the trigger itself.
+pub fn wake_time(alarm: &AlarmInput, trigger_ms: i64) -> Result<i64> {
+    let trigger = DateTime::from_timestamp_millis(trigger_ms).ok_or("Invalid trigger timestamp")?;
+    match alarm.timezone.as_deref() {
+        Some(name) => wake_time_in(alarm, trigger.with_timezone(&parse_timezone(name)?)),
+        None => wake_time_in(alarm, trigger.with_timezone(&Local)),
     }
+}
 
-    // Find next occurrence
-    for days_ahead in 0..8 {
-        let candidate = now + chrono::Duration::days(days_ahead);
-        let weekday = candidate.weekday().num_days_from_sunday() as i32;
-
-        if active_days.contains(&weekday) {
-            if let Some(trigger) =
-                sample_window_for_day(candidate, start_time, end_time, crosses_midnight, now, kind)?
-            {
-                return Ok(Some(trigger));
-            }
-        }
+/// Checks a pre-alarm rings ahead of its alarm, audibly but below full volume, and
+/// stops by itself.
+pub fn validate_pre_alarm(pre_alarm: &PreAlarm) -> Result<()> {
+    if !(1..=MAX_PRE_ALARM_MINUTES).contains(&pre_alarm.minutes_before) {
+        return Err(format!(
+            "A pre-alarm must ring 1 to {MAX_PRE_ALARM_MINUTES} minutes before its alarm"
+        )
+        .into());
+    }
+    if !(1..100).contains(&pre_alarm.volume_percent) {
+        return Err("A pre-alarm's volume must be 1 to 99 percent".into());
+    }
+    if !(1..=MAX_PRE_ALARM_RING_SECONDS).contains(&pre_alarm.ring_seconds) {
+        return Err(
+            format!("A pre-alarm must ring for 1 to {MAX_PRE_ALARM_RING_SECONDS} seconds").into(),
+        );
     }
+    Ok(())
+}
 
-    Ok(None)
+/// When a pre-alarm rings ahead of the trigger at `trigger_ms`, if that's still to
+/// come at `now_ms`. One that would already have rung is dropped, not rung late.
+pub fn pre_alarm_trigger(pre_alarm: &PreAlarm, trigger_ms: i64, now_ms: i64) -> Option<i64> {
+    let at = trigger_ms - pre_alarm.minutes_before as i64 * 60_000;
+    (at > now_ms).then_some(at)
 }
 
-/// Try to sample a trigger from the window instance that starts on `candidate`'s date.
-fn sample_window_for_day(
-    candidate: DateTime<Local>,
-    start_time: NaiveTime,
-    end_time: NaiveTime,
-    crosses_midnight: bool,
-    now: DateTime<Local>,
-    kind: ReferenceKind,
-) -> Result<Option<i64>> {
-    // Use earliest() to handle DST fallback safely
-    let Some(window_start) = candidate
-        .date_naive()
-        .and_time(start_time)
-        .and_local_timezone(Local)
-        .earliest()
-    else {
-        return Ok(None);
+/// Checks a snooze policy's intervals and cut-off time.
+pub fn validate_snooze_policy(policy: &SnoozePolicy) -> Result<()> {
+    if policy
+        .intervals_minutes
+        .iter()
+        .any(|m| !(1..=MAX_SNOOZE_MINUTES).contains(m))
+    {
+        return Err(format!("Snooze intervals must be 1 to {MAX_SNOOZE_MINUTES} minutes").into());
+    }
+    if let Some(time) = &policy.no_snooze_after {
+        NaiveTime::parse_from_str(time, "%H:%M")?;
+    }
+    Ok(())
+}
+
+/// Checks a ring timeout's length. One of 0 minutes is allowed here: it's how a save
+/// removes the timeout.
+pub fn validate_ring_timeout(timeout: &RingTimeout) -> Result<()> {
+    if timeout.minutes > MAX_RING_TIMEOUT_MINUTES {
+        return Err(
+            format!("Ring timeout must be at most {MAX_RING_TIMEOUT_MINUTES} minutes").into(),
+        );
+    }
+    Ok(())
+}
+
+/// Checks how long a backup alarm waits. One of 0 minutes is allowed here: it's how a
+/// save removes the backup.
+pub fn validate_backup_alarm(backup: &BackupAlarm) -> Result<()> {
+    if backup.minutes_after > MAX_BACKUP_ALARM_MINUTES {
+        return Err(format!(
+            "A backup alarm must ring at most {MAX_BACKUP_ALARM_MINUTES} minutes after its alarm"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Checks an alarm in follow-up mode names the alarm it follows: only that alarm's
+/// dismissal ever sets it ringing.
+pub fn validate_follows_an_alarm(alarm: &AlarmInput) -> Result<()> {
+    if alarm.mode == AlarmMode::FollowUp && alarm.follow_up.is_none() {
+        return Err("A follow-up alarm must name the alarm it follows".into());
+    }
+    Ok(())
+}
+
+/// Checks a follow-up waits a sensible time after the alarm it follows, and that
+/// following that alarm's own chain never leads back to `alarm_id`.
+///
+/// - `alarm_id`: the follow-up being saved, or `None` while it's new.
+/// - `followed`: each stored follow-up alarm's id, mapped to the alarm it follows.
+pub fn validate_follow_up(
+    alarm_id: Option<i32>,
+    follow_up: &FollowUp,
+    followed: &HashMap<i32, i32>,
+) -> Result<()> {
+    if !(1..=MAX_FOLLOW_UP_MINUTES).contains(&follow_up.minutes_after) {
+        return Err(format!(
+            "A follow-up alarm must ring 1 to {MAX_FOLLOW_UP_MINUTES} minutes after its alarm"
+        )
+        .into());
+    }
+
+    // A new alarm has no id anything could follow yet, so can't close a cycle.
+    let Some(alarm_id) = alarm_id else {
+        return Ok(());
     };
+    let mut seen = HashSet::new();
+    let mut current = follow_up.alarm_id;
+    while seen.insert(current) {
+        if current == alarm_id {
+            return Err("A follow-up alarm can't follow itself, even through others".into());
+        }
+        match followed.get(&current) {
+            Some(&next) => current = next,
+            None => break,
+        }
+    }
+    Ok(())
+}
 
-    let end_date = if crosses_midnight {
-        candidate.date_naive() + chrono::Duration::days(1)
-    } else {
-        candidate.date_naive()
+/// Whether an occurrence snoozed `snooze_count` times so far may be snoozed again at
+/// `now_ms` under the alarm's policy, and for how long. `no_snooze_after` holds from
+/// that clock time in the alarm's zone until midnight.
+pub fn snooze_allowance(
+    alarm: &AlarmInput,
+    snooze_count: u32,
+    now_ms: i64,
+) -> Result<SnoozeAllowance> {
+    let refuse = |reason: String| SnoozeAllowance {
+        allowed: false,
+        minutes: None,
+        reason: Some(reason),
     };
-    let Some(window_end) = end_date
-        .and_time(end_time)
-        .and_local_timezone(Local)
-        .earliest()
-    else {
-        return Ok(None);
+    let Some(policy) = &alarm.snooze_policy else {
+        return Ok(SnoozeAllowance {
+            allowed: true,
+            minutes: None,
+            reason: None,
+        });
     };
 
-    if window_end <= now {
-        // This occurrence has already fully elapsed.
-        return Ok(None);
+    match policy.max_snoozes {
+        Some(0) => return Ok(refuse("Snoozing is off for this alarm".into())),
+        Some(max) if snooze_count >= max => {
+            return Ok(refuse(format!(
+                "Already snoozed {snooze_count} of {max} times"
+            )))
+        }
+        _ => {}
+    }
+    if let Some(time) = &policy.no_snooze_after {
+        if clock_time(alarm, now_ms)? >= NaiveTime::parse_from_str(time, "%H:%M")? {
+            return Ok(refuse(format!("No snoozing after {time}")));
+        }
     }
 
-    let sample_start = match kind {
-        ReferenceKind::Fresh if window_start <= now => {
-            // Already inside the window: sample the remaining time, with a
-            // small lead so the alarm never fires "immediately".
-            std::cmp::max(
-                now + chrono::Duration::seconds(MIN_LEAD_SECONDS),
-                window_start,
-            )
+    // Past the end of the list, the last interval repeats
+    let minutes = policy
+        .intervals_minutes
+        .get(snooze_count as usize)
+        .or(policy.intervals_minutes.last())
+        .copied();
+    Ok(SnoozeAllowance {
+        allowed: true,
+        minutes,
+        reason: None,
+    })
+}
+
+/// A clock time an upcoming occurrence uses that a DST change skips or repeats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DstAdjustment {
+    pub date: NaiveDate, // the occurrence it affects
+    pub time: NaiveTime, // the fixed time, window bound or interval instance affected
+    pub transition: DstTransition,
+}
+
+/// The first occurrence from `now_ms` through the one at `trigger_ms` whose clock
+/// time falls in a DST gap or overlap, so the user can be told what the alarm's
+/// policy does with it -- including a skipped one the trigger has already passed
+/// over. For a one-shot alarm with no trigger, its date is checked. Sun-based alarms
+/// are anchored to absolute times and never are.
+pub fn dst_adjustment(
+    alarm: &AlarmInput,
+    now_ms: i64,
+    trigger_ms: Option<i64>,
+    ctx: &ScheduleContext,
+) -> Result<Option<DstAdjustment>> {
+    let now = DateTime::from_timestamp_millis(now_ms).ok_or("Invalid reference timestamp")?;
+    match alarm.timezone.as_deref() {
+        Some(name) => {
+            let zone = parse_timezone(name)?;
+            dst_adjustment_in(alarm, now.with_timezone(&zone), trigger_ms, ctx)
         }
-        _ if window_start > now => window_start,
-        // Not eligible for in-progress sampling and the window has already
-        // started (e.g. right after a dismissal) — this occurrence is
-        // spent; the caller moves on to the next active day.
-        _ => return Ok(None),
+        None => dst_adjustment_in(alarm, now.with_timezone(&Local), trigger_ms, ctx),
+    }
+}
+
+fn dst_adjustment_in<Z: TimeZone>(
+    alarm: &AlarmInput,
+    now: DateTime<Z>,
+    trigger_ms: Option<i64>,
+    ctx: &ScheduleContext,
+) -> Result<Option<DstAdjustment>>
+where
+    Z::Offset: Copy,
+{
+    // Each clock time the alarm uses, with how many days after the occurrence date.
+    let times = match alarm.mode {
+        AlarmMode::Fixed | AlarmMode::FixedOnce => {
+            let time = alarm
+                .fixed_time
+                .as_ref()
+                .ok_or("Fixed alarm missing fixedTime")?;
+            vec![(NaiveTime::parse_from_str(time, "%H:%M")?, 0)]
+        }
+        AlarmMode::Window | AlarmMode::WindowOnce => {
+            let window = Window::from_alarm(alarm)?;
+            vec![
+                (window.start, 0),
+                (window.end, i64::from(window.crosses_midnight)),
+            ]
+        }
+        AlarmMode::Interval => Interval::from_alarm(alarm)?.clock_times().collect(),
+        AlarmMode::Solar | AlarmMode::SolarWindow | AlarmMode::FollowUp => return Ok(None),
     };
 
-    if sample_start >= window_end {
-        return Ok(None);
-    }
+    let zone = now.timezone();
+    let dates: Vec<NaiveDate> = if alarm.mode.is_one_shot() {
+        unskipped_one_shot_date(alarm, ctx)?.into_iter().collect()
+    } else {
+        let Some(trigger_ms) = trigger_ms else {
+            return Ok(None);
+        };
+        let trigger =
+            DateTime::from_timestamp_millis(trigger_ms).ok_or("Invalid trigger timestamp")?;
+        let last = occurrence_date_in(alarm, trigger.with_timezone(&zone))?;
+        DaySchedule::from_alarm(alarm, ctx)?
+            .occurrences_from(now.date_naive() - chrono::Duration::days(1))
+            .take_while(|d| *d <= last)
+            .collect()
+    };
 
-    // Pick a random minute within the window so the alarm always fires at
-    // the top of the minute (:00 seconds). The end bound is exclusive.
-    let sample_start_minute = ceil_to_minute(sample_start);
-    let window_end_minute = floor_to_minute(window_end - chrono::Duration::milliseconds(1));
+    // Whatever the policy, the latest reading of the time says whether it's still ahead.
+    let latest = DstPolicy {
+        gap: DstGapPolicy::ShiftForward,
+        overlap: DstOverlapPolicy::FireAtSecond,
+    };
+    for date in dates {
+        for (time, days_after) in &times {
+            let local = (date + chrono::Duration::days(*days_after)).and_time(*time);
+            let Some(transition) = dst_transition(local, &zone) else {
+                continue;
+            };
+            if at_local(local, &zone, latest).is_some_and(|at| at > now) {
+                return Ok(Some(DstAdjustment {
+                    date,
+                    time: *time,
+                    transition,
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
 
-    if sample_start_minute > window_end_minute {
-        return Ok(None);
+fn occurrence_date_in<Z: TimeZone>(alarm: &AlarmInput, trigger: DateTime<Z>) -> Result<NaiveDate>
+where
+    Z::Offset: Copy,
+{
+    let date = trigger.date_naive();
+
+    if alarm.mode.is_solar() {
+        // The event an offset is measured from can fall on a neighbouring day.
+        let solar = SolarWindow::from_alarm(alarm)?;
+        let zone = trigger.timezone();
+        let opened_by = |d: &NaiveDate| solar.opens_on(*d, &zone).filter(|open| *open <= trigger);
+        return Ok([
+            date + chrono::Duration::days(1),
+            date,
+            date - chrono::Duration::days(1),
+        ]
+        .into_iter()
+        .filter(|d| opened_by(d).is_some())
+        .max_by_key(opened_by)
+        .unwrap_or(date));
     }
 
-    let span_mins = window_end_minute
-        .signed_duration_since(sample_start_minute)
-        .num_minutes();
-    let random_offset_mins = rand::thread_rng().gen_range(0..=span_mins);
-    let trigger = sample_start_minute + chrono::Duration::minutes(random_offset_mins);
+    if alarm.mode.is_window() || alarm.mode == AlarmMode::Interval {
+        let start = alarm
+            .window_start
+            .as_ref()
+            .ok_or("Window alarm missing windowStart")?;
+        let start_time = NaiveTime::parse_from_str(start, "%H:%M")?;
+        if trigger.time() < start_time {
+            return Ok(date - chrono::Duration::days(1));
+        }
+    }
+    Ok(date)
+}
 
-    Ok(Some(trigger.timestamp_millis()))
+fn wake_time_in<Z: TimeZone>(alarm: &AlarmInput, trigger: DateTime<Z>) -> Result<i64>
+where
+    Z::Offset: Copy,
+{
+    let zone = trigger.timezone();
+    let opens = if alarm.mode == AlarmMode::SolarWindow {
+        let date = occurrence_date_in(alarm, trigger)?;
+        SolarWindow::from_alarm(alarm)?.opens_on(date, &zone)
+    } else if alarm.mode.is_window() || alarm.mode == AlarmMode::Interval {
+        let start = alarm
+            .window_start
+            .as_ref()
+            .ok_or("Window alarm missing windowStart")?;
+        let start_time = NaiveTime::parse_from_str(start, "%H:%M")?;
+        let date = if alarm.mode.is_one_shot() {
+            parse_one_shot_date(alarm)?
+        } else {
+            occurrence_date_in(alarm, trigger)?
+        };
+        at_local(
+            date.and_time(start_time),
+            &zone,
+            alarm.dst_policy.unwrap_or_default(),
+        )
+    } else {
+        None
+    };
+    // A window start the clocks skip leaves the trigger as the best guess.
+    Ok(opens
+        .filter(|at| *at <= trigger)
+        .unwrap_or(trigger)
+        .timestamp_millis())
 }
 
-fn floor_to_minute(dt: DateTime<Local>) -> DateTime<Local> {
-    dt - chrono::Duration::seconds(dt.second() as i64)
-        - chrono::Duration::nanoseconds(dt.nanosecond() as i64)
+/// Which calendar days a recurring (non-one-shot) alarm occurs on, before skip dates.
+enum DayPattern<'a> {
+    /// Every week on these days ([0-6] where 0=Sun).
+    Weekdays(&'a [i32]),
+    /// An RRULE started on `anchor`; `active_days` is ignored.
+    Rule {
+        rule: RecurrenceRule,
+        anchor: NaiveDate,
+    },
 }
 
-fn ceil_to_minute(dt: DateTime<Local>) -> DateTime<Local> {
-    let floored = floor_to_minute(dt);
-    if floored < dt {
-        floored + chrono::Duration::minutes(1)
-    } else {
-        floored
+impl DayPattern<'_> {
+    fn next_on_or_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            DayPattern::Weekdays(active_days) => date
+                .iter_days()
+                .take(7)
+                .find(|d| active_days.contains(&(d.weekday().num_days_from_sunday() as i32))),
+            DayPattern::Rule { rule, anchor } => rule.next_on_or_after(*anchor, date),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::{DateTime, Datelike};
+/// The days a recurring alarm actually fires on: its pattern minus skipped dates.
+struct DaySchedule<'a> {
+    pattern: DayPattern<'a>,
+    skipped: Vec<NaiveDate>,
+}
 
-    #[test]
-    fn test_fixed_alarm_calculation() {
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("09:00".into()),
-            active_days: vec![1, 2, 3, 4, 5], // Weekdays
-            ..Default::default()
+impl<'a> DaySchedule<'a> {
+    fn from_alarm(alarm: &'a AlarmInput, ctx: &ScheduleContext) -> Result<Self> {
+        let pattern = match alarm.recurrence_rule.as_ref() {
+            None => DayPattern::Weekdays(&alarm.active_days),
+            Some(rule) => {
+                let anchor = alarm
+                    .recurrence_anchor
+                    .as_ref()
+                    .ok_or("Recurring alarm missing recurrenceAnchor")?;
+                DayPattern::Rule {
+                    rule: rule.parse::<RecurrenceRule>()?,
+                    anchor: NaiveDate::parse_from_str(anchor, "%Y-%m-%d")?,
+                }
+            }
         };
-
-        let trigger = calculate_next_trigger(&input).unwrap();
-        assert!(trigger.is_some());
+        Ok(DaySchedule {
+            pattern,
+            skipped: skipped_dates(alarm, ctx)?,
+        })
     }
 
-    #[test]
-    fn test_window_randomization() {
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Window,
-            window_start: Some("07:00".into()),
-            window_end: Some("07:30".into()),
-            active_days: vec![1, 2, 3, 4, 5],
-            ..Default::default()
-        };
+    /// The first occurrence date on or after `date`, if the schedule has one.
+    fn next_on_or_after(&self, mut date: NaiveDate) -> Option<NaiveDate> {
+        loop {
+            let next = self.pattern.next_on_or_after(date)?;
+            if !self.skipped.contains(&next) {
+                return Some(next);
+            }
+            date = next.succ_opt()?;
+        }
+    }
 
-        let trigger = calculate_next_trigger(&input).unwrap().unwrap();
+    fn occurs_on(&self, date: NaiveDate) -> bool {
+        self.next_on_or_after(date) == Some(date)
+    }
 
-        // Verify trigger is in the future
-        let now = Local::now().timestamp_millis();
-        assert!(trigger > now);
+    /// Occurrence dates from `date` onwards. Bounded, since callers only ever need to
+    /// step past today's spent occurrence and the odd DST gap; a handful of tries is
+    /// enough for any schedule.
+    fn dates_from(&self, date: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.occurrences_from(date).take(8)
+    }
 
-        // Run multiple times to verify randomness
-        let _trigger2 = calculate_next_trigger(&input).unwrap().unwrap();
-        // Note: Could be same due to same day, but algorithm is random
+    fn occurrences_from(&self, date: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        std::iter::successors(self.next_on_or_after(date), move |d| {
+            self.next_on_or_after(d.succ_opt()?)
+        })
     }
+}
 
-    #[test]
-    fn test_disabled_alarm() {
-        let input = AlarmInput {
-            enabled: false,
-            ..Default::default()
-        };
+fn calculate_fixed_trigger<Z: TimeZone>(
+    time_str: &str,
+    schedule: &DaySchedule,
+    now: DateTime<Z>,
+    dst: DstPolicy,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    let target_time = NaiveTime::parse_from_str(time_str, "%H:%M")?;
 
-        let trigger = calculate_next_trigger(&input).unwrap();
-        assert!(trigger.is_none());
+    // Find next occurrence on the schedule
+    for date in schedule.dates_from(now.date_naive()) {
+        // If the policy skips a DST gap, continue to the next occurrence
+        if let Some(candidate_dt) = at_local(date.and_time(target_time), &now.timezone(), dst) {
+            if candidate_dt > now {
+                return Ok(Some(candidate_dt.timestamp_millis()));
+            }
+        }
     }
 
-    #[test]
-    fn test_recurrence_wrap_around() {
-        let now = Local::now();
-        // Calculate a day 2 days ago to ensure we look forward
-        let past_day = (now.weekday().num_days_from_sunday() as i32 + 7 - 2) % 7;
+    // The schedule has no remaining occurrences
+    Ok(None)
+}
 
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("12:00".into()),
-            active_days: vec![past_day],
-            ..Default::default()
-        };
+/// A one-shot occurrence that has already passed yields `None` rather than rolling
+/// over to another day -- there is no "next" occurrence to find.
+fn calculate_one_shot_fixed_trigger<Z: TimeZone>(
+    time_str: &str,
+    date: NaiveDate,
+    now: DateTime<Z>,
+    dst: DstPolicy,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    let target_time = NaiveTime::parse_from_str(time_str, "%H:%M")?;
 
-        let trigger_ts = calculate_next_trigger(&input).unwrap().unwrap();
-        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
-            .unwrap()
-            .with_timezone(&Local);
+    Ok(at_local(date.and_time(target_time), &now.timezone(), dst)
+        .filter(|candidate_dt| *candidate_dt > now)
+        .map(|candidate_dt| candidate_dt.timestamp_millis()))
+}
 
-        assert!(trigger_dt > now);
+/// The instant a clock time falls on, settling DST gaps and overlaps by `dst`; `None`
+/// when the policy skips a time the clocks jump over.
+fn at_local<Z: TimeZone>(local: NaiveDateTime, zone: &Z, dst: DstPolicy) -> Option<DateTime<Z>>
+where
+    Z::Offset: Copy,
+{
+    match local.and_local_timezone(zone.clone()) {
+        LocalResult::Single(at) => Some(at),
+        LocalResult::Ambiguous(first, second) => Some(match dst.overlap {
+            DstOverlapPolicy::FireAtFirst => first,
+            DstOverlapPolicy::FireAtSecond => second,
+        }),
+        LocalResult::None => match dst.gap {
+            DstGapPolicy::Skip => None,
+            DstGapPolicy::ShiftForward => {
+                // Read the time with the offset in force before the jump, which lands
+                // the same distance past it (02:30 on a one-hour jump is 03:30).
+                let before = zone
+                    .offset_from_utc_datetime(&(local - chrono::Duration::days(1)))
+                    .fix();
+                let at = local.and_local_timezone(before).single()?;
+                Some(at.with_timezone(zone))
+            }
+        },
+    }
+}
 
-        // Should be next week (5 days from now roughly)
-        // We expect it to be 5 days ahead
-        let diff = trigger_dt.signed_duration_since(now);
-        assert!(diff.num_days() >= 4); // At least 4 full days
+/// Whether a clock time is skipped or repeated by a DST change in `zone`.
+fn dst_transition<Z: TimeZone>(local: NaiveDateTime, zone: &Z) -> Option<DstTransition> {
+    match local.and_local_timezone(zone.clone()) {
+        LocalResult::Single(_) => None,
+        LocalResult::Ambiguous(..) => Some(DstTransition::Overlap),
+        LocalResult::None => Some(DstTransition::Gap),
+    }
+}
+
+fn calculate_window_trigger<Z: TimeZone>(
+    window: &Window,
+    schedule: &DaySchedule,
+    now: DateTime<Z>,
+    kind: ReferenceKind,
+    seed: &SampleSeed,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    // Overnight windows: "now" might already be inside the tail end of a
+    // window that started yesterday (e.g. it's 00:30 and yesterday's
+    // 23:00-01:00 window is still open). Only a fresh query may resample
+    // that remaining time; prefer it over jumping to the next occurrence.
+    if window.crosses_midnight && kind == ReferenceKind::Fresh {
+        let yesterday = (now - chrono::Duration::days(1)).date_naive();
+        if schedule.occurs_on(yesterday) {
+            if let Some(trigger) = sample_window_for_day(window, yesterday, now, kind, seed)? {
+                return Ok(Some(trigger));
+            }
+        }
+    }
+
+    // Find next occurrence
+    for date in schedule.dates_from(now.date_naive()) {
+        if let Some(trigger) = sample_window_for_day(window, date, now, kind, seed)? {
+            return Ok(Some(trigger));
+        }
+    }
+
+    Ok(None)
+}
+
+/// A window alarm's daily span and how minutes are drawn from it, validated once per
+/// calculation.
+struct Window {
+    start: NaiveTime,
+    end: NaiveTime,
+    // If end <= start, the window crosses midnight (e.g. 23:00 -> 01:00);
+    // its end falls on the day after whichever day it starts on.
+    crosses_midnight: bool,
+    shape: WindowShape,
+    dst: DstPolicy,
+}
+
+/// A [`WindowDistribution`] resolved against its window, in minutes from the window
+/// start.
+enum WindowShape {
+    Uniform,
+    TriangularStart,
+    TriangularEnd,
+    TruncatedNormal { mean: f64, std_dev: f64 },
+    AvoidLast(i64),
+    // Minutes from the window start that land on a sleep-cycle boundary; never empty.
+    SleepCycles(Vec<i64>),
+}
+
+impl Window {
+    fn from_alarm(alarm: &AlarmInput) -> Result<Self> {
+        let start = alarm
+            .window_start
+            .as_ref()
+            .ok_or("Window alarm missing windowStart")?;
+        let end = alarm
+            .window_end
+            .as_ref()
+            .ok_or("Window alarm missing windowEnd")?;
+        let start = NaiveTime::parse_from_str(start, "%H:%M")?;
+        let end = NaiveTime::parse_from_str(end, "%H:%M")?;
+
+        if start == end {
+            return Err("Window end must differ from start".into());
+        }
+        let length_mins = minutes_after(start, end);
+
+        let distribution = alarm.window_distribution.clone().unwrap_or_default();
+        let shape = WindowShape::resolve(
+            &distribution,
+            length_mins,
+            Some(start),
+            parse_bedtime(alarm)?,
+        )?;
+
+        Ok(Window {
+            start,
+            end,
+            crosses_midnight: end <= start,
+            shape,
+            dst: alarm.dst_policy.unwrap_or_default(),
+        })
+    }
+}
+
+impl Window {
+    /// The instants the window that opens on `date` opens and closes, or `None` if
+    /// either falls in a DST gap the alarm's policy skips.
+    fn bounds_on<Z: TimeZone>(
+        &self,
+        date: NaiveDate,
+        zone: &Z,
+    ) -> Option<(DateTime<Z>, DateTime<Z>)>
+    where
+        Z::Offset: Copy,
+    {
+        let start = at_local(date.and_time(self.start), zone, self.dst)?;
+
+        let end_date = if self.crosses_midnight {
+            date + chrono::Duration::days(1)
+        } else {
+            date
+        };
+        let end = at_local(end_date.and_time(self.end), zone, self.dst)?;
+
+        Some((start, end))
+    }
+}
+
+fn calculate_interval_trigger<Z: TimeZone>(
+    interval: &Interval,
+    schedule: &DaySchedule,
+    now: DateTime<Z>,
+) -> Option<i64>
+where
+    Z::Offset: Copy,
+{
+    // Yesterday's span may still be running past midnight.
+    let yesterday = (now - chrono::Duration::days(1)).date_naive();
+    let dates = Some(yesterday)
+        .filter(|d| schedule.occurs_on(*d))
+        .into_iter()
+        .chain(schedule.dates_from(now.date_naive()));
+
+    let zone = now.timezone();
+    for date in dates {
+        if let Some(at) = interval.instances_on(date, &zone).find(|at| *at > now) {
+            return Some(at.timestamp_millis());
+        }
+    }
+    None
+}
+
+/// An interval alarm's daily span and the gap between its instances, validated once
+/// per calculation.
+struct Interval {
+    start: NaiveTime,
+    // Minutes from `start` to the span's end, which may fall after midnight
+    span_minutes: i64,
+    every_minutes: i64,
+    dst: DstPolicy,
+}
+
+impl Interval {
+    fn from_alarm(alarm: &AlarmInput) -> Result<Self> {
+        let start = alarm
+            .window_start
+            .as_ref()
+            .ok_or("Interval alarm missing windowStart")?;
+        let end = alarm
+            .window_end
+            .as_ref()
+            .ok_or("Interval alarm missing windowEnd")?;
+        let every = alarm
+            .interval_minutes
+            .ok_or("Interval alarm missing intervalMinutes")?;
+        let start = NaiveTime::parse_from_str(start, "%H:%M")?;
+        let end = NaiveTime::parse_from_str(end, "%H:%M")?;
+
+        if start == end {
+            return Err("Interval end must differ from start".into());
+        }
+        if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&every) {
+            return Err(format!(
+                "An interval alarm must repeat every {MIN_INTERVAL_MINUTES} to \
+                 {MAX_INTERVAL_MINUTES} minutes"
+            )
+            .into());
+        }
+
+        Ok(Interval {
+            start,
+            span_minutes: minutes_after(start, end),
+            every_minutes: i64::from(every),
+            dst: alarm.dst_policy.unwrap_or_default(),
+        })
+    }
+
+    /// The local times of the instances in the span that opens on `date`, from its
+    /// start to its end inclusive.
+    fn local_instances(&self, date: NaiveDate) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        let opens = date.and_time(self.start);
+        (0..=self.span_minutes / self.every_minutes)
+            .map(move |n| opens + chrono::Duration::minutes(n * self.every_minutes))
+    }
+
+    /// The instants of the instances in the span that opens on `date`, leaving out any
+    /// in a DST gap the alarm's policy skips.
+    fn instances_on<'a, Z: TimeZone>(
+        &'a self,
+        date: NaiveDate,
+        zone: &'a Z,
+    ) -> impl Iterator<Item = DateTime<Z>> + 'a
+    where
+        Z::Offset: Copy,
+    {
+        self.local_instances(date)
+            .filter_map(move |local| at_local(local, zone, self.dst))
+    }
+
+    /// Each instance's clock time, with how many days after the span's opening day it
+    /// falls.
+    fn clock_times(&self) -> impl Iterator<Item = (NaiveTime, i64)> + '_ {
+        let opening_day = NaiveDate::default();
+        self.local_instances(opening_day)
+            .map(move |local| (local.time(), (local.date() - opening_day).num_days()))
+    }
+}
+
+fn parse_bedtime(alarm: &AlarmInput) -> Result<Option<NaiveTime>> {
+    alarm
+        .bedtime
+        .as_deref()
+        .map(|b| Ok(NaiveTime::parse_from_str(b, "%H:%M")?))
+        .transpose()
+}
+
+/// Minutes from `from` forward to `to`, wrapping past midnight.
+fn minutes_after(from: NaiveTime, to: NaiveTime) -> i64 {
+    (to - from).num_minutes().rem_euclid(24 * 60)
+}
+
+impl WindowShape {
+    /// `start` is the window's opening clock time, which a truncated normal's target
+    /// and sleep-cycle boundaries are measured from; windows that move from day to day
+    /// have none. `bedtime` is when the sleep before the window began.
+    fn resolve(
+        distribution: &WindowDistribution,
+        length_mins: i64,
+        start: Option<NaiveTime>,
+        bedtime: Option<NaiveTime>,
+    ) -> Result<Self> {
+        Ok(match distribution {
+            WindowDistribution::Uniform => WindowShape::Uniform,
+            WindowDistribution::TriangularStart => WindowShape::TriangularStart,
+            WindowDistribution::TriangularEnd => WindowShape::TriangularEnd,
+            WindowDistribution::TruncatedNormal {
+                target,
+                std_dev_minutes,
+            } => {
+                let start = start.ok_or("A moving window can't target a clock time")?;
+                let target = NaiveTime::parse_from_str(target, "%H:%M")?;
+                let mean = minutes_after(start, target);
+                if mean >= length_mins {
+                    return Err("Distribution target must fall inside the window".into());
+                }
+                if !std_dev_minutes.is_finite() || *std_dev_minutes <= 0.0 {
+                    return Err("Distribution spread must be a positive number of minutes".into());
+                }
+                WindowShape::TruncatedNormal {
+                    mean: mean as f64,
+                    std_dev: *std_dev_minutes,
+                }
+            }
+            WindowDistribution::AvoidLast { minutes } => {
+                if i64::from(*minutes) >= length_mins {
+                    return Err("Cannot avoid the whole window".into());
+                }
+                WindowShape::AvoidLast(i64::from(*minutes))
+            }
+            WindowDistribution::SleepCycles => {
+                let start = start.ok_or("A moving window can't align to sleep cycles")?;
+                // Without a bedtime there's nothing to count cycles from yet.
+                let Some(bedtime) = bedtime else {
+                    return Ok(WindowShape::Uniform);
+                };
+                let asleep = minutes_after(bedtime, start);
+                let first =
+                    (SLEEP_CYCLE_MINUTES - asleep % SLEEP_CYCLE_MINUTES) % SLEEP_CYCLE_MINUTES;
+                let boundaries: Vec<i64> = (first..length_mins)
+                    .step_by(SLEEP_CYCLE_MINUTES as usize)
+                    .collect();
+                if boundaries.is_empty() {
+                    WindowShape::Uniform
+                } else {
+                    WindowShape::SleepCycles(boundaries)
+                }
+            }
+        })
+    }
+
+    /// Draws a minute offset in `lo..=hi` for a window whose minutes run `0..=span`.
+    /// `None` when the distribution allows nothing in that range.
+    fn draw(&self, rng: &mut ChaCha8Rng, span: i64, lo: i64, hi: i64) -> Option<i64> {
+        match *self {
+            WindowShape::Uniform => Some(uniform_in(rng, lo, hi)),
+            // Any boundary still in range, or uniform if the range has none left.
+            WindowShape::SleepCycles(ref boundaries) => {
+                let fits: Vec<i64> = boundaries
+                    .iter()
+                    .copied()
+                    .filter(|b| (lo..=hi).contains(b))
+                    .collect();
+                if fits.is_empty() {
+                    Some(uniform_in(rng, lo, hi))
+                } else {
+                    Some(fits[uniform_in(rng, 0, fits.len() as i64 - 1) as usize])
+                }
+            }
+            WindowShape::AvoidLast(minutes) => {
+                let hi = hi.min(span - minutes);
+                (lo <= hi).then(|| uniform_in(rng, lo, hi))
+            }
+            // Inverse-CDF sampling of a triangle over the window's `span + 1` minutes,
+            // restricted to the CDF range covering `lo..=hi`.
+            WindowShape::TriangularStart => {
+                let n = (span + 1) as f64;
+                let cdf = |x: f64| 1.0 - (1.0 - x / n).powi(2);
+                let (from, to) = (cdf(lo as f64), cdf((hi + 1) as f64));
+                let u = from + (to - from) * unit_f64(rng);
+                Some(((n * (1.0 - (1.0 - u).sqrt())) as i64).clamp(lo, hi))
+            }
+            WindowShape::TriangularEnd => {
+                let n = (span + 1) as f64;
+                let cdf = |x: f64| (x / n).powi(2);
+                let (from, to) = (cdf(lo as f64), cdf((hi + 1) as f64));
+                let u = from + (to - from) * unit_f64(rng);
+                Some(((n * u.sqrt()) as i64).clamp(lo, hi))
+            }
+            WindowShape::TruncatedNormal { mean, std_dev } => {
+                for _ in 0..NORMAL_MAX_ATTEMPTS {
+                    // Box-Muller; 1 - u keeps the logarithm's argument in (0, 1].
+                    let u1 = 1.0 - unit_f64(rng);
+                    let u2 = unit_f64(rng);
+                    let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+                    let x = (mean + std_dev * z).round() as i64;
+                    if (lo..=hi).contains(&x) {
+                        return Some(x);
+                    }
+                }
+                // Only `lo..=hi` far out in a tail gets here; its nearest edge is the
+                // most likely minute left.
+                Some((mean.round() as i64).clamp(lo, hi))
+            }
+        }
+    }
+}
+
+/// Rejection-sampling budget for the truncated normal before falling back to the
+/// range's most likely minute.
+const NORMAL_MAX_ATTEMPTS: usize = 64;
+
+/// Try to sample a trigger from the window instance that starts on `date`.
+fn sample_window_for_day<Z: TimeZone>(
+    window: &Window,
+    date: NaiveDate,
+    now: DateTime<Z>,
+    kind: ReferenceKind,
+    seed: &SampleSeed,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    let Some((window_start, window_end)) = window.bounds_on(date, &now.timezone()) else {
+        return Ok(None);
+    };
+
+    sample_between(
+        window_start,
+        window_end,
+        &window.shape,
+        date,
+        now,
+        kind,
+        seed,
+    )
+}
+
+/// Samples a trigger from one window occurrence, seeded by the date it belongs to.
+fn sample_between<Z: TimeZone>(
+    window_start: DateTime<Z>,
+    window_end: DateTime<Z>,
+    shape: &WindowShape,
+    date: NaiveDate,
+    now: DateTime<Z>,
+    kind: ReferenceKind,
+    seed: &SampleSeed,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    if window_end <= now {
+        // This occurrence has already fully elapsed.
+        return Ok(None);
+    }
+
+    let sample_start = match kind {
+        ReferenceKind::Fresh if window_start <= now => {
+            // Already inside the window: sample the remaining time, with a
+            // small lead so the alarm never fires "immediately".
+            std::cmp::max(
+                now + chrono::Duration::seconds(MIN_LEAD_SECONDS),
+                window_start,
+            )
+        }
+        _ if window_start > now => window_start,
+        // Not eligible for in-progress sampling and the window has already
+        // started (e.g. right after a dismissal) — this occurrence is
+        // spent; the caller moves on to the next active day.
+        _ => return Ok(None),
+    };
+
+    if sample_start >= window_end {
+        return Ok(None);
+    }
+
+    // Pick a random minute within the window so the alarm always fires at
+    // the top of the minute (:00 seconds). The end bound is exclusive.
+    let window_start_minute = ceil_to_minute(window_start);
+    let sample_start_minute = ceil_to_minute(sample_start);
+    let window_end_minute = floor_to_minute(window_end - chrono::Duration::milliseconds(1));
+
+    if sample_start_minute > window_end_minute {
+        return Ok(None);
+    }
+
+    // The occurrence's minute is drawn from the whole window, so it doesn't
+    // depend on when the calculation happens to run.
+    let mut rng = seed.rng_for(date);
+    let span = window_end_minute
+        .signed_duration_since(window_start_minute)
+        .num_minutes();
+    let lo = sample_start_minute
+        .signed_duration_since(window_start_minute)
+        .num_minutes();
+    let Some(picked) = shape.draw(&mut rng, span, 0, span) else {
+        return Ok(None);
+    };
+
+    let offset = if picked >= lo {
+        picked
+    } else {
+        // That minute has already gone by (e.g. the alarm was enabled late in an
+        // open window), so draw again from what's left of it.
+        let Some(redrawn) = shape.draw(&mut rng, span, lo, span) else {
+            return Ok(None);
+        };
+        redrawn
+    };
+    let trigger = window_start_minute + chrono::Duration::minutes(offset);
+
+    Ok(Some(trigger.timestamp_millis()))
+}
+
+/// A solar alarm's anchor and, for `SOLAR_WINDOW`, how its window is sampled.
+struct SolarWindow {
+    solar: SolarSchedule,
+    // `None` for `SOLAR`, which fires exactly at the anchor.
+    window: Option<(i64, WindowShape)>,
+}
+
+impl SolarWindow {
+    fn from_alarm(alarm: &AlarmInput) -> Result<Self> {
+        let solar = alarm.solar.clone().ok_or("Solar alarm missing solar")?;
+        if !(-90.0..=90.0).contains(&solar.latitude) {
+            return Err("Latitude must be between -90 and 90 degrees".into());
+        }
+        if !(-180.0..=180.0).contains(&solar.longitude) {
+            return Err("Longitude must be between -180 and 180 degrees".into());
+        }
+
+        let window = if alarm.mode == AlarmMode::SolarWindow {
+            let length_mins = match solar.window_minutes {
+                Some(minutes) if minutes > 0 => i64::from(minutes),
+                _ => return Err("Solar window alarm needs a window length".into()),
+            };
+            let distribution = alarm.window_distribution.clone().unwrap_or_default();
+            Some((
+                length_mins,
+                WindowShape::resolve(&distribution, length_mins, None, parse_bedtime(alarm)?)?,
+            ))
+        } else {
+            None
+        };
+
+        Ok(SolarWindow { solar, window })
+    }
+
+    /// When the occurrence for `date` fires (`SOLAR`) or opens (`SOLAR_WINDOW`): the
+    /// event plus the offset, on the minute. `None` if the event doesn't happen.
+    fn opens_on<Z: TimeZone>(&self, date: NaiveDate, zone: &Z) -> Option<DateTime<Z>>
+    where
+        Z::Offset: Copy,
+    {
+        let event = solar_event_utc(
+            date,
+            self.solar.latitude,
+            self.solar.longitude,
+            self.solar.event,
+        )?;
+        let anchor = event + chrono::Duration::minutes(i64::from(self.solar.offset_minutes));
+        Some(floor_to_minute(anchor.with_timezone(zone)))
+    }
+}
+
+fn calculate_solar_trigger<Z: TimeZone>(
+    solar: &SolarWindow,
+    schedule: &DaySchedule,
+    now: DateTime<Z>,
+    kind: ReferenceKind,
+    seed: &SampleSeed,
+) -> Result<Option<i64>>
+where
+    Z::Offset: Copy,
+{
+    // Start a day early: a large offset can carry yesterday's occurrence past
+    // midnight. Days without the event (polar day or night) are passed over.
+    let from = now.date_naive() - chrono::Duration::days(1);
+    let until = now.date_naive() + chrono::Duration::days(SOLAR_SEARCH_DAYS);
+    for date in schedule.occurrences_from(from).take_while(|d| *d <= until) {
+        let Some(opens) = solar.opens_on(date, &now.timezone()) else {
+            continue;
+        };
+        let trigger = match &solar.window {
+            None => Some(opens.timestamp_millis()).filter(|_| opens > now),
+            Some((length_mins, shape)) => {
+                let closes = opens + chrono::Duration::minutes(*length_mins);
+                sample_between(opens, closes, shape, date, now, kind, seed)?
+            }
+        };
+        if trigger.is_some() {
+            return Ok(trigger);
+        }
+    }
+
+    Ok(None)
+}
+
+/// The instant `event` happens on `date` at the given position, or `None` on days
+/// the sun never crosses the event's altitude (polar day or polar night).
+///
+/// Uses the low-precision solar position from the sunrise equation (mean anomaly,
+/// equation of centre, ecliptic longitude, declination), good to about a minute
+/// away from the poles -- plenty for an alarm, and computed with no network.
+pub fn solar_event_utc(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+    event: SolarEvent,
+) -> Option<DateTime<Utc>> {
+    // Days from the J2000 epoch (2000-01-01 12:00 UTC) to local solar noon on `date`.
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1)?;
+    let mean_noon = (date - j2000).num_days() as f64 - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let centre =
+        1.9148 * anomaly.sin() + 0.0200 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + centre + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let sin_declination = ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin();
+    let cos_declination = (1.0 - sin_declination * sin_declination).sqrt();
+    let altitude: f64 = match event {
+        SolarEvent::Sunrise | SolarEvent::Sunset => -0.833,
+        SolarEvent::CivilDawn | SolarEvent::CivilDusk => -6.0,
+    };
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (altitude.to_radians().sin() - latitude.sin() * sin_declination)
+        / (latitude.cos() * cos_declination);
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        // Above 1 the sun stays below the altitude all day; below -1, above it.
+        return None;
+    }
+
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    let days = match event {
+        SolarEvent::Sunrise | SolarEvent::CivilDawn => transit - half_day,
+        SolarEvent::Sunset | SolarEvent::CivilDusk => transit + half_day,
+    };
+    const J2000_MILLIS: i64 = 946_728_000_000;
+    DateTime::from_timestamp_millis(J2000_MILLIS + (days * 86_400_000.0).round() as i64)
+}
+
+/// An alarm's upcoming occurrences for previews (e.g. a week-ahead timeline), in
+/// order. Window alarms report their window bounds rather than a sampled minute,
+/// since a minute is only drawn when an occurrence is actually scheduled, and interval
+/// alarms one occurrence a day from their first instance to their last. Skip dates and
+/// holidays are honoured; snoozes are not.
+pub fn upcoming_occurrences(
+    alarm: &AlarmInput,
+    now_ms: i64,
+    query: &OccurrenceQuery,
+    ctx: &ScheduleContext,
+) -> Result<Vec<Occurrence>> {
+    let now = DateTime::from_timestamp_millis(now_ms).ok_or("Invalid reference timestamp")?;
+    match alarm.timezone.as_deref() {
+        Some(name) => {
+            let zone = parse_timezone(name)?;
+            upcoming_occurrences_in(alarm, now.with_timezone(&zone), query, ctx)
+        }
+        None => upcoming_occurrences_in(alarm, now.with_timezone(&Local), query, ctx),
+    }
+}
+
+fn upcoming_occurrences_in<Z: TimeZone>(
+    alarm: &AlarmInput,
+    now: DateTime<Z>,
+    query: &OccurrenceQuery,
+    ctx: &ScheduleContext,
+) -> Result<Vec<Occurrence>>
+where
+    Z::Offset: Copy,
+{
+    let (from, until, count) = match query {
+        OccurrenceQuery::Next { count } => {
+            if *count == 0 || *count > MAX_PREVIEW_OCCURRENCES {
+                return Err(format!(
+                    "Occurrence count must be between 1 and {MAX_PREVIEW_OCCURRENCES}"
+                )
+                .into());
+            }
+            // Yesterday's overnight window may still be open.
+            let today = now.date_naive();
+            (
+                today - chrono::Duration::days(1),
+                today + chrono::Duration::days(MAX_PREVIEW_DAYS),
+                *count as usize,
+            )
+        }
+        OccurrenceQuery::Range { from, to } => {
+            let from = NaiveDate::parse_from_str(from, "%Y-%m-%d")?;
+            let to = NaiveDate::parse_from_str(to, "%Y-%m-%d")?;
+            if to < from || (to - from).num_days() >= MAX_PREVIEW_DAYS {
+                return Err(format!(
+                    "Occurrence range must run forwards and span at most {MAX_PREVIEW_DAYS} days"
+                )
+                .into());
+            }
+            (from, to, usize::MAX)
+        }
+    };
+    // A follow-up's occurrences depend on when another alarm is dismissed.
+    if !alarm.enabled || alarm.mode == AlarmMode::FollowUp {
+        return Ok(Vec::new());
+    }
+
+    let timing = Timing::from_alarm(alarm)?;
+    let dates: Vec<NaiveDate> = if alarm.mode.is_one_shot() {
+        unskipped_one_shot_date(alarm, ctx)?
+            .filter(|d| (from..=until).contains(d))
+            .into_iter()
+            .collect()
+    } else {
+        DaySchedule::from_alarm(alarm, ctx)?
+            .occurrences_from(from)
+            .take_while(|d| *d <= until)
+            .collect()
+    };
+
+    let zone = now.timezone();
+    let upcoming = dates.into_iter().filter_map(|date| {
+        let (starts_at, ends_at) = timing.bounds_on(date, &zone)?;
+        Some(Occurrence {
+            alarm_id: alarm.id.unwrap_or(0),
+            date: date.format("%Y-%m-%d").to_string(),
+            starts_at: starts_at.timestamp_millis(),
+            ends_at: ends_at.map(|end| end.timestamp_millis()),
+        })
+    });
+    Ok(match query {
+        // Only what's still to come: a fixed time ahead, or a window not yet closed.
+        OccurrenceQuery::Next { .. } => {
+            let now_ms = now.timestamp_millis();
+            upcoming
+                .filter(|o| o.ends_at.unwrap_or(o.starts_at) > now_ms)
+                .take(count)
+                .collect()
+        }
+        OccurrenceQuery::Range { .. } => upcoming.collect(),
+    })
+}
+
+/// When an alarm's occurrence happens on a given date, independent of sampling.
+enum Timing {
+    Fixed(NaiveTime, DstPolicy),
+    Window(Window),
+    Solar(SolarWindow),
+    Interval(Interval),
+}
+
+impl Timing {
+    fn from_alarm(alarm: &AlarmInput) -> Result<Self> {
+        Ok(match alarm.mode {
+            AlarmMode::Fixed | AlarmMode::FixedOnce => {
+                let time = alarm
+                    .fixed_time
+                    .as_ref()
+                    .ok_or("Fixed alarm missing fixedTime")?;
+                Timing::Fixed(
+                    NaiveTime::parse_from_str(time, "%H:%M")?,
+                    alarm.dst_policy.unwrap_or_default(),
+                )
+            }
+            AlarmMode::Window | AlarmMode::WindowOnce => Timing::Window(Window::from_alarm(alarm)?),
+            AlarmMode::Solar | AlarmMode::SolarWindow => {
+                Timing::Solar(SolarWindow::from_alarm(alarm)?)
+            }
+            AlarmMode::Interval => Timing::Interval(Interval::from_alarm(alarm)?),
+            AlarmMode::FollowUp => return Err("Follow-up alarms have no fixed timing".into()),
+        })
+    }
+
+    /// The occurrence's trigger (or window opening) and, for windows, its close. An
+    /// interval's runs from its first instance to its last.
+    /// `None` when it doesn't happen that day (a skipped DST gap, or no sunrise).
+    fn bounds_on<Z: TimeZone>(
+        &self,
+        date: NaiveDate,
+        zone: &Z,
+    ) -> Option<(DateTime<Z>, Option<DateTime<Z>>)>
+    where
+        Z::Offset: Copy,
+    {
+        match self {
+            Timing::Fixed(time, dst) => Some((at_local(date.and_time(*time), zone, *dst)?, None)),
+            Timing::Window(window) => {
+                let (start, end) = window.bounds_on(date, zone)?;
+                Some((start, Some(end)))
+            }
+            Timing::Solar(solar) => {
+                let opens = solar.opens_on(date, zone)?;
+                let closes = solar
+                    .window
+                    .as_ref()
+                    .map(|(length_mins, _)| opens + chrono::Duration::minutes(*length_mins));
+                Some((opens, closes))
+            }
+            Timing::Interval(interval) => {
+                let mut instances = interval.instances_on(date, zone);
+                let first = instances.next()?;
+                Some((first, instances.last().or(Some(first))))
+            }
+        }
+    }
+}
+
+fn floor_to_minute<Z: TimeZone>(dt: DateTime<Z>) -> DateTime<Z>
+where
+    Z::Offset: Copy,
+{
+    dt - chrono::Duration::seconds(dt.second() as i64)
+        - chrono::Duration::nanoseconds(dt.nanosecond() as i64)
+}
+
+fn ceil_to_minute<Z: TimeZone>(dt: DateTime<Z>) -> DateTime<Z>
+where
+    Z::Offset: Copy,
+{
+    let floored = floor_to_minute(dt);
+    if floored < dt {
+        floored + chrono::Duration::minutes(1)
+    } else {
+        floored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alarm::clock::{Clock, ManualClock};
+    use chrono::{DateTime, Datelike};
+
+    /// The suite's "now": a fixed local Wednesday noon on a manual clock, so no
+    /// test depends on, or flakes around, when the suite actually runs.
+    fn suite_clock() -> ManualClock {
+        ManualClock::new(local_at("2030-01-02", "12:00").with_timezone(&Utc))
+    }
+
+    fn local_now(clock: &ManualClock) -> DateTime<Local> {
+        clock.now().with_timezone(&Local)
+    }
+
+    #[test]
+    fn test_fixed_alarm_calculation() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![1, 2, 3, 4, 5], // Weekdays
+            ..Default::default()
+        };
+
+        let trigger =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap();
+        assert!(trigger.is_some());
+    }
+
+    #[test]
+    fn test_window_randomization() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("07:00".into()),
+            window_end: Some("07:30".into()),
+            active_days: vec![1, 2, 3, 4, 5],
+            ..Default::default()
+        };
+
+        let trigger =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+
+        // Verify trigger is in the future
+        let now = local_now(&clock).timestamp_millis();
+        assert!(trigger > now);
+
+        // Sampling is seeded per occurrence, so recalculating doesn't re-roll
+        let trigger2 =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        assert_eq!(trigger, trigger2);
+    }
+
+    #[test]
+    fn test_disabled_alarm() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: false,
+            ..Default::default()
+        };
+
+        let trigger =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap();
+        assert!(trigger.is_none());
+    }
+
+    #[test]
+    fn test_recurrence_wrap_around() {
+        let clock = suite_clock();
+        let now = local_now(&clock);
+        // Calculate a day 2 days ago to ensure we look forward
+        let past_day = (now.weekday().num_days_from_sunday() as i32 + 7 - 2) % 7;
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("12:00".into()),
+            active_days: vec![past_day],
+            ..Default::default()
+        };
+
+        let trigger_ts =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(trigger_dt > now);
+
+        // Should be next week (5 days from now roughly)
+        // We expect it to be 5 days ahead
+        let diff = trigger_dt.signed_duration_since(now);
+        assert!(diff.num_days() >= 4); // At least 4 full days
+    }
+
+    #[test]
+    fn test_recurrence_nearest_day() {
+        let clock = suite_clock();
+        let now = local_now(&clock);
+        let today_idx = now.weekday().num_days_from_sunday() as i32;
+
+        // Create active days: today + 2 days, and today + 5 days
+        let day1 = (today_idx + 2) % 7;
+        let day2 = (today_idx + 5) % 7;
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("12:00".into()),
+            active_days: vec![day1, day2],
+            ..Default::default()
+        };
+
+        let trigger_ts =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(trigger_dt > now);
+
+        // Should pick the nearest one (day1, which is +2 days)
+        let diff = trigger_dt.signed_duration_since(now);
+        // It might be +1 day and some hours, or +2 days.
+        // Basically checking it's closer to +2 days than +5 days.
+        assert!(diff.num_days() <= 3);
+
+        // Double check the weekday matches day1
+        assert_eq!(trigger_dt.weekday().num_days_from_sunday() as i32, day1);
+    }
+
+    #[test]
+    fn test_calculate_after_skips_current_occurrence() {
+        let clock = suite_clock();
+        let now = local_now(&clock);
+        let today_idx = now.weekday().num_days_from_sunday() as i32;
+        let tomorrow_idx = (today_idx + 1) % 7;
+        let target_time = (now + chrono::Duration::minutes(5))
+            .format("%H:%M")
+            .to_string();
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some(target_time),
+            active_days: vec![today_idx, tomorrow_idx],
+            ..Default::default()
+        };
+
+        let first_trigger =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        let skipped_trigger = calculate_next_trigger_after(
+            &input,
+            first_trigger + 1_000,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(skipped_trigger > first_trigger);
+
+        let first_day = DateTime::from_timestamp_millis(first_trigger)
+            .unwrap()
+            .with_timezone(&Local)
+            .weekday()
+            .num_days_from_sunday() as i32;
+        let skipped_day = DateTime::from_timestamp_millis(skipped_trigger)
+            .unwrap()
+            .with_timezone(&Local)
+            .weekday()
+            .num_days_from_sunday() as i32;
+
+        assert_eq!(first_day, today_idx);
+        assert_eq!(skipped_day, tomorrow_idx);
+    }
+
+    #[test]
+    fn test_window_zero_length_errors() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("07:00".into()),
+            window_end: Some("07:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        };
+
+        assert!(
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_overnight_window_crossing_midnight_does_not_error() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("23:30".into()),
+            window_end: Some("00:15".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        };
+
+        let trigger =
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap();
+        assert!(trigger.is_some());
+    }
+
+    #[test]
+    fn test_window_samples_remaining_time_when_already_open() {
+        // Noon, so the "now +/- offset" window below can't straddle midnight,
+        // where .format("%H:%M") would silently drop the date.
+        let clock = suite_clock();
+        let now = local_now(&clock);
+        let today_idx = now.weekday().num_days_from_sunday() as i32;
+        let start = (now - chrono::Duration::minutes(10))
+            .format("%H:%M")
+            .to_string();
+        let end = (now + chrono::Duration::minutes(20))
+            .format("%H:%M")
+            .to_string();
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some(start),
+            window_end: Some(end),
+            active_days: vec![today_idx],
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        // Should sample from the remaining time of today's window, not skip to next week.
+        assert!(trigger_dt > now);
+        assert!(trigger_dt.signed_duration_since(now) < chrono::Duration::minutes(21));
+        assert_eq!(
+            trigger_dt.weekday().num_days_from_sunday() as i32,
+            today_idx
+        );
+    }
+
+    #[test]
+    fn test_overnight_window_samples_remaining_time_from_last_night() {
+        let clock = suite_clock();
+        clock.set(local_at("2030-01-02", "00:10").with_timezone(&Utc));
+        let now = local_now(&clock);
+        let today_idx = now.weekday().num_days_from_sunday() as i32;
+        let yesterday_idx = (today_idx + 6) % 7;
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            // Only yesterday is active, so the forward-looking loop alone
+            // (which starts at "today") would miss this entirely.
+            active_days: vec![yesterday_idx],
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        // Should sample from the remaining ~50 minutes of last night's window.
+        assert!(trigger_dt > now);
+        assert!(trigger_dt.signed_duration_since(now) < chrono::Duration::minutes(51));
+    }
+
+    #[test]
+    fn test_after_occurrence_does_not_resample_open_window() {
+        // Noon, for the same reason as the sibling test above.
+        let clock = suite_clock();
+        let now = local_now(&clock);
+        let today_idx = now.weekday().num_days_from_sunday() as i32;
+        let start = (now - chrono::Duration::minutes(10))
+            .format("%H:%M")
+            .to_string();
+        let end = (now + chrono::Duration::minutes(20))
+            .format("%H:%M")
+            .to_string();
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some(start),
+            window_end: Some(end),
+            // Only today is active, so the only way to get another trigger
+            // is to skip forward a full week.
+            active_days: vec![today_idx],
+            ..Default::default()
+        };
+
+        // Simulate dismissing a trigger that fired a moment ago, inside this same window.
+        let fired_at = now.timestamp_millis();
+        let after =
+            calculate_next_trigger_after(&input, fired_at + 1_000, &ScheduleContext::default())
+                .unwrap();
+
+        if let Some(ts) = after {
+            let dt = DateTime::from_timestamp_millis(ts)
+                .unwrap()
+                .with_timezone(&Local);
+            // Must not resample later today's remaining window time.
+            assert!(dt.signed_duration_since(now) > chrono::Duration::days(6));
+        }
+    }
+
+    fn local_noon(days_from_today: i64) -> DateTime<Local> {
+        let today = local_now(&suite_clock()).date_naive();
+        let day = today + chrono::Duration::days(days_from_today);
+        local_at(&day.format("%Y-%m-%d").to_string(), "12:00")
+    }
+
+    #[test]
+    fn test_one_shot_fixed_fires_on_its_date_regardless_of_active_days() {
+        let now = local_noon(0);
+        let date = now.date_naive() + chrono::Duration::days(3);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: Some("07:15".into()),
+            // Deliberately empty: a one-shot alarm has no weekday recurrence.
+            active_days: vec![],
+            one_shot_date: Some(date.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let expected = date
+            .and_hms_opt(7, 15, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap();
+
+        assert_eq!(trigger_ts, expected.timestamp_millis());
+    }
+
+    #[test]
+    fn test_one_shot_fixed_in_the_past_does_not_roll_over() {
+        let now = local_noon(0);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            // Earlier today: a recurring alarm would move on to its next active day.
+            fixed_time: Some("09:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            one_shot_date: Some(now.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        };
+
+        let trigger = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap();
+        assert!(trigger.is_none());
+    }
+
+    #[test]
+    fn test_one_shot_after_occurrence_has_no_next_trigger() {
+        let now = local_noon(0);
+        let date = now.date_naive() + chrono::Duration::days(1);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: Some("07:00".into()),
+            one_shot_date: Some(date.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        };
+
+        let first = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let after =
+            calculate_next_trigger_after(&input, first + 1_000, &ScheduleContext::default())
+                .unwrap();
+
+        assert!(after.is_none());
+    }
+
+    #[test]
+    fn test_one_shot_window_samples_inside_its_date() {
+        let now = local_noon(0);
+        let date = now.date_naive() + chrono::Duration::days(2);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::WindowOnce,
+            fixed_time: None,
+            window_start: Some("06:30".into()),
+            window_end: Some("07:00".into()),
+            active_days: vec![],
+            one_shot_date: Some(date.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(trigger_dt.date_naive(), date);
+        assert!(trigger_dt.time() >= NaiveTime::from_hms_opt(6, 30, 0).unwrap());
+        assert!(trigger_dt.time() < NaiveTime::from_hms_opt(7, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_one_shot_overnight_window_still_pending_after_midnight() {
+        // 00:10 on the day after the window opened.
+        let now = local_noon(0)
+            .date_naive()
+            .and_hms_opt(0, 10, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap();
+        let opened_on = now.date_naive() - chrono::Duration::days(1);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::WindowOnce,
+            fixed_time: None,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            one_shot_date: Some(opened_on.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(trigger_dt > now);
+        assert!(trigger_dt.signed_duration_since(now) < chrono::Duration::minutes(51));
+    }
+
+    #[test]
+    fn test_one_shot_missing_date_errors() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: Some("07:00".into()),
+            one_shot_date: None,
+            ..Default::default()
+        };
+
+        assert!(
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .is_err()
+        );
+    }
+
+    fn ymd(date: NaiveDate) -> String {
+        date.format("%Y-%m-%d").to_string()
+    }
+
+    #[test]
+    fn test_recurrence_rule_replaces_active_days() {
+        let now = local_noon(0);
+        let anchor = now.date_naive() - chrono::Duration::days(1);
+
+        // Every third day from yesterday: next is two days out, even though
+        // active_days would have allowed today.
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("18:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            recurrence_rule: Some("FREQ=DAILY;INTERVAL=3".into()),
+            recurrence_anchor: Some(ymd(anchor)),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(trigger_dt.date_naive(), anchor + chrono::Duration::days(3));
+        assert_eq!(
+            trigger_dt.time(),
+            NaiveTime::from_hms_opt(18, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_recurrence_rule_looks_past_a_week() {
+        let now = local_noon(0);
+        let anchor = now.date_naive() + chrono::Duration::days(1);
+
+        // Monthly on the anchor's date: after the first occurrence, the next one is
+        // weeks away -- well beyond the old 8-day scan.
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("09:00".into()),
+            recurrence_rule: Some("FREQ=MONTHLY;INTERVAL=2".into()),
+            recurrence_anchor: Some(ymd(anchor)),
+            ..Default::default()
+        };
+
+        let first = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let second =
+            calculate_next_trigger_after(&input, first + 1_000, &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        let second_dt = DateTime::from_timestamp_millis(second)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(second_dt.date_naive() > anchor + chrono::Duration::days(27));
+        assert!(second_dt.date_naive() <= anchor + chrono::Duration::days(62));
+    }
+
+    #[test]
+    fn test_recurrence_rule_window_resamples_last_night_only_if_it_occurred() {
+        // 00:30 -- inside the tail of an overnight 23:00-01:00 window.
+        let clock = suite_clock();
+        clock.set(local_at("2030-01-03", "00:30").with_timezone(&Utc));
+        let now = local_now(&clock);
+        let yesterday = now.date_naive() - chrono::Duration::days(1);
+
+        let input = |anchor: NaiveDate| AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            recurrence_rule: Some("FREQ=DAILY;INTERVAL=2".into()),
+            recurrence_anchor: Some(ymd(anchor)),
+            ..Default::default()
+        };
+
+        let open = calculate_next_trigger_from(
+            &input(yesterday),
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(open < (now + chrono::Duration::minutes(30)).timestamp_millis());
+
+        let not_last_night = calculate_next_trigger_from(
+            &input(yesterday - chrono::Duration::days(1)),
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        // Tonight's window is the next one on the rule.
+        assert!(
+            not_last_night >= (now + chrono::Duration::minutes(22 * 60 + 30)).timestamp_millis()
+        );
+        assert!(
+            not_last_night < (now + chrono::Duration::minutes(24 * 60 + 30)).timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn test_exhausted_recurrence_rule_has_no_trigger() {
+        let clock = suite_clock();
+        let input = AlarmInput {
+            enabled: true,
+            recurrence_rule: Some("FREQ=DAILY;COUNT=2".into()),
+            recurrence_anchor: Some("2020-01-01".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_recurrence_rule_errors() {
+        let clock = suite_clock();
+        let missing_anchor = AlarmInput {
+            enabled: true,
+            recurrence_rule: Some("FREQ=DAILY".into()),
+            ..Default::default()
+        };
+        assert!(calculate_next_trigger(
+            &missing_anchor,
+            clock.now_millis(),
+            &ScheduleContext::default()
+        )
+        .is_err());
+
+        let unsupported = AlarmInput {
+            enabled: true,
+            recurrence_rule: Some("FREQ=SECONDLY".into()),
+            recurrence_anchor: Some("2026-01-01".into()),
+            ..Default::default()
+        };
+        assert!(calculate_next_trigger(
+            &unsupported,
+            clock.now_millis(),
+            &ScheduleContext::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_skip_date_moves_to_following_occurrence() {
+        let now = local_noon(0);
+        let tomorrow = now.date_naive() + chrono::Duration::days(1);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            skip_dates: Some(vec![ymd(tomorrow)]),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(
+            trigger_dt.date_naive(),
+            tomorrow + chrono::Duration::days(1)
+        );
+    }
+
+    #[test]
+    fn test_skip_dates_can_cover_more_than_a_week() {
+        let now = local_noon(0);
+        let today = now.date_naive();
+        let weekday = today.weekday().num_days_from_sunday() as i32;
+
+        // Weekly on today's weekday, with the next three occurrences skipped.
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![weekday],
+            skip_dates: Some(
+                (1..=3)
+                    .map(|w| ymd(today + chrono::Duration::weeks(w)))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        let trigger_ts = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(trigger_dt.date_naive(), today + chrono::Duration::weeks(4));
+    }
+
+    #[test]
+    fn test_skip_date_suppresses_open_overnight_window() {
+        // 00:30 -- inside the tail of last night's 23:00-01:00 window.
+        let clock = suite_clock();
+        clock.set(local_at("2030-01-03", "00:30").with_timezone(&Utc));
+        let now = local_now(&clock);
+        let yesterday = now.date_naive() - chrono::Duration::days(1);
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            skip_dates: Some(vec![ymd(yesterday)]),
+            ..Default::default()
+        };
+
+        let trigger = calculate_next_trigger_from(
+            &input,
+            now,
+            ReferenceKind::Fresh,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(trigger >= (now + chrono::Duration::minutes(22 * 60 + 30)).timestamp_millis());
+    }
+
+    #[test]
+    fn test_skipped_one_shot_has_no_trigger() {
+        let clock = suite_clock();
+        let date = local_noon(2).date_naive();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![],
+            one_shot_date: Some(ymd(date)),
+            skip_dates: Some(vec![ymd(date)]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_occurrence_date_of_overnight_window_is_the_opening_day() {
+        let day = local_noon(1).date_naive();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            ..Default::default()
+        };
+        let at = |date: NaiveDate, h: u32, m: u32| {
+            date.and_hms_opt(h, m, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap()
+                .timestamp_millis()
+        };
+
+        assert_eq!(occurrence_date(&input, at(day, 23, 40)).unwrap(), day);
+        assert_eq!(
+            occurrence_date(&input, at(day + chrono::Duration::days(1), 0, 20)).unwrap(),
+            day
+        );
+    }
+
+    #[test]
+    fn test_wake_time_of_a_window_alarm_is_its_window_start() {
+        let day = local_noon(1).date_naive();
+        let at = |date: NaiveDate, h: u32, m: u32| {
+            date.and_hms_opt(h, m, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap()
+                .timestamp_millis()
+        };
+        let window = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("23:30".into()),
+            window_end: Some("00:30".into()),
+            ..Default::default()
+        };
+        let fixed = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("07:00".into()),
+            ..Default::default()
+        };
+
+        let next_day = day + chrono::Duration::days(1);
+        assert_eq!(
+            wake_time(&window, at(next_day, 0, 10)).unwrap(),
+            at(day, 23, 30)
+        );
+        assert_eq!(wake_time(&fixed, at(day, 7, 0)).unwrap(), at(day, 7, 0));
+    }
+
+    #[test]
+    fn test_normalize_skip_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+        let dates = [
+            "2026-06-20",
+            "2026-06-01",
+            "2026-06-14",
+            "2026-06-20",
+            "2026-06-16",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            normalize_skip_dates(&dates, today).unwrap(),
+            ["2026-06-14", "2026-06-16", "2026-06-20"]
+        );
+        assert!(normalize_skip_dates(&["20260620".to_string()], today).is_err());
+    }
+
+    #[test]
+    fn test_skip_holidays_steps_over_holiday_dates() {
+        let now = local_noon(0);
+        let tomorrow = now.date_naive() + chrono::Duration::days(1);
+        let ctx = ScheduleContext::default()
+            .with_holidays(&[ymd(tomorrow)])
+            .unwrap();
+
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            skip_holidays: Some(true),
+            ..Default::default()
+        };
+        let trigger_date = |input: &AlarmInput| {
+            let ts = calculate_next_trigger_from(input, now, ReferenceKind::Fresh, &ctx)
+                .unwrap()
+                .unwrap();
+            DateTime::from_timestamp_millis(ts)
+                .unwrap()
+                .with_timezone(&Local)
+                .date_naive()
+        };
+
+        assert_eq!(trigger_date(&input), tomorrow + chrono::Duration::days(1));
+
+        // Alarms that haven't opted in ignore the holiday calendar.
+        let opted_out = AlarmInput {
+            skip_holidays: Some(false),
+            ..input
+        };
+        assert_eq!(trigger_date(&opted_out), tomorrow);
+    }
+
+    #[test]
+    fn test_one_shot_on_a_holiday_is_skipped_when_opted_in() {
+        let clock = suite_clock();
+        let date = local_noon(2).date_naive();
+        let ctx = ScheduleContext::default()
+            .with_holidays(&[ymd(date)])
+            .unwrap();
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::FixedOnce,
+            fixed_time: Some("09:00".into()),
+            active_days: vec![],
+            one_shot_date: Some(ymd(date)),
+            skip_holidays: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            calculate_next_trigger(&input, clock.now_millis(), &ctx).unwrap(),
+            None
+        );
+    }
+
+    fn local_at(date: &str, time: &str) -> DateTime<Local> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+    }
+
+    fn seeded_window_alarm(id: i32) -> AlarmInput {
+        AlarmInput {
+            id: Some(id),
+            enabled: true,
+            mode: AlarmMode::Window,
+            fixed_time: None,
+            window_start: Some("06:30".into()),
+            window_end: Some("07:30".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        }
+    }
+
+    fn minute_of(trigger_ms: i64) -> String {
+        DateTime::from_timestamp_millis(trigger_ms)
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn test_window_sample_is_deterministic_per_occurrence() {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let input = seeded_window_alarm(7);
+        let sample = |now| {
+            calculate_next_trigger_from(&input, now, ReferenceKind::Fresh, &ctx)
+                .unwrap()
+                .unwrap()
+        };
+
+        // Recalculating at different times before the window opens lands on the
+        // same, exactly known minute. The minute is pinned so a dependency bump
+        // that moved the generator's output would fail here, not in users' alarms.
+        let first = sample(local_at("2030-01-01", "12:00"));
+        assert_eq!(first, sample(local_at("2030-01-01", "23:59")));
+        assert_eq!(first, sample(local_at("2030-01-02", "06:00")));
+        assert_eq!(minute_of(first), "2030-01-02 07:02");
     }
 
     #[test]
-    fn test_recurrence_nearest_day() {
-        let now = Local::now();
-        let today_idx = now.weekday().num_days_from_sunday() as i32;
+    fn test_window_sample_varies_by_alarm_date_and_install() {
+        let now = local_at("2030-01-01", "12:00");
+        let sample = |id: i32, secret: &str, now| {
+            calculate_next_trigger_from(
+                &seeded_window_alarm(id),
+                now,
+                ReferenceKind::Fresh,
+                &ScheduleContext::new(secret.into()),
+            )
+            .unwrap()
+            .unwrap()
+        };
+        let time_of_day = |ms| minute_of(ms)[11..].to_string();
+
+        let by_id: std::collections::HashSet<_> = (1..=10)
+            .map(|id| time_of_day(sample(id, "a", now)))
+            .collect();
+        let by_secret: std::collections::HashSet<_> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|secret| time_of_day(sample(1, secret, now)))
+            .collect();
+        let by_date: std::collections::HashSet<_> = (0..10)
+            .map(|d| time_of_day(sample(1, "a", now + chrono::Duration::days(d))))
+            .collect();
+
+        assert!(by_id.len() > 1);
+        assert!(by_secret.len() > 1);
+        assert!(by_date.len() > 1);
+    }
+
+    #[test]
+    fn test_window_sample_already_passed_redraws_from_remaining_time() {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let input = seeded_window_alarm(7);
+        let planned = calculate_next_trigger_from(
+            &input,
+            local_at("2030-01-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+
+        // Enabled again a minute after the planned time, while the window is open.
+        let late = DateTime::from_timestamp_millis(planned)
+            .unwrap()
+            .with_timezone(&Local)
+            + chrono::Duration::minutes(1);
+        let redrawn = calculate_next_trigger_from(&input, late, ReferenceKind::Fresh, &ctx)
+            .unwrap()
+            .unwrap();
+
+        assert!(redrawn > late.timestamp_millis());
+        assert!(redrawn < local_at("2030-01-02", "07:30").timestamp_millis());
+    }
+
+    /// Minutes after 06:30 drawn for 400 different alarms on one date.
+    fn sampled_offsets(distribution: WindowDistribution) -> Vec<i64> {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let now = local_at("2030-01-01", "12:00");
+        let window_start = local_at("2030-01-02", "06:30").timestamp_millis();
+        (1..=400)
+            .map(|id| {
+                let input = AlarmInput {
+                    window_distribution: Some(distribution.clone()),
+                    ..seeded_window_alarm(id)
+                };
+                let trigger = calculate_next_trigger_from(&input, now, ReferenceKind::Fresh, &ctx)
+                    .unwrap()
+                    .unwrap();
+                (trigger - window_start) / 60_000
+            })
+            .collect()
+    }
+
+    fn mean(offsets: &[i64]) -> f64 {
+        offsets.iter().sum::<i64>() as f64 / offsets.len() as f64
+    }
+
+    #[test]
+    fn test_window_distributions_stay_inside_the_window() {
+        for distribution in [
+            WindowDistribution::Uniform,
+            WindowDistribution::TriangularStart,
+            WindowDistribution::TriangularEnd,
+            WindowDistribution::TruncatedNormal {
+                target: "07:25".into(),
+                std_dev_minutes: 30.0,
+            },
+            WindowDistribution::AvoidLast { minutes: 15 },
+        ] {
+            let offsets = sampled_offsets(distribution.clone());
+            assert!(
+                offsets.iter().all(|o| (0..60).contains(o)),
+                "{distribution:?} sampled outside 06:30-07:30"
+            );
+        }
+    }
+
+    #[test]
+    fn test_window_distribution_shapes() {
+        // Means over a 60-minute window: uniform ~29.5, triangles ~20 and ~40.
+        let uniform = mean(&sampled_offsets(WindowDistribution::Uniform));
+        let early = mean(&sampled_offsets(WindowDistribution::TriangularStart));
+        let late = mean(&sampled_offsets(WindowDistribution::TriangularEnd));
+        assert!((26.0..33.0).contains(&uniform), "uniform mean {uniform}");
+        assert!(
+            (17.0..23.0).contains(&early),
+            "triangular start mean {early}"
+        );
+        assert!((37.0..43.0).contains(&late), "triangular end mean {late}");
+
+        let normal = sampled_offsets(WindowDistribution::TruncatedNormal {
+            target: "06:45".into(),
+            std_dev_minutes: 4.0,
+        });
+        assert!((14.0..16.0).contains(&mean(&normal)));
+        let within_two_sd = normal.iter().filter(|o| (7..=23).contains(*o)).count();
+        assert!(within_two_sd > 360, "{within_two_sd} of 400 within 2 sd");
+    }
+
+    #[test]
+    fn test_avoid_last_never_fires_in_the_final_minutes() {
+        let offsets = sampled_offsets(WindowDistribution::AvoidLast { minutes: 20 });
+        assert!(offsets.iter().all(|o| *o < 40));
+        assert!(offsets.iter().any(|o| *o >= 35));
+    }
+
+    #[test]
+    fn test_avoid_last_has_nothing_left_once_the_allowed_part_passed() {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let input = AlarmInput {
+            window_distribution: Some(WindowDistribution::AvoidLast { minutes: 20 }),
+            ..seeded_window_alarm(7)
+        };
+
+        // 07:15 is inside the window but past its allowed first 40 minutes, so
+        // today is spent and tomorrow's window is used.
+        let trigger = calculate_next_trigger_from(
+            &input,
+            local_at("2030-01-02", "07:15"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(minute_of(trigger).starts_with("2030-01-03 0"));
+    }
+
+    #[test]
+    fn test_truncated_normal_target_in_overnight_window() {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let now = local_at("2030-01-01", "12:00");
+        let target = local_at("2030-01-02", "00:30").timestamp_millis();
+        let offsets: Vec<i64> = (1..=200)
+            .map(|id| {
+                let input = AlarmInput {
+                    id: Some(id),
+                    enabled: true,
+                    mode: AlarmMode::Window,
+                    fixed_time: None,
+                    window_start: Some("23:00".into()),
+                    window_end: Some("01:00".into()),
+                    window_distribution: Some(WindowDistribution::TruncatedNormal {
+                        target: "00:30".into(),
+                        std_dev_minutes: 5.0,
+                    }),
+                    active_days: vec![0, 1, 2, 3, 4, 5, 6],
+                    ..Default::default()
+                };
+                let trigger = calculate_next_trigger_from(&input, now, ReferenceKind::Fresh, &ctx)
+                    .unwrap()
+                    .unwrap();
+                (trigger - target) / 60_000
+            })
+            .collect();
+
+        // Clustered on the far side of midnight, not around 23:00 + 30 minutes.
+        assert!(offsets.iter().all(|o| (-90..30).contains(o)));
+        assert!(mean(&offsets).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_truncated_normal_far_tail_falls_back_to_nearest_minute() {
+        let shape = WindowShape::TruncatedNormal {
+            mean: 0.0,
+            std_dev: 1.0,
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        assert_eq!(shape.draw(&mut rng, 59, 50, 59), Some(50));
+    }
+
+    #[test]
+    fn test_invalid_window_distributions_error() {
+        let clock = suite_clock();
+        let ctx = ScheduleContext::default();
+        for distribution in [
+            WindowDistribution::AvoidLast { minutes: 60 },
+            WindowDistribution::TruncatedNormal {
+                target: "08:00".into(),
+                std_dev_minutes: 5.0,
+            },
+            WindowDistribution::TruncatedNormal {
+                target: "07:00".into(),
+                std_dev_minutes: 0.0,
+            },
+        ] {
+            let input = AlarmInput {
+                window_distribution: Some(distribution.clone()),
+                ..seeded_window_alarm(7)
+            };
+            assert!(
+                calculate_next_trigger(&input, clock.now_millis(), &ctx).is_err(),
+                "{distribution:?} should be rejected"
+            );
+        }
+    }
+
+    const LONDON: (f64, f64) = (51.5074, -0.1278);
+    const TROMSO: (f64, f64) = (69.6496, 18.9560);
+    const LONGYEARBYEN: (f64, f64) = (78.2232, 15.6267);
+    const MCMURDO: (f64, f64) = (-77.8460, 166.6760);
+
+    fn solar_alarm(
+        id: i32,
+        mode: AlarmMode,
+        (latitude, longitude): (f64, f64),
+        event: SolarEvent,
+    ) -> AlarmInput {
+        AlarmInput {
+            id: Some(id),
+            enabled: true,
+            mode,
+            fixed_time: None,
+            solar: Some(SolarSchedule {
+                event,
+                latitude,
+                longitude,
+                offset_minutes: 0,
+                window_minutes: None,
+            }),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        }
+    }
+
+    fn utc_at(date: &str, time: &str) -> DateTime<Utc> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+            .and_utc()
+    }
+
+    fn event_on(
+        date: &str,
+        (latitude, longitude): (f64, f64),
+        event: SolarEvent,
+    ) -> Option<DateTime<Utc>> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        solar_event_utc(date, latitude, longitude, event)
+    }
+
+    fn assert_near(actual: DateTime<Utc>, expected: DateTime<Utc>) {
+        let diff = (actual - expected).num_minutes().abs();
+        assert!(diff <= 2, "{actual} is {diff} minutes from {expected}");
+    }
+
+    #[test]
+    fn test_solar_events_match_published_times() {
+        // London, midsummer 2030: sunrise 04:43 BST, sunset 21:21 BST.
+        assert_near(
+            event_on("2030-06-21", LONDON, SolarEvent::Sunrise).unwrap(),
+            utc_at("2030-06-21", "03:43"),
+        );
+        assert_near(
+            event_on("2030-06-21", LONDON, SolarEvent::Sunset).unwrap(),
+            utc_at("2030-06-21", "20:21"),
+        );
+        // Midwinter: sunrise 08:04 GMT, sunset 15:54 GMT.
+        assert_near(
+            event_on("2030-12-21", LONDON, SolarEvent::Sunrise).unwrap(),
+            utc_at("2030-12-21", "08:04"),
+        );
+        assert_near(
+            event_on("2030-12-21", LONDON, SolarEvent::Sunset).unwrap(),
+            utc_at("2030-12-21", "15:54"),
+        );
+
+        let dawn = event_on("2030-12-21", LONDON, SolarEvent::CivilDawn).unwrap();
+        let dusk = event_on("2030-12-21", LONDON, SolarEvent::CivilDusk).unwrap();
+        assert_near(dawn, utc_at("2030-12-21", "07:24"));
+        assert_near(dusk, utc_at("2030-12-21", "16:34"));
+    }
+
+    #[test]
+    fn test_polar_night_and_midnight_sun_have_no_events() {
+        // Tromsø: the sun doesn't rise in December, but civil twilight still comes.
+        assert_eq!(event_on("2030-12-21", TROMSO, SolarEvent::Sunrise), None);
+        assert_eq!(event_on("2030-12-21", TROMSO, SolarEvent::Sunset), None);
+        assert!(event_on("2030-12-21", TROMSO, SolarEvent::CivilDawn).is_some());
+        // ... and doesn't set in June.
+        assert_eq!(event_on("2030-06-21", TROMSO, SolarEvent::Sunset), None);
+
+        // Svalbard in midwinter is too dark even for civil twilight.
+        assert_eq!(
+            event_on("2030-12-21", LONGYEARBYEN, SolarEvent::CivilDawn),
+            None
+        );
+
+        // The southern hemisphere is the other way round.
+        assert_eq!(event_on("2030-06-21", MCMURDO, SolarEvent::Sunrise), None);
+        assert_eq!(event_on("2030-12-21", MCMURDO, SolarEvent::Sunset), None);
+        assert!(event_on("2030-03-01", MCMURDO, SolarEvent::Sunrise).is_some());
+    }
+
+    #[test]
+    fn test_sunrise_alarm_waits_out_polar_night() {
+        let ctx = ScheduleContext::default();
+        let input = solar_alarm(1, AlarmMode::Solar, TROMSO, SolarEvent::Sunrise);
+        let trigger = calculate_next_trigger_from(
+            &input,
+            local_at("2030-12-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+
+        // The sun is next seen in mid-January.
+        assert!(trigger > utc_at("2031-01-10", "00:00").timestamp_millis());
+        assert!(trigger < utc_at("2031-01-20", "00:00").timestamp_millis());
+    }
+
+    #[test]
+    fn test_sunset_alarm_waits_out_midnight_sun() {
+        let ctx = ScheduleContext::default();
+        let input = solar_alarm(1, AlarmMode::Solar, TROMSO, SolarEvent::Sunset);
+        let trigger = calculate_next_trigger_from(
+            &input,
+            local_at("2030-06-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(trigger > utc_at("2030-07-18", "00:00").timestamp_millis());
+        assert!(trigger < utc_at("2030-07-28", "00:00").timestamp_millis());
+    }
+
+    #[test]
+    fn test_event_that_never_happens_has_no_trigger() {
+        // The sun never rises over the North Pole in December, so a sunrise alarm
+        // that only occurs in December has nothing to fire on.
+        let ctx = ScheduleContext::default();
+        let mut input = solar_alarm(1, AlarmMode::Solar, (90.0, 0.0), SolarEvent::Sunrise);
+        input.recurrence_rule = Some("FREQ=YEARLY;BYMONTH=12".into());
+        input.recurrence_anchor = Some("2030-01-01".into());
+
+        let trigger = calculate_next_trigger_from(
+            &input,
+            local_at("2030-06-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(trigger, None);
+    }
+
+    #[test]
+    fn test_solar_offset_fires_on_the_minute() {
+        let ctx = ScheduleContext::default();
+        let mut input = solar_alarm(1, AlarmMode::Solar, LONDON, SolarEvent::Sunrise);
+        input.solar.as_mut().unwrap().offset_minutes = -30;
+        let now = local_at("2030-03-10", "12:00");
+
+        let trigger = calculate_next_trigger_from(&input, now, ReferenceKind::Fresh, &ctx)
+            .unwrap()
+            .unwrap();
+        assert_eq!(trigger % 60_000, 0);
+
+        let solar = SolarWindow::from_alarm(&input).unwrap();
+        let date = occurrence_date(&input, trigger).unwrap();
+        assert_eq!(
+            solar.opens_on(date, &Local).unwrap().timestamp_millis(),
+            trigger
+        );
+        let sunrise = solar_event_utc(date, LONDON.0, LONDON.1, SolarEvent::Sunrise).unwrap();
+        let early = sunrise.timestamp_millis() - trigger;
+        assert!((30 * 60_000..31 * 60_000).contains(&early));
+    }
+
+    #[test]
+    fn test_solar_window_samples_before_sunrise() {
+        let ctx = ScheduleContext::new("install-secret".into());
+        let now = local_at("2030-03-10", "12:00");
+        let mut offsets = std::collections::HashSet::new();
+        for id in 1..=50 {
+            let mut input = solar_alarm(id, AlarmMode::SolarWindow, LONDON, SolarEvent::Sunrise);
+            let solar = input.solar.as_mut().unwrap();
+            solar.offset_minutes = -20;
+            solar.window_minutes = Some(20);
+
+            let trigger = calculate_next_trigger_from(&input, now, ReferenceKind::Fresh, &ctx)
+                .unwrap()
+                .unwrap();
+            let date = occurrence_date(&input, trigger).unwrap();
+            let opens = SolarWindow::from_alarm(&input)
+                .unwrap()
+                .opens_on(date, &Local)
+                .unwrap()
+                .timestamp_millis();
+            let offset = (trigger - opens) / 60_000;
+            assert!(
+                (0..20).contains(&offset),
+                "{offset} minutes into the window"
+            );
+            offsets.insert(offset);
+        }
+        assert!(offsets.len() > 5);
+    }
+
+    #[test]
+    fn test_solar_occurrence_date_follows_offset_past_midnight() {
+        // Ten hours after a London sunset is past midnight in any nearby zone.
+        let ctx = ScheduleContext::default();
+        let mut input = solar_alarm(1, AlarmMode::Solar, LONDON, SolarEvent::Sunset);
+        input.solar.as_mut().unwrap().offset_minutes = 600;
+
+        let trigger = calculate_next_trigger_from(
+            &input,
+            local_at("2030-12-10", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        let date = occurrence_date(&input, trigger).unwrap();
+        let sunset = solar_event_utc(date, LONDON.0, LONDON.1, SolarEvent::Sunset).unwrap();
+        assert!((trigger - sunset.timestamp_millis() - 600 * 60_000).abs() < 60_000);
+    }
+
+    #[test]
+    fn test_invalid_solar_alarms_error() {
+        let clock = suite_clock();
+        let ctx = ScheduleContext::default();
+
+        let no_window = solar_alarm(1, AlarmMode::SolarWindow, LONDON, SolarEvent::Sunrise);
+        assert!(calculate_next_trigger(&no_window, clock.now_millis(), &ctx).is_err());
+
+        let mut targeted = no_window.clone();
+        targeted.solar.as_mut().unwrap().window_minutes = Some(30);
+        targeted.window_distribution = Some(WindowDistribution::TruncatedNormal {
+            target: "06:00".into(),
+            std_dev_minutes: 5.0,
+        });
+        assert!(calculate_next_trigger(&targeted, clock.now_millis(), &ctx).is_err());
+
+        let off_the_globe = solar_alarm(1, AlarmMode::Solar, (91.0, 0.0), SolarEvent::Sunrise);
+        assert!(calculate_next_trigger(&off_the_globe, clock.now_millis(), &ctx).is_err());
+
+        let mut missing = off_the_globe;
+        missing.solar = None;
+        assert!(calculate_next_trigger(&missing, clock.now_millis(), &ctx).is_err());
+    }
+
+    fn zoned_at(zone: &str, date: &str, time: &str) -> DateTime<Tz> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+            .and_local_timezone(parse_timezone(zone).unwrap())
+            .earliest()
+            .unwrap()
+    }
+
+    fn minute_in(zone: &str, trigger_ms: i64) -> String {
+        DateTime::from_timestamp_millis(trigger_ms)
+            .unwrap()
+            .with_timezone(&parse_timezone(zone).unwrap())
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    fn pinned_fixed_alarm(zone: &str, time: &str) -> AlarmInput {
+        AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Fixed,
+            fixed_time: Some(time.into()),
+            timezone: Some(zone.into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pinned_alarm_fires_in_its_own_zone() {
+        let ctx = ScheduleContext::default();
+        let input = pinned_fixed_alarm("Asia/Tokyo", "07:00");
+
+        // 12:00 UTC is 21:00 in Tokyo, so the next 07:00 there is tomorrow's.
+        let trigger = calculate_next_trigger_from(
+            &input,
+            utc_at("2030-01-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(minute_in("Asia/Tokyo", trigger), "2030-01-02 07:00");
+        assert_eq!(trigger, utc_at("2030-01-01", "22:00").timestamp_millis());
+    }
+
+    #[test]
+    fn test_pinned_alarm_active_days_follow_its_zone() {
+        let ctx = ScheduleContext::default();
+        let mut input = pinned_fixed_alarm("Pacific/Auckland", "07:00");
+        input.active_days = vec![1]; // Mondays, Auckland time
+
+        // Sunday 16:00 UTC is already 05:00 on Monday in Auckland.
+        let trigger = calculate_next_trigger_from(
+            &input,
+            utc_at("2030-01-06", "16:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(minute_in("Pacific/Auckland", trigger), "2030-01-07 07:00");
+    }
+
+    #[test]
+    fn test_pinned_alarm_skips_its_zones_spring_forward_gap() {
+        // Britain skips 01:00-02:00 on 2030-03-31.
+        let ctx = ScheduleContext::default();
+        let input = pinned_fixed_alarm("Europe/London", "01:30");
+        let trigger = calculate_next_trigger_from(
+            &input,
+            zoned_at("Europe/London", "2030-03-30", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(minute_in("Europe/London", trigger), "2030-04-01 01:30");
+    }
+
+    #[test]
+    fn test_pinned_window_sample_matches_the_same_wall_clock_anywhere() {
+        // The occurrence pinned in test_window_sample_is_deterministic_per_occurrence,
+        // without depending on the machine's zone.
+        let ctx = ScheduleContext::new("install-secret".into());
+        let input = AlarmInput {
+            timezone: Some("America/Toronto".into()),
+            ..seeded_window_alarm(7)
+        };
+        let trigger = calculate_next_trigger_from(
+            &input,
+            zoned_at("America/Toronto", "2030-01-01", "12:00"),
+            ReferenceKind::Fresh,
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(minute_in("America/Toronto", trigger), "2030-01-02 07:02");
+    }
+
+    #[test]
+    fn test_pinned_occurrence_date_uses_its_zone() {
+        let input = AlarmInput {
+            timezone: Some("Asia/Tokyo".into()),
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            ..seeded_window_alarm(7)
+        };
+        let trigger = zoned_at("Asia/Tokyo", "2030-01-02", "00:30").timestamp_millis();
+        assert_eq!(
+            occurrence_date(&input, trigger).unwrap(),
+            NaiveDate::from_ymd_opt(2030, 1, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_zone_errors() {
+        let clock = suite_clock();
+        let input = pinned_fixed_alarm("Mars/Olympus_Mons", "07:00");
+        assert!(
+            calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+                .is_err()
+        );
+    }
+
+    fn next_on(clock: &ManualClock, input: &AlarmInput) -> String {
+        let trigger =
+            calculate_next_trigger(input, clock.now_millis(), &ScheduleContext::default())
+                .unwrap()
+                .unwrap();
+        minute_in(input.timezone.as_deref().unwrap(), trigger)
+    }
+
+    #[test]
+    fn test_clock_across_midnight() {
+        let input = pinned_fixed_alarm("Europe/Berlin", "00:00");
+        let clock = ManualClock::new(
+            zoned_at("Europe/Berlin", "2030-01-01", "23:59").with_timezone(&Utc)
+                + chrono::Duration::seconds(30),
+        );
+        assert_eq!(next_on(&clock, &input), "2030-01-02 00:00");
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert_eq!(next_on(&clock, &input), "2030-01-03 00:00");
+    }
+
+    #[test]
+    fn test_clock_across_spring_forward() {
+        // Toronto skips 02:00-03:00 on 2030-03-10.
+        let input = pinned_fixed_alarm("America/Toronto", "02:30");
+        let clock = ManualClock::new(
+            zoned_at("America/Toronto", "2030-03-09", "12:00").with_timezone(&Utc),
+        );
+        assert_eq!(next_on(&clock, &input), "2030-03-11 02:30");
+
+        clock.advance(chrono::Duration::days(2));
+        assert_eq!(next_on(&clock, &input), "2030-03-12 02:30");
+    }
+
+    #[test]
+    fn test_clock_across_fall_back_fires_once() {
+        // Toronto repeats 01:00-02:00 on 2030-11-03: 01:30 EDT is 05:30 UTC and
+        // 01:30 EST is 06:30 UTC. Only the first one is the alarm.
+        let input = pinned_fixed_alarm("America/Toronto", "01:30");
+        let clock = ManualClock::new(utc_at("2030-11-03", "05:00"));
+        let first = calculate_next_trigger(&input, clock.now_millis(), &ScheduleContext::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(first, utc_at("2030-11-03", "05:30").timestamp_millis());
+
+        // Between the two 01:30s the next one is tomorrow's, not the repeat.
+        clock.advance(chrono::Duration::minutes(31));
+        assert_eq!(next_on(&clock, &input), "2030-11-04 01:30");
+    }
+
+    fn preview(input: &AlarmInput, now: DateTime<Utc>, query: OccurrenceQuery) -> Vec<Occurrence> {
+        upcoming_occurrences(
+            input,
+            now.timestamp_millis(),
+            &query,
+            &ScheduleContext::default(),
+        )
+        .unwrap()
+    }
+
+    fn next(count: u32) -> OccurrenceQuery {
+        OccurrenceQuery::Next { count }
+    }
+
+    fn range(from: &str, to: &str) -> OccurrenceQuery {
+        OccurrenceQuery::Range {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    #[test]
+    fn test_preview_next_fixed_occurrences() {
+        let input = AlarmInput {
+            id: Some(3),
+            active_days: vec![1, 3, 5],
+            ..pinned_fixed_alarm("Europe/Berlin", "07:00")
+        };
+        // 2030-01-07 is a Monday, and its 07:00 has already passed.
+        let now = zoned_at("Europe/Berlin", "2030-01-07", "08:00").with_timezone(&Utc);
+
+        let upcoming = preview(&input, now, next(4));
+        let dates: Vec<_> = upcoming.iter().map(|o| o.date.as_str()).collect();
+        assert_eq!(
+            dates,
+            ["2030-01-09", "2030-01-11", "2030-01-14", "2030-01-16"]
+        );
+        assert_eq!(
+            minute_in("Europe/Berlin", upcoming[0].starts_at),
+            "2030-01-09 07:00"
+        );
+        assert!(upcoming
+            .iter()
+            .all(|o| o.alarm_id == 3 && o.ends_at.is_none()));
+    }
+
+    #[test]
+    fn test_preview_reports_window_bounds_not_samples() {
+        let input = AlarmInput {
+            timezone: Some("Europe/Berlin".into()),
+            ..seeded_window_alarm(9)
+        };
+        let now = zoned_at("Europe/Berlin", "2030-01-07", "12:00").with_timezone(&Utc);
+
+        for occurrence in preview(&input, now, next(3)) {
+            let start = minute_in("Europe/Berlin", occurrence.starts_at);
+            let end = minute_in("Europe/Berlin", occurrence.ends_at.unwrap());
+            assert_eq!(&start[11..], input.window_start.as_deref().unwrap());
+            assert_eq!(&end[11..], input.window_end.as_deref().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_preview_includes_open_overnight_window() {
+        let input = AlarmInput {
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some("23:00".into()),
+            window_end: Some("01:00".into()),
+            timezone: Some("Europe/Berlin".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        };
+        // Half past midnight: yesterday's window is still open.
+        let now = zoned_at("Europe/Berlin", "2030-01-08", "00:30").with_timezone(&Utc);
+
+        let upcoming = preview(&input, now, next(2));
+        assert_eq!(upcoming[0].date, "2030-01-07");
+        assert_eq!(
+            minute_in("Europe/Berlin", upcoming[0].ends_at.unwrap()),
+            "2030-01-08 01:00"
+        );
+        assert_eq!(upcoming[1].date, "2030-01-08");
+    }
+
+    #[test]
+    fn test_preview_range_ignores_now_and_honours_skips() {
+        let input = AlarmInput {
+            skip_dates: Some(vec!["2030-01-03".into()]),
+            ..pinned_fixed_alarm("Asia/Tokyo", "06:30")
+        };
+        // Well after the range: a range preview is about the dates, not what's left.
+        let now = utc_at("2030-06-01", "00:00");
+
+        let upcoming = preview(&input, now, range("2030-01-01", "2030-01-05"));
+        let dates: Vec<_> = upcoming.iter().map(|o| o.date.as_str()).collect();
+        assert_eq!(
+            dates,
+            ["2030-01-01", "2030-01-02", "2030-01-04", "2030-01-05"]
+        );
+    }
+
+    #[test]
+    fn test_preview_one_shot_and_disabled() {
+        let once = AlarmInput {
+            mode: AlarmMode::FixedOnce,
+            one_shot_date: Some("2030-01-10".into()),
+            ..pinned_fixed_alarm("Europe/Berlin", "09:00")
+        };
+        let now = zoned_at("Europe/Berlin", "2030-01-07", "12:00").with_timezone(&Utc);
+
+        let upcoming = preview(&once, now, next(5));
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(
+            minute_in("Europe/Berlin", upcoming[0].starts_at),
+            "2030-01-10 09:00"
+        );
+        assert!(preview(&once, now, range("2030-01-11", "2030-01-20")).is_empty());
+
+        let disabled = AlarmInput {
+            enabled: false,
+            ..pinned_fixed_alarm("Europe/Berlin", "09:00")
+        };
+        assert!(preview(&disabled, now, next(5)).is_empty());
+    }
+
+    #[test]
+    fn test_preview_solar_skips_polar_night() {
+        let input = AlarmInput {
+            timezone: Some("Europe/Oslo".into()),
+            ..solar_alarm(4, AlarmMode::Solar, TROMSO, SolarEvent::Sunrise)
+        };
+        // Tromsø has no sunrise from late November until mid January.
+        let upcoming = preview(
+            &input,
+            utc_at("2030-01-01", "00:00"),
+            range("2030-01-01", "2030-01-31"),
+        );
+        assert!(!upcoming.is_empty());
+        assert!(upcoming.iter().all(|o| o.date.as_str() >= "2030-01-14"));
+        assert!(upcoming.iter().all(|o| o.ends_at.is_none()));
+
+        let windowed = AlarmInput {
+            timezone: Some("Europe/Oslo".into()),
+            solar: Some(SolarSchedule {
+                window_minutes: Some(20),
+                ..input.solar.clone().unwrap()
+            }),
+            ..solar_alarm(4, AlarmMode::SolarWindow, TROMSO, SolarEvent::Sunrise)
+        };
+        let upcoming = preview(&windowed, utc_at("2030-03-01", "00:00"), next(1));
+        assert_eq!(
+            upcoming[0].ends_at.unwrap() - upcoming[0].starts_at,
+            20 * 60_000
+        );
+    }
+
+    #[test]
+    fn test_preview_rejects_bad_queries() {
+        let clock = suite_clock();
+        let input = pinned_fixed_alarm("Europe/Berlin", "07:00");
+        let ctx = ScheduleContext::default();
+        let now = clock.now_millis();
+        for query in [
+            next(0),
+            next(MAX_PREVIEW_OCCURRENCES + 1),
+            range("2030-01-05", "2030-01-01"),
+            range("2030-01-01", "2031-06-01"),
+            range("2030-01-01", "soon"),
+        ] {
+            assert!(
+                upcoming_occurrences(&input, now, &query, &ctx).is_err(),
+                "{query:?} should be rejected"
+            );
+        }
+    }
+
+    fn sleep_cycle_alarm(id: i32, start: &str, end: &str, bedtime: Option<&str>) -> AlarmInput {
+        AlarmInput {
+            id: Some(id),
+            enabled: true,
+            mode: AlarmMode::Window,
+            window_start: Some(start.into()),
+            window_end: Some(end.into()),
+            window_distribution: Some(WindowDistribution::SleepCycles),
+            bedtime: bedtime.map(Into::into),
+            timezone: Some("Europe/Berlin".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            ..Default::default()
+        }
+    }
+
+    fn sleep_cycle_minutes(input: &AlarmInput, days: i64) -> Vec<String> {
+        let ctx = ScheduleContext::new("secret".into());
+        (0..days)
+            .map(|day| {
+                let now = zoned_at("Europe/Berlin", "2030-01-01", "12:00").with_timezone(&Utc)
+                    + chrono::Duration::days(day);
+                let trigger = calculate_next_trigger(input, now.timestamp_millis(), &ctx)
+                    .unwrap()
+                    .unwrap();
+                minute_in("Europe/Berlin", trigger)[11..].to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sleep_cycles_land_on_a_boundary() {
+        // 22:45 + 5 cycles = 06:15, + 6 cycles = 07:45; both inside 06:00-08:00.
+        let input = sleep_cycle_alarm(1, "06:00", "08:00", Some("22:45"));
+        let minutes = sleep_cycle_minutes(&input, 30);
+        assert!(
+            minutes.iter().all(|m| m == "06:15" || m == "07:45"),
+            "{minutes:?}"
+        );
+        assert!(minutes.iter().any(|m| m == "06:15"));
+        assert!(minutes.iter().any(|m| m == "07:45"));
+    }
+
+    #[test]
+    fn test_sleep_cycles_fall_back_to_uniform() {
+        // 23:00 + 5 cycles = 06:30, just past a 06:00-06:30 window.
+        let no_fit = sleep_cycle_alarm(2, "06:00", "06:30", Some("23:00"));
+        let minutes = sleep_cycle_minutes(&no_fit, 30);
+        assert!(minutes
+            .iter()
+            .all(|m| ("06:00".."06:30").contains(&m.as_str())));
+        assert!(
+            minutes
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+                > 5
+        );
 
-        // Create active days: today + 2 days, and today + 5 days
-        let day1 = (today_idx + 2) % 7;
-        let day2 = (today_idx + 5) % 7;
+        // No bedtime recorded yet: nothing to count cycles from.
+        let no_bedtime = sleep_cycle_alarm(2, "06:00", "06:30", None);
+        assert_eq!(sleep_cycle_minutes(&no_bedtime, 30), minutes);
+    }
 
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some("12:00".into()),
-            active_days: vec![day1, day2],
-            ..Default::default()
+    #[test]
+    fn test_sleep_cycles_skip_a_passed_boundary() {
+        // Enabled at 06:20 inside the window: 06:15 has gone, so 07:45 is the one left.
+        let input = sleep_cycle_alarm(3, "06:00", "08:00", Some("22:45"));
+        let now = zoned_at("Europe/Berlin", "2030-01-02", "06:20").with_timezone(&Utc);
+        let trigger = calculate_next_trigger(
+            &input,
+            now.timestamp_millis(),
+            &ScheduleContext::new("secret".into()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(minute_in("Europe/Berlin", trigger), "2030-01-02 07:45");
+    }
+
+    #[test]
+    fn test_sleep_cycles_rejects_moving_window_and_bad_bedtime() {
+        let clock = suite_clock();
+        let ctx = ScheduleContext::default();
+        let solar = AlarmInput {
+            window_distribution: Some(WindowDistribution::SleepCycles),
+            bedtime: Some("22:00".into()),
+            solar: Some(SolarSchedule {
+                window_minutes: Some(30),
+                ..solar_alarm(4, AlarmMode::SolarWindow, LONDON, SolarEvent::Sunrise)
+                    .solar
+                    .unwrap()
+            }),
+            ..solar_alarm(4, AlarmMode::SolarWindow, LONDON, SolarEvent::Sunrise)
         };
+        assert!(calculate_next_trigger(&solar, clock.now_millis(), &ctx).is_err());
 
-        let trigger_ts = calculate_next_trigger(&input).unwrap().unwrap();
-        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
-            .unwrap()
-            .with_timezone(&Local);
+        let bad = sleep_cycle_alarm(5, "06:00", "08:00", Some("late"));
+        assert!(calculate_next_trigger(&bad, clock.now_millis(), &ctx).is_err());
+    }
 
-        assert!(trigger_dt > now);
+    #[test]
+    fn test_clock_time_in_alarm_zone() {
+        let input = sleep_cycle_alarm(6, "06:00", "08:00", None);
+        let at = zoned_at("Europe/Berlin", "2030-01-01", "22:47").with_timezone(&Utc)
+            + chrono::Duration::seconds(42);
+        assert_eq!(
+            clock_time(&input, at.timestamp_millis()).unwrap(),
+            NaiveTime::from_hms_opt(22, 47, 0).unwrap()
+        );
+    }
 
-        // Should pick the nearest one (day1, which is +2 days)
-        let diff = trigger_dt.signed_duration_since(now);
-        // It might be +1 day and some hours, or +2 days.
-        // Basically checking it's closer to +2 days than +5 days.
-        assert!(diff.num_days() <= 3);
+    fn with_dst(input: AlarmInput, gap: DstGapPolicy, overlap: DstOverlapPolicy) -> AlarmInput {
+        AlarmInput {
+            dst_policy: Some(DstPolicy { gap, overlap }),
+            ..input
+        }
+    }
 
-        // Double check the weekday matches day1
-        assert_eq!(trigger_dt.weekday().num_days_from_sunday() as i32, day1);
+    #[test]
+    fn test_dst_gap_shift_forward() {
+        // Toronto skips 02:00-03:00 on 2030-03-10.
+        let input = with_dst(
+            pinned_fixed_alarm("America/Toronto", "02:30"),
+            DstGapPolicy::ShiftForward,
+            DstOverlapPolicy::FireAtFirst,
+        );
+        let clock = ManualClock::new(
+            zoned_at("America/Toronto", "2030-03-09", "12:00").with_timezone(&Utc),
+        );
+        assert_eq!(next_on(&clock, &input), "2030-03-10 03:30");
+
+        // Lord Howe Island only jumps half an hour, 02:00 to 02:30.
+        let input = with_dst(
+            pinned_fixed_alarm("Australia/Lord_Howe", "02:15"),
+            DstGapPolicy::ShiftForward,
+            DstOverlapPolicy::FireAtFirst,
+        );
+        let clock = ManualClock::new(
+            zoned_at("Australia/Lord_Howe", "2030-10-05", "12:00").with_timezone(&Utc),
+        );
+        assert_eq!(next_on(&clock, &input), "2030-10-06 02:45");
     }
 
     #[test]
-    fn test_calculate_after_skips_current_occurrence() {
-        let now = Local::now();
-        let today_idx = now.weekday().num_days_from_sunday() as i32;
-        let tomorrow_idx = (today_idx + 1) % 7;
-        let target_time = (now + chrono::Duration::minutes(5))
-            .format("%H:%M")
-            .to_string();
+    fn test_dst_overlap_fire_at_second() {
+        // Toronto repeats 01:00-02:00 on 2030-11-03; the second 01:30 is 06:30 UTC.
+        let input = with_dst(
+            pinned_fixed_alarm("America/Toronto", "01:30"),
+            DstGapPolicy::Skip,
+            DstOverlapPolicy::FireAtSecond,
+        );
+        let now = utc_at("2030-11-03", "05:00").timestamp_millis();
+        let trigger = calculate_next_trigger(&input, now, &ScheduleContext::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(trigger, utc_at("2030-11-03", "06:30").timestamp_millis());
+    }
 
-        let input = AlarmInput {
+    #[test]
+    fn test_dst_policy_moves_window_bounds() {
+        let window = AlarmInput {
             enabled: true,
-            mode: AlarmMode::Fixed,
-            fixed_time: Some(target_time),
-            active_days: vec![today_idx, tomorrow_idx],
+            mode: AlarmMode::Window,
+            window_start: Some("02:15".into()),
+            window_end: Some("02:45".into()),
+            timezone: Some("America/Toronto".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
             ..Default::default()
         };
+        let now = utc_at("2030-03-09", "00:00");
 
-        let first_trigger = calculate_next_trigger(&input).unwrap().unwrap();
-        let skipped_trigger = calculate_next_trigger_after(&input, first_trigger + 1_000)
-            .unwrap()
-            .unwrap();
+        let skipped = preview(&window, now, range("2030-03-10", "2030-03-10"));
+        assert!(skipped.is_empty());
 
-        assert!(skipped_trigger > first_trigger);
+        let shifted = with_dst(
+            window,
+            DstGapPolicy::ShiftForward,
+            DstOverlapPolicy::FireAtFirst,
+        );
+        let upcoming = preview(&shifted, now, range("2030-03-10", "2030-03-10"));
+        assert_eq!(
+            minute_in("America/Toronto", upcoming[0].starts_at),
+            "2030-03-10 03:15"
+        );
+        assert_eq!(
+            minute_in("America/Toronto", upcoming[0].ends_at.unwrap()),
+            "2030-03-10 03:45"
+        );
+    }
 
-        let first_day = DateTime::from_timestamp_millis(first_trigger)
-            .unwrap()
-            .with_timezone(&Local)
-            .weekday()
-            .num_days_from_sunday() as i32;
-        let skipped_day = DateTime::from_timestamp_millis(skipped_trigger)
-            .unwrap()
-            .with_timezone(&Local)
-            .weekday()
-            .num_days_from_sunday() as i32;
+    fn adjustment_at(input: &AlarmInput, now: DateTime<Utc>) -> Option<DstAdjustment> {
+        let ctx = ScheduleContext::default();
+        let trigger = calculate_next_trigger(input, now.timestamp_millis(), &ctx).unwrap();
+        dst_adjustment(input, now.timestamp_millis(), trigger, &ctx).unwrap()
+    }
 
-        assert_eq!(first_day, today_idx);
-        assert_eq!(skipped_day, tomorrow_idx);
+    #[test]
+    fn test_dst_adjustment_reports_a_skipped_gap() {
+        let input = pinned_fixed_alarm("America/Toronto", "02:30");
+        let adjustment = adjustment_at(&input, utc_at("2030-03-09", "20:00")).unwrap();
+        assert_eq!(
+            adjustment.date,
+            NaiveDate::from_ymd_opt(2030, 3, 10).unwrap()
+        );
+        assert_eq!(adjustment.time, NaiveTime::from_hms_opt(2, 30, 0).unwrap());
+        assert_eq!(adjustment.transition, DstTransition::Gap);
+
+        // Once the night has passed there's nothing left to warn about.
+        assert_eq!(adjustment_at(&input, utc_at("2030-03-10", "12:00")), None);
+        // Nor on an ordinary week.
+        assert_eq!(adjustment_at(&input, utc_at("2030-04-01", "12:00")), None);
     }
 
     #[test]
-    fn test_window_zero_length_errors() {
-        let input = AlarmInput {
+    fn test_dst_adjustment_reports_overlap_and_window_bounds() {
+        let input = pinned_fixed_alarm("America/Toronto", "01:30");
+        let adjustment = adjustment_at(&input, utc_at("2030-11-02", "20:00")).unwrap();
+        assert_eq!(adjustment.transition, DstTransition::Overlap);
+
+        // Only the window's end lands in the gap.
+        let window = AlarmInput {
             enabled: true,
             mode: AlarmMode::Window,
-            window_start: Some("07:00".into()),
-            window_end: Some("07:00".into()),
+            window_start: Some("01:30".into()),
+            window_end: Some("02:30".into()),
+            timezone: Some("America/Toronto".into()),
             active_days: vec![0, 1, 2, 3, 4, 5, 6],
             ..Default::default()
         };
-
-        assert!(calculate_next_trigger(&input).is_err());
+        let adjustment = adjustment_at(&window, utc_at("2030-03-09", "20:00")).unwrap();
+        assert_eq!(adjustment.time, NaiveTime::from_hms_opt(2, 30, 0).unwrap());
     }
 
     #[test]
-    fn test_overnight_window_crossing_midnight_does_not_error() {
+    fn test_dst_adjustment_one_shot_without_trigger() {
         let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Window,
-            window_start: Some("23:30".into()),
-            window_end: Some("00:15".into()),
-            active_days: vec![0, 1, 2, 3, 4, 5, 6],
+            mode: AlarmMode::FixedOnce,
+            one_shot_date: Some("2030-03-10".into()),
+            ..pinned_fixed_alarm("America/Toronto", "02:30")
+        };
+        let now = utc_at("2030-03-01", "12:00");
+        let ctx = ScheduleContext::default();
+        assert_eq!(
+            calculate_next_trigger(&input, now.timestamp_millis(), &ctx).unwrap(),
+            None
+        );
+        let adjustment = dst_adjustment(&input, now.timestamp_millis(), None, &ctx).unwrap();
+        assert_eq!(adjustment.unwrap().transition, DstTransition::Gap);
+
+        // Solar alarms follow absolute times and are never adjusted.
+        let solar = AlarmInput {
+            timezone: Some("America/Toronto".into()),
+            ..solar_alarm(8, AlarmMode::Solar, LONDON, SolarEvent::Sunrise)
+        };
+        assert_eq!(adjustment_at(&solar, utc_at("2030-03-09", "20:00")), None);
+    }
+
+    fn pre_alarm(minutes_before: u32) -> PreAlarm {
+        PreAlarm {
+            minutes_before,
+            sound_uri: None,
+            sound_title: None,
+            volume_percent: 30,
+            ring_seconds: 60,
+            skip_main_on_dismiss: false,
+        }
+    }
+
+    #[test]
+    fn test_pre_alarm_trigger_leads_the_alarm() {
+        let trigger = utc_at("2030-03-04", "07:00").timestamp_millis();
+        let now = utc_at("2030-03-04", "06:00").timestamp_millis();
+        assert_eq!(
+            pre_alarm_trigger(&pre_alarm(15), trigger, now),
+            Some(utc_at("2030-03-04", "06:45").timestamp_millis())
+        );
+
+        // Saved too close to the alarm for the pre-alarm to still ring first.
+        let late = utc_at("2030-03-04", "06:50").timestamp_millis();
+        assert_eq!(pre_alarm_trigger(&pre_alarm(15), trigger, late), None);
+        let exact = utc_at("2030-03-04", "06:45").timestamp_millis();
+        assert_eq!(pre_alarm_trigger(&pre_alarm(15), trigger, exact), None);
+    }
+
+    #[test]
+    fn test_validate_pre_alarm_bounds() {
+        assert!(validate_pre_alarm(&pre_alarm(10)).is_ok());
+        assert!(validate_pre_alarm(&pre_alarm(0)).is_err());
+        assert!(validate_pre_alarm(&pre_alarm(MAX_PRE_ALARM_MINUTES + 1)).is_err());
+
+        let full_volume = PreAlarm {
+            volume_percent: 100,
+            ..pre_alarm(10)
+        };
+        assert!(validate_pre_alarm(&full_volume).is_err());
+        let silent = PreAlarm {
+            volume_percent: 0,
+            ..pre_alarm(10)
+        };
+        assert!(validate_pre_alarm(&silent).is_err());
+
+        let endless = PreAlarm {
+            ring_seconds: 0,
+            ..pre_alarm(10)
+        };
+        assert!(validate_pre_alarm(&endless).is_err());
+        let too_long = PreAlarm {
+            ring_seconds: MAX_PRE_ALARM_RING_SECONDS + 1,
+            ..pre_alarm(10)
+        };
+        assert!(validate_pre_alarm(&too_long).is_err());
+    }
+
+    fn snooze_policy_alarm(policy: SnoozePolicy) -> AlarmInput {
+        AlarmInput {
+            timezone: Some("America/Toronto".into()),
+            snooze_policy: Some(policy),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snooze_allowance_decays_and_runs_out() {
+        let alarm = snooze_policy_alarm(SnoozePolicy {
+            max_snoozes: Some(4),
+            intervals_minutes: vec![10, 5, 3],
+            no_snooze_after: None,
+        });
+        let now = utc_at("2030-03-04", "11:00").timestamp_millis();
+        let minutes: Vec<_> = (0..5)
+            .map(|count| snooze_allowance(&alarm, count, now).unwrap().minutes)
+            .collect();
+        assert_eq!(minutes, [Some(10), Some(5), Some(3), Some(3), None]);
+
+        let spent = snooze_allowance(&alarm, 4, now).unwrap();
+        assert!(!spent.allowed);
+        assert_eq!(
+            spent.reason.as_deref(),
+            Some("Already snoozed 4 of 4 times")
+        );
+
+        let off = snooze_policy_alarm(SnoozePolicy {
+            max_snoozes: Some(0),
+            ..Default::default()
+        });
+        assert!(!snooze_allowance(&off, 0, now).unwrap().allowed);
+    }
+
+    #[test]
+    fn test_snooze_allowance_cut_off_is_local_to_the_alarm() {
+        let alarm = snooze_policy_alarm(SnoozePolicy {
+            no_snooze_after: Some("08:00".into()),
+            ..Default::default()
+        });
+        // 07:59 and 08:00 in Toronto (UTC-5)
+        let before = utc_at("2030-03-04", "12:59").timestamp_millis();
+        let at = utc_at("2030-03-04", "13:00").timestamp_millis();
+        let free = snooze_allowance(&alarm, 7, before).unwrap();
+        assert!(free.allowed);
+        assert_eq!(free.minutes, None);
+        let refused = snooze_allowance(&alarm, 0, at).unwrap();
+        assert!(!refused.allowed);
+        assert_eq!(refused.reason.as_deref(), Some("No snoozing after 08:00"));
+
+        let unrestricted = AlarmInput::default();
+        assert!(snooze_allowance(&unrestricted, 99, at).unwrap().allowed);
+    }
+
+    #[test]
+    fn test_validate_snooze_policy() {
+        assert!(validate_snooze_policy(&SnoozePolicy {
+            max_snoozes: Some(3),
+            intervals_minutes: vec![10, 5, 3],
+            no_snooze_after: Some("08:00".into()),
+        })
+        .is_ok());
+        for intervals in [vec![0], vec![10, MAX_SNOOZE_MINUTES + 1]] {
+            let policy = SnoozePolicy {
+                intervals_minutes: intervals,
+                ..Default::default()
+            };
+            assert!(validate_snooze_policy(&policy).is_err());
+        }
+        let bad_time = SnoozePolicy {
+            no_snooze_after: Some("8am".into()),
             ..Default::default()
         };
+        assert!(validate_snooze_policy(&bad_time).is_err());
+    }
 
-        let trigger = calculate_next_trigger(&input).unwrap();
-        assert!(trigger.is_some());
+    #[test]
+    fn test_validate_ring_timeout() {
+        let timeout = |minutes| RingTimeout {
+            minutes,
+            action: RingTimeoutAction::Dismiss,
+            escalate_to_watch: false,
+        };
+        assert!(validate_ring_timeout(&timeout(0)).is_ok());
+        assert!(validate_ring_timeout(&timeout(MAX_RING_TIMEOUT_MINUTES)).is_ok());
+        assert!(validate_ring_timeout(&timeout(MAX_RING_TIMEOUT_MINUTES + 1)).is_err());
     }
 
     #[test]
-    fn test_window_samples_remaining_time_when_already_open() {
-        // Use a synthetic mid-day "now" (rather than the real wall clock) so
-        // this can't flake when the suite happens to run within 20 minutes
-        // of local midnight, where a naive "now +/- offset" window would
-        // straddle the date boundary that .format("%H:%M") silently drops.
-        let now = Local::now()
-            .date_naive()
-            .and_hms_opt(12, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .earliest()
-            .unwrap();
-        let today_idx = now.weekday().num_days_from_sunday() as i32;
-        let start = (now - chrono::Duration::minutes(10))
-            .format("%H:%M")
-            .to_string();
-        let end = (now + chrono::Duration::minutes(20))
-            .format("%H:%M")
-            .to_string();
+    fn test_validate_backup_alarm() {
+        let backup = |minutes_after| BackupAlarm {
+            minutes_after,
+            sound_uri: None,
+            sound_title: None,
+            watch_only: false,
+        };
+        assert!(validate_backup_alarm(&backup(0)).is_ok());
+        assert!(validate_backup_alarm(&backup(MAX_BACKUP_ALARM_MINUTES)).is_ok());
+        assert!(validate_backup_alarm(&backup(MAX_BACKUP_ALARM_MINUTES + 1)).is_err());
+    }
 
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Window,
-            window_start: Some(start),
-            window_end: Some(end),
-            active_days: vec![today_idx],
+    fn interval_alarm(start: &str, end: &str, every: u32) -> AlarmInput {
+        AlarmInput {
+            mode: AlarmMode::Interval,
+            fixed_time: None,
+            window_start: Some(start.into()),
+            window_end: Some(end.into()),
+            interval_minutes: Some(every),
+            timezone: Some("America/Toronto".into()),
+            active_days: vec![0, 1, 2, 3, 4, 5, 6],
             ..Default::default()
+        }
+    }
+
+    fn toronto_ms(date: &str, time: &str) -> i64 {
+        zoned_at("America/Toronto", date, time).timestamp_millis()
+    }
+
+    #[test]
+    fn test_interval_alarm_steps_through_its_span() {
+        let ctx = ScheduleContext::default();
+        let input = interval_alarm("08:00", "20:00", 240);
+        let next = |now| {
+            let trigger = calculate_next_trigger(&input, now, &ctx).unwrap().unwrap();
+            minute_in("America/Toronto", trigger)
         };
 
-        let trigger_ts = calculate_next_trigger_from(&input, now, ReferenceKind::Fresh)
+        assert_eq!(next(toronto_ms("2030-01-01", "07:00")), "2030-01-01 08:00");
+        assert_eq!(next(toronto_ms("2030-01-01", "09:00")), "2030-01-01 12:00");
+        // The end is an instance too, since the span divides evenly.
+        assert_eq!(next(toronto_ms("2030-01-01", "19:00")), "2030-01-01 20:00");
+        assert_eq!(next(toronto_ms("2030-01-01", "20:00")), "2030-01-02 08:00");
+
+        // A span that doesn't divide evenly stops at the last instance inside it.
+        let uneven = interval_alarm("08:00", "09:00", 25);
+        let trigger = calculate_next_trigger(&uneven, toronto_ms("2030-01-01", "08:51"), &ctx)
             .unwrap()
             .unwrap();
-        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
-            .unwrap()
-            .with_timezone(&Local);
-
-        // Should sample from the remaining time of today's window, not skip to next week.
-        assert!(trigger_dt > now);
-        assert!(trigger_dt.signed_duration_since(now) < chrono::Duration::minutes(21));
-        assert_eq!(
-            trigger_dt.weekday().num_days_from_sunday() as i32,
-            today_idx
-        );
+        assert_eq!(minute_in("America/Toronto", trigger), "2030-01-02 08:00");
     }
 
     #[test]
-    fn test_overnight_window_samples_remaining_time_from_last_night() {
-        // Use a synthetic "now" of 00:10 so this isn't flaky depending on
-        // when the test suite actually runs.
-        let synthetic_now = Local::now()
-            .date_naive()
-            .and_hms_opt(0, 10, 0)
+    fn test_dismissing_an_interval_instance_moves_on_within_the_span() {
+        let ctx = ScheduleContext::default();
+        let input = interval_alarm("08:00", "20:00", 240);
+        let rang = toronto_ms("2030-01-01", "12:00");
+
+        let next = calculate_next_trigger_after(&input, rang + 1_000, &ctx)
             .unwrap()
-            .and_local_timezone(Local)
-            .earliest()
             .unwrap();
-        let today_idx = synthetic_now.weekday().num_days_from_sunday() as i32;
-        let yesterday_idx = (today_idx + 6) % 7;
+        assert_eq!(minute_in("America/Toronto", next), "2030-01-01 16:00");
+    }
 
+    #[test]
+    fn test_overnight_interval_span_runs_past_midnight() {
+        let ctx = ScheduleContext::default();
+        // Tuesdays only: 22:00, 00:00 and 02:00.
         let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Window,
-            window_start: Some("23:00".into()),
-            window_end: Some("01:00".into()),
-            // Only yesterday is active, so the forward-looking loop alone
-            // (which starts at "today") would miss this entirely.
-            active_days: vec![yesterday_idx],
-            ..Default::default()
+            active_days: vec![2],
+            ..interval_alarm("22:00", "02:00", 120)
         };
 
-        let trigger_ts = calculate_next_trigger_from(&input, synthetic_now, ReferenceKind::Fresh)
+        // Early Wednesday, Tuesday's span is still running.
+        let now = toronto_ms("2030-01-02", "00:30");
+        let trigger = calculate_next_trigger(&input, now, &ctx).unwrap().unwrap();
+        assert_eq!(minute_in("America/Toronto", trigger), "2030-01-02 02:00");
+        assert_eq!(
+            occurrence_date(&input, trigger).unwrap(),
+            NaiveDate::from_ymd_opt(2030, 1, 1).unwrap()
+        );
+
+        let after = calculate_next_trigger_after(&input, trigger + 1_000, &ctx)
             .unwrap()
             .unwrap();
-        let trigger_dt = DateTime::from_timestamp_millis(trigger_ts)
-            .unwrap()
-            .with_timezone(&Local);
-
-        // Should sample from the remaining ~50 minutes of last night's window.
-        assert!(trigger_dt > synthetic_now);
-        assert!(trigger_dt.signed_duration_since(synthetic_now) < chrono::Duration::minutes(51));
+        assert_eq!(minute_in("America/Toronto", after), "2030-01-08 22:00");
     }
 
     #[test]
-    fn test_after_occurrence_does_not_resample_open_window() {
-        // Synthetic mid-day "now" for the same reason as the sibling test above.
-        let now = Local::now()
-            .date_naive()
-            .and_hms_opt(12, 0, 0)
+    fn test_interval_alarm_skips_instances_in_a_dst_gap() {
+        let ctx = ScheduleContext::default();
+        // Toronto skips 02:00-03:00 on 2030-03-10.
+        let input = interval_alarm("01:00", "04:00", 30);
+        let trigger = calculate_next_trigger(&input, toronto_ms("2030-03-10", "01:45"), &ctx)
             .unwrap()
-            .and_local_timezone(Local)
-            .earliest()
             .unwrap();
-        let today_idx = now.weekday().num_days_from_sunday() as i32;
-        let start = (now - chrono::Duration::minutes(10))
-            .format("%H:%M")
-            .to_string();
-        let end = (now + chrono::Duration::minutes(20))
-            .format("%H:%M")
-            .to_string();
+        assert_eq!(minute_in("America/Toronto", trigger), "2030-03-10 03:00");
+
+        let adjustment = dst_adjustment(
+            &input,
+            toronto_ms("2030-03-09", "12:00"),
+            Some(trigger),
+            &ctx,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(adjustment.transition, DstTransition::Gap);
+        assert_eq!(adjustment.time, NaiveTime::from_hms_opt(2, 0, 0).unwrap());
+    }
 
-        let input = AlarmInput {
-            enabled: true,
-            mode: AlarmMode::Window,
-            window_start: Some(start),
-            window_end: Some(end),
-            // Only today is active, so the only way to get another trigger
-            // is to skip forward a full week.
-            active_days: vec![today_idx],
+    #[test]
+    fn test_interval_alarm_previews_one_occurrence_a_day() {
+        let input = interval_alarm("08:00", "20:00", 240);
+        let now = zoned_at("America/Toronto", "2030-01-01", "09:00").with_timezone(&Utc);
+        let occurrences = preview(&input, now, next(2));
+
+        assert_eq!(occurrences.len(), 2);
+        assert_eq!(occurrences[0].date, "2030-01-01");
+        assert_eq!(
+            minute_in("America/Toronto", occurrences[0].starts_at),
+            "2030-01-01 08:00"
+        );
+        assert_eq!(
+            occurrences[0]
+                .ends_at
+                .map(|end| minute_in("America/Toronto", end)),
+            Some("2030-01-01 20:00".into())
+        );
+        assert_eq!(occurrences[1].date, "2030-01-02");
+    }
+
+    #[test]
+    fn test_interval_alarm_validation() {
+        let ctx = ScheduleContext::default();
+        let now = toronto_ms("2030-01-01", "07:00");
+        let invalid = [
+            interval_alarm("08:00", "08:00", 60),
+            interval_alarm("08:00", "20:00", MIN_INTERVAL_MINUTES - 1),
+            interval_alarm("08:00", "20:00", MAX_INTERVAL_MINUTES + 1),
+            AlarmInput {
+                interval_minutes: None,
+                ..interval_alarm("08:00", "20:00", 60)
+            },
+        ];
+        for input in invalid {
+            assert!(calculate_next_trigger(&input, now, &ctx).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_follow_up() {
+        let follow = |alarm_id, minutes_after| FollowUp {
+            alarm_id,
+            minutes_after,
+        };
+        // 3 follows 2, which follows 1.
+        let followed = HashMap::from([(2, 1), (3, 2)]);
+
+        assert!(validate_follow_up(None, &follow(3, 40), &followed).is_ok());
+        assert!(validate_follow_up(Some(4), &follow(3, 40), &followed).is_ok());
+        assert!(validate_follow_up(Some(2), &follow(1, MAX_FOLLOW_UP_MINUTES), &followed).is_ok());
+        assert!(validate_follow_up(None, &follow(1, 0), &followed).is_err());
+        assert!(
+            validate_follow_up(None, &follow(1, MAX_FOLLOW_UP_MINUTES + 1), &followed).is_err()
+        );
+
+        // Following itself, or anything down its own chain, is a cycle.
+        assert!(validate_follow_up(Some(1), &follow(1, 40), &followed).is_err());
+        assert!(validate_follow_up(Some(1), &follow(3, 40), &followed).is_err());
+        assert!(validate_follow_up(Some(2), &follow(3, 40), &followed).is_err());
+
+        // A cycle already stored elsewhere doesn't loop forever.
+        let looped = HashMap::from([(5, 6), (6, 5)]);
+        assert!(validate_follow_up(Some(1), &follow(5, 40), &looped).is_ok());
+    }
+
+    #[test]
+    fn test_follow_up_alarms_wait_for_a_dismissal() {
+        let clock = suite_clock();
+        let ctx = ScheduleContext::new("install-secret".into());
+        let alarm = AlarmInput {
+            id: Some(2),
+            mode: AlarmMode::FollowUp,
+            fixed_time: None,
+            follow_up: Some(FollowUp {
+                alarm_id: 1,
+                minutes_after: 40,
+            }),
             ..Default::default()
         };
+        let now = local_now(&clock).timestamp_millis();
+        assert_eq!(calculate_next_trigger(&alarm, now, &ctx).unwrap(), None);
+        assert_eq!(
+            calculate_next_trigger_after(&alarm, now, &ctx).unwrap(),
+            None
+        );
+        let query = OccurrenceQuery::Next { count: 5 };
+        assert!(upcoming_occurrences(&alarm, now, &query, &ctx)
+            .unwrap()
+            .is_empty());
 
-        // Simulate dismissing a trigger that fired a moment ago, inside this same window.
-        let fired_at = now.timestamp_millis();
-        let after = calculate_next_trigger_after(&input, fired_at + 1_000).unwrap();
+        assert!(validate_follows_an_alarm(&alarm).is_ok());
 
-        if let Some(ts) = after {
-            let dt = DateTime::from_timestamp_millis(ts)
-                .unwrap()
-                .with_timezone(&Local);
-            // Must not resample later today's remaining window time.
-            assert!(dt.signed_duration_since(now) > chrono::Duration::days(6));
-        }
+        let missing = AlarmInput {
+            follow_up: None,
+            ..alarm
+        };
+        assert!(validate_follows_an_alarm(&missing).is_err());
+        assert!(calculate_next_trigger(&missing, now, &ctx).is_err());
     }
 }
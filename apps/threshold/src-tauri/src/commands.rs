@@ -4,7 +4,11 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::alarm::events::SyncReason;
-use crate::alarm::{AlarmCoordinator, AlarmInput, AlarmRecord};
+use crate::alarm::{
+    AlarmCoordinator, AlarmEventSource, AlarmGroup, AlarmHistoryEntry, AlarmInput, AlarmPause,
+    AlarmRecord, BedtimeReminder, HistoryQuery, Holiday, Occurrence, OccurrenceQuery, PreAlarm,
+    SnoozeAllowance, Timer, WakeReport,
+};
 use crate::SnoozeLengthState;
 use crate::TimeFormatKnownState;
 use crate::TimeFormatState;
@@ -45,6 +49,22 @@ pub async fn get_alarm<R: Runtime>(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+/// Check whether a ringing alarm may be snoozed under its snooze policy, and for how
+/// long, so the ringing screen can hide or relabel its snooze button.
+///
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: alarm identifier.
+pub async fn get_snooze_allowance(
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<SnoozeAllowance, String> {
+    coordinator
+        .get_snooze_allowance(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 /// Create or update an alarm and emit granular events.
 ///
@@ -81,6 +101,444 @@ pub async fn toggle_alarm<R: Runtime>(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+/// Replace the occurrence dates an alarm skips, keeping it enabled.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: alarm identifier.
+/// - `dates`: occurrence dates ("YYYY-MM-DD") to skip.
+pub async fn set_alarm_skip_dates<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+    dates: Vec<String>,
+) -> Result<AlarmRecord, String> {
+    coordinator
+        .set_skip_dates(&app, id, dates)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Skip an alarm's next scheduled occurrence, keeping it enabled.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: alarm identifier.
+pub async fn skip_next_alarm<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<AlarmRecord, String> {
+    coordinator
+        .skip_next_occurrence(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Record when the user went to bed, for sleep-cycle windows to count cycles from.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: alarm identifier.
+/// - `at`: epoch millis the user went to bed (e.g. a reminder's dismissal); now if omitted.
+pub async fn record_alarm_bedtime<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+    at: Option<i64>,
+) -> Result<AlarmRecord, String> {
+    coordinator
+        .record_bedtime(&app, id, at)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add, change or remove the quieter pre-alarm that rings ahead of an alarm.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: alarm identifier.
+/// - `pre_alarm`: pre-alarm settings, or null to remove it.
+pub async fn set_alarm_pre_alarm<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+    pre_alarm: Option<PreAlarm>,
+) -> Result<AlarmRecord, String> {
+    coordinator
+        .set_pre_alarm(&app, id, pre_alarm)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Preview upcoming occurrences for the timeline view, for one alarm or all of them.
+///
+/// - `coordinator`: alarm coordinator state.
+/// - `alarm_id`: alarm to preview, or omitted for every alarm.
+/// - `query`: the next N occurrences, or those dated within a range.
+pub async fn get_upcoming_occurrences(
+    coordinator: State<'_, AlarmCoordinator>,
+    alarm_id: Option<i32>,
+    query: OccurrenceQuery,
+) -> Result<Vec<Occurrence>, String> {
+    coordinator
+        .get_upcoming_occurrences(alarm_id, &query)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch fired, snoozed, dismissed and missed occurrences, newest first.
+///
+/// - `coordinator`: alarm coordinator state.
+/// - `query`: alarm and date range to fetch; omitted fields don't filter.
+pub async fn get_alarm_history(
+    coordinator: State<'_, AlarmCoordinator>,
+    query: HistoryQuery,
+) -> Result<Vec<AlarmHistoryEntry>, String> {
+    coordinator
+        .get_alarm_history(&query)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Summarise the history into wake-up statistics for the report charts.
+///
+/// - `coordinator`: alarm coordinator state.
+/// - `alarm_id`: alarm to report on, or omitted for every alarm.
+/// - `from`: earliest epoch-millisecond time to include, or omitted for no limit.
+/// - `to`: latest epoch-millisecond time to include, or omitted for no limit.
+pub async fn get_wake_report(
+    coordinator: State<'_, AlarmCoordinator>,
+    alarm_id: Option<i32>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<WakeReport, String> {
+    coordinator
+        .get_wake_report(alarm_id, from, to)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch how long past its trigger an undismissed alarm is left before it's recorded
+/// as missed and moved on.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_missed_alarm_grace(
+    coordinator: State<'_, AlarmCoordinator>,
+) -> Result<u32, String> {
+    coordinator
+        .get_missed_grace_minutes()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Set the missed-alarm grace window, applied from the next missed-alarm check.
+///
+/// - `coordinator`: alarm coordinator state.
+/// - `minutes`: grace window length in minutes.
+pub async fn set_missed_alarm_grace(
+    coordinator: State<'_, AlarmCoordinator>,
+    minutes: u32,
+) -> Result<(), String> {
+    coordinator
+        .set_missed_grace_minutes(minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch the bedtime reminder's settings.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_bedtime_reminder(
+    coordinator: State<'_, AlarmCoordinator>,
+) -> Result<BedtimeReminder, String> {
+    coordinator
+        .get_bedtime_reminder()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Change the bedtime reminder's settings, moving or cancelling the reminder to suit.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `reminder`: whether it's on, the target sleep and the wind-down, in minutes.
+pub async fn set_bedtime_reminder<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    reminder: BedtimeReminder,
+) -> Result<BedtimeReminder, String> {
+    coordinator
+        .set_bedtime_reminder(&app, reminder)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch every imported holiday for the holiday settings screen.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_holidays(
+    coordinator: State<'_, AlarmCoordinator>,
+) -> Result<Vec<Holiday>, String> {
+    coordinator.get_holidays().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Import an .ics calendar's all-day events as holidays and reschedule affected alarms.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `name`: calendar name; re-importing the same name replaces its dates.
+/// - `contents`: raw .ics text, read by the frontend.
+pub async fn import_holidays_ics<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    name: String,
+    contents: String,
+) -> Result<Vec<Holiday>, String> {
+    coordinator
+        .import_holidays_ics(&app, &name, &contents)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Import a bundled regional holiday set and reschedule affected alarms.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `region`: region code (`CA`, `GB` or `US`).
+pub async fn import_bundled_holidays<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    region: String,
+) -> Result<Vec<Holiday>, String> {
+    coordinator
+        .import_bundled_holidays(&app, &region)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Remove one holiday source and reschedule affected alarms.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `source`: holiday source to remove.
+pub async fn clear_holidays<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    source: String,
+) -> Result<Vec<Holiday>, String> {
+    coordinator
+        .clear_holidays(&app, &source)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch every timer for the timers screen.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_timers(coordinator: State<'_, AlarmCoordinator>) -> Result<Vec<Timer>, String> {
+    coordinator.get_timers().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Start a countdown timer and schedule it to ring.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `duration_seconds`: how long it runs.
+/// - `label`: optional label, e.g. "Nap".
+pub async fn start_timer<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    duration_seconds: u32,
+    label: Option<String>,
+) -> Result<Timer, String> {
+    coordinator
+        .start_timer(&app, duration_seconds, label)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Pause a running timer.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: timer identifier.
+pub async fn pause_timer<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<Timer, String> {
+    coordinator
+        .pause_timer(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Resume a paused timer.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: timer identifier.
+pub async fn resume_timer<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<Timer, String> {
+    coordinator
+        .resume_timer(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Cancel a timer, stopping it if it's ringing.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: timer identifier.
+pub async fn cancel_timer<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<(), String> {
+    coordinator
+        .cancel_timer(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Fetch every alarm group for the groups screen.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_alarm_groups(
+    coordinator: State<'_, AlarmCoordinator>,
+) -> Result<Vec<AlarmGroup>, String> {
+    coordinator.get_groups().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Create or update an alarm group.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: group to update, or `None` to create one.
+/// - `name`: group name.
+/// - `alarm_ids`: the alarms in the group.
+pub async fn save_alarm_group<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: Option<i32>,
+    name: String,
+    alarm_ids: Vec<i32>,
+) -> Result<AlarmGroup, String> {
+    coordinator
+        .save_group(&app, id, name, alarm_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Delete an alarm group, leaving its alarms as they are.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: group identifier.
+pub async fn delete_alarm_group<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<(), String> {
+    coordinator
+        .delete_group(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Switch every alarm in a group on or off in one revision.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: group identifier.
+/// - `enabled`: desired enabled state.
+pub async fn set_alarm_group_enabled<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+    enabled: bool,
+) -> Result<Vec<AlarmRecord>, String> {
+    coordinator
+        .set_group_enabled(&app, id, enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Get every alarm pause, soonest to end first.
+///
+/// - `coordinator`: alarm coordinator state.
+pub async fn get_alarm_pauses(
+    coordinator: State<'_, AlarmCoordinator>,
+) -> Result<Vec<AlarmPause>, String> {
+    coordinator.get_pauses().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Switch every alarm, or one group's, off until a date.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `group_id`: group to pause, or `None` for every alarm.
+/// - `until`: "YYYY-MM-DD" the alarms come back on.
+pub async fn pause_alarms<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    group_id: Option<i32>,
+    until: String,
+) -> Result<AlarmPause, String> {
+    coordinator
+        .pause_alarms(&app, group_id, until)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// End a pause early, switching its alarms back on.
+///
+/// - `app`: app handle for command context.
+/// - `coordinator`: alarm coordinator state.
+/// - `id`: pause identifier.
+pub async fn resume_alarm_pause<R: Runtime>(
+    app: AppHandle<R>,
+    coordinator: State<'_, AlarmCoordinator>,
+    id: i32,
+) -> Result<(), String> {
+    coordinator
+        .end_pause(&app, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 /// Delete an alarm, create a tombstone, and emit deletion events.
 ///
@@ -104,13 +562,15 @@ pub async fn delete_alarm<R: Runtime>(
 /// - `app`: app handle for command context.
 /// - `coordinator`: alarm coordinator state.
 /// - `id`: alarm identifier.
+/// - `source`: where the dismissal came from; omitted means the app's own screens.
 pub async fn dismiss_alarm<R: Runtime>(
     app: AppHandle<R>,
     coordinator: State<'_, AlarmCoordinator>,
     id: i32,
+    source: Option<AlarmEventSource>,
 ) -> Result<(), String> {
     coordinator
-        .dismiss_alarm(&app, id)
+        .dismiss_alarm(&app, id, source.unwrap_or(AlarmEventSource::PhoneUi))
         .await
         .map_err(|e| e.to_string())
 }
@@ -122,20 +582,28 @@ pub async fn dismiss_alarm<R: Runtime>(
 /// - `coordinator`: alarm coordinator state.
 /// - `id`: alarm identifier.
 /// - `snoozed_until`: absolute epoch-millisecond timestamp for the new trigger.
+/// - `source`: where the snooze came from; omitted means the app's own screens.
 pub async fn snooze_alarm<R: Runtime>(
     app: AppHandle<R>,
     coordinator: State<'_, AlarmCoordinator>,
     id: i32,
     snoozed_until: i64,
+    source: Option<AlarmEventSource>,
 ) -> Result<(), String> {
     coordinator
-        .snooze_alarm(&app, id, snoozed_until)
+        .snooze_alarm(
+            &app,
+            id,
+            snoozed_until,
+            source.unwrap_or(AlarmEventSource::PhoneUi),
+        )
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-/// Report a native alarm firing without mutating alarm state.
+/// Report a native alarm firing without mutating alarm state. Returns `false` for a
+/// backup alarm whose occurrence was attended to as it fired, which mustn't ring.
 ///
 /// - `app`: app handle for command context.
 /// - `coordinator`: alarm coordinator state.
@@ -146,7 +614,7 @@ pub async fn report_alarm_fired<R: Runtime>(
     coordinator: State<'_, AlarmCoordinator>,
     id: i32,
     actual_fired_at: i64,
-) -> Result<(), String> {
+) -> Result<bool, String> {
     coordinator
         .report_alarm_fired(&app, id, actual_fired_at)
         .await
@@ -191,6 +659,7 @@ pub async fn test_watch_ring<R: Runtime>(app: AppHandle<R>) -> Result<(), String
         label: Some("Test Watch Ring".to_string()),
         revision: 0,
         snooze_length_minutes: snooze,
+        snooze_allowed: true,
         is_24_hour: app
             .try_state::<TimeFormatState>()
             .map(|s| s.load(Ordering::Relaxed))
@@ -199,6 +668,10 @@ pub async fn test_watch_ring<R: Runtime>(app: AppHandle<R>) -> Result<(), String
             .try_state::<TimeFormatKnownState>()
             .map(|s| s.load(Ordering::Relaxed))
             .unwrap_or(false),
+        pre_alarm_for: None,
+        ring_seconds: None,
+        backup_for: None,
+        timer_for: None,
     };
     app.emit("alarm:fired", &event).map_err(|e| e.to_string())
 }
@@ -6,7 +6,7 @@
 pub mod alarm;
 pub mod commands;
 
-use alarm::{database::AlarmDatabase, AlarmCoordinator};
+use alarm::{database::AlarmDatabase, AlarmCoordinator, AlarmEventSource};
 use std::sync::atomic::{AtomicBool, AtomicI32};
 use std::sync::Arc;
 use tauri::{Listener, Manager};
@@ -117,6 +117,34 @@ pub fn run() {
         commands::get_alarm,
         commands::save_alarm,
         commands::toggle_alarm,
+        commands::set_alarm_skip_dates,
+        commands::skip_next_alarm,
+        commands::record_alarm_bedtime,
+        commands::set_alarm_pre_alarm,
+        commands::get_snooze_allowance,
+        commands::get_upcoming_occurrences,
+        commands::get_alarm_history,
+        commands::get_wake_report,
+        commands::get_missed_alarm_grace,
+        commands::set_missed_alarm_grace,
+        commands::get_bedtime_reminder,
+        commands::set_bedtime_reminder,
+        commands::get_holidays,
+        commands::import_holidays_ics,
+        commands::import_bundled_holidays,
+        commands::clear_holidays,
+        commands::get_timers,
+        commands::start_timer,
+        commands::pause_timer,
+        commands::resume_timer,
+        commands::cancel_timer,
+        commands::get_alarm_groups,
+        commands::save_alarm_group,
+        commands::delete_alarm_group,
+        commands::set_alarm_group_enabled,
+        commands::get_alarm_pauses,
+        commands::pause_alarms,
+        commands::resume_alarm_pause,
         commands::delete_alarm,
         commands::dismiss_alarm,
         commands::snooze_alarm,
@@ -408,7 +436,7 @@ pub fn run() {
                         }
 
                         if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
-                            match coord.dismiss_alarm(&handle, cmd.alarm_id).await {
+                            match coord.dismiss_alarm(&handle, cmd.alarm_id, AlarmEventSource::Watch).await {
                                 Ok(_) => log::info!("watch: dismissed alarm {}", cmd.alarm_id),
                                 Err(e) => log::error!("watch: failed to dismiss alarm {}: {e}", cmd.alarm_id),
                             }
@@ -434,10 +462,15 @@ pub fn run() {
                         }
 
                         if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
-                            // Watch snooze is always now-anchored (ringing alarm)
+                            // Watch snooze is always now-anchored (ringing alarm). The
+                            // coordinator applies the alarm's snooze policy, whose interval
+                            // replaces the watch's length and which may refuse the snooze.
                             let snoozed_until = chrono::Utc::now().timestamp_millis()
                                 + cmd.snooze_length_minutes * 60 * 1000;
-                            match coord.snooze_alarm(&handle, cmd.alarm_id, snoozed_until).await {
+                            match coord
+                                .snooze_alarm(&handle, cmd.alarm_id, snoozed_until, AlarmEventSource::Watch)
+                                .await
+                            {
                                 Ok(_) => log::info!("watch: snoozed alarm {} for {} min", cmd.alarm_id, cmd.snooze_length_minutes),
                                 Err(e) => log::error!("watch: failed to snooze alarm {}: {e}", cmd.alarm_id),
                             }
@@ -462,20 +495,75 @@ pub fn run() {
                     let handle = native_alarm_handle.clone();
                     tauri::async_runtime::spawn(async move {
                         if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
-                            if let Err(error) = coord
+                            match coord
                                 .report_alarm_fired(&handle, payload.id, payload.actual_fired_at)
                                 .await
                             {
-                                log::error!(
+                                Ok(true) => {}
+                                // A backup alarm that fired as its occurrence was dismissed
+                                // or snoozed: stop whatever it started ringing.
+                                Ok(false) => {
+                                    #[cfg(mobile)]
+                                    if let Err(e) = handle.alarm_manager().stop_ringing() {
+                                        log::error!("alarm-manager: failed to stop stale backup alarm: {e}");
+                                    }
+                                }
+                                Err(error) => log::error!(
                                     "alarm-manager: failed to report native alarm fired for {}: {error}",
                                     payload.id
-                                );
+                                ),
                             }
                         }
                     });
                 }
             });
 
+            // Ring timeout. Every alarm:fired, native or reported by the frontend, is watched
+            // over by the coordinator, which escalates, then snoozes or dismisses, a ring
+            // nobody attends to. Pre-alarms stop on their own ring timer instead, and a
+            // backup alarm rings within the ring its alarm's timeout is already watching. A
+            // timer rings until someone stops it.
+            let ring_timeout_handle = app.handle().clone();
+            app.handle().listen("alarm:fired", move |event| {
+                #[derive(serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct RingStarted {
+                    id: i32,
+                    #[serde(default)]
+                    pre_alarm_for: Option<i32>,
+                    #[serde(default)]
+                    backup_for: Option<i32>,
+                    #[serde(default)]
+                    timer_for: Option<i32>,
+                }
+
+                let Ok(fired) = serde_json::from_str::<RingStarted>(event.payload()) else {
+                    return;
+                };
+                if fired.pre_alarm_for.is_some()
+                    || fired.backup_for.is_some()
+                    || fired.timer_for.is_some()
+                {
+                    return;
+                }
+                let handle = ring_timeout_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
+                        match coord.supervise_ring(&handle, fired.id).await {
+                            Ok(Some(action)) => {
+                                #[cfg(mobile)]
+                                if let Err(e) = handle.alarm_manager().stop_ringing() {
+                                    log::error!("ring timeout: failed to stop phone ringing: {e}");
+                                }
+                                log::info!("ring timeout: alarm {} unattended, {action:?}", fired.id);
+                            }
+                            Ok(None) => {}
+                            Err(e) => log::error!("ring timeout: failed for alarm {}: {e}", fired.id),
+                        }
+                    }
+                });
+            });
+
             // Native phone alarm-dismissed callback from alarm-manager plugin (notification
             // Dismiss action on AlarmRingingService). Handled directly in Rust core, same
             // as alarm-manager:native-fired and the wear:alarm:* listeners above — no TS
@@ -491,7 +579,10 @@ pub fn run() {
                     let handle = native_dismiss_handle.clone();
                     tauri::async_runtime::spawn(async move {
                         if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
-                            if let Err(error) = coord.dismiss_alarm(&handle, payload.id).await {
+                            if let Err(error) = coord
+                                .dismiss_alarm(&handle, payload.id, AlarmEventSource::Notification)
+                                .await
+                            {
                                 log::error!(
                                     "alarm-manager: failed to dismiss native-requested alarm {}: {error}",
                                     payload.id
@@ -520,10 +611,15 @@ pub fn run() {
                             .try_state::<SnoozeLengthState>()
                             .map(|s| s.load(std::sync::atomic::Ordering::Relaxed))
                             .unwrap_or(10) as i64;
+                        // The alarm's snooze policy, applied by the coordinator, overrides
+                        // this length and may refuse the snooze altogether.
                         let snoozed_until = chrono::Utc::now().timestamp_millis() + minutes * 60 * 1000;
 
                         if let Some(coord) = handle.try_state::<AlarmCoordinator>() {
-                            match coord.snooze_alarm(&handle, payload.id, snoozed_until).await {
+                            match coord
+                                .snooze_alarm(&handle, payload.id, snoozed_until, AlarmEventSource::Notification)
+                                .await
+                            {
                                 Ok(_) => log::info!(
                                     "alarm-manager: snoozed native-requested alarm {} for {} min",
                                     payload.id,
@@ -578,9 +674,10 @@ pub fn run() {
                             );
                         }
 
-                        // A stale import means the one-shot occurrence already passed.
-                        // Re-importing it now would silently turn a single alarm the user
-                        // never asked to keep into an ongoing recurring one, so discard it.
+                        // A stale import means the requested occurrence already passed.
+                        // For a one-time request there is nothing left to fire, and for a
+                        // recurring one the user can't have seen the alarm they asked for
+                        // take effect, so discard it either way.
                         if payload.trigger_at > 0
                             && chrono::Utc::now().timestamp_millis() >= payload.trigger_at
                         {
@@ -607,6 +704,29 @@ pub fn run() {
 
                         let time_str = format!("{:02}:{:02}", payload.hour, payload.minute);
 
+                        // No requested days means "one-time" per the SET_ALARM contract,
+                        // which maps exactly onto a FixedOnce alarm dated to the occurrence
+                        // Kotlin resolved (in device-local time, same as the scheduler).
+                        let (mode, one_shot_date) = if payload.active_days.is_empty() {
+                            use chrono::TimeZone;
+                            let Some(occurrence) =
+                                chrono::Local.timestamp_millis_opt(payload.trigger_at).single()
+                            else {
+                                log::error!(
+                                    "alarm-manager: import {} has no valid one-time occurrence ({})",
+                                    payload.id,
+                                    payload.trigger_at
+                                );
+                                return;
+                            };
+                            (
+                                alarm::AlarmMode::FixedOnce,
+                                Some(occurrence.format("%Y-%m-%d").to_string()),
+                            )
+                        } else {
+                            (alarm::AlarmMode::Fixed, None)
+                        };
+
                         let known = match coord.get_all_alarms(&handle).await {
                             Ok(alarms) => alarms,
                             Err(error) => {
@@ -619,9 +739,10 @@ pub fn run() {
                         };
 
                         let duplicate = known.iter().any(|a| {
-                            a.mode == alarm::AlarmMode::Fixed
+                            a.mode == mode
                                 && a.fixed_time.as_deref() == Some(time_str.as_str())
                                 && a.label.as_deref() == Some(payload.label.as_str())
+                                && a.one_shot_date == one_shot_date
                         });
 
                         if duplicate {
@@ -629,29 +750,19 @@ pub fn run() {
                             return;
                         }
 
-                        let active_days = if !payload.active_days.is_empty() {
-                            payload.active_days.clone()
-                        } else {
-                            // No requested days means "one-time" per the SET_ALARM
-                            // contract; Threshold has no true one-shot concept, so fall
-                            // back to the resolved occurrence's weekday.
-                            use chrono::Datelike;
-                            chrono::DateTime::from_timestamp_millis(payload.trigger_at)
-                                .map(|dt| vec![dt.weekday().num_days_from_sunday() as i32])
-                                .unwrap_or_else(|| vec![0])
-                        };
-
                         let input = alarm::AlarmInput {
                             id: None,
                             label: Some(payload.label.clone()),
                             enabled: true,
-                            mode: alarm::AlarmMode::Fixed,
+                            mode,
                             fixed_time: Some(time_str),
                             window_start: None,
                             window_end: None,
-                            active_days,
+                            active_days: payload.active_days.clone(),
+                            one_shot_date,
                             sound_uri: None,
                             sound_title: None,
+                            ..Default::default()
                         };
 
                         match coord.save_alarm(&handle, input).await {
@@ -688,6 +799,26 @@ pub fn run() {
                 }
             });
 
+            // Pauses end at local midnight and occurrences run out of grace at any
+            // time, so look for both every few minutes
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(
+                    tokio::time::Duration::from_secs(300) // 5 minutes
+                );
+                loop {
+                    interval.tick().await;
+                    if let Some(coord) = app_handle.try_state::<AlarmCoordinator>() {
+                        if let Err(e) = coord.detect_missed(&app_handle).await {
+                            log::error!("Failed to detect missed alarms: {e}");
+                        }
+                        if let Err(e) = coord.end_due_pauses(&app_handle).await {
+                            log::error!("Failed to end due pauses: {e}");
+                        }
+                    }
+                }
+            });
+
             Ok(())
         })
         .run(tauri::generate_context!())
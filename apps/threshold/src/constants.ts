@@ -23,3 +23,18 @@ export const SPECIAL_ALARM_IDS = {
 	/** Test alarm that navigates back instead of minimizing */
 	TEST_ALARM: 999,
 } as const;
+
+/**
+ * Pre-alarms ring under their alarm's id plus this offset (mirrors the backend's PRE_ALARM_ID_OFFSET)
+ */
+export const PRE_ALARM_ID_OFFSET = 2_000_000;
+
+/**
+ * Backup alarms ring under their alarm's id plus this offset (mirrors the backend's BACKUP_ALARM_ID_OFFSET)
+ */
+export const BACKUP_ALARM_ID_OFFSET = 3_000_000;
+
+/**
+ * Timers ring under their own id plus this offset, above every backup alarm's id (mirrors the backend's TIMER_ID_OFFSET)
+ */
+export const TIMER_ID_OFFSET = 4_000_000;
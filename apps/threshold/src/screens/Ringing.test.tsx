@@ -14,9 +14,15 @@ import Ringing from './Ringing';
 expect.extend(matchers);
 import { alarmManagerService } from '../services/AlarmManagerService';
 import { AlarmService } from '../services/AlarmService';
+import { SettingsService } from '../services/SettingsService';
 import { appManagementService } from '../services/AppManagementService';
 import { PlatformUtils } from '../utils/PlatformUtils';
-import { SPECIAL_ALARM_IDS, ROUTES } from '../constants';
+import {
+	BACKUP_ALARM_ID_OFFSET,
+	PRE_ALARM_ID_OFFSET,
+	SPECIAL_ALARM_IDS,
+	ROUTES,
+} from '../constants';
 import * as tauriWindow from '@tauri-apps/api/window';
 
 // --- Mocks ---
@@ -35,6 +41,7 @@ vi.mock('../services/AlarmService', () => ({
 	AlarmService: {
 		get: vi.fn(),
 		dismiss: vi.fn(),
+		getSnoozeAllowance: vi.fn(),
 	},
 }));
 
@@ -138,6 +145,14 @@ describe('Ringing Screen Logic', () => {
 			soundTitle: null,
 		});
 
+		(AlarmService.getSnoozeAllowance as any).mockResolvedValue({
+			allowed: true,
+			minutes: null,
+			reason: null,
+		});
+
+		(SettingsService.getSilenceAfter as any).mockReturnValue(20);
+
 		// Setup Platform Default (Desktop)
 		(PlatformUtils.isDesktop as any).mockReturnValue(true);
 		(PlatformUtils.isMobile as any).mockReturnValue(false);
@@ -248,6 +263,42 @@ describe('Ringing Screen Logic', () => {
 		expect(appManagementService.minimizeApp).not.toHaveBeenCalled();
 	});
 
+	it('should snooze for the length the snooze policy sets', async () => {
+		// Arrange
+		(AlarmService.getSnoozeAllowance as any).mockResolvedValue({
+			allowed: true,
+			minutes: 5,
+			reason: null,
+		});
+
+		// Act
+		renderWithTheme(<Ringing />);
+
+		const snoozeBtn = await screen.findByRole('button', { name: /snooze \(5m\)/i });
+		fireEvent.click(snoozeBtn);
+
+		// Assert
+		await waitFor(() => {
+			expect(alarmManagerService.snoozeRinging).toHaveBeenCalledWith(1, 5);
+		});
+	});
+
+	it('should replace the snooze button when the snooze policy refuses one', async () => {
+		// Arrange
+		(AlarmService.getSnoozeAllowance as any).mockResolvedValue({
+			allowed: false,
+			minutes: null,
+			reason: 'No snoozing after 08:00',
+		});
+
+		// Act
+		renderWithTheme(<Ringing />);
+
+		// Assert
+		expect(await screen.findByText('No snoozing after 08:00')).toBeInTheDocument();
+		expect(screen.queryByRole('button', { name: /snooze/i })).not.toBeInTheDocument();
+	});
+
 	it('should snooze and minimise window on mobile', async () => {
 		// Arrange
 		(PlatformUtils.isDesktop as any).mockReturnValue(false);
@@ -270,4 +321,119 @@ describe('Ringing Screen Logic', () => {
 			expect(mockNavigate).toHaveBeenCalledWith({ to: ROUTES.HOME, replace: true });
 		});
 	});
+
+	it('should stop a pre-alarm after its ring time without dismissing', async () => {
+		// Arrange
+		const router = await import('@tanstack/react-router');
+		(router.useParams as any).mockReturnValue({ id: String(PRE_ALARM_ID_OFFSET + 1) });
+		(AlarmService.get as any).mockResolvedValue({
+			id: 1,
+			label: 'Morning Alarm',
+			enabled: true,
+			mode: 'FIXED',
+			fixedTime: '08:00',
+			windowStart: null,
+			windowEnd: null,
+			activeDays: [],
+			nextTrigger: null,
+			soundUri: '',
+			soundTitle: null,
+			preAlarm: {
+				minutesBefore: 10,
+				soundUri: null,
+				soundTitle: null,
+				volumePercent: 30,
+				ringSeconds: 0.05,
+				skipMainOnDismiss: false,
+			},
+		});
+
+		// Act
+		renderWithTheme(<Ringing />);
+
+		// Assert
+		await waitFor(() => {
+			expect(alarmManagerService.stopRinging).toHaveBeenCalled();
+			expect(mockWindow.close).toHaveBeenCalled();
+		});
+		expect(AlarmService.dismiss).not.toHaveBeenCalled();
+	});
+
+	it('should keep a backup alarm ringing rather than treat it as a pre-alarm', async () => {
+		// Arrange
+		const router = await import('@tanstack/react-router');
+		(router.useParams as any).mockReturnValue({ id: String(BACKUP_ALARM_ID_OFFSET + 1) });
+		(AlarmService.get as any).mockResolvedValue({
+			id: 1,
+			label: 'Morning Alarm',
+			enabled: true,
+			mode: 'FIXED',
+			fixedTime: '08:00',
+			windowStart: null,
+			windowEnd: null,
+			activeDays: [],
+			nextTrigger: null,
+			soundUri: '',
+			soundTitle: null,
+			preAlarm: {
+				minutesBefore: 10,
+				soundUri: null,
+				soundTitle: null,
+				volumePercent: 30,
+				ringSeconds: 0.05,
+				skipMainOnDismiss: false,
+			},
+			backupAlarm: { minutesAfter: 5, soundUri: null, soundTitle: null, watchOnly: false },
+		});
+
+		// Act
+		renderWithTheme(<Ringing />);
+		expect(await screen.findByText('Morning Alarm')).toBeInTheDocument();
+		await new Promise((resolve) => setTimeout(resolve, 80));
+
+		// Assert
+		expect(alarmManagerService.stopRinging).not.toHaveBeenCalled();
+		expect(mockWindow.close).not.toHaveBeenCalled();
+	});
+
+	it('should dismiss as unattended once the silence timer runs out', async () => {
+		// Arrange: 3ms
+		(SettingsService.getSilenceAfter as any).mockReturnValue(0.00005);
+
+		// Act
+		renderWithTheme(<Ringing />);
+
+		// Assert
+		await waitFor(() => {
+			expect(AlarmService.dismiss).toHaveBeenCalledWith(1, 'UNATTENDED');
+			expect(mockWindow.close).toHaveBeenCalled();
+		});
+	});
+
+	it('should leave an alarm with its own ring timeout to the backend', async () => {
+		// Arrange: 30ms, outlasting the alarm's load
+		(SettingsService.getSilenceAfter as any).mockReturnValue(0.0005);
+		(AlarmService.get as any).mockResolvedValue({
+			id: 1,
+			label: 'Morning Alarm',
+			enabled: true,
+			mode: 'FIXED',
+			fixedTime: '08:00',
+			windowStart: null,
+			windowEnd: null,
+			activeDays: [],
+			nextTrigger: null,
+			soundUri: '',
+			soundTitle: null,
+			ringTimeout: { minutes: 10, action: 'SNOOZE', escalateToWatch: false },
+		});
+
+		// Act
+		renderWithTheme(<Ringing />);
+		expect(await screen.findByText('Morning Alarm')).toBeInTheDocument();
+		await new Promise((resolve) => setTimeout(resolve, 80));
+
+		// Assert
+		expect(AlarmService.dismiss).not.toHaveBeenCalled();
+	});
 });
@@ -15,8 +15,14 @@ import { listen } from '@tauri-apps/api/event';
 import '../theme/ringing.css';
 import { TimeFormatHelper } from '../utils/TimeFormatHelper';
 import { AlarmService } from '../services/AlarmService';
-import { AlarmRecord } from '../types/alarm';
-import { ROUTES, SPECIAL_ALARM_IDS } from '../constants';
+import { AlarmEventSource, AlarmRecord, SnoozeAllowance } from '../types/alarm';
+import {
+	BACKUP_ALARM_ID_OFFSET,
+	PRE_ALARM_ID_OFFSET,
+	ROUTES,
+	SPECIAL_ALARM_IDS,
+	TIMER_ID_OFFSET,
+} from '../constants';
 import { SettingsService } from '../services/SettingsService';
 import { appManagementService } from '../services/AppManagementService';
 import ThresholdIndicator from './ThresholdIndicator';
@@ -25,6 +31,18 @@ const Ringing: React.FC = () => {
 	const { id } = useParams({ from: '/ringing/$id' });
 	const alarmId = Number.parseInt(id, 10);
 	const [alarm, setAlarm] = useState<AlarmRecord | null>(null);
+	// The alarm's snooze policy can shorten the snooze or refuse it altogether
+	const [snoozeAllowance, setSnoozeAllowance] = useState<SnoozeAllowance | null>(null);
+	// A pre-alarm rings under an offset id with its own quieter settings
+	const preAlarm =
+		alarmId > PRE_ALARM_ID_OFFSET && alarmId < BACKUP_ALARM_ID_OFFSET
+			? (alarm?.preAlarm ?? null)
+			: null;
+	// So does a backup alarm, with its own sound
+	const backupAlarm =
+		alarmId > BACKUP_ALARM_ID_OFFSET && alarmId < TIMER_ID_OFFSET
+			? (alarm?.backupAlarm ?? null)
+			: null;
 	const [timeStr, setTimeStr] = useState<string>('');
 	const navigate = useNavigate();
 	const isClosingRef = useRef(false);
@@ -90,6 +108,11 @@ const Ringing: React.FC = () => {
 			} catch (e) {
 				console.error('Failed to load ringing alarm', e);
 			}
+			try {
+				setSnoozeAllowance((await AlarmService.getSnoozeAllowance(alarmId)) ?? null);
+			} catch (e) {
+				console.error('Failed to load snooze allowance', e);
+			}
 		};
 		loadAlarm();
 
@@ -173,34 +196,43 @@ const Ringing: React.FC = () => {
 		};
 	}, [alarmId, closeRingingWindow]);
 
-	const handleDismiss = useCallback(async () => {
-		console.log('[Ringing] Dismissing Alarm', alarmId);
-		await alarmManagerService.stopRinging();
+	const dismissAlarm = useCallback(
+		async (source?: AlarmEventSource) => {
+			console.log('[Ringing] Dismissing Alarm', alarmId);
+			await alarmManagerService.stopRinging();
 
-		// Notify backend to dismiss (reschedule)
-		try {
-			await AlarmService.dismiss(alarmId);
-		} catch (e) {
-			console.error('Failed to dismiss alarm in backend', e);
-		}
+			// Notify backend to dismiss (reschedule)
+			try {
+				await AlarmService.dismiss(alarmId, source);
+			} catch (e) {
+				console.error('Failed to dismiss alarm in backend', e);
+			}
 
-		await closeRingingWindow();
-	}, [alarmId, closeRingingWindow]);
+			await closeRingingWindow();
+		},
+		[alarmId, closeRingingWindow],
+	);
+	const handleDismiss = useCallback(() => dismissAlarm(), [dismissAlarm]);
 
+	const snoozeMinutes = snoozeAllowance?.minutes ?? snoozeLength;
 	const handleSnooze = async () => {
-		console.log('Snoozing Alarm', alarmId, 'for', snoozeLength, 'minutes');
-		await alarmManagerService.snoozeRinging(alarmId, snoozeLength);
+		console.log('Snoozing Alarm', alarmId, 'for', snoozeMinutes, 'minutes');
+		await alarmManagerService.snoozeRinging(alarmId, snoozeMinutes);
 		await closeRingingWindow();
 	};
 
 	// Silence After Timer
+	const ringTimeout = alarm?.ringTimeout ?? null;
 	useEffect(() => {
+		// Pre-alarms stop on their own ring timer below, without dismissing, and an
+		// alarm's own ring timeout is run by the backend instead
+		if (preAlarm || ringTimeout) return;
 		if (silenceAfter > 0) {
 			console.log(`Setting silence timer for ${silenceAfter} minutes`);
 			const timer = setTimeout(
 				() => {
 					console.log(`Silence limit reached (${silenceAfter}m). Dismissing alarm.`);
-					handleDismiss();
+					dismissAlarm('UNATTENDED');
 				},
 				silenceAfter * 60 * 1000,
 			);
@@ -208,7 +240,18 @@ const Ringing: React.FC = () => {
 		} else {
 			console.log('Silence timer disabled (Never or 0)');
 		}
-	}, [silenceAfter, handleDismiss]);
+	}, [silenceAfter, dismissAlarm, preAlarm, ringTimeout]);
+
+	// Pre-alarm Ring Timer: stop quietly and leave the alarm itself scheduled
+	useEffect(() => {
+		if (!preAlarm) return;
+		const timer = setTimeout(async () => {
+			console.log(`[Ringing] Pre-alarm rang for ${preAlarm.ringSeconds}s. Stopping.`);
+			await alarmManagerService.stopRinging();
+			await closeRingingWindow();
+		}, preAlarm.ringSeconds * 1000);
+		return () => clearTimeout(timer);
+	}, [preAlarm, closeRingingWindow]);
 
 	// Audio playback logic for desktop
 	useEffect(() => {
@@ -216,7 +259,9 @@ const Ringing: React.FC = () => {
 			return;
 		}
 
-		console.log('[Ringing] Audio Effect Triggered. soundUri:', alarm.soundUri);
+		const soundUri = preAlarm ? preAlarm.soundUri : (backupAlarm?.soundUri ?? alarm.soundUri);
+		const volume = preAlarm ? preAlarm.volumePercent / 100 : 1;
+		console.log('[Ringing] Audio Effect Triggered. soundUri:', soundUri);
 		let audio: HTMLAudioElement | null = null;
 		let synthInterval: any = null;
 		let isCleanedUp = false;
@@ -236,7 +281,7 @@ const Ringing: React.FC = () => {
 				osc.frequency.setValueAtTime(880, audioCtx.currentTime); // A5
 
 				// Increased volume for synth fallback
-				gain.gain.setValueAtTime(0.5, audioCtx.currentTime);
+				gain.gain.setValueAtTime(0.5 * volume, audioCtx.currentTime);
 				gain.gain.exponentialRampToValueAtTime(0.0001, audioCtx.currentTime + 0.5);
 
 				osc.connect(gain);
@@ -251,23 +296,23 @@ const Ringing: React.FC = () => {
 		};
 
 		const startAudio = async () => {
-			if (!alarm.soundUri) {
+			if (!soundUri) {
 				startSynthFallback('No soundUri');
 				return;
 			}
 
 			try {
-				let assetUrl = alarm.soundUri;
+				let assetUrl = soundUri;
 
 				// Better heuristic: if it looks like an absolute path and isn't a likely web-root relative path
-				const isAbsolutePath = alarm.soundUri.startsWith('/') || alarm.soundUri.includes(':\\');
+				const isAbsolutePath = soundUri.startsWith('/') || soundUri.includes(':\\');
 				const isWebRootPath =
-					alarm.soundUri.startsWith('/alarms/') || alarm.soundUri.startsWith('/static/');
+					soundUri.startsWith('/alarms/') || soundUri.startsWith('/static/');
 
 				// IF the file is actually inside our public folder, use the relative path instead of asset protocol
 				// This is much safer and avoids "URL can't be shown" security errors
-				if (isAbsolutePath && alarm.soundUri.includes('/public/alarms/')) {
-					const fileName = alarm.soundUri.split('/public/alarms/').pop();
+				if (isAbsolutePath && soundUri.includes('/public/alarms/')) {
+					const fileName = soundUri.split('/public/alarms/').pop();
 					assetUrl = `/alarms/${fileName}`;
 					console.log(
 						'[Ringing] Detected bundled asset from absolute path. Using relative URL:',
@@ -275,7 +320,7 @@ const Ringing: React.FC = () => {
 					);
 				} else if (isAbsolutePath && !isWebRootPath) {
 					// Truly external file
-					assetUrl = convertFileSrc(alarm.soundUri);
+					assetUrl = convertFileSrc(soundUri);
 					console.log('[Ringing] External file detected. Using asset protocol:', assetUrl);
 				}
 
@@ -283,6 +328,7 @@ const Ringing: React.FC = () => {
 
 				audio = new Audio(assetUrl);
 				audio.loop = true;
+				audio.volume = volume;
 
 				audio.addEventListener('error', (e: any) => {
 					if (isCleanedUp) return;
@@ -337,7 +383,7 @@ const Ringing: React.FC = () => {
 				clearInterval(synthInterval);
 			}
 		};
-	}, [alarm]);
+	}, [alarm, preAlarm, backupAlarm]);
 
 	// Global click listener to "unlock" audio if it was blocked
 	useEffect(() => {
@@ -416,14 +462,18 @@ const Ringing: React.FC = () => {
 						Stop Alarm
 					</Button>
 
-					<Button
-						variant="outlined"
-						fullWidth
-						className="ringing-btn-snooze"
-						onClick={handleSnooze}
-					>
-						Snooze ({snoozeLength}m)
-					</Button>
+					{snoozeAllowance?.allowed === false ? (
+						<Typography className="ringing-snooze-note">{snoozeAllowance.reason}</Typography>
+					) : (
+						<Button
+							variant="outlined"
+							fullWidth
+							className="ringing-btn-snooze"
+							onClick={handleSnooze}
+						>
+							Snooze ({snoozeMinutes}m)
+						</Button>
+					)}
 
 					{audioError && !isAudioUnlocked && (
 						<Button
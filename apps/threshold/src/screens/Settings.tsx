@@ -84,6 +84,7 @@ const Settings: React.FC = () => {
 	// New Settings State
 	const [silenceAfter, setSilenceAfter] = useState<number>(SettingsService.getSilenceAfter());
 	const [snoozeLength, setSnoozeLength] = useState<number>(SettingsService.getSnoozeLength());
+	const [missedGrace, setMissedGrace] = useState<number | null>(null);
 	const [snoozeDialogOpen, setSnoozeDialogOpen] = useState(false);
 	const [isExportingLogs, setIsExportingLogs] = useState(false);
 	const [permissionStatus, setPermissionStatus] = useState<Record<PermissionKey, boolean | null>>({
@@ -98,6 +99,14 @@ const Settings: React.FC = () => {
 		setIsAndroid(PlatformUtils.getPlatform() === 'android');
 	}, []);
 
+	// Kept in the Rust database rather than SettingsService, since it's read at launch
+	// before the webview is up.
+	useEffect(() => {
+		AlarmService.getMissedAlarmGrace()
+			.then(setMissedGrace)
+			.catch((e) => console.error('Failed to load missed alarm grace:', e));
+	}, []);
+
 	// Re-checked on every window focus regain (not just on mount) so both the Alarm Settings
 	// banner and the Developer settings diagnostic clear themselves after the user flips a
 	// toggle in system Settings and switches back, without needing to leave and re-enter this
@@ -309,6 +318,34 @@ const Settings: React.FC = () => {
 				</FormControl>
 			</ListItem>
 
+			{missedGrace !== null && (
+				<ListItem sx={{ px }}>
+					<FormControl fullWidth>
+						<InputLabel id="missed-grace-label">Count As Missed After</InputLabel>
+						<Select
+							labelId="missed-grace-label"
+							value={missedGrace}
+							label="Count As Missed After"
+							onChange={async (e) => {
+								const val = Number(e.target.value);
+								try {
+									await AlarmService.setMissedAlarmGrace(val);
+									setMissedGrace(val);
+								} catch (err) {
+									console.error('Failed to save missed alarm grace:', err);
+								}
+							}}
+						>
+							<MenuItem value={5}>5 minutes</MenuItem>
+							<MenuItem value={15}>15 minutes</MenuItem>
+							<MenuItem value={30}>30 minutes</MenuItem>
+							<MenuItem value={60}>1 hour</MenuItem>
+							<MenuItem value={120}>2 hours</MenuItem>
+						</Select>
+					</FormControl>
+				</ListItem>
+			)}
+
 			<ListItemButton onClick={() => setSnoozeDialogOpen(true)} sx={{ px }}>
 				<ListItemText
 					primary="Snooze Length"
@@ -85,7 +85,7 @@ describe('AlarmManagerService', () => {
 		});
 		(AlarmService.snooze as any).mockResolvedValue(undefined);
 		(AlarmService.dismiss as any).mockResolvedValue(undefined);
-		(AlarmService.reportFired as any).mockResolvedValue(undefined);
+		(AlarmService.reportFired as any).mockResolvedValue(true);
 		(AlarmService.toggle as any).mockResolvedValue(undefined);
 		(AlarmService.save as any).mockResolvedValue({ id: 99 });
 		(AlarmService.delete as any).mockResolvedValue(undefined);
@@ -258,7 +258,7 @@ describe('AlarmManagerService', () => {
 			},
 		});
 
-		expect(AlarmService.dismiss).toHaveBeenCalledWith(9);
+		expect(AlarmService.dismiss).toHaveBeenCalledWith(9, 'NOTIFICATION');
 	});
 
 	it('dismisses upcoming actions when Android bridge payload is wrapped', async () => {
@@ -288,7 +288,7 @@ describe('AlarmManagerService', () => {
 			},
 		]);
 
-		expect(AlarmService.dismiss).toHaveBeenCalledWith(9);
+		expect(AlarmService.dismiss).toHaveBeenCalledWith(9, 'NOTIFICATION');
 	});
 
 	it('snoozes upcoming actions without stopping active ringing', async () => {
@@ -317,9 +317,10 @@ describe('AlarmManagerService', () => {
 		});
 
 		// Upcoming snooze anchors to nextTrigger + snoozeLength
-		const [calledId, calledTimestamp] = (AlarmService.snooze as any).mock.calls[0];
+		const [calledId, calledTimestamp, calledSource] = (AlarmService.snooze as any).mock.calls[0];
 		expect(calledId).toBe(11);
 		expect(calledTimestamp).toBe(nextTrigger + 10 * 60_000);
+		expect(calledSource).toBe('NOTIFICATION');
 		expect(invoke).not.toHaveBeenCalledWith('plugin:alarm-manager|stop_ringing');
 		// Toast confirmation is a separate reaction to the alarm:snoozed event
 		// (Rust emits it after AlarmService.snooze succeeds) — see the dedicated
@@ -346,6 +347,52 @@ describe('AlarmManagerService', () => {
 		expect(AlarmService.reportFired).toHaveBeenCalledWith(23, expect.any(Number));
 	});
 
+	it('rings once the backend reports the fire', async () => {
+		const service = new AlarmManagerService();
+		const router = { navigate: vi.fn() };
+		(PlatformUtils.isMobile as any).mockReturnValue(true);
+		service.setRouter(router);
+
+		await (service as any).handleAlarmRing(3_000_011);
+
+		expect(sendNotification).toHaveBeenCalledWith(
+			expect.objectContaining({ body: 'Your alarm is ringing!' }),
+		);
+		expect(router.navigate).toHaveBeenCalledWith({
+			to: '/ringing/$id',
+			params: { id: '3000011' },
+		});
+	});
+
+	it('does not ring a backup alarm the backend stood down as it fired', async () => {
+		const service = new AlarmManagerService();
+		const router = { navigate: vi.fn() };
+		(PlatformUtils.isMobile as any).mockReturnValue(true);
+		(AlarmService.reportFired as any).mockResolvedValue(false);
+		service.setRouter(router);
+
+		await (service as any).handleAlarmRing(3_000_011);
+
+		expect(AlarmService.reportFired).toHaveBeenCalledWith(3_000_011, expect.any(Number));
+		expect(sendNotification).not.toHaveBeenCalled();
+		expect(router.navigate).not.toHaveBeenCalled();
+	});
+
+	it('posts the bedtime reminder on alarm-notify without ringing', async () => {
+		const service = new AlarmManagerService();
+
+		await service.init();
+		(sendNotification as any).mockClear();
+		await emit('alarm-notify', { id: 5_000_000 });
+
+		expect(sendNotification).toHaveBeenCalledWith({
+			id: 5_000_000,
+			title: 'Time to wind down',
+			body: 'Head to bed soon to get your sleep before your next alarm',
+		});
+		expect(AlarmService.reportFired).not.toHaveBeenCalled();
+	});
+
 	it('registers dynamic snooze labels and refreshes them when snooze length changes', async () => {
 		const service = new AlarmManagerService();
 		(PlatformUtils.isMobile as any).mockReturnValue(true);
@@ -467,6 +514,37 @@ describe('AlarmManagerService', () => {
 		expect(showToast).toHaveBeenCalled();
 	});
 
+	it('publishes a toast when alarm:dst:adjusted warns about a skipped time', async () => {
+		const service = new AlarmManagerService();
+		(PlatformUtils.isMobile as any).mockReturnValue(true);
+		(PlatformUtils.getPlatform as any).mockReturnValue('android');
+
+		await service.init();
+
+		const dstHandlers = eventListeners.get('alarm:dst:adjusted') ?? [];
+		expect(dstHandlers.length).toBe(1);
+
+		for (const handler of dstHandlers) {
+			await handler({
+				payload: {
+					id: 4,
+					label: 'Gym',
+					date: '2030-03-10',
+					localTime: '02:30',
+					transition: 'GAP',
+					policy: { gap: 'SKIP', overlap: 'FIRE_AT_FIRST' },
+					nextTrigger: null,
+				},
+			});
+		}
+
+		expect(showToast).toHaveBeenCalledWith(
+			expect.objectContaining({
+				message: `"Gym" won't go off on 2030-03-10: the clocks skip 02:30`,
+			}),
+		);
+	});
+
 	it('deleteAlarm only calls AlarmService.delete and relies on alarm:cancelled listener', async () => {
 		const service = new AlarmManagerService();
 		await service.deleteAlarm(99);
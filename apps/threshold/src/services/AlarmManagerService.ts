@@ -12,7 +12,7 @@ import { stopRinging as stopRingingNative } from 'tauri-plugin-alarm-manager-api
 import { PlatformUtils } from '../utils/PlatformUtils';
 import { sendNotification } from '@tauri-apps/plugin-notification';
 import { Alarm } from '@threshold/core/types';
-import { AlarmInput, AlarmRecord } from '../types/alarm';
+import { AlarmInput, AlarmRecord, DstPolicy } from '../types/alarm';
 import { AlarmService } from './AlarmService';
 import { SettingsService } from './SettingsService';
 import { TimeFormatHelper } from '../utils/TimeFormatHelper';
@@ -25,6 +25,16 @@ import { notificationToastService } from './NotificationToastService';
 
 type NotificationUpcomingResyncPayload = NotificationUpcomingResyncEvent | null | undefined;
 
+type AlarmDstAdjustedPayload = {
+	id: number;
+	label: string | null;
+	date: string;
+	localTime: string;
+	transition: 'GAP' | 'OVERLAP';
+	policy: DstPolicy;
+	nextTrigger: number | null;
+};
+
 export class AlarmManagerService {
 	private initPromise: Promise<void> | null = null;
 	private router: any = null;
@@ -105,15 +115,24 @@ export class AlarmManagerService {
 
 				await notificationToastService.init();
 
-				console.log('[AlarmManager] Setting up event listener 1/6: alarm-ring...');
+				console.log('[AlarmManager] Setting up event listener 1/8: alarm-ring...');
 				// Listen for alarms ringing from the Rust Backend (Desktop)
 				await listen<{ id: number }>('alarm-ring', (event) => {
 					console.log(`[AlarmManager] Received alarm-ring event for ID: ${event.payload.id}`);
 					this.handleAlarmRing(event.payload.id);
 				});
-				console.log('[AlarmManager] Event listener 1/6 registered.');
+				console.log('[AlarmManager] Event listener 1/8 registered.');
+
+				console.log('[AlarmManager] Setting up event listener 2/8: alarm-notify...');
+				// The bedtime reminder comes due here on desktop (Android posts it natively);
+				// it only notifies, and nothing rings or is reported fired.
+				await listen<{ id: number }>('alarm-notify', (event) => {
+					console.log(`[AlarmManager] Received alarm-notify event for ID: ${event.payload.id}`);
+					this.handleReminder(event.payload.id);
+				});
+				console.log('[AlarmManager] Event listener 2/8 registered.');
 
-				console.log('[AlarmManager] Setting up event listener 2/6: alarms:batch:updated...');
+				console.log('[AlarmManager] Setting up event listener 3/8: alarms:batch:updated...');
 				// Native scheduling itself is driven Rust-side now (the alarm-manager plugin
 				// listens directly to alarm:scheduled/alarm:cancelled) -- this only resyncs the
 				// JS-rendered "upcoming" pre-notifications, an unrelated UI concern.
@@ -123,9 +142,9 @@ export class AlarmManagerService {
 						reason: 'alarm-batch-updated',
 					});
 				});
-				console.log('[AlarmManager] Event listener 2/6 registered.');
+				console.log('[AlarmManager] Event listener 3/8 registered.');
 
-				console.log('[AlarmManager] Setting up event listener 3/6: alarm:cancelled...');
+				console.log('[AlarmManager] Setting up event listener 4/8: alarm:cancelled...');
 				// Native cancellation is handled Rust-side now; this only cancels the upcoming
 				// pre-notification, which is a separate JS-rendered concern.
 				await listen<{ id: number; reason: string }>('alarm:cancelled', async (event) => {
@@ -135,9 +154,9 @@ export class AlarmManagerService {
 					);
 					await alarmNotificationService.cancelUpcomingNotification(id);
 				});
-				console.log('[AlarmManager] Event listener 3/6 registered.');
+				console.log('[AlarmManager] Event listener 4/8 registered.');
 
-				console.log('[AlarmManager] Setting up event listener 4/6: settings-changed...');
+				console.log('[AlarmManager] Setting up event listener 5/8: settings-changed...');
 				await listen<{ key?: string; value?: unknown }>('settings-changed', async (event) => {
 					if (event.payload?.key !== 'is24h') return;
 					if (!PlatformUtils.isMobile()) return;
@@ -147,10 +166,10 @@ export class AlarmManagerService {
 						reason: 'settings-24h-changed',
 					});
 				});
-				console.log('[AlarmManager] Event listener 4/6 registered.');
+				console.log('[AlarmManager] Event listener 5/8 registered.');
 
 				console.log(
-					'[AlarmManager] Setting up event listener 5/6: notifications:upcoming:resync...',
+					'[AlarmManager] Setting up event listener 6/8: notifications:upcoming:resync...',
 				);
 				await listen<NotificationUpcomingResyncEvent>(
 					'notifications:upcoming:resync',
@@ -158,9 +177,9 @@ export class AlarmManagerService {
 						await this.resyncUpcomingNotifications(event.payload);
 					},
 				);
-				console.log('[AlarmManager] Event listener 5/6 registered.');
+				console.log('[AlarmManager] Event listener 6/8 registered.');
 
-				console.log('[AlarmManager] Setting up event listener 6/6: alarm:snoozed...');
+				console.log('[AlarmManager] Setting up event listener 7/8: alarm:snoozed...');
 				// Unified snooze confirmation toast — Rust emits alarm:snoozed for every
 				// snooze regardless of source (native ringing notification, watch, upcoming
 				// notification, in-app Ringing screen), so one listener here covers all of
@@ -171,7 +190,15 @@ export class AlarmManagerService {
 						await this.publishSnoozeToast(event.payload);
 					},
 				);
-				console.log('[AlarmManager] Event listener 6/6 registered.');
+				console.log('[AlarmManager] Event listener 7/8 registered.');
+
+				console.log('[AlarmManager] Setting up event listener 8/8: alarm:dst:adjusted...');
+				// Rust warns whenever an upcoming occurrence lands on a DST change, so a
+				// skipped or moved alarm on clock-change night is never a silent surprise.
+				await listen<AlarmDstAdjustedPayload>('alarm:dst:adjusted', async (event) => {
+					await this.publishDstToast(event.payload);
+				});
+				console.log('[AlarmManager] Event listener 8/8 registered.');
 
 				// Native imports (e.g. Android's "Set Alarm" intent) are handled entirely in
 				// Rust now -- the alarm-manager plugin's import Channel dispatches or queues
@@ -236,7 +263,7 @@ export class AlarmManagerService {
 
 	private async dismissNextOccurrence(alarmId: number): Promise<void> {
 		await alarmNotificationService.cancelUpcomingNotification(alarmId);
-		await AlarmService.dismiss(alarmId);
+		await AlarmService.dismiss(alarmId, 'NOTIFICATION');
 	}
 
 	private async publishSnoozeToast(payload: {
@@ -260,6 +287,25 @@ export class AlarmManagerService {
 		}
 	}
 
+	private async publishDstToast(payload: AlarmDstAdjustedPayload): Promise<void> {
+		const name = payload.label ? `"${payload.label}"` : 'Alarm';
+		let message: string;
+		if (payload.transition === 'GAP' && payload.policy.gap === 'SKIP') {
+			message = `${name} won't go off on ${payload.date}: the clocks skip ${payload.localTime}`;
+		} else if (payload.transition === 'GAP') {
+			message = `${name} will go off later on ${payload.date}: the clocks skip ${payload.localTime}`;
+		} else {
+			const which = payload.policy.overlap === 'FIRE_AT_FIRST' ? 'first' : 'second';
+			message = `${payload.localTime} happens twice on ${payload.date}; ${name} will go off the ${which} time`;
+		}
+
+		try {
+			await alarmNotificationService.publishToast({ kind: 'generic', message });
+		} catch (e) {
+			console.warn('[AlarmManager] Failed to publish DST toast', e);
+		}
+	}
+
 	private async resyncUpcomingNotifications(
 		payload: NotificationUpcomingResyncPayload,
 	): Promise<void> {
@@ -311,12 +357,35 @@ export class AlarmManagerService {
 		// Floor ensures the new trigger is always in the future even if the alarm was slow to dismiss.
 		const snoozedUntil = Math.max(Date.now() + 60_000, anchor + minutes * 60_000);
 		await alarmNotificationService.cancelUpcomingNotification(id);
-		await AlarmService.snooze(id, snoozedUntil);
+		await AlarmService.snooze(id, snoozedUntil, 'NOTIFICATION');
+	}
+
+	private async handleReminder(id: number) {
+		try {
+			await sendNotification({
+				id,
+				title: 'Time to wind down',
+				body: 'Head to bed soon to get your sleep before your next alarm',
+			});
+		} catch (e) {
+			console.error('[AlarmManager] Failed to send reminder notification', e);
+		}
 	}
 
 	private async handleAlarmRing(id: number) {
 		await alarmNotificationService.cancelUpcomingNotification(id);
 
+		// A backup alarm can fire just as its occurrence is attended to; the backend
+		// stands it down, and nothing rings. If the report fails, ring anyway.
+		try {
+			if ((await AlarmService.reportFired(id, Date.now())) === false) {
+				console.log(`[AlarmManager] Alarm ${id} was stood down as it fired, not ringing`);
+				return;
+			}
+		} catch (e) {
+			console.error('[AlarmManager] Failed to report alarm fired', e);
+		}
+
 		const isMobile = PlatformUtils.isMobile();
 		try {
 			await sendNotification({
@@ -328,12 +397,6 @@ export class AlarmManagerService {
 			console.error('[AlarmManager] Failed to send ringing notification', e);
 		}
 
-		try {
-			await AlarmService.reportFired(id, Date.now());
-		} catch (e) {
-			console.error('[AlarmManager] Failed to report alarm fired', e);
-		}
-
 		try {
 			const { WebviewWindow } = await import('@tauri-apps/api/webviewWindow');
 			const mobile = PlatformUtils.isMobile();
@@ -31,8 +31,26 @@ describe('AlarmService', () => {
 		fixedTime: '07:00',
 		windowStart: null,
 		windowEnd: null,
+		windowDistribution: { kind: 'UNIFORM' },
+		intervalMinutes: null,
+		bedtime: null,
+		solar: null,
+		timezone: null,
+		dstPolicy: { gap: 'SKIP', overlap: 'FIRE_AT_FIRST' },
 		activeDays: [1, 2, 3, 4, 5],
+		oneShotDate: null,
+		recurrenceRule: null,
+		recurrenceAnchor: null,
+		skipDates: [],
+		skipHolidays: false,
+		preAlarm: null,
+		snoozePolicy: null,
+		snoozeCount: 0,
+		ringTimeout: null,
+		backupAlarm: null,
+		followUp: null,
 		nextTrigger: 1625097600000,
+		snoozedFrom: null,
 		soundUri: 'test_uri',
 		soundTitle: 'Test Sound',
 		revision: 1,
@@ -125,6 +143,381 @@ describe('AlarmService', () => {
 		});
 	});
 
+	describe('setSkipDates', () => {
+		it('should invoke set_alarm_skip_dates', async () => {
+			(invoke as any).mockResolvedValue(mockAlarm);
+
+			const result = await AlarmService.setSkipDates(1, ['2026-12-25']);
+
+			expect(invoke).toHaveBeenCalledWith('set_alarm_skip_dates', {
+				id: 1,
+				dates: ['2026-12-25'],
+			});
+			expect(result).toEqual(mockAlarm);
+		});
+	});
+
+	describe('skipNext', () => {
+		it('should invoke skip_next_alarm', async () => {
+			(invoke as any).mockResolvedValue(mockAlarm);
+
+			const result = await AlarmService.skipNext(1);
+
+			expect(invoke).toHaveBeenCalledWith('skip_next_alarm', { id: 1 });
+			expect(result).toEqual(mockAlarm);
+		});
+	});
+
+	describe('recordBedtime', () => {
+		it('should invoke record_alarm_bedtime', async () => {
+			(invoke as any).mockResolvedValue(mockAlarm);
+
+			const result = await AlarmService.recordBedtime(1, 1792360800000);
+
+			expect(invoke).toHaveBeenCalledWith('record_alarm_bedtime', {
+				id: 1,
+				at: 1792360800000,
+			});
+			expect(result).toEqual(mockAlarm);
+		});
+	});
+
+	describe('setPreAlarm', () => {
+		it('should invoke set_alarm_pre_alarm', async () => {
+			const preAlarm = {
+				minutesBefore: 15,
+				soundUri: null,
+				soundTitle: null,
+				volumePercent: 30,
+				ringSeconds: 60,
+				skipMainOnDismiss: true,
+			};
+			(invoke as any).mockResolvedValue({ ...mockAlarm, preAlarm });
+
+			const result = await AlarmService.setPreAlarm(1, preAlarm);
+
+			expect(invoke).toHaveBeenCalledWith('set_alarm_pre_alarm', { id: 1, preAlarm });
+			expect(result.preAlarm).toEqual(preAlarm);
+		});
+
+		it('should pass null to remove the pre-alarm', async () => {
+			(invoke as any).mockResolvedValue(mockAlarm);
+
+			await AlarmService.setPreAlarm(1, null);
+
+			expect(invoke).toHaveBeenCalledWith('set_alarm_pre_alarm', { id: 1, preAlarm: null });
+		});
+	});
+
+	describe('getHistory', () => {
+		const entry = {
+			id: 1,
+			alarmId: 1,
+			kind: 'DISMISSED',
+			source: 'WATCH',
+			scheduledTrigger: 1792396800000,
+			actualFiredAt: 1792396800500,
+			occurredAt: 1792396860000,
+			nextTrigger: 1792483200000,
+			sampled: true,
+		};
+
+		it('should invoke get_alarm_history for one alarm and range', async () => {
+			(invoke as any).mockResolvedValue([entry]);
+			const query = { alarmId: 1, from: 1792310400000, to: 1792396800000 };
+
+			const result = await AlarmService.getHistory(query);
+
+			expect(invoke).toHaveBeenCalledWith('get_alarm_history', { query });
+			expect(result).toEqual([entry]);
+		});
+
+		it('should fetch everything when no query is given', async () => {
+			(invoke as any).mockResolvedValue([]);
+
+			await AlarmService.getHistory();
+
+			expect(invoke).toHaveBeenCalledWith('get_alarm_history', { query: {} });
+		});
+	});
+
+	describe('getWakeReport', () => {
+		it('should invoke get_wake_report with the range', async () => {
+			const report = {
+				overall: { mornings: 1, missed: 0, averageSecondsToDismiss: 120, averageSnoozes: 0 },
+				window: { mornings: 1, missed: 0, averageSecondsToDismiss: 120, averageSnoozes: 0 },
+				fixed: { mornings: 0, missed: 0, averageSecondsToDismiss: null, averageSnoozes: null },
+				weekdays: [],
+				fireLatency: [],
+				averageFireLatencyMs: null,
+			};
+			(invoke as any).mockResolvedValue(report);
+
+			const result = await AlarmService.getWakeReport({ from: 1792310400000, to: 1792396800000 });
+
+			expect(invoke).toHaveBeenCalledWith('get_wake_report', {
+				from: 1792310400000,
+				to: 1792396800000,
+			});
+			expect(result).toEqual(report);
+		});
+	});
+
+	describe('missed alarm grace', () => {
+		it('should invoke get_missed_alarm_grace', async () => {
+			(invoke as any).mockResolvedValue(30);
+
+			const result = await AlarmService.getMissedAlarmGrace();
+
+			expect(invoke).toHaveBeenCalledWith('get_missed_alarm_grace');
+			expect(result).toBe(30);
+		});
+
+		it('should invoke set_missed_alarm_grace with the minutes', async () => {
+			(invoke as any).mockResolvedValue(undefined);
+
+			await AlarmService.setMissedAlarmGrace(60);
+
+			expect(invoke).toHaveBeenCalledWith('set_missed_alarm_grace', { minutes: 60 });
+		});
+	});
+
+	describe('bedtime reminder', () => {
+		const reminder = { enabled: true, sleepMinutes: 480, windDownMinutes: 30 };
+
+		it('should invoke get_bedtime_reminder', async () => {
+			(invoke as any).mockResolvedValue(reminder);
+
+			const result = await AlarmService.getBedtimeReminder();
+
+			expect(invoke).toHaveBeenCalledWith('get_bedtime_reminder');
+			expect(result).toEqual(reminder);
+		});
+
+		it('should invoke set_bedtime_reminder with the settings', async () => {
+			(invoke as any).mockResolvedValue(reminder);
+
+			await AlarmService.setBedtimeReminder(reminder);
+
+			expect(invoke).toHaveBeenCalledWith('set_bedtime_reminder', { reminder });
+		});
+	});
+
+	describe('getUpcomingOccurrences', () => {
+		const occurrence = {
+			alarmId: 1,
+			date: '2026-10-19',
+			startsAt: 1792396800000,
+			endsAt: 1792398600000,
+		};
+
+		it('should invoke get_upcoming_occurrences for one alarm', async () => {
+			(invoke as any).mockResolvedValue([occurrence]);
+
+			const result = await AlarmService.getUpcomingOccurrences({ kind: 'NEXT', count: 7 }, 1);
+
+			expect(invoke).toHaveBeenCalledWith('get_upcoming_occurrences', {
+				alarmId: 1,
+				query: { kind: 'NEXT', count: 7 },
+			});
+			expect(result).toEqual([occurrence]);
+		});
+
+		it('should invoke get_upcoming_occurrences for all alarms', async () => {
+			(invoke as any).mockResolvedValue([]);
+
+			await AlarmService.getUpcomingOccurrences({
+				kind: 'RANGE',
+				from: '2026-10-19',
+				to: '2026-10-25',
+			});
+
+			expect(invoke).toHaveBeenCalledWith('get_upcoming_occurrences', {
+				alarmId: undefined,
+				query: { kind: 'RANGE', from: '2026-10-19', to: '2026-10-25' },
+			});
+		});
+	});
+
+	describe('holidays', () => {
+		const holiday = { date: '2026-07-01', name: 'Canada Day', source: 'region:CA' };
+
+		it('should invoke get_holidays', async () => {
+			(invoke as any).mockResolvedValue([holiday]);
+
+			const result = await AlarmService.getHolidays();
+
+			expect(invoke).toHaveBeenCalledWith('get_holidays');
+			expect(result).toEqual([holiday]);
+		});
+
+		it('should invoke import_holidays_ics', async () => {
+			(invoke as any).mockResolvedValue([holiday]);
+
+			await AlarmService.importHolidaysIcs('Work', 'BEGIN:VCALENDAR');
+
+			expect(invoke).toHaveBeenCalledWith('import_holidays_ics', {
+				name: 'Work',
+				contents: 'BEGIN:VCALENDAR',
+			});
+		});
+
+		it('should invoke import_bundled_holidays', async () => {
+			(invoke as any).mockResolvedValue([holiday]);
+
+			await AlarmService.importBundledHolidays('CA');
+
+			expect(invoke).toHaveBeenCalledWith('import_bundled_holidays', { region: 'CA' });
+		});
+
+		it('should invoke clear_holidays', async () => {
+			(invoke as any).mockResolvedValue([]);
+
+			await AlarmService.clearHolidays('region:CA');
+
+			expect(invoke).toHaveBeenCalledWith('clear_holidays', { source: 'region:CA' });
+		});
+	});
+
+	describe('timers', () => {
+		const timer = {
+			id: 1,
+			label: 'Nap',
+			durationSeconds: 1200,
+			state: 'RUNNING',
+			endsAt: 1625098800000,
+			remainingMs: null,
+			createdAt: 1625097600000,
+			revision: 3,
+		};
+
+		it('should invoke get_timers', async () => {
+			(invoke as any).mockResolvedValue([timer]);
+
+			const result = await AlarmService.getTimers();
+
+			expect(invoke).toHaveBeenCalledWith('get_timers');
+			expect(result).toEqual([timer]);
+		});
+
+		it('should invoke start_timer with the duration and label', async () => {
+			(invoke as any).mockResolvedValue(timer);
+
+			const result = await AlarmService.startTimer(1200, 'Nap');
+
+			expect(invoke).toHaveBeenCalledWith('start_timer', { durationSeconds: 1200, label: 'Nap' });
+			expect(result).toEqual(timer);
+		});
+
+		it('should start an unlabelled timer with a null label', async () => {
+			(invoke as any).mockResolvedValue({ ...timer, label: null });
+
+			await AlarmService.startTimer(300);
+
+			expect(invoke).toHaveBeenCalledWith('start_timer', { durationSeconds: 300, label: null });
+		});
+
+		it('should invoke pause_timer, resume_timer and cancel_timer', async () => {
+			(invoke as any).mockResolvedValue(timer);
+
+			await AlarmService.pauseTimer(1);
+			await AlarmService.resumeTimer(1);
+			await AlarmService.cancelTimer(1);
+
+			expect(invoke).toHaveBeenCalledWith('pause_timer', { id: 1 });
+			expect(invoke).toHaveBeenCalledWith('resume_timer', { id: 1 });
+			expect(invoke).toHaveBeenCalledWith('cancel_timer', { id: 1 });
+		});
+	});
+
+	describe('groups', () => {
+		const group = { id: 2, name: 'Work week', alarmIds: [1], revision: 4 };
+
+		it('should invoke get_alarm_groups', async () => {
+			(invoke as any).mockResolvedValue([group]);
+
+			const result = await AlarmService.getGroups();
+
+			expect(invoke).toHaveBeenCalledWith('get_alarm_groups');
+			expect(result).toEqual([group]);
+		});
+
+		it('should invoke save_alarm_group', async () => {
+			(invoke as any).mockResolvedValue(group);
+
+			await AlarmService.saveGroup(null, 'Work week', [1]);
+
+			expect(invoke).toHaveBeenCalledWith('save_alarm_group', {
+				id: null,
+				name: 'Work week',
+				alarmIds: [1],
+			});
+		});
+
+		it('should invoke delete_alarm_group', async () => {
+			(invoke as any).mockResolvedValue(undefined);
+
+			await AlarmService.deleteGroup(2);
+
+			expect(invoke).toHaveBeenCalledWith('delete_alarm_group', { id: 2 });
+		});
+
+		it('should switch a whole group in one call', async () => {
+			(invoke as any).mockResolvedValue([{ ...mockAlarm, enabled: false }]);
+
+			const result = await AlarmService.setGroupEnabled(2, false);
+
+			expect(invoke).toHaveBeenCalledTimes(1);
+			expect(invoke).toHaveBeenCalledWith('set_alarm_group_enabled', { id: 2, enabled: false });
+			expect(result[0].enabled).toBe(false);
+		});
+	});
+
+	describe('pauses', () => {
+		const pause = {
+			id: 1,
+			groupId: null,
+			until: '2026-10-25',
+			resumesAt: 1792900800000,
+			alarmIds: [1],
+			revision: 6,
+		};
+
+		it('should invoke get_alarm_pauses', async () => {
+			(invoke as any).mockResolvedValue([pause]);
+
+			const result = await AlarmService.getPauses();
+
+			expect(invoke).toHaveBeenCalledWith('get_alarm_pauses');
+			expect(result).toEqual([pause]);
+		});
+
+		it('should pause every alarm when no group is given', async () => {
+			(invoke as any).mockResolvedValue(pause);
+
+			await AlarmService.pauseAlarms('2026-10-25');
+
+			expect(invoke).toHaveBeenCalledWith('pause_alarms', { groupId: null, until: '2026-10-25' });
+		});
+
+		it('should pause one group', async () => {
+			(invoke as any).mockResolvedValue({ ...pause, groupId: 2 });
+
+			const result = await AlarmService.pauseAlarms('2026-10-25', 2);
+
+			expect(invoke).toHaveBeenCalledWith('pause_alarms', { groupId: 2, until: '2026-10-25' });
+			expect(result.groupId).toBe(2);
+		});
+
+		it('should invoke resume_alarm_pause', async () => {
+			(invoke as any).mockResolvedValue(undefined);
+
+			await AlarmService.resumePause(1);
+
+			expect(invoke).toHaveBeenCalledWith('resume_alarm_pause', { id: 1 });
+		});
+	});
+
 	describe('delete', () => {
 		it('should invoke delete_alarm', async () => {
 			(invoke as any).mockResolvedValue(undefined);
@@ -143,6 +536,14 @@ describe('AlarmService', () => {
 
 			expect(invoke).toHaveBeenCalledWith('dismiss_alarm', { id: 1 });
 		});
+
+		it('should pass the source along', async () => {
+			(invoke as any).mockResolvedValue(undefined);
+
+			await AlarmService.dismiss(1, 'NOTIFICATION');
+
+			expect(invoke).toHaveBeenCalledWith('dismiss_alarm', { id: 1, source: 'NOTIFICATION' });
+		});
 	});
 
 	describe('snooze', () => {
@@ -154,15 +555,48 @@ describe('AlarmService', () => {
 
 			expect(invoke).toHaveBeenCalledWith('snooze_alarm', { id: 1, snoozedUntil });
 		});
+
+		it('should pass the source along', async () => {
+			(invoke as any).mockResolvedValue(undefined);
+
+			await AlarmService.snooze(1, 123456, 'NOTIFICATION');
+
+			expect(invoke).toHaveBeenCalledWith('snooze_alarm', {
+				id: 1,
+				snoozedUntil: 123456,
+				source: 'NOTIFICATION',
+			});
+		});
+	});
+
+	describe('getSnoozeAllowance', () => {
+		it('should invoke get_snooze_allowance', async () => {
+			const allowance = { allowed: false, minutes: null, reason: 'No snoozing after 08:00' };
+			(invoke as any).mockResolvedValue(allowance);
+
+			const result = await AlarmService.getSnoozeAllowance(1);
+
+			expect(invoke).toHaveBeenCalledWith('get_snooze_allowance', { id: 1 });
+			expect(result).toEqual(allowance);
+		});
 	});
 
 	describe('reportFired', () => {
 		it('should invoke report_alarm_fired', async () => {
-			(invoke as any).mockResolvedValue(undefined);
+			(invoke as any).mockResolvedValue(true);
 
-			await AlarmService.reportFired(1, 123456);
+			const result = await AlarmService.reportFired(1, 123456);
 
 			expect(invoke).toHaveBeenCalledWith('report_alarm_fired', { id: 1, actualFiredAt: 123456 });
+			expect(result).toBe(true);
+		});
+
+		it('should return false for a stood-down backup alarm', async () => {
+			(invoke as any).mockResolvedValue(false);
+
+			const result = await AlarmService.reportFired(3_000_001, 123456);
+
+			expect(result).toBe(false);
 		});
 	});
 
@@ -5,7 +5,23 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import type { AlarmRecord, AlarmInput } from '../types/alarm';
+import type {
+	AlarmEventSource,
+	AlarmGroup,
+	AlarmHistoryEntry,
+	AlarmPause,
+	AlarmRecord,
+	AlarmInput,
+	BedtimeReminder,
+	HistoryQuery,
+	Holiday,
+	Occurrence,
+	OccurrenceQuery,
+	PreAlarm,
+	SnoozeAllowance,
+	Timer,
+	WakeReport,
+} from '../types/alarm';
 
 export class AlarmService {
 	/**
@@ -47,6 +63,206 @@ export class AlarmService {
 		return await invoke<AlarmRecord>('toggle_alarm', { id, enabled });
 	}
 
+	/**
+	 * Replace the occurrence dates (YYYY-MM-DD) an alarm skips
+	 */
+	static async setSkipDates(id: number, dates: string[]): Promise<AlarmRecord> {
+		return await invoke<AlarmRecord>('set_alarm_skip_dates', { id, dates });
+	}
+
+	/**
+	 * Record when the user went to bed (now if omitted) for sleep-cycle windows
+	 */
+	static async recordBedtime(id: number, at?: number): Promise<AlarmRecord> {
+		return await invoke<AlarmRecord>('record_alarm_bedtime', { id, at });
+	}
+
+	/**
+	 * Add, change or (with null) remove the quieter pre-alarm that rings ahead of an alarm
+	 */
+	static async setPreAlarm(id: number, preAlarm: PreAlarm | null): Promise<AlarmRecord> {
+		return await invoke<AlarmRecord>('set_alarm_pre_alarm', { id, preAlarm });
+	}
+
+	/**
+	 * Skip the next occurrence without disabling the alarm
+	 */
+	static async skipNext(id: number): Promise<AlarmRecord> {
+		return await invoke<AlarmRecord>('skip_next_alarm', { id });
+	}
+
+	/**
+	 * Preview upcoming occurrences for one alarm, or for all alarms when no id is
+	 * given. Window alarms come back as window bounds rather than sampled times.
+	 */
+	static async getUpcomingOccurrences(
+		query: OccurrenceQuery,
+		alarmId?: number,
+	): Promise<Occurrence[]> {
+		return await invoke<Occurrence[]>('get_upcoming_occurrences', { alarmId, query });
+	}
+
+	/**
+	 * Get fired, snoozed, dismissed and missed occurrences, newest first, for one alarm
+	 * and/or a range of epoch-millisecond times
+	 */
+	static async getHistory(query: HistoryQuery = {}): Promise<AlarmHistoryEntry[]> {
+		return await invoke<AlarmHistoryEntry[]>('get_alarm_history', { query });
+	}
+
+	/**
+	 * Get wake-up statistics from the history, for one alarm or all of them, optionally
+	 * between two epoch-millisecond times
+	 */
+	static async getWakeReport(
+		options: { alarmId?: number; from?: number; to?: number } = {},
+	): Promise<WakeReport> {
+		return await invoke<WakeReport>('get_wake_report', options);
+	}
+
+	/**
+	 * Get how many minutes past its trigger an undismissed alarm is left before it's
+	 * recorded as missed and moved on to its next occurrence
+	 */
+	static async getMissedAlarmGrace(): Promise<number> {
+		return await invoke<number>('get_missed_alarm_grace');
+	}
+
+	/**
+	 * Set the missed-alarm grace window in minutes (5 to 720), applied from the next missed-alarm check
+	 */
+	static async setMissedAlarmGrace(minutes: number): Promise<void> {
+		await invoke('set_missed_alarm_grace', { minutes });
+	}
+
+	/**
+	 * Get the bedtime reminder's settings
+	 */
+	static async getBedtimeReminder(): Promise<BedtimeReminder> {
+		return await invoke<BedtimeReminder>('get_bedtime_reminder');
+	}
+
+	/**
+	 * Set the bedtime reminder, which goes off the target sleep (180 to 960 minutes) and
+	 * wind-down (up to 180) before the next alarm, or its window's start
+	 */
+	static async setBedtimeReminder(reminder: BedtimeReminder): Promise<BedtimeReminder> {
+		return await invoke<BedtimeReminder>('set_bedtime_reminder', { reminder });
+	}
+
+	/**
+	 * Get every imported holiday
+	 */
+	static async getHolidays(): Promise<Holiday[]> {
+		return await invoke<Holiday[]>('get_holidays');
+	}
+
+	/**
+	 * Import an .ics calendar's all-day events as holidays
+	 */
+	static async importHolidaysIcs(name: string, contents: string): Promise<Holiday[]> {
+		return await invoke<Holiday[]>('import_holidays_ics', { name, contents });
+	}
+
+	/**
+	 * Import a bundled regional holiday set ('CA', 'GB' or 'US')
+	 */
+	static async importBundledHolidays(region: string): Promise<Holiday[]> {
+		return await invoke<Holiday[]>('import_bundled_holidays', { region });
+	}
+
+	/**
+	 * Remove every holiday from one source
+	 */
+	static async clearHolidays(source: string): Promise<Holiday[]> {
+		return await invoke<Holiday[]>('clear_holidays', { source });
+	}
+
+	/**
+	 * Get every timer, running, paused or ringing
+	 */
+	static async getTimers(): Promise<Timer[]> {
+		return await invoke<Timer[]>('get_timers');
+	}
+
+	/**
+	 * Start a countdown timer that rings once, like a nap alarm
+	 */
+	static async startTimer(durationSeconds: number, label?: string): Promise<Timer> {
+		return await invoke<Timer>('start_timer', { durationSeconds, label: label ?? null });
+	}
+
+	/**
+	 * Pause a running timer
+	 */
+	static async pauseTimer(id: number): Promise<Timer> {
+		return await invoke<Timer>('pause_timer', { id });
+	}
+
+	/**
+	 * Resume a paused timer
+	 */
+	static async resumeTimer(id: number): Promise<Timer> {
+		return await invoke<Timer>('resume_timer', { id });
+	}
+
+	/**
+	 * Cancel a timer, stopping it if it's ringing
+	 */
+	static async cancelTimer(id: number): Promise<void> {
+		await invoke('cancel_timer', { id });
+	}
+
+	/**
+	 * Get every alarm group, by name
+	 */
+	static async getGroups(): Promise<AlarmGroup[]> {
+		return await invoke<AlarmGroup[]>('get_alarm_groups');
+	}
+
+	/**
+	 * Create a group (no id) or rename one and replace its alarms
+	 */
+	static async saveGroup(id: number | null, name: string, alarmIds: number[]): Promise<AlarmGroup> {
+		return await invoke<AlarmGroup>('save_alarm_group', { id, name, alarmIds });
+	}
+
+	/**
+	 * Delete a group, leaving its alarms as they are
+	 */
+	static async deleteGroup(id: number): Promise<void> {
+		await invoke('delete_alarm_group', { id });
+	}
+
+	/**
+	 * Switch every alarm in a group on or off at once. Returns the alarms that changed.
+	 */
+	static async setGroupEnabled(id: number, enabled: boolean): Promise<AlarmRecord[]> {
+		return await invoke<AlarmRecord[]>('set_alarm_group_enabled', { id, enabled });
+	}
+
+	/**
+	 * Get every pause, soonest to end first
+	 */
+	static async getPauses(): Promise<AlarmPause[]> {
+		return await invoke<AlarmPause[]>('get_alarm_pauses');
+	}
+
+	/**
+	 * Switch every alarm, or one group's, off until a "YYYY-MM-DD" date. Pausing what's
+	 * already paused moves the end of that pause instead.
+	 */
+	static async pauseAlarms(until: string, groupId: number | null = null): Promise<AlarmPause> {
+		return await invoke<AlarmPause>('pause_alarms', { groupId, until });
+	}
+
+	/**
+	 * End a pause early, switching its alarms back on
+	 */
+	static async resumePause(id: number): Promise<void> {
+		await invoke('resume_alarm_pause', { id });
+	}
+
 	/**
 	 * Delete alarm
 	 */
@@ -55,24 +271,38 @@ export class AlarmService {
 	}
 
 	/**
-	 * Dismiss ringing alarm
+	 * Dismiss ringing alarm. `source` is recorded in the history; omitted means the
+	 * app's own screens.
 	 */
-	static async dismiss(id: number): Promise<void> {
-		await invoke('dismiss_alarm', { id });
+	static async dismiss(id: number, source?: AlarmEventSource): Promise<void> {
+		await invoke('dismiss_alarm', { id, source });
+	}
+
+	/**
+	 * Snooze ringing alarm. `source` is recorded in the history; omitted means the
+	 * app's own screens.
+	 */
+	static async snooze(
+		id: number,
+		snoozedUntil: number,
+		source?: AlarmEventSource,
+	): Promise<void> {
+		await invoke('snooze_alarm', { id, snoozedUntil, source });
 	}
 
 	/**
-	 * Snooze ringing alarm
+	 * Whether a ringing alarm may be snoozed under its snooze policy, and for how long
 	 */
-	static async snooze(id: number, snoozedUntil: number): Promise<void> {
-		await invoke('snooze_alarm', { id, snoozedUntil });
+	static async getSnoozeAllowance(id: number): Promise<SnoozeAllowance> {
+		return await invoke<SnoozeAllowance>('get_snooze_allowance', { id });
 	}
 
 	/**
-	 * Report a fired alarm (lifecycle event)
+	 * Report a fired alarm (lifecycle event). Resolves false for a backup alarm whose
+	 * occurrence was dismissed or snoozed as it fired, which mustn't ring.
 	 */
-	static async reportFired(id: number, actualFiredAt: number): Promise<void> {
-		await invoke('report_alarm_fired', { id, actualFiredAt });
+	static async reportFired(id: number, actualFiredAt: number): Promise<boolean> {
+		return await invoke<boolean>('report_alarm_fired', { id, actualFiredAt });
 	}
 
 	/**
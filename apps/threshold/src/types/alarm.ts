@@ -8,9 +8,160 @@ export { AlarmMode };
 /**
  * Complete alarm configuration (returned to TypeScript)
  */
-export type AlarmRecord = { id: number, label: string | null, enabled: boolean, mode: AlarmMode, fixedTime: string | null, windowStart: string | null, windowEnd: string | null, activeDays: Array<number>, nextTrigger: number | null, soundUri: string | null, soundTitle: string | null, revision: number, };
+export type AlarmRecord = { id: number, label: string | null, enabled: boolean, mode: AlarmMode, fixedTime: string | null, windowStart: string | null, windowEnd: string | null, windowDistribution: WindowDistribution, intervalMinutes: number | null, bedtime: string | null, solar: SolarSchedule | null, timezone: string | null, dstPolicy: DstPolicy, activeDays: Array<number>, oneShotDate: string | null, recurrenceRule: string | null, recurrenceAnchor: string | null, skipDates: Array<string>, skipHolidays: boolean, preAlarm: PreAlarm | null, snoozePolicy: SnoozePolicy | null, snoozeCount: number, ringTimeout: RingTimeout | null, backupAlarm: BackupAlarm | null, followUp: FollowUp | null, nextTrigger: number | null, snoozedFrom: number | null, soundUri: string | null, soundTitle: string | null, revision: number, };
 
 /**
  * Input for creating/updating alarms (from TypeScript)
  */
-export type AlarmInput = { id?: number | null, label?: string | null, enabled: boolean, mode: AlarmMode, fixedTime?: string | null, windowStart?: string | null, windowEnd?: string | null, activeDays: Array<number>, soundUri?: string | null, soundTitle?: string | null, };
+export type AlarmInput = { id?: number | null, label?: string | null, enabled: boolean, mode: AlarmMode, fixedTime?: string | null, windowStart?: string | null, windowEnd?: string | null, windowDistribution?: WindowDistribution | null, intervalMinutes?: number | null, bedtime?: string | null, solar?: SolarSchedule | null, timezone?: string | null, dstPolicy?: DstPolicy | null, activeDays: Array<number>, oneShotDate?: string | null, recurrenceRule?: string | null, recurrenceAnchor?: string | null, skipDates?: Array<string> | null, skipHolidays?: boolean | null, preAlarm?: PreAlarm | null, snoozePolicy?: SnoozePolicy | null, ringTimeout?: RingTimeout | null, backupAlarm?: BackupAlarm | null, followUp?: FollowUp | null, soundUri?: string | null, soundTitle?: string | null, };
+
+/**
+ * How a window alarm's minute is picked between `window_start` and `window_end`.
+ * Stored as JSON in the alarm row.
+ */
+export type WindowDistribution = { "kind": "UNIFORM" } | { "kind": "TRIANGULAR_START" } | { "kind": "TRIANGULAR_END" } | { "kind": "TRUNCATED_NORMAL", target: string, stdDevMinutes: number, } | { "kind": "AVOID_LAST", minutes: number, } | { "kind": "SLEEP_CYCLES" };
+
+/**
+ * Where, and relative to which solar event, a solar alarm fires. Computed on the
+ * device; no network lookup.
+ */
+export type SolarSchedule = { event: SolarEvent, latitude: number, longitude: number, offsetMinutes: number, windowMinutes: number | null, };
+
+/**
+ * The sun position a solar alarm is anchored to.
+ */
+export type SolarEvent = "SUNRISE" | "SUNSET" | "CIVIL_DAWN" | "CIVIL_DUSK";
+
+/**
+ * What a clock time does on the nights daylight saving time starts or ends. Stored
+ * as JSON in the alarm row.
+ */
+export type DstPolicy = { gap: DstGapPolicy, overlap: DstOverlapPolicy, };
+
+/**
+ * A time that doesn't exist because the clocks spring forward past it (e.g. 02:30).
+ */
+export type DstGapPolicy = "SKIP" | "SHIFT_FORWARD";
+
+/**
+ * A time that happens twice because the clocks fall back over it (e.g. 01:30).
+ */
+export type DstOverlapPolicy = "FIRE_AT_FIRST" | "FIRE_AT_SECOND";
+
+/**
+ * A gentle first stage that rings `minutes_before` the alarm's trigger, quietly and
+ * only for a while. Stored as JSON in the alarm row.
+ */
+export type PreAlarm = { minutesBefore: number, soundUri: string | null, soundTitle: string | null, volumePercent: number, ringSeconds: number, skipMainOnDismiss: boolean, };
+
+/**
+ * Limits on snoozing an occurrence, enforced whichever device the snooze comes from.
+ * Stored as JSON in the alarm row.
+ */
+export type SnoozePolicy = { maxSnoozes: number | null, intervalsMinutes: Array<number>, noSnoozeAfter: string | null, };
+
+/**
+ * Whether a ringing occurrence may be snoozed now, and for how long.
+ */
+export type SnoozeAllowance = { allowed: boolean, minutes: number | null, reason: string | null, };
+
+/**
+ * What happens to an occurrence left ringing with nobody dismissing or snoozing it.
+ * Stored as JSON in the alarm row.
+ */
+export type RingTimeout = { minutes: number, action: RingTimeoutAction, escalateToWatch: boolean, };
+
+export type RingTimeoutAction = "SNOOZE" | "DISMISS";
+
+/**
+ * A second, louder alarm that rings `minutes_after` an occurrence first rang if it
+ * still hasn't been dismissed or snoozed by then. Stored as JSON in the alarm row.
+ */
+export type BackupAlarm = { minutesAfter: number, soundUri: string | null, soundTitle: string | null, watchOnly: boolean, };
+
+/**
+ * The alarm a follow-up alarm rings after. Stored as JSON in the alarm row.
+ */
+export type FollowUp = { alarmId: number, minutesAfter: number, };
+
+/**
+ * One upcoming occurrence of an alarm, for previews such as a week-ahead timeline.
+ */
+export type Occurrence = { alarmId: number, date: string, startsAt: number, endsAt: number | null, };
+
+/**
+ * Which occurrences a preview asks for.
+ */
+export type OccurrenceQuery = { "kind": "NEXT", count: number, } | { "kind": "RANGE", from: string, to: string, };
+
+/**
+ * A day off imported from an .ics file or a bundled regional set
+ */
+export type Holiday = { date: string, name: string, source: string, };
+
+/**
+ * A countdown timer, such as a nap: it rings once, `duration_seconds` after it was
+ * started plus however long it spent paused, and is gone once dismissed.
+ */
+export type Timer = { id: number, label: string | null, durationSeconds: number, state: TimerState, endsAt: number | null, remainingMs: number | null, createdAt: number, revision: number, };
+
+export type TimerState = "RUNNING" | "PAUSED";
+
+/**
+ * A named set of alarms, such as "Work week" or "Travel", switched on or off
+ * together. An alarm can be in any number of groups.
+ */
+export type AlarmGroup = { id: number, name: string, alarmIds: Array<number>, revision: number, };
+
+/**
+ * Alarms switched off until a date, e.g. while away on holiday: every alarm, or one
+ * group's. The alarms it switched off come back on when it ends.
+ */
+export type AlarmPause = { id: number, groupId: number | null, until: string, resumesAt: number, alarmIds: Array<number>, revision: number, };
+
+/**
+ * An opt-in nudge to start winding down, timed back from the next alarm so there's
+ * still `sleep_minutes` of sleep before it once the wind-down is over.
+ */
+export type BedtimeReminder = { enabled: boolean, sleepMinutes: number, windDownMinutes: number, };
+
+/**
+ * What happened to an alarm occurrence, as recorded in its history.
+ */
+export type AlarmEventKind = "FIRED" | "SNOOZED" | "DISMISSED" | "MISSED";
+
+/**
+ * Where a history event came from.
+ */
+export type AlarmEventSource = "PHONE_UI" | "NOTIFICATION" | "WATCH" | "SYSTEM" | "UNATTENDED";
+
+/**
+ * One fired, snoozed, dismissed or missed occurrence in an alarm's history. Rows
+ * outlive the alarm, so a deleted alarm's history still counts.
+ */
+export type AlarmHistoryEntry = { id: number, alarmId: number, kind: AlarmEventKind, source: AlarmEventSource, scheduledTrigger: number | null, actualFiredAt: number | null, occurredAt: number, nextTrigger: number | null, sampled: boolean | null, };
+
+/**
+ * Which history rows to fetch, newest first.
+ */
+export type HistoryQuery = { alarmId?: number | null, from?: number | null, to?: number | null, limit?: number | null, };
+
+/**
+ * Wake-up statistics drawn from the history, shaped for the report's charts.
+ */
+export type WakeReport = { overall: WakeStats, window: WakeStats, fixed: WakeStats, weekdays: Array<WeekdayWakeTimes>, fireLatency: Array<FireLatency>, averageFireLatencyMs: number | null, };
+
+/**
+ * Averages over the mornings an alarm rang and was dismissed.
+ */
+export type WakeStats = { mornings: number, missed: number, averageSecondsToDismiss: number | null, averageSnoozes: number | null, };
+
+/**
+ * The spread of wake-up times on one weekday.
+ */
+export type WeekdayWakeTimes = { weekday: number, wakeMinutes: Array<number>, medianMinute: number | null, };
+
+/**
+ * How far an occurrence's first ring drifted from its trigger.
+ */
+export type FireLatency = { alarmId: number, triggerAt: number, latencyMs: number, };
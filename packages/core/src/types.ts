@@ -8,6 +8,79 @@ export type DayOfWeek = 0 | 1 | 2 | 3 | 4 | 5 | 6; // 0=Sunday, 1=Monday, etc.
 export enum AlarmMode {
 	Fixed = 'FIXED',
 	Window = 'WINDOW',
+	FixedOnce = 'FIXED_ONCE',
+	WindowOnce = 'WINDOW_ONCE',
+	Solar = 'SOLAR',
+	SolarWindow = 'SOLAR_WINDOW',
+	Interval = 'INTERVAL',
+	FollowUp = 'FOLLOW_UP',
+}
+
+export type SolarEvent = 'SUNRISE' | 'SUNSET' | 'CIVIL_DAWN' | 'CIVIL_DUSK';
+
+export interface SolarSchedule {
+	event: SolarEvent;
+	latitude: number; // degrees, north positive
+	longitude: number; // degrees, east positive
+	offsetMinutes: number; // negative fires before the event
+	windowMinutes: number | null; // SOLAR_WINDOW only
+}
+
+export type WindowDistribution =
+	| { kind: 'UNIFORM' }
+	| { kind: 'TRIANGULAR_START' }
+	| { kind: 'TRIANGULAR_END' }
+	| { kind: 'TRUNCATED_NORMAL'; target: string; stdDevMinutes: number } // target HH:mm
+	| { kind: 'AVOID_LAST'; minutes: number }
+	| { kind: 'SLEEP_CYCLES' };
+
+// A quieter first stage that rings minutesBefore the alarm, for ringSeconds at most
+export interface PreAlarm {
+	minutesBefore: number;
+	soundUri: string | null;
+	soundTitle: string | null;
+	volumePercent: number; // 1-99
+	ringSeconds: number;
+	// Dismissing the pre-alarm also skips the alarm it comes before
+	skipMainOnDismiss: boolean;
+}
+
+// Limits on snoozing each occurrence, enforced whichever device the snooze comes from
+export interface SnoozePolicy {
+	maxSnoozes: number | null; // 0 turns snoozing off
+	// Minutes for the 1st, 2nd, ... snooze, the last one repeating (e.g. [10, 5, 3])
+	intervalsMinutes: number[];
+	noSnoozeAfter: string | null; // HH:mm
+}
+
+// What happens to a ring nobody dismisses or snoozes within `minutes`
+export interface RingTimeout {
+	minutes: number; // 1-60
+	action: 'SNOOZE' | 'DISMISS'; // a snooze the policy refuses dismisses instead
+	// Ring the watch insistently halfway through
+	escalateToWatch: boolean;
+}
+
+// A louder second alarm if a ring is still undismissed `minutesAfter` it started
+export interface BackupAlarm {
+	minutesAfter: number; // 1-60
+	soundUri: string | null; // the alarm's own sound if null
+	soundTitle: string | null;
+	// Ring only the watch, leaving the phone silent
+	watchOnly: boolean;
+}
+
+// FOLLOW_UP mode: rings minutesAfter the alarm alarmId is dismissed, e.g. "leave the house"
+export interface FollowUp {
+	alarmId: number; // may itself be a follow-up, but never lead back to this alarm
+	minutesAfter: number; // 1-720
+}
+
+// What a clock time does when the clocks spring forward past it (gap) or fall back
+// over it (overlap)
+export interface DstPolicy {
+	gap: 'SKIP' | 'SHIFT_FORWARD';
+	overlap: 'FIRE_AT_FIRST' | 'FIRE_AT_SECOND';
 }
 
 export interface Alarm {
@@ -22,10 +95,54 @@ export interface Alarm {
 	// Random Window Mode
 	windowStart?: string; // HH:mm
 	windowEnd?: string; // HH:mm
+	// How the minute is picked inside the window (uniform when omitted)
+	windowDistribution?: WindowDistribution;
+	// When the user went to bed; SLEEP_CYCLES windows prefer 90-minute boundaries from it
+	bedtime?: string | null; // HH:mm
+
+	// Interval Mode: every intervalMinutes from windowStart to windowEnd (e.g. hourly chimes)
+	intervalMinutes?: number | null; // 5-720
+
+	// Solar Modes: offset from sunrise/sunset, computed on the device
+	solar?: SolarSchedule | null;
+
+	// IANA zone (e.g. Europe/London) the times above are in; the device's zone when unset
+	timezone?: string | null;
+	// Skips gaps and fires at the first of an overlap when omitted
+	dstPolicy?: DstPolicy;
 
 	// Recurrence: Array of days (0-6) where the alarm is active
 	activeDays: DayOfWeek[];
 
+	// One-shot modes: the single date the alarm fires on (YYYY-MM-DD)
+	oneShotDate?: string;
+
+	// RFC 5545 RRULE subset (e.g. FREQ=WEEKLY;INTERVAL=2) anchored on recurrenceAnchor
+	// (YYYY-MM-DD); when set it replaces activeDays
+	recurrenceRule?: string;
+	recurrenceAnchor?: string;
+
+	// Occurrence dates (YYYY-MM-DD) the alarm stays enabled but does not fire on
+	skipDates?: string[];
+	// Also skip dates in the imported holiday calendar
+	skipHolidays?: boolean;
+
+	// Rings quietly ahead of the alarm; omitting it on save keeps the existing one
+	preAlarm?: PreAlarm | null;
+
+	// Snooze limits; omitting it on save keeps the existing one, an empty one removes it
+	snoozePolicy?: SnoozePolicy | null;
+	snoozeCount?: number; // snoozes of the current occurrence
+
+	// Ring timeout; omitting it on save keeps the existing one, 0 minutes removes it
+	ringTimeout?: RingTimeout | null;
+
+	// Backup alarm; omitting it on save keeps the existing one, 0 minutes removes it
+	backupAlarm?: BackupAlarm | null;
+
+	// Follow-up Mode: the alarm whose dismissal sets nextTrigger
+	followUp?: FollowUp | null;
+
 	// Sound
 	soundUri?: string | null;
 	soundTitle?: string | null;
@@ -36,6 +36,18 @@ class ScheduleRequest {
     var id: Int = 0
     var triggerAt: Long = 0
     var soundUri: String? = null
+    // Pre-alarms only: a quieter ring that stops by itself.
+    var volumePercent: Int? = null
+    var ringSeconds: Int? = null
+    // False when the alarm's snooze policy won't allow snoozing this ring.
+    var snoozeAllowed: Boolean? = null
+    // Backup alarms only: rings at full alarm volume, or on the watch alone.
+    var backupFor: Int? = null
+    var watchOnly: Boolean? = null
+    // Bedtime reminders only: posts a notification instead of ringing.
+    var notificationOnly: Boolean? = null
+    // Pause ends only: reports the fire without ringing or notifying.
+    var wakeOnly: Boolean? = null
 }
 
 @InvokeArg
@@ -254,7 +266,19 @@ class AlarmManagerPlugin(private val activity: android.app.Activity) : Plugin(ac
         val args = invoke.parseArgs(ScheduleRequest::class.java)
 
         // TODO: Remove this compatibility command once scheduling is fully event-driven.
-        AlarmUtils.scheduleAlarm(activity, args.id, args.triggerAt, args.soundUri)
+        AlarmUtils.scheduleAlarm(
+            activity,
+            args.id,
+            args.triggerAt,
+            args.soundUri,
+            args.volumePercent,
+            args.ringSeconds,
+            args.snoozeAllowed ?: true,
+            args.backupFor != null,
+            args.watchOnly ?: false,
+            args.notificationOnly ?: false,
+            args.wakeOnly ?: false,
+        )
         invoke.resolve()
     }
 
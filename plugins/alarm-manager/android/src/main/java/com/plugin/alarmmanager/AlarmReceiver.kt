@@ -30,8 +30,33 @@ class AlarmReceiver : BroadcastReceiver() {
             return
         }
 
+        // The bedtime reminder isn't an alarm: it posts its notification, and nothing
+        // rings or reports a fire.
+        if (intent.getBooleanExtra("ALARM_NOTIFICATION_ONLY", false)) {
+            ReminderNotification.post(context, alarmId)
+            Log.d("AlarmReceiver", "========== ALARM RECEIVER END (notification only) ==========")
+            NativeEventLog.log(context, "AlarmReceiver", "Posted reminder id=$alarmId, nothing rung")
+            return
+        }
+
         AlarmManagerPlugin.notifyAlarmFired(context, alarmId)
 
+        // A pause's end is acted on by the app from the fired event alone, queued until
+        // it next starts if it isn't running.
+        if (intent.getBooleanExtra("ALARM_WAKE_ONLY", false)) {
+            Log.d("AlarmReceiver", "========== ALARM RECEIVER END (wake only) ==========")
+            NativeEventLog.log(context, "AlarmReceiver", "Reported wake-up id=$alarmId, nothing rung")
+            return
+        }
+
+        // A watch-only backup alarm is rung by the watch from the fired event alone.
+        if (intent.getBooleanExtra("ALARM_WATCH_ONLY", false)) {
+            Log.d("AlarmReceiver", "Watch-only alarm $alarmId, not ringing the phone")
+            Log.d("AlarmReceiver", "========== ALARM RECEIVER END (watch only) ==========")
+            NativeEventLog.log(context, "AlarmReceiver", "Alarm id=$alarmId is watch-only, phone not rung")
+            return
+        }
+
         // Start the foreground service for sound/notification
         // The notification's full-screen intent will launch the app with the alarm ID
         // and onNewIntent() in the plugin will handle emitting the event to the frontend
@@ -39,6 +64,15 @@ class AlarmReceiver : BroadcastReceiver() {
         val serviceIntent = Intent(context, AlarmRingingService::class.java).apply {
             putExtra("ALARM_ID", alarmId)
             putExtra("ALARM_SOUND_URI", soundUri)
+            // Set for pre-alarms only (see AlarmUtils.scheduleAlarm)
+            if (intent.hasExtra("ALARM_VOLUME_PERCENT")) {
+                putExtra("ALARM_VOLUME_PERCENT", intent.getIntExtra("ALARM_VOLUME_PERCENT", 100))
+            }
+            if (intent.hasExtra("ALARM_RING_SECONDS")) {
+                putExtra("ALARM_RING_SECONDS", intent.getIntExtra("ALARM_RING_SECONDS", 0))
+            }
+            putExtra("ALARM_SNOOZE_ALLOWED", intent.getBooleanExtra("ALARM_SNOOZE_ALLOWED", true))
+            putExtra("ALARM_LOUD", intent.getBooleanExtra("ALARM_LOUD", false))
         }
 
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
@@ -12,11 +12,14 @@ import android.app.Service
 import android.content.Context
 import android.content.Intent
 import android.media.AudioAttributes
+import android.media.AudioManager
 import android.media.MediaPlayer
 import android.media.RingtoneManager
 import android.net.Uri
 import android.os.Build
+import android.os.Handler
 import android.os.IBinder
+import android.os.Looper
 import android.os.PowerManager
 import android.os.VibrationEffect
 import android.os.Vibrator
@@ -30,6 +33,16 @@ class AlarmRingingService : Service() {
     private var vibrator: Vibrator? = null
     private var wakeLock: PowerManager.WakeLock? = null
     private var currentAlarmId: Int = -1
+    // Alarm stream volume to put back once a backup alarm, which raises it, stops.
+    private var savedAlarmVolume: Int? = null
+    private val handler = Handler(Looper.getMainLooper())
+
+    // Pre-alarms ring for a bounded time, then stop without dismissing anything.
+    private val ringTimeout = Runnable {
+        Log.d(TAG, "Ring time elapsed for alarm $currentAlarmId")
+        NativeEventLog.log(applicationContext, TAG, "Ring time elapsed for alarm id=$currentAlarmId, stopping")
+        stopSelf()
+    }
 
     companion object {
         const val CHANNEL_ID = "alarm_ringing_service"
@@ -85,15 +98,42 @@ class AlarmRingingService : Service() {
         }
 
         val soundUriStr = intent.getStringExtra("ALARM_SOUND_URI")
+        val volumePercent = if (intent.hasExtra("ALARM_VOLUME_PERCENT")) {
+            intent.getIntExtra("ALARM_VOLUME_PERCENT", 100)
+        } else {
+            null
+        }
+        val ringSeconds = if (intent.hasExtra("ALARM_RING_SECONDS")) {
+            intent.getIntExtra("ALARM_RING_SECONDS", 0)
+        } else {
+            null
+        }
+        val snoozeAllowed = intent.getBooleanExtra("ALARM_SNOOZE_ALLOWED", true)
+        val loud = intent.getBooleanExtra("ALARM_LOUD", false)
         currentAlarmId = intent.getIntExtra("ALARM_ID", -1)
         currentlyRingingAlarmId = currentAlarmId
 
         Log.d(TAG, "Starting service for alarm $currentAlarmId with sound $soundUriStr")
         NativeEventLog.log(applicationContext, TAG, "Ringing service starting for alarm id=$currentAlarmId")
 
-        startForegroundNotification(buildLaunchIntent())
-        playAudio(soundUriStr)
-        startVibration()
+        // A pre-alarm may still be ringing when its alarm fires; the alarm takes over.
+        handler.removeCallbacks(ringTimeout)
+        stopAudio()
+        stopVibration()
+        restoreAlarmVolume()
+
+        startForegroundNotification(buildLaunchIntent(), snoozeAllowed)
+        if (loud) {
+            raiseAlarmVolume()
+        }
+        playAudio(soundUriStr, volumePercent)
+        // A quieter pre-alarm is a gentle nudge, so it doesn't vibrate either.
+        if (volumePercent == null) {
+            startVibration()
+        }
+        if (ringSeconds != null && ringSeconds > 0) {
+            handler.postDelayed(ringTimeout, ringSeconds * 1000L)
+        }
 
         return START_STICKY
     }
@@ -107,8 +147,10 @@ class AlarmRingingService : Service() {
             currentlyRingingAlarmId = -1
         }
 
+        handler.removeCallbacks(ringTimeout)
         stopAudio()
         stopVibration()
+        restoreAlarmVolume()
 
         if (wakeLock?.isHeld == true) {
             wakeLock?.release()
@@ -129,7 +171,7 @@ class AlarmRingingService : Service() {
         }
     }
 
-    private fun startForegroundNotification(launchIntent: Intent) {
+    private fun startForegroundNotification(launchIntent: Intent, snoozeAllowed: Boolean) {
         val notificationManager = getSystemService(Context.NOTIFICATION_SERVICE) as NotificationManager
 
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
@@ -172,7 +214,7 @@ class AlarmRingingService : Service() {
             PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
         )
 
-        val notification = NotificationCompat.Builder(this, CHANNEL_ID)
+        val builder = NotificationCompat.Builder(this, CHANNEL_ID)
             .setSmallIcon(android.R.drawable.ic_lock_idle_alarm)
             .setContentTitle("Alarm Ringing")
             .setContentText("Tap to open")
@@ -187,8 +229,11 @@ class AlarmRingingService : Service() {
             .setOnlyAlertOnce(true)
             .setContentIntent(contentPendingIntent)
             .addAction(android.R.drawable.ic_menu_close_clear_cancel, "Dismiss", dismissPendingIntent)
-            .addAction(android.R.drawable.ic_popup_reminder, "Snooze", snoozePendingIntent)
-            .build()
+        // The alarm's snooze policy may have run out of snoozes for this ring
+        if (snoozeAllowed) {
+            builder.addAction(android.R.drawable.ic_popup_reminder, "Snooze", snoozePendingIntent)
+        }
+        val notification = builder.build()
 
         startForeground(NOTIFICATION_ID, notification)
         NativeEventLog.log(
@@ -198,7 +243,7 @@ class AlarmRingingService : Service() {
         )
     }
 
-    private fun playAudio(uriStr: String?) {
+    private fun playAudio(uriStr: String?, volumePercent: Int?) {
         var uri: Uri? = null
         if (uriStr != null) {
             try {
@@ -233,6 +278,10 @@ class AlarmRingingService : Service() {
                         .build()
                 )
                 isLooping = true
+                if (volumePercent != null) {
+                    val volume = volumePercent.coerceIn(1, 100) / 100f
+                    setVolume(volume, volume)
+                }
                 prepare()
                 start()
             }
@@ -241,6 +290,35 @@ class AlarmRingingService : Service() {
         }
     }
 
+    // A backup alarm follows one that was slept through, so it doesn't trust the user's
+    // alarm volume and rings at the stream's maximum until it stops.
+    private fun raiseAlarmVolume() {
+        val audioManager = getSystemService(Context.AUDIO_SERVICE) as AudioManager
+        try {
+            savedAlarmVolume = audioManager.getStreamVolume(AudioManager.STREAM_ALARM)
+            audioManager.setStreamVolume(
+                AudioManager.STREAM_ALARM,
+                audioManager.getStreamMaxVolume(AudioManager.STREAM_ALARM),
+                0
+            )
+        } catch (e: SecurityException) {
+            // Do Not Disturb can refuse volume changes; ring at the current volume instead.
+            Log.e(TAG, "Failed to raise alarm volume", e)
+            savedAlarmVolume = null
+        }
+    }
+
+    private fun restoreAlarmVolume() {
+        val volume = savedAlarmVolume ?: return
+        savedAlarmVolume = null
+        try {
+            val audioManager = getSystemService(Context.AUDIO_SERVICE) as AudioManager
+            audioManager.setStreamVolume(AudioManager.STREAM_ALARM, volume, 0)
+        } catch (e: SecurityException) {
+            Log.e(TAG, "Failed to restore alarm volume", e)
+        }
+    }
+
     private fun stopAudio() {
         try {
             mediaPlayer?.stop()
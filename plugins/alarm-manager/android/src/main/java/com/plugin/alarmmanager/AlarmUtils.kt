@@ -11,20 +11,62 @@ import android.content.Context
 import android.content.Intent
 import android.util.Log
 
+/** A scheduled alarm as saved for boot recovery. */
+data class StoredAlarm(
+    val id: Int,
+    val triggerAt: Long,
+    val soundUri: String?,
+    val volumePercent: Int?,
+    val ringSeconds: Int?,
+    val snoozeAllowed: Boolean,
+    val loud: Boolean,
+    val watchOnly: Boolean,
+    val notificationOnly: Boolean,
+    val wakeOnly: Boolean,
+)
+
 object AlarmUtils {
     private const val PREFS_NAME = "ThresholdNative"
     private const val TAG = "AlarmUtils"
 
+    /**
+     * Schedule an alarm with AlarmManager. [volumePercent] and [ringSeconds] are set for
+     * pre-alarms only, which ring quieter and stop by themselves. [snoozeAllowed] false
+     * leaves the Snooze action off the ringing notification. [loud] and [watchOnly] are
+     * for backup alarms, which ring at full alarm volume or leave the ringing to the watch.
+     * [notificationOnly] is for the bedtime reminder, which only posts a notification and
+     * so isn't set as an alarm clock either. [wakeOnly] is for a pause's end, which only
+     * reports its fire for the app to switch the paused alarms back on.
+     */
     fun scheduleAlarm(
         context: Context,
         id: Int,
         triggerAtMillis: Long,
-        soundUri: String?
+        soundUri: String?,
+        volumePercent: Int? = null,
+        ringSeconds: Int? = null,
+        snoozeAllowed: Boolean = true,
+        loud: Boolean = false,
+        watchOnly: Boolean = false,
+        notificationOnly: Boolean = false,
+        wakeOnly: Boolean = false,
     ) {
         Log.d(TAG, "Scheduling alarm $id at $triggerAtMillis")
 
         // 1. Save to SharedPreferences for boot recovery
-        saveToPrefs(context, id, triggerAtMillis, soundUri)
+        saveToPrefs(
+            context,
+            id,
+            triggerAtMillis,
+            soundUri,
+            volumePercent,
+            ringSeconds,
+            snoozeAllowed,
+            loud,
+            watchOnly,
+            notificationOnly,
+            wakeOnly,
+        )
 
         // 2. Schedule via AlarmManager
         val alarmManager = context.getSystemService(Context.ALARM_SERVICE) as AlarmManager
@@ -34,6 +76,17 @@ object AlarmUtils {
             if (soundUri != null) {
                 putExtra("ALARM_SOUND_URI", soundUri)
             }
+            if (volumePercent != null) {
+                putExtra("ALARM_VOLUME_PERCENT", volumePercent)
+            }
+            if (ringSeconds != null) {
+                putExtra("ALARM_RING_SECONDS", ringSeconds)
+            }
+            putExtra("ALARM_SNOOZE_ALLOWED", snoozeAllowed)
+            putExtra("ALARM_LOUD", loud)
+            putExtra("ALARM_WATCH_ONLY", watchOnly)
+            putExtra("ALARM_NOTIFICATION_ONLY", notificationOnly)
+            putExtra("ALARM_WAKE_ONLY", wakeOnly)
         }
 
         val pendingIntent = PendingIntent.getBroadcast(
@@ -43,8 +96,12 @@ object AlarmUtils {
             PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
         )
 
-        val info = AlarmManager.AlarmClockInfo(triggerAtMillis, pendingIntent)
-        alarmManager.setAlarmClock(info, pendingIntent)
+        if (notificationOnly || wakeOnly) {
+            alarmManager.setExactAndAllowWhileIdle(AlarmManager.RTC_WAKEUP, triggerAtMillis, pendingIntent)
+        } else {
+            val info = AlarmManager.AlarmClockInfo(triggerAtMillis, pendingIntent)
+            alarmManager.setAlarmClock(info, pendingIntent)
+        }
 
         Log.d(TAG, "Alarm $id scheduled successfully")
     }
@@ -74,7 +131,19 @@ object AlarmUtils {
         Log.d(TAG, "Alarm $id cancelled successfully")
     }
 
-    private fun saveToPrefs(context: Context, id: Int, trigger: Long, soundUri: String?) {
+    private fun saveToPrefs(
+        context: Context,
+        id: Int,
+        trigger: Long,
+        soundUri: String?,
+        volumePercent: Int?,
+        ringSeconds: Int?,
+        snoozeAllowed: Boolean,
+        loud: Boolean,
+        watchOnly: Boolean,
+        notificationOnly: Boolean,
+        wakeOnly: Boolean,
+    ) {
         val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
         prefs.edit().apply {
             putLong("alarm_$id", trigger)
@@ -83,6 +152,41 @@ object AlarmUtils {
             } else {
                 remove("alarm_sound_$id")
             }
+            if (volumePercent != null) {
+                putInt("alarm_volume_$id", volumePercent)
+            } else {
+                remove("alarm_volume_$id")
+            }
+            if (ringSeconds != null) {
+                putInt("alarm_ring_seconds_$id", ringSeconds)
+            } else {
+                remove("alarm_ring_seconds_$id")
+            }
+            if (!snoozeAllowed) {
+                putBoolean("alarm_no_snooze_$id", true)
+            } else {
+                remove("alarm_no_snooze_$id")
+            }
+            if (loud) {
+                putBoolean("alarm_loud_$id", true)
+            } else {
+                remove("alarm_loud_$id")
+            }
+            if (watchOnly) {
+                putBoolean("alarm_watch_only_$id", true)
+            } else {
+                remove("alarm_watch_only_$id")
+            }
+            if (notificationOnly) {
+                putBoolean("alarm_notification_only_$id", true)
+            } else {
+                remove("alarm_notification_only_$id")
+            }
+            if (wakeOnly) {
+                putBoolean("alarm_wake_only_$id", true)
+            } else {
+                remove("alarm_wake_only_$id")
+            }
             apply()
         }
     }
@@ -92,6 +196,13 @@ object AlarmUtils {
         prefs.edit().apply {
             remove("alarm_$id")
             remove("alarm_sound_$id")
+            remove("alarm_volume_$id")
+            remove("alarm_ring_seconds_$id")
+            remove("alarm_no_snooze_$id")
+            remove("alarm_loud_$id")
+            remove("alarm_watch_only_$id")
+            remove("alarm_notification_only_$id")
+            remove("alarm_wake_only_$id")
             apply()
         }
     }
@@ -101,19 +212,49 @@ object AlarmUtils {
         return prefs.contains("alarm_$id")
     }
 
-    fun loadAllFromPrefs(context: Context): List<Triple<Int, Long, String?>> {
+    fun loadAllFromPrefs(context: Context): List<StoredAlarm> {
         val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
         val all = prefs.all
-        val alarms = mutableListOf<Triple<Int, Long, String?>>()
+        val alarms = mutableListOf<StoredAlarm>()
 
         for ((key, value) in all) {
-            if (key.startsWith("alarm_") && !key.contains("sound")) {
+            if (key.startsWith("alarm_")) {
+                // Only the trigger key is "alarm_<id>"; the rest carry a field name too.
                 val idStr = key.removePrefix("alarm_")
                 val id = idStr.toIntOrNull() ?: continue
                 val trigger = value as? Long ?: continue
                 val soundUri = prefs.getString("alarm_sound_$id", null)
+                val volumePercent = if (prefs.contains("alarm_volume_$id")) {
+                    prefs.getInt("alarm_volume_$id", 100)
+                } else {
+                    null
+                }
+                val ringSeconds = if (prefs.contains("alarm_ring_seconds_$id")) {
+                    prefs.getInt("alarm_ring_seconds_$id", 0)
+                } else {
+                    null
+                }
+
+                val snoozeAllowed = !prefs.getBoolean("alarm_no_snooze_$id", false)
+                val loud = prefs.getBoolean("alarm_loud_$id", false)
+                val watchOnly = prefs.getBoolean("alarm_watch_only_$id", false)
+                val notificationOnly = prefs.getBoolean("alarm_notification_only_$id", false)
+                val wakeOnly = prefs.getBoolean("alarm_wake_only_$id", false)
 
-                alarms.add(Triple(id, trigger, soundUri))
+                alarms.add(
+                    StoredAlarm(
+                        id,
+                        trigger,
+                        soundUri,
+                        volumePercent,
+                        ringSeconds,
+                        snoozeAllowed,
+                        loud,
+                        watchOnly,
+                        notificationOnly,
+                        wakeOnly,
+                    )
+                )
             }
         }
 
@@ -23,14 +23,31 @@ class BootReceiver : BroadcastReceiver() {
 
             var rescheduled = 0
             var expired = 0
-            for ((id, trigger, soundUri) in alarms) {
-                if (trigger > now) {
-                    AlarmUtils.scheduleAlarm(context, id, trigger, soundUri)
-                    Log.d(TAG, "Rescheduled alarm $id")
+            for (alarm in alarms) {
+                if (alarm.triggerAt > now) {
+                    AlarmUtils.scheduleAlarm(
+                        context,
+                        alarm.id,
+                        alarm.triggerAt,
+                        alarm.soundUri,
+                        alarm.volumePercent,
+                        alarm.ringSeconds,
+                        alarm.snoozeAllowed,
+                        alarm.loud,
+                        alarm.watchOnly,
+                        alarm.notificationOnly,
+                        alarm.wakeOnly,
+                    )
+                    Log.d(TAG, "Rescheduled alarm ${alarm.id}")
                     rescheduled++
                 } else {
-                    Log.d(TAG, "Cleaning up expired alarm $id")
-                    AlarmUtils.cancelAlarm(context, id)
+                    // A pause that ended while the phone was off still has to end, so
+                    // its wake-up is reported, for the app to act on when it starts.
+                    if (alarm.wakeOnly) {
+                        AlarmManagerPlugin.notifyAlarmFired(context, alarm.id, alarm.triggerAt)
+                    }
+                    Log.d(TAG, "Cleaning up expired alarm ${alarm.id}")
+                    AlarmUtils.cancelAlarm(context, alarm.id)
                     expired++
                 }
             }
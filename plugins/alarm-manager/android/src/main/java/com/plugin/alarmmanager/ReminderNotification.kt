@@ -0,0 +1,63 @@
+// Plain notification for scheduled reminders that don't ring, such as bedtime
+//
+// (c) Copyright 2026 Liminal HQ, Scott Morris
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+package com.plugin.alarmmanager
+
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.PendingIntent
+import android.content.Context
+import android.os.Build
+import android.util.Log
+import androidx.core.app.NotificationCompat
+
+object ReminderNotification {
+    private const val CHANNEL_ID = "bedtime_reminder"
+    private const val TAG = "ReminderNotification"
+
+    /** Post the reminder scheduled under [id]; tapping it opens the app. */
+    fun post(context: Context, id: Int) {
+        val notificationManager =
+            context.getSystemService(Context.NOTIFICATION_SERVICE) as NotificationManager
+
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            val channel = NotificationChannel(
+                CHANNEL_ID,
+                "Bedtime Reminder",
+                NotificationManager.IMPORTANCE_DEFAULT
+            ).apply {
+                description = "Reminds you to wind down before your next alarm"
+            }
+            notificationManager.createNotificationChannel(channel)
+        }
+
+        val contentPendingIntent = context.packageManager
+            .getLaunchIntentForPackage(context.packageName)
+            ?.let {
+                PendingIntent.getActivity(
+                    context,
+                    id,
+                    it,
+                    PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
+                )
+            }
+
+        val notification = NotificationCompat.Builder(context, CHANNEL_ID)
+            .setSmallIcon(android.R.drawable.ic_lock_idle_alarm)
+            .setContentTitle("Time to wind down")
+            .setContentText("Head to bed soon to get your sleep before your next alarm")
+            .setCategory(NotificationCompat.CATEGORY_REMINDER)
+            .setAutoCancel(true)
+            .setContentIntent(contentPendingIntent)
+            .build()
+
+        try {
+            notificationManager.notify(id, notification)
+        } catch (e: SecurityException) {
+            // Notifications turned off for the app; there's nowhere else to remind.
+            Log.e(TAG, "Failed to post reminder $id", e)
+        }
+    }
+}
@@ -14,16 +14,11 @@ import java.util.Calendar
 
 // EXTRA_DAYS uses Calendar.SUNDAY(1)..SATURDAY(7); Threshold's activeDays uses 0=Sunday..6=Saturday.
 // Absent/empty requestedDays means "one-time, next occurrence only" per the SET_ALARM contract --
-// Threshold has no true one-shot concept (every alarm recurs on activeDays), so the honest
-// translation is a single-day array for whichever weekday the resolved occurrence falls on.
-// A standalone function (not a method) so it's trivially unit-testable without any Android
-// framework dependency -- both parameters are already-resolved Calendar day-of-week values (1-7).
-internal fun resolveActiveDays(requestedDays: List<Int>?, fallbackCalendarDay: Int): List<Int> {
-    return if (requestedDays != null && requestedDays.isNotEmpty()) {
-        requestedDays.map { it - 1 }
-    } else {
-        listOf(fallbackCalendarDay - 1)
-    }
+// that's passed through as an empty array, which Rust imports as a one-shot alarm dated to
+// triggerAt rather than a weekly one. A standalone function (not a method) so it's trivially
+// unit-testable without any Android framework dependency.
+internal fun resolveActiveDays(requestedDays: List<Int>?): List<Int> {
+    return requestedDays?.map { it - 1 } ?: emptyList()
 }
 
 private const val TAG = "SetAlarmActivity"
@@ -70,7 +65,7 @@ class SetAlarmActivity : Activity() {
         val triggerAt = calendar.timeInMillis
 
         val requestedDays = intent.getIntegerArrayListExtra(AlarmClock.EXTRA_DAYS)
-        val activeDays = resolveActiveDays(requestedDays, calendar.get(Calendar.DAY_OF_WEEK))
+        val activeDays = resolveActiveDays(requestedDays)
 
         // 3. Generate ID (Random for now, or timestamp based)
         val id = (System.currentTimeMillis() % Int.MAX_VALUE).toInt()
@@ -20,6 +20,45 @@ pub fn init<R: Runtime>(
     })
 }
 
+/// What a scheduled id does when it comes due.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fire {
+    /// Ring it, through the frontend's `alarm-ring` listener.
+    Ring,
+    /// Notify, through the frontend's `alarm-notify` listener, which posts the bedtime
+    /// reminder.
+    Notify,
+    /// Report the fire straight to the app, as Android's receiver does, for it to
+    /// act on without ringing anything.
+    Wake,
+}
+
+impl Fire {
+    fn emit<R: Runtime>(self, app: &tauri::AppHandle<R>, id: i32) {
+        let result = match self {
+            Fire::Ring => app.emit("alarm-ring", RingEventPayload { id }),
+            Fire::Notify => app.emit("alarm-notify", RingEventPayload { id }),
+            Fire::Wake => app.emit(
+                "alarm-manager:native-fired",
+                NativeAlarmFiredPayload {
+                    id,
+                    actual_fired_at: now_millis(),
+                },
+            ),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to emit {:?} event for alarm {}: {}", self, id, e);
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or(std::time::Duration::ZERO)
+        .as_millis() as i64
+}
+
 pub struct AlarmManager<R: Runtime> {
     app: tauri::AppHandle<R>,
     tasks: Arc<Mutex<HashMap<i32, JoinHandle<()>>>>,
@@ -27,7 +66,16 @@ pub struct AlarmManager<R: Runtime> {
 
 impl<R: Runtime> AlarmManager<R> {
     pub fn schedule(&self, payload: ScheduleRequest) -> crate::Result<()> {
-        self.schedule_internal(payload.id, payload.trigger_at);
+        // A reminder only notifies, and a wake-up only reports its fire, so neither
+        // may look like a ring to listeners.
+        let fire = if payload.notification_only == Some(true) {
+            Fire::Notify
+        } else if payload.wake_only == Some(true) {
+            Fire::Wake
+        } else {
+            Fire::Ring
+        };
+        self.schedule_internal(payload.id, payload.trigger_at, fire);
         Ok(())
     }
 
@@ -36,7 +84,7 @@ impl<R: Runtime> AlarmManager<R> {
         Ok(())
     }
 
-    fn schedule_internal(&self, id: i32, trigger_at: i64) {
+    fn schedule_internal(&self, id: i32, trigger_at: i64, fire: Fire) {
         println!("Desktop: Schedule alarm {} for {}", id, trigger_at);
 
         // Cancel existing
@@ -46,12 +94,7 @@ impl<R: Runtime> AlarmManager<R> {
         let tasks_map = self.tasks.clone();
 
         let task = tokio::spawn(async move {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or(std::time::Duration::ZERO)
-                .as_millis() as i64;
-
-            let delay_ms = trigger_at - now;
+            let delay_ms = trigger_at - now_millis();
 
             if delay_ms > 0 {
                 println!("Desktop: Sleeping for {} ms", delay_ms);
@@ -61,9 +104,7 @@ impl<R: Runtime> AlarmManager<R> {
             }
 
             println!("Desktop: Alarm {} firing!", id);
-            if let Err(e) = app_handle.emit("alarm-ring", RingEventPayload { id }) {
-                eprintln!("Failed to emit alarm-ring event: {}", e);
-            }
+            fire.emit(&app_handle, id);
 
             // Cleanup
             let mut map = tasks_map.lock().unwrap();
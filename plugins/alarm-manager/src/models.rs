@@ -11,6 +11,33 @@ pub struct ScheduleRequest {
     pub id: i32,
     pub trigger_at: i64,
     pub sound_uri: Option<String>,
+    /// Pre-alarms only: percent of the alarm stream's volume to ring at; full if unset.
+    #[serde(default)]
+    pub volume_percent: Option<u8>,
+    /// Pre-alarms only: stop ringing by itself after this long; rings until dismissed
+    /// if unset.
+    #[serde(default)]
+    pub ring_seconds: Option<u32>,
+    /// `Some(false)` leaves the Snooze action off the ringing notification, when the
+    /// alarm's snooze policy won't allow one.
+    #[serde(default)]
+    pub snooze_allowed: Option<bool>,
+    /// Backup alarms only: the alarm whose undismissed ring this one backs up. Rings
+    /// with the alarm stream raised to full volume.
+    #[serde(default)]
+    pub backup_for: Option<i32>,
+    /// Backup alarms only: `Some(true)` reports the fire, for the watch to ring, without
+    /// ringing the phone.
+    #[serde(default)]
+    pub watch_only: Option<bool>,
+    /// Bedtime reminders only: `Some(true)` posts a notification when it's due instead
+    /// of ringing or reporting a fire.
+    #[serde(default)]
+    pub notification_only: Option<bool>,
+    /// Pause ends only: `Some(true)` reports the fire, for the app to end the pause,
+    /// without ringing or notifying.
+    #[serde(default)]
+    pub wake_only: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,11 +54,11 @@ pub struct ImportedAlarm {
     pub minute: i32,
     pub label: String,
     /// Days of week this alarm is active on, 0=Sunday..6=Saturday. Empty means
-    /// "one-time" per the SET_ALARM contract -- Kotlin already resolves this to a
-    /// single-day array before sending, so it's non-empty in practice, but the type
-    /// doesn't promise that.
+    /// "one-time" per the SET_ALARM contract, imported as a one-shot alarm on
+    /// `trigger_at`'s date.
     pub active_days: Vec<i32>,
-    /// Epoch millis of the originally-computed one-shot occurrence, for staleness checks.
+    /// Epoch millis of the originally-computed next occurrence, for staleness checks
+    /// and for dating one-time imports.
     pub trigger_at: i64,
 }
 
@@ -57,8 +57,14 @@ class AlarmRingRequest {
     var hour: Int? = null
     var minute: Int? = null
     var snoozeLengthMinutes: Int = 10
+    var snoozeAllowed: Boolean = true
     var is24Hour: Boolean = false
     var is24HourKnown: Boolean = false
+    var preAlarm: Boolean = false
+    // Null rings until dismissed; omitted by the Rust side rather than sent as null.
+    var ringSeconds: Int? = null
+    // Rung unattended on the phone for a while: ring insistently, even if already ringing
+    var escalated: Boolean = false
 }
 
 @InvokeArg
@@ -200,8 +206,12 @@ class WearSyncPlugin(private val activity: Activity) : Plugin(activity) {
                     put("hour", hour)
                     put("minute", minute)
                     put("snoozeLengthMinutes", args.snoozeLengthMinutes)
+                    put("snoozeAllowed", args.snoozeAllowed)
                     put("is24Hour", args.is24Hour)
                     put("is24HourKnown", args.is24HourKnown)
+                    put("preAlarm", args.preAlarm)
+                    args.ringSeconds?.let { put("ringSeconds", it) }
+                    put("escalated", args.escalated)
                 }
                 val payload = json.toString().toByteArray()
 
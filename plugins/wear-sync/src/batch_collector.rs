@@ -93,7 +93,7 @@ impl BatchCollector {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::SyncReason;
+    use crate::models::{AlarmsSyncNeeded, SyncReason};
     use crate::publisher::WearSyncPublisher;
     use std::sync::{Arc, Mutex};
     use tokio::time::{sleep, Duration};
@@ -109,19 +109,11 @@ mod tests {
             self.batches.lock().unwrap().push((ids, revision));
         }
 
-        fn publish_immediate(
-            &self,
-            reason: &SyncReason,
-            revision: i64,
-            _all_alarms_json: Option<String>,
-            _snooze_length_minutes: i32,
-            _is_24_hour: bool,
-            _is_24_hour_known: bool,
-        ) {
+        fn publish_immediate(&self, payload: AlarmsSyncNeeded) {
             self.immediate
                 .lock()
                 .unwrap()
-                .push((reason.clone(), revision));
+                .push((payload.reason, payload.revision));
         }
     }
 
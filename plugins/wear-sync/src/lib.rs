@@ -33,9 +33,9 @@ pub use mobile::WearSync;
 
 use batch_collector::BatchCollector;
 use models::{
-    AlarmDismissRequest, AlarmFired, AlarmRingRequest, AlarmSnoozeRequest, AlarmsBatchUpdated,
-    AlarmsSyncNeeded, PublishRequest, WatchDeleteAlarm, WatchDismissAlarm, WatchMessage,
-    WatchSaveAlarm, WatchSnoozeAlarm, WatchSyncRequest,
+    AlarmDismissRequest, AlarmEscalated, AlarmFired, AlarmRingRequest, AlarmSnoozeRequest,
+    AlarmsBatchUpdated, AlarmsSyncNeeded, PublishRequest, WatchDeleteAlarm, WatchDismissAlarm,
+    WatchMessage, WatchSaveAlarm, WatchSnoozeAlarm, WatchSyncRequest,
 };
 use publisher::{ChannelPublisher, PublishCommand, WearSyncPublisher};
 
@@ -131,8 +131,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                                 hour: None,
                                 minute: None,
                                 snooze_length_minutes: fired.snooze_length_minutes,
+                                snooze_allowed: fired.snooze_allowed,
                                 is_24_hour: fired.is_24_hour,
                                 is_24_hour_known: fired.is_24_hour_known,
+                                pre_alarm: fired.pre_alarm_for.is_some(),
+                                ring_seconds: fired.ring_seconds,
+                                // A backup alarm follows a ring nobody answered, so the
+                                // watch rings it insistently from the start.
+                                escalated: fired.backup_for.is_some(),
                             };
 
                             let alarm_id = request.alarm_id;
@@ -156,6 +162,50 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                 }
             });
 
+            // Listen for alarm escalations — an alarm left ringing unattended on
+            // the phone rings the watch insistently, in case it's within reach.
+            let escalate_app = app.clone();
+            app.listen("alarm:escalated", move |event| {
+                match serde_json::from_str::<AlarmEscalated>(event.payload()) {
+                    Ok(escalated) => {
+                        let app = escalate_app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let wear_sync = app.state::<WearSync<R>>();
+                            let request = AlarmRingRequest {
+                                alarm_id: escalated.id,
+                                label: escalated.label.unwrap_or_default(),
+                                hour: None,
+                                minute: None,
+                                snooze_length_minutes: escalated.snooze_length_minutes,
+                                snooze_allowed: escalated.snooze_allowed,
+                                is_24_hour: escalated.is_24_hour,
+                                is_24_hour_known: escalated.is_24_hour_known,
+                                pre_alarm: false,
+                                ring_seconds: None,
+                                escalated: true,
+                            };
+
+                            let alarm_id = request.alarm_id;
+                            match wear_sync.send_alarm_ring(request) {
+                                Ok(()) => {
+                                    log::info!(
+                                        "wear-sync: sent alarm escalation to watch for id={alarm_id}"
+                                    );
+                                }
+                                Err(error) => {
+                                    log::error!(
+                                        "wear-sync: failed to send alarm escalation to watch: {error}"
+                                    );
+                                }
+                            }
+                        });
+                    }
+                    Err(error) => {
+                        log::warn!("wear-sync: failed to parse alarm:escalated payload: {error}");
+                    }
+                }
+            });
+
             // Mirror phone alarm dismiss lifecycle to the watch so tapping
             // stop on phone halts watch ringing immediately.
             let dismiss_app = app.clone();
@@ -294,14 +344,17 @@ fn spawn_publish_task<R: Runtime>(
                         log::error!("wear-sync: failed to emit batch_ready: {error}");
                     }
                 }
-                PublishCommand::Immediate {
+                PublishCommand::Immediate(AlarmsSyncNeeded {
                     reason,
                     revision,
                     all_alarms_json,
+                    all_timers_json,
+                    all_groups_json,
+                    all_pauses_json,
                     snooze_length_minutes,
                     is_24_hour,
                     is_24_hour_known,
-                } => {
+                }) => {
                     log::info!(
                         "wear-sync: immediate publish ({:?}) at revision {}",
                         reason,
@@ -312,10 +365,22 @@ fn spawn_publish_task<R: Runtime>(
                     let all_alarms: Vec<serde_json::Value> = all_alarms_json
                         .and_then(|json| serde_json::from_str(&json).ok())
                         .unwrap_or_default();
+                    let timers: Vec<serde_json::Value> = all_timers_json
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
+                    let groups: Vec<serde_json::Value> = all_groups_json
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
+                    let pauses: Vec<serde_json::Value> = all_pauses_json
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
 
                     let response = sync_protocol::SyncResponse::FullSync {
                         current_revision: revision,
                         all_alarms,
+                        timers,
+                        groups,
+                        pauses,
                     };
                     let alarms_json = serde_json::to_string(&response).unwrap_or_default();
 
@@ -440,14 +505,7 @@ async fn handle_sync_needed(
         // any partial batch of IDs.
     }
 
-    publisher.publish_immediate(
-        &payload.reason,
-        payload.revision,
-        payload.all_alarms_json,
-        payload.snooze_length_minutes,
-        payload.is_24_hour,
-        payload.is_24_hour_known,
-    );
+    publisher.publish_immediate(payload);
 }
 
 #[cfg(test)]
@@ -477,19 +535,11 @@ mod tests {
                 .push(PublishCall::Batch(ids, revision));
         }
 
-        fn publish_immediate(
-            &self,
-            reason: &SyncReason,
-            revision: i64,
-            _all_alarms_json: Option<String>,
-            _snooze_length_minutes: i32,
-            _is_24_hour: bool,
-            _is_24_hour_known: bool,
-        ) {
+        fn publish_immediate(&self, payload: AlarmsSyncNeeded) {
             self.calls
                 .lock()
                 .unwrap()
-                .push(PublishCall::Immediate(reason.clone(), revision));
+                .push(PublishCall::Immediate(payload.reason, payload.revision));
         }
     }
 
@@ -504,6 +554,9 @@ mod tests {
             reason: SyncReason::ForceSync,
             revision: 41,
             all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
             snooze_length_minutes: 10,
             is_24_hour: false,
             is_24_hour_known: false,
@@ -533,6 +586,9 @@ mod tests {
             reason: SyncReason::Initialize,
             revision: 1,
             all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
             snooze_length_minutes: 10,
             is_24_hour: false,
             is_24_hour_known: false,
@@ -562,6 +618,9 @@ mod tests {
             reason: SyncReason::Reconnect,
             revision: 11,
             all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
             snooze_length_minutes: 10,
             is_24_hour: false,
             is_24_hour_known: false,
@@ -588,7 +647,17 @@ mod tests {
         let publisher = ChannelPublisher::new(tx);
 
         publisher.publish_batch(vec![1, 2], 5);
-        publisher.publish_immediate(&SyncReason::ForceSync, 6, None, 10, false, false);
+        publisher.publish_immediate(AlarmsSyncNeeded {
+            reason: SyncReason::ForceSync,
+            revision: 6,
+            all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
+            snooze_length_minutes: 10,
+            is_24_hour: false,
+            is_24_hour_known: false,
+        });
 
         let cmd1 = rx.recv().await.unwrap();
         match cmd1 {
@@ -601,9 +670,9 @@ mod tests {
 
         let cmd2 = rx.recv().await.unwrap();
         match cmd2 {
-            PublishCommand::Immediate {
+            PublishCommand::Immediate(AlarmsSyncNeeded {
                 reason, revision, ..
-            } => {
+            }) => {
                 assert_eq!(reason, SyncReason::ForceSync);
                 assert_eq!(revision, 6);
             }
@@ -626,6 +695,9 @@ mod tests {
             reason: SyncReason::BatchComplete,
             revision: 51,
             all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
             snooze_length_minutes: 10,
             is_24_hour: false,
             is_24_hour_known: false,
@@ -635,6 +707,6 @@ mod tests {
 
         // Pending batch is cancelled, only immediate FullSync is sent
         let cmd = rx.recv().await.unwrap();
-        assert!(matches!(cmd, PublishCommand::Immediate { .. }));
+        assert!(matches!(cmd, PublishCommand::Immediate(_)));
     }
 }
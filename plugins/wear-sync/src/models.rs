@@ -23,6 +23,15 @@ pub struct AlarmsSyncNeeded {
     /// Pre-serialised JSON array of all alarms (populated by the app crate).
     #[serde(default)]
     pub all_alarms_json: Option<String>,
+    /// Pre-serialised JSON array of all timers (populated by the app crate).
+    #[serde(default)]
+    pub all_timers_json: Option<String>,
+    /// Pre-serialised JSON array of all alarm groups (populated by the app crate).
+    #[serde(default)]
+    pub all_groups_json: Option<String>,
+    /// Pre-serialised JSON array of all alarm pauses (populated by the app crate).
+    #[serde(default)]
+    pub all_pauses_json: Option<String>,
     /// Snooze duration in minutes (from phone settings).
     #[serde(default = "default_snooze_length")]
     pub snooze_length_minutes: i32,
@@ -129,21 +138,37 @@ pub struct AlarmFired {
     pub actual_fired_at: i64,
     pub label: Option<String>,
     pub revision: i64,
-    /// Snooze duration in minutes (synced from phone settings).
+    /// Snooze duration in minutes (the alarm's snooze policy, else phone settings).
     #[serde(default = "default_snooze_length")]
     pub snooze_length_minutes: i32,
+    /// Whether the alarm's snooze policy allows snoozing this ring.
+    #[serde(default = "default_snooze_allowed")]
+    pub snooze_allowed: bool,
     /// Time format preference from phone settings (`true` = 24-hour clock).
     #[serde(default = "default_is_24_hour")]
     pub is_24_hour: bool,
     /// Whether the phone time format value is explicitly known.
     #[serde(default = "default_is_24_hour_known")]
     pub is_24_hour_known: bool,
+    /// Set when a pre-alarm fired: the alarm it rings ahead of.
+    #[serde(default)]
+    pub pre_alarm_for: Option<i32>,
+    /// Pre-alarms only: how long it rings before stopping by itself.
+    #[serde(default)]
+    pub ring_seconds: Option<u32>,
+    /// Set when a backup alarm fired: the alarm whose undismissed ring it backs up.
+    #[serde(default)]
+    pub backup_for: Option<i32>,
 }
 
 fn default_snooze_length() -> i32 {
     10
 }
 
+fn default_snooze_allowed() -> bool {
+    true
+}
+
 fn default_is_24_hour() -> bool {
     false
 }
@@ -166,10 +191,35 @@ pub struct AlarmRingRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub minute: Option<i32>,
     pub snooze_length_minutes: i32,
+    /// `false` hides the watch's snooze button: the alarm's snooze policy won't allow it.
+    #[serde(default = "default_snooze_allowed")]
+    pub snooze_allowed: bool,
     #[serde(default = "default_is_24_hour")]
     pub is_24_hour: bool,
     #[serde(default = "default_is_24_hour_known")]
     pub is_24_hour_known: bool,
+    /// A quieter pre-alarm rather than the alarm itself: the watch only nudges.
+    #[serde(default)]
+    pub pre_alarm: bool,
+    /// Stop ringing by itself after this long; omitted rings until dismissed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ring_seconds: Option<u32>,
+    /// The phone has rung unattended for half its ring timeout: ring insistently,
+    /// even if this alarm is already ringing.
+    #[serde(default)]
+    pub escalated: bool,
+}
+
+/// Payload for the alarm:escalated event (from alarm coordinator).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmEscalated {
+    pub id: i32,
+    pub label: Option<String>,
+    pub snooze_length_minutes: i32,
+    pub snooze_allowed: bool,
+    pub is_24_hour: bool,
+    pub is_24_hour_known: bool,
 }
 
 /// Request to send an alarm dismiss message to the watch.
@@ -3,19 +3,12 @@
 // (c) Copyright 2026 Liminal HQ, Scott Morris
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::models::SyncReason;
+use crate::models::AlarmsSyncNeeded;
 
 pub trait WearSyncPublisher: Send + Sync {
     fn publish_batch(&self, ids: Vec<i32>, revision: i64);
-    fn publish_immediate(
-        &self,
-        reason: &SyncReason,
-        revision: i64,
-        all_alarms_json: Option<String>,
-        snooze_length_minutes: i32,
-        is_24_hour: bool,
-        is_24_hour_known: bool,
-    );
+    /// Publish a full sync built from the app crate's snapshot in `payload`.
+    fn publish_immediate(&self, payload: AlarmsSyncNeeded);
 }
 
 // ── Channel-based publisher ──────────────────────────────────────────
@@ -26,19 +19,8 @@ pub trait WearSyncPublisher: Send + Sync {
 pub enum PublishCommand {
     /// Publish a batch of changed alarm IDs at the given revision.
     Batch { ids: Vec<i32>, revision: i64 },
-    /// Publish an immediate full sync for the given reason.
-    Immediate {
-        reason: SyncReason,
-        revision: i64,
-        /// Pre-serialised JSON array of all alarms (from the app crate).
-        all_alarms_json: Option<String>,
-        /// Snooze duration in minutes (from phone settings).
-        snooze_length_minutes: i32,
-        /// Time format preference from phone settings (`true` = 24-hour clock).
-        is_24_hour: bool,
-        /// Whether the phone time format value is explicitly known.
-        is_24_hour_known: bool,
-    },
+    /// Publish an immediate full sync of the app crate's snapshot.
+    Immediate(AlarmsSyncNeeded),
 }
 
 /// A publisher that sends commands through a `tokio::sync::mpsc` channel
@@ -65,23 +47,8 @@ impl WearSyncPublisher for ChannelPublisher {
         }
     }
 
-    fn publish_immediate(
-        &self,
-        reason: &SyncReason,
-        revision: i64,
-        all_alarms_json: Option<String>,
-        snooze_length_minutes: i32,
-        is_24_hour: bool,
-        is_24_hour_known: bool,
-    ) {
-        if let Err(error) = self.tx.send(PublishCommand::Immediate {
-            reason: reason.clone(),
-            revision,
-            all_alarms_json,
-            snooze_length_minutes,
-            is_24_hour,
-            is_24_hour_known,
-        }) {
+    fn publish_immediate(&self, payload: AlarmsSyncNeeded) {
+        if let Err(error) = self.tx.send(PublishCommand::Immediate(payload)) {
             log::error!("wear-sync: failed to send immediate publish command: {error}");
         }
     }
@@ -90,6 +57,7 @@ impl WearSyncPublisher for ChannelPublisher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::SyncReason;
 
     #[test]
     fn channel_publisher_sends_batch() {
@@ -113,28 +81,37 @@ mod tests {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
         let publisher = ChannelPublisher::new(tx);
 
-        publisher.publish_immediate(
-            &SyncReason::ForceSync,
-            100,
-            Some("[{\"id\":1}]".into()),
-            10,
-            true,
-            true,
-        );
+        publisher.publish_immediate(AlarmsSyncNeeded {
+            reason: SyncReason::ForceSync,
+            revision: 100,
+            all_alarms_json: Some("[{\"id\":1}]".into()),
+            all_timers_json: Some("[{\"id\":2}]".into()),
+            all_groups_json: Some("[{\"id\":3}]".into()),
+            all_pauses_json: Some("[{\"id\":4}]".into()),
+            snooze_length_minutes: 10,
+            is_24_hour: true,
+            is_24_hour_known: true,
+        });
 
         let cmd = rx.try_recv().unwrap();
         match cmd {
-            PublishCommand::Immediate {
+            PublishCommand::Immediate(AlarmsSyncNeeded {
                 reason,
                 revision,
                 all_alarms_json,
+                all_timers_json,
+                all_groups_json,
+                all_pauses_json,
                 snooze_length_minutes,
                 is_24_hour,
                 is_24_hour_known,
-            } => {
+            }) => {
                 assert_eq!(reason, SyncReason::ForceSync);
                 assert_eq!(revision, 100);
                 assert_eq!(all_alarms_json, Some("[{\"id\":1}]".into()));
+                assert_eq!(all_timers_json, Some("[{\"id\":2}]".into()));
+                assert_eq!(all_groups_json, Some("[{\"id\":3}]".into()));
+                assert_eq!(all_pauses_json, Some("[{\"id\":4}]".into()));
                 assert_eq!(snooze_length_minutes, 10);
                 assert!(is_24_hour);
                 assert!(is_24_hour_known);
@@ -151,6 +128,16 @@ mod tests {
 
         // Should not panic, just log an error
         publisher.publish_batch(vec![1], 1);
-        publisher.publish_immediate(&SyncReason::Initialize, 1, None, 10, false, false);
+        publisher.publish_immediate(AlarmsSyncNeeded {
+            reason: SyncReason::Initialize,
+            revision: 1,
+            all_alarms_json: None,
+            all_timers_json: None,
+            all_groups_json: None,
+            all_pauses_json: None,
+            snooze_length_minutes: 10,
+            is_24_hour: false,
+            is_24_hour_known: false,
+        });
     }
 }
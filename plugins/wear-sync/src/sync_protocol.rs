@@ -60,12 +60,18 @@ pub enum SyncResponse {
         #[serde(rename = "deletedAlarmIds")]
         deleted_alarm_ids: Vec<i32>,
     },
-    /// Complete replacement: all active alarms.
+    /// Complete replacement: all active alarms, and every timer, alarm group and pause.
     FullSync {
         #[serde(rename = "currentRevision")]
         current_revision: i64,
         #[serde(rename = "allAlarms")]
         all_alarms: Vec<serde_json::Value>,
+        #[serde(default)]
+        timers: Vec<serde_json::Value>,
+        #[serde(default)]
+        groups: Vec<serde_json::Value>,
+        #[serde(default)]
+        pauses: Vec<serde_json::Value>,
     },
 }
 
@@ -141,10 +147,14 @@ mod tests {
         let response = SyncResponse::FullSync {
             current_revision: 100,
             all_alarms: vec![],
+            timers: vec![serde_json::json!({"id": 1})],
+            groups: vec![],
+            pauses: vec![],
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"type\":\"FullSync\""));
         assert!(json.contains("\"allAlarms\":[]"));
+        assert!(json.contains("\"timers\":[{\"id\":1}]"));
     }
 
     #[test]